@@ -1,5 +1,5 @@
 #![allow(dead_code)]
-use crate::domain::entities::Package;
+use crate::domain::entities::{CacheInfo, Package};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,3 +30,12 @@ pub struct CacheInfoDto {
     pub total_size: u64,
     pub package_count: usize,
 }
+
+impl From<CacheInfo> for CacheInfoDto {
+    fn from(info: CacheInfo) -> Self {
+        Self {
+            total_size: info.total_size,
+            package_count: info.package_count,
+        }
+    }
+}
@@ -0,0 +1,403 @@
+//! In-memory repositories with canned data, for testing the use cases and
+//! the app logic without running brew.
+
+use crate::domain::entities::{
+    AuditResult, CacheInfo, CleanupPreview, DependencyNode, Package, PackageList, PackageListItem,
+    PackageType, PlannedUpgrade, ProgressCell, Release, Service, ServiceInfo, UpgradeReport,
+};
+use crate::domain::repositories::{
+    PackageListRepository, PackageRepository, ReleaseRepository, ServiceRepository,
+};
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::SystemTime;
+
+/// Serves the packages it was given and records every mutating call as
+/// `"<action> <package>"`.
+pub struct MockPackageRepository {
+    /// Those marked `outdated` are also listed as outdated.
+    installed: Vec<Package>,
+    /// Searched by name, like `brew search`.
+    available: Vec<Package>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockPackageRepository {
+    pub fn new(installed: Vec<Package>) -> Self {
+        Self {
+            installed,
+            available: Vec::new(),
+            calls: Mutex::default(),
+        }
+    }
+
+    pub fn with_available(mut self, available: Vec<Package>) -> Self {
+        self.available = available;
+        self
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        lock(&self.calls).clone()
+    }
+
+    fn record(&self, action: &str, package: &str) {
+        lock(&self.calls).push(format!("{} {}", action, package).trim_end().to_string());
+    }
+}
+
+#[async_trait]
+impl PackageRepository for MockPackageRepository {
+    async fn get_installed_packages(&self, package_type: PackageType) -> Result<Vec<Package>> {
+        Ok(of_type(&self.installed, package_type))
+    }
+
+    async fn get_outdated_packages(&self, package_type: PackageType) -> Result<Vec<Package>> {
+        Ok(of_type(&self.installed, package_type)
+            .into_iter()
+            .filter(|p| p.outdated)
+            .collect())
+    }
+
+    async fn install_package(&self, package: &Package, _progress: &ProgressCell) -> Result<()> {
+        self.record("install", &package.name);
+        Ok(())
+    }
+
+    async fn is_installed(&self, package: &Package) -> Result<bool> {
+        Ok(self.installed.iter().any(|p| p.name == package.name))
+    }
+
+    async fn uninstall_package(&self, package: &Package) -> Result<()> {
+        self.record("uninstall", &package.name);
+        Ok(())
+    }
+
+    async fn zap_cask(&self, package: &Package) -> Result<()> {
+        self.record("zap", &package.name);
+        Ok(())
+    }
+
+    async fn update_package(&self, package: &Package, _progress: &ProgressCell) -> Result<()> {
+        self.record("update", &package.name);
+        Ok(())
+    }
+
+    async fn update_metadata(&self, _progress: &ProgressCell) -> Result<()> {
+        self.record("update_metadata", "");
+        Ok(())
+    }
+
+    async fn update_all(&self, _progress: &ProgressCell) -> Result<UpgradeReport> {
+        self.record("update_all", "");
+        Ok(UpgradeReport {
+            upgraded: self
+                .installed
+                .iter()
+                .filter(|p| p.outdated)
+                .map(|p| p.name.clone())
+                .collect(),
+            failed: Vec::new(),
+        })
+    }
+
+    async fn get_upgrade_preview(&self) -> Result<Vec<PlannedUpgrade>> {
+        Ok(self
+            .installed
+            .iter()
+            .filter(|p| p.outdated)
+            .map(|p| PlannedUpgrade {
+                name: p.name.clone(),
+                current_version: p.version.clone(),
+                new_version: p.available_version.clone().unwrap_or_default(),
+            })
+            .collect())
+    }
+
+    /// Only installed packages can be fetched.
+    async fn fetch_packages(
+        &self,
+        packages: &[Package],
+        _progress: &ProgressCell,
+    ) -> Result<Vec<String>> {
+        let mut fetched = Vec::new();
+        for package in packages {
+            self.record("fetch", &package.name);
+            if self.installed.iter().any(|p| p.name == package.name) {
+                fetched.push(package.name.clone());
+            }
+        }
+        Ok(fetched)
+    }
+
+    async fn get_cleanup_preview(&self) -> Result<CleanupPreview> {
+        Ok(empty_preview())
+    }
+
+    async fn get_cleanup_old_versions_preview(&self) -> Result<CleanupPreview> {
+        Ok(empty_preview())
+    }
+
+    async fn get_cache_info(&self, _cache: &Path) -> Result<CacheInfo> {
+        Ok(CacheInfo {
+            total_size: 0,
+            package_count: 0,
+        })
+    }
+
+    async fn clean_cache(&self) -> Result<()> {
+        self.record("clean_cache", "");
+        Ok(())
+    }
+
+    async fn cleanup_old_versions(&self) -> Result<()> {
+        self.record("cleanup_old_versions", "");
+        Ok(())
+    }
+
+    async fn get_autoremove_preview(&self, _cellar: &Path) -> Result<CleanupPreview> {
+        Ok(empty_preview())
+    }
+
+    async fn autoremove(&self) -> Result<()> {
+        self.record("autoremove", "");
+        Ok(())
+    }
+
+    async fn search_packages(
+        &self,
+        query: &str,
+        package_type: PackageType,
+    ) -> Result<Vec<Package>> {
+        Ok(of_type(&self.available, package_type)
+            .into_iter()
+            .filter(|p| p.name.contains(query))
+            .collect())
+    }
+
+    async fn get_package_info(&self, name: &str, package_type: PackageType) -> Result<Package> {
+        self.record("info", name);
+        self.installed
+            .iter()
+            .chain(&self.available)
+            .find(|p| p.name == name && p.package_type == package_type)
+            .cloned()
+            .ok_or_else(|| anyhow!("No available formula or cask with the name \"{}\"", name))
+    }
+
+    async fn get_packages_info(
+        &self,
+        names: &[String],
+        package_type: PackageType,
+    ) -> Result<Vec<Package>> {
+        self.record("info", &names.join(" "));
+        Ok(self
+            .installed
+            .iter()
+            .chain(&self.available)
+            .filter(|p| p.package_type == package_type && names.contains(&p.name))
+            .cloned()
+            .collect())
+    }
+
+    async fn pin_package(&self, package: &Package) -> Result<()> {
+        self.record("pin", &package.name);
+        Ok(())
+    }
+
+    async fn unpin_package(&self, package: &Package) -> Result<()> {
+        self.record("unpin", &package.name);
+        Ok(())
+    }
+
+    async fn audit_package(&self, package: &Package) -> Result<AuditResult> {
+        self.record("audit", &package.name);
+        Ok(AuditResult {
+            passed: true,
+            findings: Vec::new(),
+        })
+    }
+
+    async fn dependency_tree(&self, package: &Package) -> Result<DependencyNode> {
+        self.record("deps", &package.name);
+        Ok(DependencyNode::new(package.name.clone()))
+    }
+
+    async fn get_install_dates(&self, packages: &[Package]) -> Result<HashMap<String, SystemTime>> {
+        Ok(packages
+            .iter()
+            .filter_map(|p| {
+                let installed = self.installed.iter().find(|i| i.name == p.name)?;
+                Some((p.name.clone(), installed.installed_at?))
+            })
+            .collect())
+    }
+}
+
+/// Serves the services it was given and records start/stop/restart calls.
+pub struct MockServiceRepository {
+    services: Vec<Service>,
+    calls: Mutex<Vec<String>>,
+}
+
+impl MockServiceRepository {
+    pub fn new(services: Vec<Service>) -> Self {
+        Self {
+            services,
+            calls: Mutex::default(),
+        }
+    }
+
+    pub fn calls(&self) -> Vec<String> {
+        lock(&self.calls).clone()
+    }
+
+    fn record(&self, action: &str, name: &str) -> Result<()> {
+        if !self.services.iter().any(|s| s.name == name) {
+            return Err(anyhow!("Formula `{}` is not installed", name));
+        }
+        lock(&self.calls).push(format!("{} {}", action, name));
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ServiceRepository for MockServiceRepository {
+    async fn list_services(&self) -> Result<Vec<Service>> {
+        Ok(self.services.clone())
+    }
+
+    async fn get_service_info(&self, name: &str) -> Result<ServiceInfo> {
+        let service = self
+            .services
+            .iter()
+            .find(|s| s.name == name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Formula `{}` is not installed", name))?;
+        Ok(ServiceInfo {
+            running: service.status.is_running(),
+            service,
+            pid: None,
+            exit_code: None,
+            registered: None,
+            log_path: None,
+            error_log_path: None,
+        })
+    }
+
+    async fn start_service(&self, name: &str) -> Result<()> {
+        self.record("start", name)
+    }
+
+    async fn stop_service(&self, name: &str) -> Result<()> {
+        self.record("stop", name)
+    }
+
+    async fn stop_all_services(&self) -> Result<()> {
+        lock(&self.calls).push("stop --all".to_string());
+        Ok(())
+    }
+
+    async fn restart_service(&self, name: &str) -> Result<()> {
+        self.record("restart", name)
+    }
+
+    async fn cleanup_services(&self) -> Result<String> {
+        lock(&self.calls).push("cleanup".to_string());
+        Ok(String::new())
+    }
+}
+
+/// Exports the packages of a `MockPackageRepository` and keeps whatever
+/// was imported.
+pub struct MockPackageListRepository {
+    installed: Vec<Package>,
+    imported: Mutex<Vec<PackageListItem>>,
+}
+
+impl MockPackageListRepository {
+    pub fn new(installed: Vec<Package>) -> Self {
+        Self {
+            installed,
+            imported: Mutex::default(),
+        }
+    }
+
+    pub fn imported(&self) -> Vec<PackageListItem> {
+        lock(&self.imported).clone()
+    }
+}
+
+#[async_trait]
+impl PackageListRepository for MockPackageListRepository {
+    async fn export_package_list(&self) -> Result<PackageList> {
+        let mut list = PackageList::new().with_export_date("2024-01-01T00:00:00Z".to_string());
+        for package in &self.installed {
+            let mut item = PackageListItem::new(package.name.clone(), package.package_type.clone());
+            if let Some(version) = &package.version {
+                item = item.with_version(version.clone());
+            }
+            match package.package_type {
+                PackageType::Formula => list.add_formula(item),
+                PackageType::Cask => list.add_cask(item),
+            }
+        }
+        Ok(list)
+    }
+
+    async fn import_packages(&self, package_list: &PackageList) -> Result<Vec<String>> {
+        let items: Vec<PackageListItem> = package_list
+            .formulae
+            .iter()
+            .chain(&package_list.casks)
+            .cloned()
+            .collect();
+        let names = items.iter().map(|item| item.name.clone()).collect();
+        lock(&self.imported).extend(items);
+        Ok(names)
+    }
+}
+
+/// Always reports the same latest release.
+pub struct MockReleaseRepository {
+    latest: Release,
+}
+
+impl MockReleaseRepository {
+    pub fn new(version: &str) -> Self {
+        Self {
+            latest: Release {
+                version: version.to_string(),
+                url: format!("https://example.com/releases/{}", version),
+            },
+        }
+    }
+}
+
+#[async_trait]
+impl ReleaseRepository for MockReleaseRepository {
+    async fn latest_release(&self) -> Result<Release> {
+        Ok(self.latest.clone())
+    }
+}
+
+fn of_type(packages: &[Package], package_type: PackageType) -> Vec<Package> {
+    packages
+        .iter()
+        .filter(|p| p.package_type == package_type)
+        .cloned()
+        .collect()
+}
+
+fn empty_preview() -> CleanupPreview {
+    CleanupPreview {
+        items: Vec::new(),
+        total_size: 0,
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -1,4 +1,6 @@
 pub mod dto;
+#[cfg(test)]
+pub mod mock_repositories;
 pub mod use_case_container;
 pub mod use_cases;
 
@@ -1,5 +1,7 @@
 use crate::application::use_cases::*;
-use crate::domain::repositories::{PackageListRepository, PackageRepository, ServiceRepository};
+use crate::domain::repositories::{
+    PackageListRepository, PackageRepository, ReleaseRepository, ServiceRepository,
+};
 use std::sync::Arc;
 
 pub struct UseCaseContainer {
@@ -9,18 +11,27 @@ pub struct UseCaseContainer {
     pub uninstall: Arc<UninstallPackage>,
     pub update: Arc<UpdatePackage>,
     pub update_all: Arc<UpdateAllPackages>,
+    pub fetch: Arc<FetchPackages>,
     pub clean_cache: Arc<CleanCache>,
+    pub cache_info: Arc<GetCacheInfo>,
     pub cleanup_old_versions: Arc<CleanupOldVersions>,
+    pub autoremove: Arc<Autoremove>,
     pub search: Arc<SearchPackages>,
     pub get_package_info: Arc<GetPackageInfo>,
+    pub audit: Arc<AuditPackage>,
+    pub dependency_tree: Arc<GetDependencyTree>,
     pub pin: Arc<PinPackage>,
     pub unpin: Arc<UnpinPackage>,
+    pub install_dates: Arc<GetInstallDates>,
     pub list_services: Arc<ListServices>,
+    pub service_info: Arc<GetServiceInfo>,
     pub start_service: Arc<StartService>,
     pub stop_service: Arc<StopService>,
     pub restart_service: Arc<RestartService>,
+    pub cleanup_services: Arc<CleanupServices>,
     pub export_packages: Arc<ExportPackages>,
     pub import_packages: Arc<ImportPackages>,
+    pub check_for_update: Arc<CheckForUpdate>,
 }
 
 impl UseCaseContainer {
@@ -28,28 +39,89 @@ impl UseCaseContainer {
         package_repository: Arc<dyn PackageRepository>,
         service_repository: Arc<dyn ServiceRepository>,
         package_list_repository: Arc<dyn PackageListRepository>,
+        release_repository: Arc<dyn ReleaseRepository>,
     ) -> Self {
+        let get_package_info = Arc::new(GetPackageInfo::new(Arc::clone(&package_repository)));
         Self {
             list_installed: Arc::new(ListInstalledPackages::new(Arc::clone(&package_repository))),
-            list_outdated: Arc::new(ListOutdatedPackages::new(Arc::clone(&package_repository))),
+            list_outdated: Arc::new(ListOutdatedPackages::new(
+                Arc::clone(&package_repository),
+                Arc::clone(&get_package_info),
+            )),
             install: Arc::new(InstallPackage::new(Arc::clone(&package_repository))),
             uninstall: Arc::new(UninstallPackage::new(Arc::clone(&package_repository))),
             update: Arc::new(UpdatePackage::new(Arc::clone(&package_repository))),
             update_all: Arc::new(UpdateAllPackages::new(Arc::clone(&package_repository))),
+            fetch: Arc::new(FetchPackages::new(Arc::clone(&package_repository))),
             clean_cache: Arc::new(CleanCache::new(Arc::clone(&package_repository))),
+            cache_info: Arc::new(GetCacheInfo::new(Arc::clone(&package_repository))),
             cleanup_old_versions: Arc::new(CleanupOldVersions::new(Arc::clone(
                 &package_repository,
             ))),
+            autoremove: Arc::new(Autoremove::new(Arc::clone(&package_repository))),
             search: Arc::new(SearchPackages::new(Arc::clone(&package_repository))),
-            get_package_info: Arc::new(GetPackageInfo::new(Arc::clone(&package_repository))),
+            get_package_info,
+            audit: Arc::new(AuditPackage::new(Arc::clone(&package_repository))),
+            dependency_tree: Arc::new(GetDependencyTree::new(Arc::clone(&package_repository))),
             pin: Arc::new(PinPackage::new(Arc::clone(&package_repository))),
             unpin: Arc::new(UnpinPackage::new(Arc::clone(&package_repository))),
+            install_dates: Arc::new(GetInstallDates::new(Arc::clone(&package_repository))),
             list_services: Arc::new(ListServices::new(Arc::clone(&service_repository))),
+            service_info: Arc::new(GetServiceInfo::new(Arc::clone(&service_repository))),
             start_service: Arc::new(StartService::new(Arc::clone(&service_repository))),
             stop_service: Arc::new(StopService::new(Arc::clone(&service_repository))),
             restart_service: Arc::new(RestartService::new(Arc::clone(&service_repository))),
+            cleanup_services: Arc::new(CleanupServices::new(Arc::clone(&service_repository))),
             export_packages: Arc::new(ExportPackages::new(Arc::clone(&package_list_repository))),
             import_packages: Arc::new(ImportPackages::new(Arc::clone(&package_list_repository))),
+            check_for_update: Arc::new(CheckForUpdate::new(release_repository)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::mock_repositories::{
+        MockPackageListRepository, MockPackageRepository, MockReleaseRepository,
+        MockServiceRepository,
+    };
+    use crate::domain::entities::{Package, PackageType, ProgressCell, Service, ServiceStatus};
+
+    #[tokio::test]
+    async fn use_cases_go_through_the_given_repositories() {
+        let jq = Package::new("jq".to_string(), PackageType::Formula);
+        let packages = Arc::new(MockPackageRepository::new(vec![jq.clone()]));
+        let services = Arc::new(MockServiceRepository::new(vec![Service::new(
+            "redis".to_string(),
+            ServiceStatus::Stopped,
+        )]));
+        let container = UseCaseContainer::new(
+            packages.clone(),
+            services.clone(),
+            Arc::new(MockPackageListRepository::new(Vec::new())),
+            Arc::new(MockReleaseRepository::new("0.0.0")),
+        );
+
+        let installed = container
+            .list_installed
+            .execute(PackageType::Formula)
+            .await
+            .unwrap();
+        assert_eq!(installed.len(), 1);
+        container.pin.execute(jq.clone()).await.unwrap();
+        container
+            .update
+            .execute(&jq, &ProgressCell::default())
+            .await
+            .unwrap();
+        assert_eq!(packages.calls(), ["pin jq", "update jq"]);
+
+        assert_eq!(container.list_services.execute().await.unwrap().len(), 1);
+        let redis = container.service_info.execute("redis").await.unwrap();
+        assert_eq!(redis.service.status, ServiceStatus::Stopped);
+        container.start_service.execute("redis").await.unwrap();
+        assert!(container.stop_service.execute("postgresql").await.is_err());
+        assert_eq!(services.calls(), ["start redis"]);
+    }
+}
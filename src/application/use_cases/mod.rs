@@ -1,7 +1,9 @@
 pub mod package_list_operations;
 pub mod package_operations;
+pub mod release_operations;
 pub mod service_operations;
 
 pub use package_list_operations::*;
 pub use package_operations::*;
+pub use release_operations::*;
 pub use service_operations::*;
@@ -1,5 +1,5 @@
 use crate::domain::{
-    entities::PackageList,
+    entities::{PackageList, PackageType},
     repositories::PackageListRepository,
 };
 use anyhow::{Context, Result};
@@ -45,6 +45,57 @@ impl ExportPackages {
         
         Ok(package_list)
     }
+
+    /// What is installed now, as `execute` would write it, without writing
+    /// anything.
+    pub async fn current(&self) -> Result<PackageList> {
+        self.use_case.repository().export_package_list().await
+    }
+
+    /// Like `execute`, writing the list as Markdown for sharing instead.
+    pub async fn execute_markdown(&self, path: &Path) -> Result<PackageList> {
+        let package_list = self.use_case.repository().export_package_list().await?;
+        tokio::fs::write(path, package_list.to_markdown())
+            .await
+            .context("Failed to write package list to file")?;
+        Ok(package_list)
+    }
+
+    /// Writes `package_list` to `path` as CSV, for spreadsheets.
+    pub async fn write_csv(&self, package_list: &PackageList, path: &Path) -> Result<()> {
+        tokio::fs::write(path, to_csv(package_list))
+            .await
+            .context("Failed to write package list to file")
+    }
+}
+
+/// One row per package with columns name,type,version,pinned,outdated.
+fn to_csv(package_list: &PackageList) -> String {
+    let mut csv = String::from("name,type,version,pinned,outdated\n");
+    for item in package_list.formulae.iter().chain(&package_list.casks) {
+        let package_type = match item.package_type {
+            PackageType::Formula => "formula",
+            PackageType::Cask => "cask",
+        };
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(&item.name),
+            package_type,
+            csv_field(item.version.as_deref().unwrap_or("")),
+            item.pinned,
+            item.outdated
+        ));
+    }
+    csv
+}
+
+/// Quotes `value` if it contains anything CSV treats specially.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
 }
 
 pub struct ImportPackages {
@@ -58,19 +109,114 @@ impl ImportPackages {
         }
     }
 
-    pub async fn execute(&self, path: &Path) -> Result<()> {
+    /// Reads the list at `path` without installing anything yet.
+    pub async fn read(&self, path: &Path) -> Result<PackageList> {
         // Read the JSON file
         let json = tokio::fs::read_to_string(path)
             .await
             .context("Failed to read package list file")?;
         
         // Deserialize from JSON
-        let package_list: PackageList = serde_json::from_str(&json)
-            .context("Failed to parse package list JSON")?;
-        
+        serde_json::from_str(&json).context("Failed to parse package list JSON")
+    }
+
+    pub async fn execute(&self, package_list: &PackageList) -> Result<()> {
         // Import the packages
-        let _installed = self.use_case.repository().import_packages(&package_list).await?;
+        let _installed = self.use_case.repository().import_packages(package_list).await?;
         
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::mock_repositories::MockPackageListRepository;
+    use crate::domain::entities::Package;
+
+    #[tokio::test]
+    async fn exported_lists_import_unchanged() {
+        let path =
+            std::env::temp_dir().join(format!("brewsty-package-list-{}.json", std::process::id()));
+        let installed = vec![
+            Package::new("jq".to_string(), PackageType::Formula).with_version("1.7.1".to_string()),
+            Package::new("firefox".to_string(), PackageType::Cask),
+        ];
+        let source = Arc::new(MockPackageListRepository::new(installed));
+        let target = Arc::new(MockPackageListRepository::new(Vec::new()));
+
+        let exported = ExportPackages::new(source).execute(&path).await.unwrap();
+        let import = ImportPackages::new(target.clone());
+        import
+            .execute(&import.read(&path).await.unwrap())
+            .await
+            .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(exported.total_count(), 2);
+        let imported: Vec<_> = target
+            .imported()
+            .into_iter()
+            .map(|item| (item.name, item.package_type, item.version))
+            .collect();
+        assert_eq!(
+            imported,
+            [
+                (
+                    "jq".to_string(),
+                    PackageType::Formula,
+                    Some("1.7.1".to_string())
+                ),
+                ("firefox".to_string(), PackageType::Cask, None),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn markdown_groups_packages_by_type() {
+        let path =
+            std::env::temp_dir().join(format!("brewsty-package-list-{}.md", std::process::id()));
+        let installed = vec![
+            Package::new("jq".to_string(), PackageType::Formula).with_version("1.7.1".to_string()),
+            Package::new("wget".to_string(), PackageType::Formula),
+        ];
+        let repository = Arc::new(MockPackageListRepository::new(installed));
+
+        ExportPackages::new(repository)
+            .execute_markdown(&path)
+            .await
+            .unwrap();
+        let markdown = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            markdown,
+            "# Homebrew packages\n\n\
+             Exported 2024-01-01\n\n\
+             ## Formulae (2)\n\n\
+             - jq `1.7.1`\n\
+             - wget\n\n\
+             ## Casks (0)\n\n\
+             _None_\n"
+        );
+    }
+
+    #[test]
+    fn csv_lists_each_package_with_its_state() {
+        let mut pinned = Package::new("node@20".to_string(), PackageType::Formula)
+            .with_version("20.11.0".to_string());
+        pinned.pinned = true;
+        let outdated = Package::new("visual-studio-code".to_string(), PackageType::Cask)
+            .with_version("1.85.0,abc".to_string())
+            .set_outdated(true);
+
+        let csv = to_csv(&PackageList::from_packages(&[pinned, outdated]));
+
+        assert_eq!(
+            csv,
+            "name,type,version,pinned,outdated\n\
+             node@20,formula,20.11.0,true,false\n\
+             visual-studio-code,cask,\"1.85.0,abc\",false,true\n"
+        );
+    }
+}
@@ -1,9 +1,22 @@
+use crate::application::dto::CacheInfoDto;
 use crate::domain::{
-    entities::{CleanupPreview, Package, PackageType},
+    entities::{
+        AuditResult, CleanupPreview, DependencyNode, OperationProgress, Package, PackageType,
+        PlannedUpgrade, ProgressCell, UpgradeReport,
+    },
     repositories::PackageRepository,
 };
-use anyhow::Result;
-use std::sync::Arc;
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// Loaded package info is reused for this long before brew is asked again.
+const PACKAGE_INFO_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Loaded info by name and type, with when it was loaded.
+type PackageInfoCache = HashMap<(String, PackageType), (Instant, Package)>;
 
 pub struct RepositoryUseCase {
     repository: Arc<dyn PackageRepository>,
@@ -40,20 +53,41 @@ impl ListInstalledPackages {
 
 pub struct ListOutdatedPackages {
     use_case: RepositoryUseCase,
+    package_info: Arc<GetPackageInfo>,
 }
 
 impl ListOutdatedPackages {
-    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+    pub fn new(repository: Arc<dyn PackageRepository>, package_info: Arc<GetPackageInfo>) -> Self {
         Self {
             use_case: RepositoryUseCase::new(repository),
+            package_info,
         }
     }
 
+    /// Outdated casks say whether they update themselves, from their info.
     pub async fn execute(&self, package_type: PackageType) -> Result<Vec<Package>> {
-        self.use_case
+        let mut packages = self
+            .use_case
             .repository()
-            .get_outdated_packages(package_type)
-            .await
+            .get_outdated_packages(package_type.clone())
+            .await?;
+        if package_type == PackageType::Cask && !packages.is_empty() {
+            let names: Vec<String> = packages.iter().map(|p| p.name.clone()).collect();
+            match self.package_info.execute_many(&names, package_type).await {
+                Ok(info) => {
+                    let auto_updating: HashSet<&str> = info
+                        .iter()
+                        .filter(|p| p.auto_updates)
+                        .map(|p| p.name.as_str())
+                        .collect();
+                    for package in &mut packages {
+                        package.auto_updates = auto_updating.contains(package.name.as_str());
+                    }
+                }
+                Err(e) => tracing::warn!("Couldn't tell which casks update themselves: {}", e),
+            }
+        }
+        Ok(packages)
     }
 }
 
@@ -68,8 +102,17 @@ impl InstallPackage {
         }
     }
 
-    pub async fn execute(&self, package: Package) -> Result<()> {
-        self.use_case.repository().install_package(&package).await
+    pub async fn execute(&self, package: Package, progress: &ProgressCell) -> Result<()> {
+        self.use_case
+            .repository()
+            .install_package(&package, progress)
+            .await
+    }
+
+    /// Whether Homebrew lists `package` as installed, to confirm an install
+    /// that was reported as successful.
+    pub async fn verify(&self, package: &Package) -> Result<bool> {
+        self.use_case.repository().is_installed(package).await
     }
 }
 
@@ -87,6 +130,11 @@ impl UninstallPackage {
     pub async fn execute(&self, package: Package) -> Result<()> {
         self.use_case.repository().uninstall_package(&package).await
     }
+
+    /// Uninstalls a cask together with its leftover files.
+    pub async fn zap(&self, package: Package) -> Result<()> {
+        self.use_case.repository().zap_cask(&package).await
+    }
 }
 
 pub struct UpdatePackage {
@@ -100,8 +148,35 @@ impl UpdatePackage {
         }
     }
 
-    pub async fn execute(&self, package: &Package) -> Result<()> {
-        self.use_case.repository().update_package(&package).await
+    pub async fn execute(&self, package: &Package, progress: &ProgressCell) -> Result<()> {
+        self.use_case
+            .repository()
+            .update_package(package, progress)
+            .await
+    }
+}
+
+pub struct FetchPackages {
+    use_case: RepositoryUseCase,
+}
+
+impl FetchPackages {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    /// The names of the packages that were downloaded.
+    pub async fn execute(
+        &self,
+        packages: &[Package],
+        progress: &ProgressCell,
+    ) -> Result<Vec<String>> {
+        self.use_case
+            .repository()
+            .fetch_packages(packages, progress)
+            .await
     }
 }
 
@@ -116,8 +191,50 @@ impl UpdateAllPackages {
         }
     }
 
-    pub async fn execute(&self) -> Result<()> {
-        self.use_case.repository().update_all().await
+    pub async fn preview(&self) -> Result<Vec<PlannedUpgrade>> {
+        self.use_case.repository().get_upgrade_preview().await
+    }
+
+    /// Runs `brew update` so the preview and upgrade that follow see the
+    /// newest versions. Nothing has been upgraded yet when this fails.
+    pub async fn refresh_metadata(&self, progress: &ProgressCell) -> Result<()> {
+        tracing::info!("Refreshing metadata…");
+        progress.set(OperationProgress::RefreshingMetadata);
+        self.use_case
+            .repository()
+            .update_metadata(progress)
+            .await
+            .context("Couldn't refresh Homebrew metadata, so no packages were upgraded")
+    }
+
+    pub async fn execute(&self, progress: &ProgressCell) -> Result<UpgradeReport> {
+        tracing::info!("Upgrading…");
+        progress.set(OperationProgress::Installing);
+        self.use_case.repository().update_all(progress).await
+    }
+
+    /// Upgrades only `packages`, one at a time, for when some of what
+    /// `execute` would upgrade should be left alone. Keeps going past
+    /// failures and reports them together.
+    pub async fn execute_only(
+        &self,
+        packages: &[Package],
+        progress: &ProgressCell,
+    ) -> Result<UpgradeReport> {
+        tracing::info!("Upgrading…");
+        progress.set(OperationProgress::Installing);
+        let repository = self.use_case.repository();
+        let mut report = UpgradeReport::default();
+        for package in packages {
+            match repository.update_package(package, progress).await {
+                Ok(()) => report.upgraded.push(package.name.clone()),
+                Err(e) => {
+                    tracing::error!("Failed to update {}: {}", package.name, e);
+                    report.failed.push((package.name.clone(), e.to_string()));
+                }
+            }
+        }
+        Ok(report)
     }
 }
 
@@ -141,6 +258,22 @@ impl CleanCache {
     }
 }
 
+pub struct GetCacheInfo {
+    use_case: RepositoryUseCase,
+}
+
+impl GetCacheInfo {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn execute(&self, cache: &Path) -> Result<CacheInfoDto> {
+        Ok(self.use_case.repository().get_cache_info(cache).await?.into())
+    }
+}
+
 pub struct CleanupOldVersions {
     use_case: RepositoryUseCase,
 }
@@ -164,6 +297,29 @@ impl CleanupOldVersions {
     }
 }
 
+pub struct Autoremove {
+    use_case: RepositoryUseCase,
+}
+
+impl Autoremove {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn preview(&self, cellar: &Path) -> Result<CleanupPreview> {
+        self.use_case
+            .repository()
+            .get_autoremove_preview(cellar)
+            .await
+    }
+
+    pub async fn execute(&self) -> Result<()> {
+        self.use_case.repository().autoremove().await
+    }
+}
+
 pub struct SearchPackages {
     use_case: RepositoryUseCase,
 }
@@ -183,22 +339,148 @@ impl SearchPackages {
     }
 }
 
+/// Orders search results by how well their names match `query`: exact
+/// matches, then those starting with it, then those containing it, then the
+/// rest (brew also matches descriptions). Names are alphabetical within each
+/// group, formulae before casks of the same name. Tap prefixes such as
+/// `homebrew/core/` don't count towards the match.
+pub fn rank_search_results(query: &str, packages: &mut [Package]) {
+    let query = query.trim().to_lowercase();
+    let rank = |package: &Package| {
+        let name = package.name.to_lowercase();
+        let short = name.rsplit('/').next().unwrap_or(&name).to_string();
+        let group = if short == query {
+            0
+        } else if short.starts_with(&query) {
+            1
+        } else if short.contains(&query) {
+            2
+        } else {
+            3
+        };
+        let type_order = match package.package_type {
+            PackageType::Formula => 0,
+            PackageType::Cask => 1,
+        };
+        (group, short, name, type_order)
+    };
+    packages.sort_by_cached_key(rank);
+}
+
+/// Loads package info, keeping what was loaded for `PACKAGE_INFO_TTL` so
+/// switching tabs or searching again doesn't run `brew info` each time.
 pub struct GetPackageInfo {
     use_case: RepositoryUseCase,
+    cache: Mutex<PackageInfoCache>,
 }
 
 impl GetPackageInfo {
     pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
         Self {
             use_case: RepositoryUseCase::new(repository),
+            cache: Mutex::new(HashMap::new()),
         }
     }
 
     pub async fn execute(&self, name: &str, package_type: PackageType) -> Result<Package> {
-        self.use_case
+        let key = (name.to_string(), package_type.clone());
+        if let Some((loaded_at, package)) = self.cache().get(&key)
+            && loaded_at.elapsed() < PACKAGE_INFO_TTL
+        {
+            return Ok(package.clone());
+        }
+
+        let package = self
+            .use_case
             .repository()
             .get_package_info(name, package_type)
-            .await
+            .await?;
+        self.cache().insert(key, (Instant::now(), package.clone()));
+        Ok(package)
+    }
+
+    /// Info for several packages of one type, loading those not loaded
+    /// yet in one run. Packages brew doesn't know are left out.
+    pub async fn execute_many(
+        &self,
+        names: &[String],
+        package_type: PackageType,
+    ) -> Result<Vec<Package>> {
+        let mut packages = Vec::new();
+        let mut missing = Vec::new();
+        {
+            let cache = self.cache();
+            for name in names {
+                match cache.get(&(name.clone(), package_type.clone())) {
+                    Some((loaded_at, package)) if loaded_at.elapsed() < PACKAGE_INFO_TTL => {
+                        packages.push(package.clone());
+                    }
+                    _ => missing.push(name.clone()),
+                }
+            }
+        }
+
+        if !missing.is_empty() {
+            let loaded = self
+                .use_case
+                .repository()
+                .get_packages_info(&missing, package_type.clone())
+                .await?;
+            let mut cache = self.cache();
+            for package in loaded {
+                let key = (package.name.clone(), package_type.clone());
+                cache.insert(key, (Instant::now(), package.clone()));
+                packages.push(package);
+            }
+        }
+        Ok(packages)
+    }
+
+    /// Forgets the info loaded for `name`, after it was installed, updated
+    /// or uninstalled.
+    pub fn invalidate(&self, name: &str) {
+        self.cache().retain(|(cached, _), _| cached != name);
+    }
+
+    /// Forgets all loaded info, e.g. after Update All.
+    pub fn clear(&self) {
+        self.cache().clear();
+    }
+
+    fn cache(&self) -> MutexGuard<'_, PackageInfoCache> {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+pub struct AuditPackage {
+    use_case: RepositoryUseCase,
+}
+
+impl AuditPackage {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn execute(&self, package: &Package) -> Result<AuditResult> {
+        self.use_case.repository().audit_package(package).await
+    }
+}
+
+pub struct GetDependencyTree {
+    use_case: RepositoryUseCase,
+}
+
+impl GetDependencyTree {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn execute(&self, package: &Package) -> Result<DependencyNode> {
+        self.use_case.repository().dependency_tree(package).await
     }
 }
 
@@ -233,3 +515,182 @@ impl UnpinPackage {
         self.use_case.repository().unpin_package(&package).await
     }
 }
+
+pub struct GetInstallDates {
+    use_case: RepositoryUseCase,
+}
+
+impl GetInstallDates {
+    pub fn new(repository: Arc<dyn PackageRepository>) -> Self {
+        Self {
+            use_case: RepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn execute(&self, packages: &[Package]) -> Result<HashMap<String, SystemTime>> {
+        self.use_case.repository().get_install_dates(packages).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::mock_repositories::MockPackageRepository;
+
+    fn package(name: &str, package_type: PackageType) -> Package {
+        Package::new(name.to_string(), package_type)
+    }
+
+    fn names(packages: &[Package]) -> Vec<&str> {
+        packages.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn list_installed_returns_packages_of_the_requested_type() {
+        let repository = Arc::new(MockPackageRepository::new(vec![
+            package("jq", PackageType::Formula).with_version("1.7.1".to_string()),
+            package("firefox", PackageType::Cask),
+        ]));
+        let use_case = ListInstalledPackages::new(repository);
+
+        let formulae = use_case.execute(PackageType::Formula).await.unwrap();
+        assert_eq!(names(&formulae), ["jq"]);
+        assert_eq!(formulae[0].version.as_deref(), Some("1.7.1"));
+
+        let casks = use_case.execute(PackageType::Cask).await.unwrap();
+        assert_eq!(names(&casks), ["firefox"]);
+    }
+
+    #[tokio::test]
+    async fn outdated_casks_take_auto_updates_from_cached_info() {
+        let repository = Arc::new(MockPackageRepository::new(vec![
+            package("chrome", PackageType::Cask)
+                .set_outdated(true)
+                .set_auto_updates(true),
+            package("firefox", PackageType::Cask).set_outdated(true),
+        ]));
+        let package_info = Arc::new(GetPackageInfo::new(repository.clone()));
+        let use_case = ListOutdatedPackages::new(repository.clone(), package_info);
+
+        for _ in 0..2 {
+            let casks = use_case.execute(PackageType::Cask).await.unwrap();
+            let auto_updating: Vec<bool> = casks.iter().map(|p| p.auto_updates).collect();
+            assert_eq!(auto_updating, [true, false]);
+        }
+        assert_eq!(repository.calls(), ["info chrome firefox"]);
+    }
+
+    #[tokio::test]
+    async fn package_info_is_loaded_once_until_invalidated() {
+        let repository = Arc::new(MockPackageRepository::new(vec![package(
+            "jq",
+            PackageType::Formula,
+        )]));
+        let use_case = GetPackageInfo::new(repository.clone());
+
+        use_case.execute("jq", PackageType::Formula).await.unwrap();
+        use_case.execute("jq", PackageType::Formula).await.unwrap();
+        assert_eq!(repository.calls(), ["info jq"]);
+
+        use_case.invalidate("jq");
+        use_case.execute("jq", PackageType::Formula).await.unwrap();
+        assert!(use_case.execute("missing", PackageType::Formula).await.is_err());
+        assert!(use_case.execute("missing", PackageType::Formula).await.is_err());
+        assert_eq!(
+            repository.calls(),
+            ["info jq", "info jq", "info missing", "info missing"]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_all_can_skip_packages() {
+        let wget = package("wget", PackageType::Formula)
+            .with_version("1.24.5".to_string())
+            .with_available_version("1.25.0".to_string())
+            .set_outdated(true);
+        let repository = Arc::new(MockPackageRepository::new(vec![
+            wget.clone(),
+            package("jq", PackageType::Formula),
+        ]));
+        let use_case = UpdateAllPackages::new(repository.clone());
+
+        let preview = use_case.preview().await.unwrap();
+        assert_eq!(
+            preview,
+            [PlannedUpgrade {
+                name: "wget".to_string(),
+                current_version: Some("1.24.5".to_string()),
+                new_version: "1.25.0".to_string(),
+            }]
+        );
+
+        let progress = ProgressCell::default();
+        use_case.refresh_metadata(&progress).await.unwrap();
+        let report = use_case.execute_only(&[wget], &progress).await.unwrap();
+        assert_eq!(report.upgraded, ["wget"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(repository.calls(), ["update_metadata", "update wget"]);
+    }
+
+    #[tokio::test]
+    async fn search_matches_names_of_the_requested_type() {
+        let repository = Arc::new(MockPackageRepository::new(Vec::new()).with_available(vec![
+            package("wget", PackageType::Formula),
+            package("wget2", PackageType::Formula),
+            package("curl", PackageType::Formula),
+            package("wireshark", PackageType::Cask),
+        ]));
+        let use_case = SearchPackages::new(repository);
+
+        let formulae = use_case
+            .execute("wget", PackageType::Formula)
+            .await
+            .unwrap();
+        assert_eq!(names(&formulae), ["wget", "wget2"]);
+        assert!(
+            use_case
+                .execute("wget", PackageType::Cask)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn search_results_rank_exact_then_prefix_then_substring_matches() {
+        let mut results = vec![
+            package("bfg", PackageType::Formula),
+            package("git-lfs", PackageType::Formula),
+            package("legit", PackageType::Formula),
+            package("git", PackageType::Formula),
+            package("github", PackageType::Cask),
+            package("digital", PackageType::Cask),
+            package("gitup", PackageType::Cask),
+            package("homebrew/core/git-flow", PackageType::Formula),
+            package("git", PackageType::Cask),
+            package("Git-Cola", PackageType::Formula),
+        ];
+
+        rank_search_results("Git", &mut results);
+
+        let ranked: Vec<(&str, PackageType)> = results
+            .iter()
+            .map(|p| (p.name.as_str(), p.package_type.clone()))
+            .collect();
+        assert_eq!(
+            ranked,
+            [
+                ("git", PackageType::Formula),
+                ("git", PackageType::Cask),
+                ("Git-Cola", PackageType::Formula),
+                ("homebrew/core/git-flow", PackageType::Formula),
+                ("git-lfs", PackageType::Formula),
+                ("github", PackageType::Cask),
+                ("gitup", PackageType::Cask),
+                ("digital", PackageType::Cask),
+                ("legit", PackageType::Formula),
+                ("bfg", PackageType::Formula),
+            ]
+        );
+    }
+}
@@ -0,0 +1,44 @@
+use crate::domain::{entities::Release, repositories::ReleaseRepository};
+use anyhow::Result;
+use std::sync::Arc;
+
+pub struct CheckForUpdate {
+    repository: Arc<dyn ReleaseRepository>,
+}
+
+impl CheckForUpdate {
+    pub fn new(repository: Arc<dyn ReleaseRepository>) -> Self {
+        Self { repository }
+    }
+
+    /// The latest release if it is newer than `current_version`.
+    pub async fn execute(&self, current_version: &str) -> Result<Option<Release>> {
+        let latest = self.repository.latest_release().await?;
+        Ok(latest.is_newer_than(current_version).then_some(latest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::mock_repositories::MockReleaseRepository;
+
+    #[tokio::test]
+    async fn only_newer_releases_are_offered() {
+        let cases = [
+            ("0.7.0", "0.6.0", true),
+            ("0.10.0", "0.9.3", true),
+            ("1.0", "0.9.9", true),
+            ("0.6.0", "0.6.0", false),
+            ("0.6", "0.6.0", false),
+            ("0.5.9", "0.6.0", false),
+            ("0.6.0-beta.1", "0.6.0", false),
+        ];
+
+        for (latest, current, offered) in cases {
+            let use_case = CheckForUpdate::new(Arc::new(MockReleaseRepository::new(latest)));
+            let update = use_case.execute(current).await.unwrap();
+            assert_eq!(update.is_some(), offered, "{} over {}", latest, current);
+        }
+    }
+}
@@ -1,5 +1,5 @@
 use crate::domain::{
-    entities::Service,
+    entities::{Service, ServiceInfo},
     repositories::ServiceRepository,
 };
 use anyhow::Result;
@@ -33,6 +33,23 @@ impl ListServices {
     pub async fn execute(&self) -> Result<Vec<Service>> {
         self.use_case.repository().list_services().await
     }
+
+}
+
+pub struct GetServiceInfo {
+    use_case: ServiceRepositoryUseCase,
+}
+
+impl GetServiceInfo {
+    pub fn new(repository: Arc<dyn ServiceRepository>) -> Self {
+        Self {
+            use_case: ServiceRepositoryUseCase::new(repository),
+        }
+    }
+
+    pub async fn execute(&self, service_name: &str) -> Result<ServiceInfo> {
+        self.use_case.repository().get_service_info(service_name).await
+    }
 }
 
 pub struct StartService {
@@ -65,6 +82,11 @@ impl StopService {
     pub async fn execute(&self, service_name: &str) -> Result<()> {
         self.use_case.repository().stop_service(service_name).await
     }
+
+    /// Stops every running service in one brew call.
+    pub async fn execute_all(&self) -> Result<()> {
+        self.use_case.repository().stop_all_services().await
+    }
 }
 
 pub struct RestartService {
@@ -82,3 +104,20 @@ impl RestartService {
         self.use_case.repository().restart_service(service_name).await
     }
 }
+
+pub struct CleanupServices {
+    use_case: ServiceRepositoryUseCase,
+}
+
+impl CleanupServices {
+    pub fn new(repository: Arc<dyn ServiceRepository>) -> Self {
+        Self {
+            use_case: ServiceRepositoryUseCase::new(repository),
+        }
+    }
+
+    /// Returns what brew printed, naming the files it removed.
+    pub async fn execute(&self) -> Result<String> {
+        self.use_case.repository().cleanup_services().await
+    }
+}
@@ -1,22 +1,275 @@
+use super::PackageType;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// The default accent color, macOS system blue.
+pub const DEFAULT_ACCENT_COLOR: [u8; 3] = [0, 122, 255];
+
+/// Allowed values for `AppConfig::ui_scale`.
+pub const UI_SCALE_RANGE: RangeInclusive<f32> = 0.8..=1.5;
+
+/// Allowed values for `AppConfig::search_result_limit`.
+pub const SEARCH_RESULT_LIMIT_RANGE: RangeInclusive<usize> = 10..=5000;
+
+/// Allowed values for `AppConfig::metadata_stale_after_hours`.
+pub const METADATA_STALE_AFTER_HOURS_RANGE: RangeInclusive<u64> = 1..=720;
+
+/// Allowed values for `AutoExport::keep`.
+pub const AUTO_EXPORT_KEEP_RANGE: RangeInclusive<usize> = 1..=365;
+
+/// Allowed values for `AppConfig::services_refresh_secs`.
+pub const SERVICES_REFRESH_SECS_RANGE: RangeInclusive<u64> = 0..=3600;
+
+/// Allowed values for `AppConfig::read_timeout_secs`.
+pub const READ_TIMEOUT_SECS_RANGE: RangeInclusive<u64> = 5..=3600;
+
+/// Allowed values for `AppConfig::command_timeout_secs`.
+pub const COMMAND_TIMEOUT_SECS_RANGE: RangeInclusive<u64> = 30..=86400;
+
+/// Version of the on-disk config shape. Bump this whenever a field is renamed
+/// or restructured and add a matching migration step in the config repository.
+pub const CURRENT_CONFIG_VERSION: u32 = 1;
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct AppConfig {
+    pub version: u32,
     pub theme: ThemeMode,
+    /// RGB color for selections and links.
+    pub accent_color: [u8; 3],
+    /// Multiplier for text sizes and spacing, within `UI_SCALE_RANGE`.
+    pub ui_scale: f32,
+    /// Denser package grids with status dots instead of status text.
+    pub compact_mode: bool,
+    /// Show when each installed package was installed, in its own column.
+    pub show_install_date: bool,
+    /// Top-bar tabs the user hid, by key. Installed and Settings are always shown.
+    pub hidden_tabs: Vec<String>,
+    /// Widths the user resized table columns to, by table id.
+    pub column_widths: HashMap<String, Vec<f32>>,
+    pub output_panel_height: f32,
+    /// Output panel reduced to its toolbar row.
+    pub output_panel_collapsed: bool,
+    /// Installed tab sections folded away.
+    pub outdated_collapsed: bool,
+    pub installed_collapsed: bool,
     pub auto_update_check: bool,
+    /// Ask GitHub for a newer Brewsty release at startup; off unless chosen.
+    pub check_for_app_updates: bool,
     pub confirm_before_actions: bool,
+    pub homebrew_no_auto_update: bool,
+    /// Run `brew update` before Update All, so it upgrades against fresh metadata.
+    pub update_before_upgrade: bool,
+    /// How often the Services tab reloads while open, within
+    /// `SERVICES_REFRESH_SECS_RANGE`; 0 turns it off.
+    pub services_refresh_secs: u64,
+    /// Search results listed before the user asks for all of them, within
+    /// `SEARCH_RESULT_LIMIT_RANGE`.
+    pub search_result_limit: usize,
+    /// Metadata older than this is shown as stale in the Installed tab,
+    /// within `METADATA_STALE_AFTER_HOURS_RANGE`.
+    pub metadata_stale_after_hours: u64,
+    /// Extra variables for brew commands, such as `HOMEBREW_CASK_OPTS`, in order.
+    pub brew_env: Vec<(String, String)>,
+    /// Limit for brew queries such as list, search and info, within
+    /// `READ_TIMEOUT_SECS_RANGE`.
+    pub read_timeout_secs: u64,
+    /// Limit for brew operations that change the system (install, upgrade, cleanup, ...),
+    /// within `COMMAND_TIMEOUT_SECS_RANGE`.
+    pub command_timeout_secs: u64,
+    pub window_size: Option<[f32; 2]>,
+    pub window_position: Option<[f32; 2]>,
+    pub favorites: Vec<FavoritePackage>,
+    /// Unix time each currently outdated package was first seen outdated, by name.
+    pub outdated_since: HashMap<String, u64>,
+    /// Packages whose updates Brewsty doesn't show, by name, with the unix
+    /// time the snooze ends; `None` ignores them until the user un-ignores.
+    pub ignored_updates: HashMap<String, Option<u64>>,
+    /// Casks that update themselves which brew should update anyway, by name.
+    pub brew_updated_casks: Vec<String>,
+    /// Overrides the build's default tracing verbosity when set.
+    pub log_verbosity: Option<LogVerbosity>,
+    /// Also write logs to rotating files in the `logs` folder next to the config.
+    pub file_logging_enabled: bool,
+    /// File name of the newest crash report the user has already been shown.
+    pub seen_crash_report: Option<String>,
+    /// Package list exports written on a schedule; off unless chosen.
+    pub auto_export: AutoExport,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CURRENT_CONFIG_VERSION,
             theme: ThemeMode::System,
+            accent_color: DEFAULT_ACCENT_COLOR,
+            ui_scale: 1.0,
+            compact_mode: false,
+            show_install_date: true,
+            hidden_tabs: Vec::new(),
+            column_widths: HashMap::new(),
+            output_panel_height: 250.0,
+            output_panel_collapsed: false,
+            outdated_collapsed: false,
+            installed_collapsed: false,
             auto_update_check: true,
+            check_for_app_updates: false,
             confirm_before_actions: true,
+            homebrew_no_auto_update: false,
+            update_before_upgrade: true,
+            services_refresh_secs: 60,
+            search_result_limit: 100,
+            metadata_stale_after_hours: 24,
+            brew_env: Vec::new(),
+            read_timeout_secs: 120,
+            command_timeout_secs: 1800,
+            window_size: None,
+            window_position: None,
+            favorites: Vec::new(),
+            outdated_since: HashMap::new(),
+            ignored_updates: HashMap::new(),
+            brew_updated_casks: Vec::new(),
+            log_verbosity: None,
+            file_logging_enabled: false,
+            seen_crash_report: None,
+            auto_export: AutoExport::default(),
         }
     }
 }
 
+impl AppConfig {
+    /// Brings values edited by hand back within what Settings allows.
+    pub fn clamp_to_ranges(&mut self) {
+        self.search_result_limit = self.search_result_limit.clamp(
+            *SEARCH_RESULT_LIMIT_RANGE.start(),
+            *SEARCH_RESULT_LIMIT_RANGE.end(),
+        );
+        self.metadata_stale_after_hours = self.metadata_stale_after_hours.clamp(
+            *METADATA_STALE_AFTER_HOURS_RANGE.start(),
+            *METADATA_STALE_AFTER_HOURS_RANGE.end(),
+        );
+        self.auto_export.keep = self.auto_export.keep.clamp(
+            *AUTO_EXPORT_KEEP_RANGE.start(),
+            *AUTO_EXPORT_KEEP_RANGE.end(),
+        );
+        self.services_refresh_secs = self.services_refresh_secs.clamp(
+            *SERVICES_REFRESH_SECS_RANGE.start(),
+            *SERVICES_REFRESH_SECS_RANGE.end(),
+        );
+        self.read_timeout_secs = self.read_timeout_secs.clamp(
+            *READ_TIMEOUT_SECS_RANGE.start(),
+            *READ_TIMEOUT_SECS_RANGE.end(),
+        );
+        self.command_timeout_secs = self.command_timeout_secs.clamp(
+            *COMMAND_TIMEOUT_SECS_RANGE.start(),
+            *COMMAND_TIMEOUT_SECS_RANGE.end(),
+        );
+    }
+
+    pub fn is_favorite(&self, name: &str) -> bool {
+        self.favorites.iter().any(|f| f.name == name)
+    }
+
+    pub fn is_tab_hidden(&self, key: &str) -> bool {
+        self.hidden_tabs.iter().any(|k| k == key)
+    }
+
+    pub fn set_tab_hidden(&mut self, key: &str, hidden: bool) {
+        self.hidden_tabs.retain(|k| k != key);
+        if hidden {
+            self.hidden_tabs.push(key.to_string());
+        }
+    }
+
+    /// Stars the package if it isn't yet, otherwise removes the star.
+    pub fn toggle_favorite(&mut self, name: &str, package_type: PackageType) {
+        if self.is_favorite(name) {
+            self.favorites.retain(|f| f.name != name);
+        } else {
+            self.favorites.push(FavoritePackage {
+                name: name.to_string(),
+                package_type,
+            });
+        }
+    }
+}
+
+/// A starred package. The type is kept so it can still be installed on a
+/// machine where it isn't present yet.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct FavoritePackage {
+    pub name: String,
+    pub package_type: PackageType,
+}
+
+/// Exports the package list on startup and then every `interval`, to a
+/// dated file in `dir`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct AutoExport {
+    pub enabled: bool,
+    /// Nothing is exported until a folder is chosen.
+    pub dir: Option<PathBuf>,
+    pub interval: ExportInterval,
+    /// Older exports in `dir` are deleted beyond this many, within
+    /// `AUTO_EXPORT_KEEP_RANGE`.
+    pub keep: usize,
+}
+
+impl Default for AutoExport {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            dir: None,
+            interval: ExportInterval::Weekly,
+            keep: 10,
+        }
+    }
+}
+
+impl AutoExport {
+    /// The folder to export to, if exporting is on.
+    pub fn active_dir(&self) -> Option<&PathBuf> {
+        self.dir.as_ref().filter(|_| self.enabled)
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ExportInterval {
+    Daily,
+    Weekly,
+}
+
+impl ExportInterval {
+    pub fn duration(self) -> Duration {
+        match self {
+            Self::Daily => Duration::from_secs(24 * 60 * 60),
+            Self::Weekly => Duration::from_secs(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+impl fmt::Display for ExportInterval {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Daily => write!(f, "Daily"),
+            Self::Weekly => write!(f, "Weekly"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LogVerbosity {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ThemeMode {
     System,
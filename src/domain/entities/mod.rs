@@ -1,9 +1,23 @@
 pub mod config;
 pub mod package;
 pub mod package_list;
+pub mod package_metadata;
+pub mod progress;
+pub mod release;
 pub mod service;
 
-pub use config::{AppConfig, ThemeMode};
-pub use package::{CleanupItem, CleanupPreview, Package, PackageType};
-pub use package_list::{PackageList, PackageListItem};
-pub use service::{Service, ServiceStatus};
+pub use config::{
+    AUTO_EXPORT_KEEP_RANGE, AppConfig, AutoExport, COMMAND_TIMEOUT_SECS_RANGE,
+    CURRENT_CONFIG_VERSION, DEFAULT_ACCENT_COLOR, ExportInterval, FavoritePackage, LogVerbosity,
+    METADATA_STALE_AFTER_HOURS_RANGE, READ_TIMEOUT_SECS_RANGE, SEARCH_RESULT_LIMIT_RANGE,
+    SERVICES_REFRESH_SECS_RANGE, ThemeMode, UI_SCALE_RANGE,
+};
+pub use package::{
+    AuditResult, CacheInfo, CleanupItem, CleanupPreview, CpuArch, DependencyNode, Package,
+    PackageType, PlannedUpgrade, UpgradeReport,
+};
+pub use package_list::{PackageList, PackageListDiff, PackageListItem};
+pub use package_metadata::PackageMetadata;
+pub use progress::{OperationProgress, ProgressCell};
+pub use release::Release;
+pub use service::{Service, ServiceInfo, ServiceStatus};
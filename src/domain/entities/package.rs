@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum PackageType {
@@ -16,6 +17,33 @@ impl fmt::Display for PackageType {
     }
 }
 
+/// A CPU architecture a package can be built for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuArch {
+    Intel,
+    Arm,
+}
+
+impl CpuArch {
+    /// The architecture Brewsty is running on, if it is one of these.
+    pub fn host() -> Option<Self> {
+        match std::env::consts::ARCH {
+            "x86_64" => Some(CpuArch::Intel),
+            "aarch64" => Some(CpuArch::Arm),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for CpuArch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CpuArch::Intel => write!(f, "Intel"),
+            CpuArch::Arm => write!(f, "Apple Silicon"),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Package {
     pub name: String,
@@ -27,6 +55,16 @@ pub struct Package {
     pub outdated: bool,
     pub version_load_failed: bool,
     pub pinned: bool,
+    /// When the installed version was installed, if Homebrew's records say.
+    pub installed_at: Option<SystemTime>,
+    /// Result of the last `brew audit` this session.
+    pub audit: Option<AuditResult>,
+    /// Architectures a cask's `depends_on.arch` limits it to; empty when it
+    /// runs on any. `None` until its info has been loaded.
+    pub supported_archs: Option<Vec<CpuArch>>,
+    /// A cask whose app updates itself (`auto_updates true`), so brew leaves
+    /// its updates to the app unless asked by name.
+    pub auto_updates: bool,
 }
 
 impl Package {
@@ -41,6 +79,10 @@ impl Package {
             outdated: false,
             version_load_failed: false,
             pinned: false,
+            installed_at: None,
+            audit: None,
+            supported_archs: None,
+            auto_updates: false,
         }
     }
 
@@ -78,6 +120,24 @@ impl Package {
         self.pinned = pinned;
         self
     }
+
+    pub fn with_supported_archs(mut self, archs: Vec<CpuArch>) -> Self {
+        self.supported_archs = Some(archs);
+        self
+    }
+
+    pub fn set_auto_updates(mut self, auto_updates: bool) -> Self {
+        self.auto_updates = auto_updates;
+        self
+    }
+
+    /// False when the package is known to be built only for other
+    /// architectures than `arch`.
+    pub fn runs_natively_on(&self, arch: CpuArch) -> bool {
+        self.supported_archs
+            .as_ref()
+            .is_none_or(|archs| archs.is_empty() || archs.contains(&arch))
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -91,3 +151,53 @@ pub struct CleanupPreview {
     pub items: Vec<CleanupItem>,
     pub total_size: u64,
 }
+
+/// A package `brew upgrade` would upgrade, as listed by its dry run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedUpgrade {
+    pub name: String,
+    /// `None` when brew didn't say which version is installed.
+    pub current_version: Option<String>,
+    pub new_version: String,
+}
+
+/// Which packages an upgrade got through and which it didn't, as far as
+/// brew's output tells.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpgradeReport {
+    pub upgraded: Vec<String>,
+    /// Each package that failed, with brew's error for it.
+    pub failed: Vec<(String, String)>,
+}
+
+/// What `brew audit --strict` found wrong with a formula.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditResult {
+    pub passed: bool,
+    /// One entry per problem, as brew words it.
+    pub findings: Vec<String>,
+}
+
+/// A package in `brew deps --tree` output, with what it depends on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DependencyNode {
+    pub name: String,
+    pub children: Vec<DependencyNode>,
+}
+
+impl DependencyNode {
+    pub fn new(name: String) -> Self {
+        Self {
+            name,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// How much Homebrew's download cache holds.
+#[derive(Debug, Clone)]
+pub struct CacheInfo {
+    pub total_size: u64,
+    /// Downloaded bottles and casks.
+    pub package_count: usize,
+}
@@ -1,11 +1,17 @@
-use super::PackageType;
+use super::{Package, PackageType};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PackageListItem {
     pub name: String,
     pub package_type: PackageType,
     pub version: Option<String>,
+    /// Only known for lists built from loaded packages; brew's export
+    /// leaves these false, and they are left out of the JSON then.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pinned: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub outdated: bool,
 }
 
 impl PackageListItem {
@@ -14,6 +20,8 @@ impl PackageListItem {
             name,
             package_type,
             version: None,
+            pinned: false,
+            outdated: false,
         }
     }
 
@@ -39,6 +47,22 @@ impl PackageList {
         }
     }
 
+    /// The list of `packages`, keeping whether each is pinned or outdated.
+    pub fn from_packages(packages: &[Package]) -> Self {
+        let mut list = Self::new();
+        for package in packages {
+            let mut item = PackageListItem::new(package.name.clone(), package.package_type.clone());
+            item.version = package.version.clone();
+            item.pinned = package.pinned;
+            item.outdated = package.outdated;
+            match package.package_type {
+                PackageType::Formula => list.add_formula(item),
+                PackageType::Cask => list.add_cask(item),
+            }
+        }
+        list
+    }
+
     pub fn with_export_date(mut self, date: String) -> Self {
         self.export_date = Some(date);
         self
@@ -55,6 +79,130 @@ impl PackageList {
     pub fn total_count(&self) -> usize {
         self.formulae.len() + self.casks.len()
     }
+
+    /// A readable list with formulae and casks under their own headings, for
+    /// pasting into a README or gist.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Homebrew packages\n\n");
+        if let Some(date) = &self.export_date {
+            let date = chrono::DateTime::parse_from_rfc3339(date)
+                .map(|d| d.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|_| date.clone());
+            markdown.push_str(&format!("Exported {}\n\n", date));
+        }
+        for (heading, items) in [("Formulae", &self.formulae), ("Casks", &self.casks)] {
+            markdown.push_str(&format!("## {} ({})\n\n", heading, items.len()));
+            if items.is_empty() {
+                markdown.push_str("_None_\n");
+            }
+            for item in items {
+                match &item.version {
+                    Some(version) => markdown.push_str(&format!("- {} `{}`\n", item.name, version)),
+                    None => markdown.push_str(&format!("- {}\n", item.name)),
+                }
+            }
+            markdown.push('\n');
+        }
+        markdown.pop();
+        markdown
+    }
+
+    /// What changed going from this list to `newer`. Packages are matched by
+    /// name and type; a version counts as changed only when both lists know it.
+    pub fn diff(&self, newer: &PackageList) -> PackageListDiff {
+        let old: Vec<&PackageListItem> = self.formulae.iter().chain(&self.casks).collect();
+        let new: Vec<&PackageListItem> = newer.formulae.iter().chain(&newer.casks).collect();
+        fn find<'a>(
+            items: &[&'a PackageListItem],
+            item: &PackageListItem,
+        ) -> Option<&'a PackageListItem> {
+            items
+                .iter()
+                .find(|i| i.name == item.name && i.package_type == item.package_type)
+                .copied()
+        }
+
+        let mut diff = PackageListDiff::default();
+        for &item in &new {
+            match find(&old, item) {
+                None => diff.added.push(item.clone()),
+                Some(before) => {
+                    if let (Some(old_version), Some(new_version)) = (&before.version, &item.version)
+                        && old_version != new_version
+                    {
+                        diff.changed.push(VersionChange {
+                            name: item.name.clone(),
+                            package_type: item.package_type.clone(),
+                            old_version: old_version.clone(),
+                            new_version: new_version.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        for &item in &old {
+            if find(&new, item).is_none() {
+                diff.removed.push(item.clone());
+            }
+        }
+
+        diff.added.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.removed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff.changed.sort_by(|a, b| a.name.cmp(&b.name));
+        diff
+    }
+}
+
+/// A package whose version differs between two lists.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VersionChange {
+    pub name: String,
+    pub package_type: PackageType,
+    pub old_version: String,
+    pub new_version: String,
+}
+
+/// The packages added, removed and upgraded or downgraded between two
+/// package lists, each sorted by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PackageListDiff {
+    pub added: Vec<PackageListItem>,
+    pub removed: Vec<PackageListItem>,
+    pub changed: Vec<VersionChange>,
+}
+
+impl PackageListDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Plain text with a section per kind of change, for pasting anywhere.
+    pub fn to_text(&self) -> String {
+        let item_line = |prefix: char, item: &PackageListItem| match &item.version {
+            Some(version) => format!(
+                "{} {} {} ({})\n",
+                prefix, item.name, version, item.package_type
+            ),
+            None => format!("{} {} ({})\n", prefix, item.name, item.package_type),
+        };
+
+        let mut text = format!("Added ({})\n", self.added.len());
+        for item in &self.added {
+            text.push_str(&item_line('+', item));
+        }
+        text.push_str(&format!("\nRemoved ({})\n", self.removed.len()));
+        for item in &self.removed {
+            text.push_str(&item_line('-', item));
+        }
+        text.push_str(&format!("\nChanged ({})\n", self.changed.len()));
+        for change in &self.changed {
+            text.push_str(&format!(
+                "~ {} {} → {} ({})\n",
+                change.name, change.old_version, change.new_version, change.package_type
+            ));
+        }
+        text
+    }
 }
 
 impl Default for PackageList {
@@ -62,3 +210,131 @@ impl Default for PackageList {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(name: &str, package_type: PackageType, version: Option<&str>) -> PackageListItem {
+        let mut item = PackageListItem::new(name.to_string(), package_type);
+        item.version = version.map(String::from);
+        item
+    }
+
+    fn list(items: Vec<PackageListItem>) -> PackageList {
+        let mut list = PackageList::new();
+        for item in items {
+            match item.package_type {
+                PackageType::Formula => list.add_formula(item),
+                PackageType::Cask => list.add_cask(item),
+            }
+        }
+        list
+    }
+
+    fn names(items: &[PackageListItem]) -> Vec<&str> {
+        items.iter().map(|i| i.name.as_str()).collect()
+    }
+
+    #[test]
+    fn diff_sorts_added_removed_and_changed_packages() {
+        let old = list(vec![
+            item("wget", PackageType::Formula, Some("1.24.5")),
+            item("jq", PackageType::Formula, Some("1.6")),
+            item("node", PackageType::Formula, Some("22.0.0")),
+            item("firefox", PackageType::Cask, Some("120.0")),
+        ]);
+        let new = list(vec![
+            item("node", PackageType::Formula, Some("20.1.0")),
+            item("wget", PackageType::Formula, Some("1.25.0")),
+            item("ripgrep", PackageType::Formula, Some("14.1.0")),
+            item("firefox", PackageType::Cask, Some("120.0")),
+            item("alacritty", PackageType::Cask, None),
+        ]);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(names(&diff.added), ["alacritty", "ripgrep"]);
+        assert_eq!(names(&diff.removed), ["jq"]);
+        let changed: Vec<_> = diff
+            .changed
+            .iter()
+            .map(|c| {
+                (
+                    c.name.as_str(),
+                    c.old_version.as_str(),
+                    c.new_version.as_str(),
+                )
+            })
+            .collect();
+        // Downgrades count as changes too
+        assert_eq!(
+            changed,
+            [("node", "22.0.0", "20.1.0"), ("wget", "1.24.5", "1.25.0")]
+        );
+    }
+
+    #[test]
+    fn diff_matches_packages_by_name_and_type() {
+        let old = list(vec![item("docker", PackageType::Formula, Some("27.0"))]);
+        let new = list(vec![item("docker", PackageType::Cask, Some("4.30"))]);
+
+        let diff = old.diff(&new);
+
+        assert_eq!(
+            diff.added,
+            [item("docker", PackageType::Cask, Some("4.30"))]
+        );
+        assert_eq!(
+            diff.removed,
+            [item("docker", PackageType::Formula, Some("27.0"))]
+        );
+        assert!(diff.changed.is_empty());
+    }
+
+    #[test]
+    fn unknown_versions_are_not_changes() {
+        let old = list(vec![
+            item("jq", PackageType::Formula, None),
+            item("wget", PackageType::Formula, Some("1.24.5")),
+        ]);
+        let new = list(vec![
+            item("jq", PackageType::Formula, Some("1.7.1")),
+            item("wget", PackageType::Formula, None),
+        ]);
+
+        assert!(old.diff(&new).is_empty());
+    }
+
+    #[test]
+    fn identical_and_empty_lists_have_no_differences() {
+        let packages = list(vec![
+            item("jq", PackageType::Formula, Some("1.7.1")),
+            item("firefox", PackageType::Cask, None),
+        ]);
+
+        assert!(packages.diff(&packages).is_empty());
+        assert!(PackageList::new().diff(&PackageList::new()).is_empty());
+        assert_eq!(PackageList::new().diff(&packages).added.len(), 2);
+        assert_eq!(packages.diff(&PackageList::new()).removed.len(), 2);
+    }
+
+    #[test]
+    fn diff_text_lists_every_section_with_counts() {
+        let old = list(vec![
+            item("jq", PackageType::Formula, Some("1.6")),
+            item("wget", PackageType::Formula, Some("1.24.5")),
+        ]);
+        let new = list(vec![
+            item("jq", PackageType::Formula, Some("1.7.1")),
+            item("firefox", PackageType::Cask, None),
+        ]);
+
+        assert_eq!(
+            old.diff(&new).to_text(),
+            "Added (1)\n+ firefox (Cask)\n\n\
+             Removed (1)\n- wget 1.24.5 (Formula)\n\n\
+             Changed (1)\n~ jq 1.6 → 1.7.1 (Formula)\n"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+/// Tags and a note the user keeps on a package. Only Brewsty reads these;
+/// they are keyed by name so they outlive an uninstall and reinstall.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct PackageMetadata {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+}
+
+impl PackageMetadata {
+    /// Builds metadata from a comma-separated tag list and a note, trimming
+    /// both and dropping empty or repeated tags.
+    pub fn from_input(tags: &str, note: &str) -> Self {
+        let mut parsed: Vec<String> = Vec::new();
+        for tag in tags.split(',').map(str::trim).filter(|t| !t.is_empty()) {
+            if !parsed.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                parsed.push(tag.to_string());
+            }
+        }
+        Self {
+            tags: parsed,
+            note: note.trim().to_string(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.note.is_empty()
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_is_trimmed_and_deduplicated() {
+        let metadata = PackageMetadata::from_input(" work, media,,Work , try-later ", "  keep ");
+        assert_eq!(metadata.tags, ["work", "media", "try-later"]);
+        assert_eq!(metadata.note, "keep");
+        assert!(PackageMetadata::from_input(" , ", " ").is_empty());
+    }
+}
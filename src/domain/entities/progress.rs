@@ -0,0 +1,41 @@
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// How far a package operation has got, as far as brew's output tells.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OperationProgress {
+    /// `percent` is `None` until brew prints a progress bar for `file`.
+    Downloading { file: String, percent: Option<f32> },
+    /// Pouring, linking or building; brew shows no percentage for these.
+    Installing,
+    /// `brew update`, run before upgrading everything.
+    RefreshingMetadata,
+}
+
+/// Progress written by the task running an operation and read by the UI.
+#[derive(Clone, Default)]
+pub struct ProgressCell(Arc<Mutex<Option<OperationProgress>>>);
+
+impl ProgressCell {
+    pub fn set(&self, progress: OperationProgress) {
+        *self.0.lock().unwrap_or_else(|e| e.into_inner()) = Some(progress);
+    }
+
+    /// `None` until the operation reported anything.
+    pub fn get(&self) -> Option<OperationProgress> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Cells are equal when they are the same cell.
+impl PartialEq for ProgressCell {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Debug for ProgressCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ProgressCell").field(&self.get()).finish()
+    }
+}
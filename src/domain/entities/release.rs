@@ -0,0 +1,34 @@
+/// A published Brewsty release.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Release {
+    /// Its tag without the leading `v`, e.g. `0.7.0`.
+    pub version: String,
+    /// The release page, with notes and downloads.
+    pub url: String,
+}
+
+impl Release {
+    /// Whether this release is newer than `version`, comparing the dotted
+    /// numbers in order. Suffixes such as `-beta.1` are ignored.
+    pub fn is_newer_than(&self, version: &str) -> bool {
+        let (ours, theirs) = (numbers(&self.version), numbers(version));
+        for i in 0..ours.len().max(theirs.len()) {
+            let (a, b) = (
+                ours.get(i).copied().unwrap_or(0),
+                theirs.get(i).copied().unwrap_or(0),
+            );
+            if a != b {
+                return a > b;
+            }
+        }
+        false
+    }
+}
+
+fn numbers(version: &str) -> Vec<u64> {
+    let version = version.trim().trim_start_matches('v');
+    let core = version.split(['-', '+']).next().unwrap_or(version);
+    core.split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
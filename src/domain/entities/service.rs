@@ -21,6 +21,10 @@ pub struct Service {
     pub status: ServiceStatus,
     pub user: Option<String>,
     pub file: Option<String>,
+    /// Where the service writes its errors, falling back to its output log.
+    /// Only `brew services info` reports it, so listed services lack it.
+    #[serde(default)]
+    pub log_path: Option<String>,
 }
 
 impl Service {
@@ -30,6 +34,7 @@ impl Service {
             status,
             user: None,
             file: None,
+            log_path: None,
         }
     }
 
@@ -42,4 +47,23 @@ impl Service {
         self.file = Some(file);
         self
     }
+
+    pub fn with_log_path(mut self, log_path: String) -> Self {
+        self.log_path = Some(log_path);
+        self
+    }
+}
+
+/// Everything `brew services info` reports about one service.
+#[derive(Debug, Clone)]
+pub struct ServiceInfo {
+    pub service: Service,
+    pub running: bool,
+    pub pid: Option<u32>,
+    /// How the service last exited, if it has.
+    pub exit_code: Option<i32>,
+    /// Whether it starts at login; `None` when brew is too old to say.
+    pub registered: Option<bool>,
+    pub log_path: Option<String>,
+    pub error_log_path: Option<String>,
 }
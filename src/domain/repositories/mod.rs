@@ -1,7 +1,9 @@
 pub mod package_list_repository;
 pub mod package_repository;
+pub mod release_repository;
 pub mod service_repository;
 
 pub use package_list_repository::PackageListRepository;
 pub use package_repository::PackageRepository;
+pub use release_repository::ReleaseRepository;
 pub use service_repository::ServiceRepository;
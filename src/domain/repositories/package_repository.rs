@@ -1,22 +1,65 @@
-use crate::domain::entities::{CleanupPreview, Package, PackageType};
+use crate::domain::entities::{
+    AuditResult, CacheInfo, CleanupPreview, DependencyNode, Package, PackageType, PlannedUpgrade,
+    ProgressCell, UpgradeReport,
+};
 use anyhow::Result;
 use async_trait::async_trait;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::SystemTime;
 
 #[async_trait]
 pub trait PackageRepository: Send + Sync {
     async fn get_installed_packages(&self, package_type: PackageType) -> Result<Vec<Package>>;
     async fn get_outdated_packages(&self, package_type: PackageType) -> Result<Vec<Package>>;
-    async fn install_package(&self, package: &Package) -> Result<()>;
+    /// Reports download progress to `progress` while it runs.
+    async fn install_package(&self, package: &Package, progress: &ProgressCell) -> Result<()>;
+    async fn is_installed(&self, package: &Package) -> Result<bool>;
     async fn uninstall_package(&self, package: &Package) -> Result<()>;
-    async fn update_package(&self, package: &Package) -> Result<()>;
-    async fn update_all(&self) -> Result<()>;
+    /// Uninstalls a cask and removes every file it left behind.
+    async fn zap_cask(&self, package: &Package) -> Result<()>;
+    async fn update_package(&self, package: &Package, progress: &ProgressCell) -> Result<()>;
+    /// Refreshes brew's formula and cask metadata, like `brew update`.
+    async fn update_metadata(&self, progress: &ProgressCell) -> Result<()>;
+    /// Fails only when nothing was upgraded; brew can upgrade some packages
+    /// and fail on others, which the report tells apart.
+    async fn update_all(&self, progress: &ProgressCell) -> Result<UpgradeReport>;
+    /// What `update_all` would upgrade.
+    async fn get_upgrade_preview(&self) -> Result<Vec<PlannedUpgrade>>;
+    /// Downloads `packages` without installing them. Returns the names of
+    /// those that were downloaded; the others failed.
+    async fn fetch_packages(
+        &self,
+        packages: &[Package],
+        progress: &ProgressCell,
+    ) -> Result<Vec<String>>;
     async fn get_cleanup_preview(&self) -> Result<CleanupPreview>;
     async fn get_cleanup_old_versions_preview(&self) -> Result<CleanupPreview>;
+    /// Measures the cache folder at `cache`, brew's `--cache`.
+    async fn get_cache_info(&self, cache: &Path) -> Result<CacheInfo>;
     async fn clean_cache(&self) -> Result<()>;
     async fn cleanup_old_versions(&self) -> Result<()>;
+    /// The folders in `cellar` of formulae installed only as dependencies of
+    /// packages that are gone, which `brew autoremove` would uninstall.
+    async fn get_autoremove_preview(&self, cellar: &Path) -> Result<CleanupPreview>;
+    async fn autoremove(&self) -> Result<()>;
     async fn search_packages(&self, query: &str, package_type: PackageType)
     -> Result<Vec<Package>>;
     async fn get_package_info(&self, name: &str, package_type: PackageType) -> Result<Package>;
+    /// Info for several packages of one type in one run.
+    async fn get_packages_info(
+        &self,
+        names: &[String],
+        package_type: PackageType,
+    ) -> Result<Vec<Package>>;
     async fn pin_package(&self, package: &Package) -> Result<()>;
     async fn unpin_package(&self, package: &Package) -> Result<()>;
+    /// Runs `brew audit --strict` on a formula. Problems found are part of
+    /// the result, not an error.
+    async fn audit_package(&self, package: &Package) -> Result<AuditResult>;
+    /// Everything `package` depends on, installed or not, as brew nests it.
+    async fn dependency_tree(&self, package: &Package) -> Result<DependencyNode>;
+    /// Install times of `packages` by name; packages without a readable
+    /// record are left out.
+    async fn get_install_dates(&self, packages: &[Package]) -> Result<HashMap<String, SystemTime>>;
 }
@@ -0,0 +1,9 @@
+use crate::domain::entities::Release;
+use anyhow::Result;
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait ReleaseRepository: Send + Sync {
+    /// The newest published Brewsty release.
+    async fn latest_release(&self) -> Result<Release>;
+}
@@ -1,11 +1,18 @@
-use crate::domain::entities::Service;
+use crate::domain::entities::{Service, ServiceInfo};
 use anyhow::Result;
 use async_trait::async_trait;
 
 #[async_trait]
 pub trait ServiceRepository: Send + Sync {
     async fn list_services(&self) -> Result<Vec<Service>>;
+    /// Asks brew about one service only, which is quicker than listing all.
+    async fn get_service_info(&self, name: &str) -> Result<ServiceInfo>;
     async fn start_service(&self, name: &str) -> Result<()>;
     async fn stop_service(&self, name: &str) -> Result<()>;
+    /// Stops every running service at once, like `brew services stop --all`.
+    async fn stop_all_services(&self) -> Result<()>;
     async fn restart_service(&self, name: &str) -> Result<()>;
+    /// Removes what's left of services whose formula was uninstalled, like
+    /// `brew services cleanup`, returning what brew printed.
+    async fn cleanup_services(&self) -> Result<String>;
 }
@@ -0,0 +1,90 @@
+//! Package lists exported on a schedule to a folder the user chose, one
+//! dated file per day, such as `brewsty_2024-06-01.json`.
+
+use chrono::NaiveDate;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_PREFIX: &str = "brewsty_";
+const FILE_EXTENSION: &str = "json";
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Where the export for `date` goes in `dir`. A second export on the same
+/// day replaces the first.
+pub fn export_path(dir: &Path, date: NaiveDate) -> PathBuf {
+    dir.join(format!(
+        "{}{}.{}",
+        FILE_PREFIX,
+        date.format(DATE_FORMAT),
+        FILE_EXTENSION
+    ))
+}
+
+/// The dated exports in `dir`, newest first. Other files are left alone.
+pub fn list(dir: &Path) -> Vec<(NaiveDate, PathBuf)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut exports: Vec<_> = entries
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            Some((parse(&path)?, path))
+        })
+        .collect();
+    exports.sort_by_key(|(date, _)| std::cmp::Reverse(*date));
+    exports
+}
+
+/// Deletes all but the newest `keep` exports in `dir`.
+pub fn prune(dir: &Path, keep: usize) {
+    for (_, path) in list(dir).into_iter().skip(keep) {
+        if let Err(e) = fs::remove_file(&path) {
+            tracing::warn!("Failed to remove old export {}: {}", path.display(), e);
+        }
+    }
+}
+
+fn parse(path: &Path) -> Option<NaiveDate> {
+    if path.extension()? != FILE_EXTENSION {
+        return None;
+    }
+    let date = path.file_stem()?.to_str()?.strip_prefix(FILE_PREFIX)?;
+    NaiveDate::parse_from_str(date, DATE_FORMAT).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_the_newest_exports_and_ignores_other_files() {
+        let dir = std::env::temp_dir().join(format!("brewsty-auto-exports-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let day = |d| NaiveDate::from_ymd_opt(2024, 6, d).unwrap();
+        for d in [3, 1, 2] {
+            fs::write(export_path(&dir, day(d)), "{}").unwrap();
+        }
+        for name in [
+            "brewsty_packages.json",
+            "brewsty_2024-06-04.md",
+            "notes.txt",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+        assert!(export_path(&dir, day(1)).ends_with("brewsty_2024-06-01.json"));
+
+        prune(&dir, 2);
+        let dates: Vec<_> = list(&dir).into_iter().map(|(date, _)| date).collect();
+        let others = [
+            "brewsty_packages.json",
+            "brewsty_2024-06-04.md",
+            "notes.txt",
+        ]
+        .iter()
+        .all(|name| dir.join(name).exists());
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(dates, [day(3), day(2)]);
+        assert!(others);
+    }
+}
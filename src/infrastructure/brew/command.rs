@@ -1,17 +1,457 @@
-use crate::domain::entities::PackageType;
-use anyhow::{anyhow, Result};
+use crate::domain::entities::{PackageType, ProgressCell};
+use crate::infrastructure::brew::command_log::{self, CommandRecord, REDACTED};
+use crate::infrastructure::brew::process_registry::{self, TrackedChild};
+use crate::infrastructure::brew::progress;
+use anyhow::{Result, anyhow};
+use secrecy::{ExposeSecret, SecretString};
+use std::ffi::OsStr;
 use std::fs;
-use std::path::PathBuf;
-use std::process::{Command, Stdio};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitStatus, Output, Stdio};
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::thread;
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
+
+static BREW_PATH: RwLock<Option<PathBuf>> = RwLock::new(None);
+static NO_AUTO_UPDATE: AtomicBool = AtomicBool::new(false);
+static READ_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_READ_TIMEOUT_SECS);
+static WRITE_TIMEOUT_SECS: AtomicU64 = AtomicU64::new(DEFAULT_WRITE_TIMEOUT_SECS);
+static EXTRA_ENV: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// Environment variables Brewsty sets itself, which users may not override.
+const RESERVED_ENV_KEYS: &[&str] = &["SUDO_ASKPASS"];
+
+/// An askpass helper that doesn't exist, so sudo fails instead of asking.
+const REFUSED_ASKPASS: &str = "/nonexistent/askpass";
+
+/// What sudo prints when it stopped for want of a password it had no way
+/// to ask for, besides failing to run `REFUSED_ASKPASS`.
+const SUDO_PASSWORD_SIGNATURES: &[&str] = &[
+    // Run with -A, which brew adds whenever SUDO_ASKPASS is set
+    "sudo: no askpass program specified",
+    // Run without -A and with no terminal to prompt on
+    "sudo: a terminal is required to read the password",
+    "sudo: no tty present and no askpass program specified",
+    "sudo: a password is required",
+    "sudo: no password was provided",
+];
+
+/// Commonly used Homebrew variables offered in Settings, as name, a
+/// suggested value and what it does.
+pub const KNOWN_ENV_VARS: &[(&str, &str, &str)] = &[
+    (
+        "HOMEBREW_NO_ANALYTICS",
+        "1",
+        "Don't send anonymous usage analytics to Homebrew",
+    ),
+    (
+        "HOMEBREW_CASK_OPTS",
+        "--appdir=~/Applications",
+        "Default options for cask installs, such as where apps go",
+    ),
+    (
+        "HOMEBREW_NO_INSTALL_CLEANUP",
+        "1",
+        "Don't clean up old versions after installs and upgrades",
+    ),
+    (
+        "HOMEBREW_NO_INSTALLED_DEPENDENTS_CHECK",
+        "1",
+        "Don't upgrade or reinstall the dependents of an upgraded formula",
+    ),
+    (
+        "HOMEBREW_NO_ENV_HINTS",
+        "1",
+        "Leave hints about environment variables out of brew's output",
+    ),
+    (
+        "HOMEBREW_CURL_RETRIES",
+        "3",
+        "How many times a failed download is retried",
+    ),
+];
+
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_WRITE_TIMEOUT_SECS: u64 = 1800;
+
+/// Info is loaded for many rows at once, so a stalled lookup gives up early
+/// rather than holding a worker for the full read timeout.
+const PACKAGE_INFO_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct BrewOutput {
     pub stdout: String,
     pub stderr: String,
 }
 
+/// How sudo may ask for a password if brew needs elevation.
+enum Askpass<'a> {
+    /// Leave sudo's behaviour as inherited from Brewsty's environment.
+    Inherit,
+    /// Fail instead of asking, so the app can ask for the password.
+    Refuse,
+    /// Answer with the given askpass script.
+    Script(&'a Path),
+}
+
 pub struct BrewCommand;
 
 impl BrewCommand {
+    /// Path to the brew executable, resolved on first use.
+    pub fn brew_path() -> PathBuf {
+        BREW_PATH
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_or_insert_with(|| {
+                let path = resolve_brew_path();
+                tracing::info!("Using brew at {}", path.display());
+                path
+            })
+            .clone()
+    }
+
+    /// Whether a brew executable was found at all.
+    pub fn is_found() -> bool {
+        Self::brew_path().is_file()
+    }
+
+    /// Looks for brew again, e.g. after Homebrew was installed. Returns
+    /// whether it was found.
+    pub fn redetect() -> bool {
+        *BREW_PATH.write().unwrap_or_else(|e| e.into_inner()) = None;
+        Self::is_found()
+    }
+
+    /// Casks only exist on macOS; Homebrew on Linux manages formulae only.
+    pub fn casks_supported() -> bool {
+        cfg!(target_os = "macos")
+    }
+
+    #[cfg(all(test, unix))]
+    pub(crate) fn set_brew_path_for_tests(path: PathBuf) {
+        let mut current = BREW_PATH.write().unwrap_or_else(|e| e.into_inner());
+        assert_eq!(
+            current.get_or_insert_with(|| path.clone()),
+            &path,
+            "brew path already resolved to something else"
+        );
+    }
+
+    /// Whether `name` is set in the environment Brewsty was launched with, and
+    /// therefore inherited by every brew process.
+    pub fn env_flag_set(name: &str) -> bool {
+        std::env::var_os(name).is_some_and(|v| !v.is_empty())
+    }
+
+    /// Sets `HOMEBREW_NO_AUTO_UPDATE` for brew processes started from now on.
+    pub fn set_no_auto_update(enabled: bool) {
+        NO_AUTO_UPDATE.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Sets user-defined variables, such as `HOMEBREW_CASK_OPTS`, for brew
+    /// processes started from now on. Entries should pass `check_env_var`.
+    pub fn set_extra_env(vars: Vec<(String, String)>) {
+        *EXTRA_ENV.write().unwrap_or_else(|e| e.into_inner()) = vars;
+    }
+
+    /// Why `key=value` can't be passed to brew, if it can't.
+    pub fn check_env_var(key: &str, value: &str) -> Result<()> {
+        if key.is_empty() {
+            return Err(anyhow!("The name is empty"));
+        }
+        if key.contains('=') || key.contains('\0') {
+            return Err(anyhow!("The name can't contain '=' or NUL"));
+        }
+        if value.contains('\0') {
+            return Err(anyhow!("The value can't contain NUL"));
+        }
+        if RESERVED_ENV_KEYS.contains(&key) {
+            return Err(anyhow!("{} is managed by Brewsty", key));
+        }
+        Ok(())
+    }
+
+    /// Sets how long queries (list, search, info, ...) and operations that
+    /// change the system (install, upgrade, cleanup, ...) may run before the
+    /// brew process is killed.
+    pub fn set_timeouts(read: Duration, write: Duration) {
+        READ_TIMEOUT_SECS.store(read.as_secs().max(1), Ordering::Relaxed);
+        WRITE_TIMEOUT_SECS.store(write.as_secs().max(1), Ordering::Relaxed);
+    }
+
+    fn read_timeout() -> Duration {
+        Duration::from_secs(READ_TIMEOUT_SECS.load(Ordering::Relaxed))
+    }
+
+    fn write_timeout() -> Duration {
+        Duration::from_secs(WRITE_TIMEOUT_SECS.load(Ordering::Relaxed))
+    }
+
+    /// Like `Command::output`, but kills the process if it hasn't exited
+    /// within `timeout`.
+    pub(crate) fn output_with_timeout(command: &mut Command, timeout: Duration) -> Result<Output> {
+        let (status, stdout, stderr) =
+            Self::wait_with_timeout(command, timeout, None, |mut pipe| {
+                let mut buf = Vec::new();
+                let _ = pipe.read_to_end(&mut buf);
+                buf
+            })?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Like `output_with_timeout`, but logs each line of output as it arrives
+    /// and reports download progress found in it to `progress`. Progress bars
+    /// are not logged.
+    fn observe_with_timeout(
+        command: &mut Command,
+        timeout: Duration,
+        progress: &ProgressCell,
+    ) -> Result<Output> {
+        let progress = progress.clone();
+        let (status, stdout, stderr) =
+            Self::wait_with_timeout(command, timeout, None, move |mut pipe| {
+                let mut buf = Vec::new();
+                let mut chunk = [0u8; 4096];
+                // Start of the line being read. curl redraws its bar after a
+                // \r, so the latest bar is the unfinished line
+                let mut line_start = 0;
+                let observe = |line: &[u8]| {
+                    let line = String::from_utf8_lossy(line);
+                    if let Some(update) = progress::parse_line(&line, progress.get().as_ref()) {
+                        progress.set(update);
+                    }
+                };
+                while let Ok(n) = pipe.read(&mut chunk) {
+                    if n == 0 {
+                        break;
+                    }
+                    buf.extend_from_slice(&chunk[..n]);
+                    while let Some(end) = buf[line_start..]
+                        .iter()
+                        .position(|&b| b == b'\r' || b == b'\n')
+                    {
+                        let line = &buf[line_start..line_start + end];
+                        let text = String::from_utf8_lossy(line);
+                        if !text.trim().is_empty() && !progress::is_bar(&text) {
+                            tracing::info!("{}", text);
+                        }
+                        observe(line);
+                        line_start += end + 1;
+                    }
+                    if buf.ends_with(b"%") {
+                        observe(&buf[line_start..]);
+                    }
+                }
+                buf
+            })?;
+        Ok(Output {
+            status,
+            stdout,
+            stderr,
+        })
+    }
+
+    /// Runs `command` with `input` on stdin and logs each line of its output
+    /// as it arrives, for long-running commands whose progress should show.
+    /// Returns the exit status and the output, stderr after stdout.
+    pub(crate) fn stream_with_timeout(
+        command: &mut Command,
+        timeout: Duration,
+        input: &[u8],
+    ) -> Result<(ExitStatus, String)> {
+        let (status, stdout, stderr) =
+            Self::wait_with_timeout(command, timeout, Some(input), |pipe| {
+                let mut text = String::new();
+                for line in BufReader::new(pipe).lines().map_while(|line| line.ok()) {
+                    tracing::info!("{}", line);
+                    text.push_str(&line);
+                    text.push('\n');
+                }
+                text
+            })?;
+        Ok((status, stdout + &stderr))
+    }
+
+    /// Spawns `command`, reads its stdout and stderr with `drain` and waits
+    /// for it to exit, killing it after `timeout`.
+    fn wait_with_timeout<T, D>(
+        command: &mut Command,
+        timeout: Duration,
+        input: Option<&[u8]>,
+        drain: D,
+    ) -> Result<(ExitStatus, T, T)>
+    where
+        T: Send + 'static,
+        D: Fn(Box<dyn Read + Send>) -> T + Clone + Send + 'static,
+    {
+        // A session of its own detaches the process from the terminal
+        // Brewsty may have been started from, so sudo can't prompt there and
+        // wait for input nobody sees; without a terminal it uses the askpass
+        // helper or fails. The session is also a process group, which lets
+        // the whole brew process tree be stopped on timeout or exit, not just
+        // the top-level process
+        #[cfg(unix)]
+        // SAFETY: setsid(2) is async-signal-safe and touches no memory
+        unsafe {
+            std::os::unix::process::CommandExt::pre_exec(command, || {
+                if libc::setsid() == -1 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+
+        let mut child = command
+            .stdin(if input.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+            // Closed right after, so the process sees the end of its input
+            let _ = stdin.write_all(input);
+        }
+
+        // Drain both pipes on their own threads so a chatty process can't block
+        // on a full pipe while we wait for it to exit
+        let reader = |pipe: Box<dyn Read + Send>| {
+            let drain = drain.clone();
+            thread::spawn(move || drain(pipe))
+        };
+        let stdout_reader = child.stdout.take().map(|pipe| reader(Box::new(pipe)));
+        let stderr_reader = child.stderr.take().map(|pipe| reader(Box::new(pipe)));
+
+        let child = TrackedChild::track(child);
+        let deadline = Instant::now() + timeout;
+        let status = loop {
+            if let Some(status) = child.lock().try_wait()? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                process_registry::terminate(&mut child.lock());
+                let args: Vec<_> = command.get_args().map(|a| a.to_string_lossy()).collect();
+                return Err(anyhow!(
+                    "brew {} timed out after {}s and was stopped",
+                    args.join(" "),
+                    timeout.as_secs()
+                ));
+            }
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        let collect = |reader: Option<thread::JoinHandle<T>>| {
+            reader
+                .and_then(|handle| handle.join().ok())
+                .unwrap_or_else(|| drain(Box::new(std::io::empty())))
+        };
+
+        Ok((status, collect(stdout_reader), collect(stderr_reader)))
+    }
+
+    /// Stops any brew processes still running, e.g. when the app is closing.
+    pub fn terminate_running() -> usize {
+        process_registry::terminate_all()
+    }
+
+    /// Runs brew with `args`. Every invocation goes through here so it ends up
+    /// in the command audit log.
+    fn run(args: &[&str], timeout: Duration, askpass: Askpass) -> Result<Output> {
+        Self::run_observed(args, timeout, askpass, None)
+    }
+
+    /// Like `run`, reporting download progress to `progress` if given.
+    fn run_observed(
+        args: &[&str],
+        timeout: Duration,
+        askpass: Askpass,
+        progress: Option<&ProgressCell>,
+    ) -> Result<Output> {
+        // User variables come first so Brewsty's own settings win
+        let mut env = EXTRA_ENV.read().unwrap_or_else(|e| e.into_inner()).clone();
+        let user_vars = env.len();
+        let mut set = |key: &str, value: String| env.push((key.to_string(), value));
+        if NO_AUTO_UPDATE.load(Ordering::Relaxed) {
+            set("HOMEBREW_NO_AUTO_UPDATE", "1".to_string());
+        }
+        match askpass {
+            Askpass::Inherit => {}
+            Askpass::Refuse => set("SUDO_ASKPASS", REFUSED_ASKPASS.to_string()),
+            Askpass::Script(path) => set("SUDO_ASKPASS", path.to_string_lossy().into_owned()),
+        }
+
+        let mut command = Self::command(args, &env);
+
+        // Only the arguments are logged; the environment may carry the askpass path
+        tracing::info!("$ {}", command_log::command_line(args));
+
+        let started_at = chrono::Local::now();
+        let start = Instant::now();
+        let result = match progress {
+            Some(progress) => Self::observe_with_timeout(&mut command, timeout, progress),
+            None => Self::output_with_timeout(&mut command, timeout),
+        };
+
+        let record = CommandRecord {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            // The user's own variables may hold credentials, such as a proxy
+            // URL or a GitHub token
+            env: env
+                .into_iter()
+                .enumerate()
+                .map(|(i, (key, value))| {
+                    let value = match askpass {
+                        _ if i < user_vars => REDACTED.to_string(),
+                        Askpass::Script(_) if key == "SUDO_ASKPASS" => REDACTED.to_string(),
+                        _ => value,
+                    };
+                    (key, value)
+                })
+                .collect(),
+            started_at,
+            duration: start.elapsed(),
+            exit_code: result.as_ref().ok().and_then(|o| o.status.code()),
+            error: result.as_ref().err().map(|e| e.to_string()),
+            stdout_bytes: result.as_ref().map_or(0, |o| o.stdout.len()),
+            stderr_bytes: result.as_ref().map_or(0, |o| o.stderr.len()),
+        };
+        tracing::debug!(
+            "brew {} finished in {:.2}s with {} ({} bytes stdout, {} bytes stderr)",
+            record.args.join(" "),
+            record.duration.as_secs_f64(),
+            match (&record.error, record.exit_code) {
+                (Some(error), _) => error.clone(),
+                (None, Some(code)) => format!("exit code {}", code),
+                (None, None) => "no exit code".to_string(),
+            },
+            record.stdout_bytes,
+            record.stderr_bytes
+        );
+        command_log::record(record);
+
+        result
+    }
+
+    /// Every brew process is built here. `env` is passed to it verbatim;
+    /// no shell is involved.
+    fn command(args: &[&str], env: &[(String, String)]) -> Command {
+        let mut command = Command::new(Self::brew_path());
+        command.args(args);
+        for (key, value) in env {
+            command.env(key, value);
+        }
+        command
+    }
+
     fn get_package_type_arg(package_type: PackageType) -> &'static str {
         match package_type {
             PackageType::Formula => "--formula",
@@ -20,7 +460,18 @@ impl BrewCommand {
     }
 
     fn execute_brew(args: &[&str]) -> Result<String> {
-        let output = Command::new("brew").args(args).output()?;
+        Ok(decode_output(Self::execute_brew_raw(args)?))
+    }
+
+    /// Like `execute_brew`, but fails on invalid UTF-8 instead of replacing
+    /// it, for output that must be used verbatim, such as paths.
+    fn execute_brew_strict(args: &[&str]) -> Result<String> {
+        String::from_utf8(Self::execute_brew_raw(args)?)
+            .map_err(|_| anyhow!("brew {} printed invalid UTF-8", args.join(" ")))
+    }
+
+    fn execute_brew_raw(args: &[&str]) -> Result<Vec<u8>> {
+        let output = Self::run(args, Self::read_timeout(), Askpass::Inherit)?;
 
         if !output.status.success() {
             return Err(anyhow!(
@@ -29,39 +480,27 @@ impl BrewCommand {
             ));
         }
 
-        Ok(String::from_utf8(output.stdout)?)
+        Ok(output.stdout)
     }
 
-    fn execute_brew_with_output(args: &[&str]) -> Result<BrewOutput> {
-        // Run brew directly. When brew needs elevation, it will call sudo internally.
-        // By setting SUDO_ASKPASS to a nonexistent script and setting SUDO_ASKPASS_REQUIRE=force,
-        // we tell sudo to NOT prompt the terminal, but instead try to run that script.
-        // When the script doesn't exist, sudo fails with an error we can detect.
+    fn execute_brew_with_output(
+        args: &[&str],
+        progress: Option<&ProgressCell>,
+    ) -> Result<BrewOutput> {
+        // Run brew directly. When brew needs elevation, it calls sudo itself,
+        // passing -A because SUDO_ASKPASS is set; the helper doesn't exist, so
+        // sudo fails right away. Sudo calls without -A have no terminal to
+        // prompt on and fail too. Either way sudo says why, which is detected.
 
         tracing::debug!("Executing brew command with SUDO_ASKPASS to prevent terminal prompts");
 
-        let output = Command::new("brew")
-            .args(args)
-            .env("SUDO_ASKPASS", "/nonexistent/askpass") // Force sudo to not use terminal
-            .env("SUDO_ASKPASS_REQUIRE", "force")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?;
+        let output = Self::run_observed(args, Self::write_timeout(), Askpass::Refuse, progress)?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
-            // Check if this failed due to needing a password
-            let combined = format!("{} {}", stdout, stderr).to_lowercase();
-
-            if combined.contains("password")
-                || combined.contains("sudo")
-                || combined.contains("permission denied")
-                || combined.contains("authentication")
-                || combined.contains("privilege")
-            {
-                // This is a password/privilege error
+            if sudo_wanted_password(&stderr) || sudo_wanted_password(&stdout) {
                 tracing::debug!("Password/privilege required - will show modal");
                 return Err(anyhow!("a password is required"));
             }
@@ -71,18 +510,27 @@ impl BrewCommand {
         Ok(BrewOutput { stdout, stderr })
     }
 
-    fn create_askpass_script(password: &str) -> Result<PathBuf> {
+    pub(super) fn create_askpass_script(password: &SecretString) -> Result<PathBuf> {
         // Create a temporary askpass script that echoes the password
         // This script will be called by sudo when it needs the password
         let temp_dir = std::env::temp_dir();
         let script_path = temp_dir.join("brewsty_askpass.sh");
 
-        let script_content = format!(
-            "#!/bin/bash\necho '{}'\n",
-            password.replace("'", "'\\''") // Escape single quotes for shell
-        );
+        // The script body holds the plain password, so it is preallocated (no reallocation
+        // copies left behind) and wiped once written
+        let secret = password.expose_secret();
+        let mut script_content = Zeroizing::new(String::with_capacity(secret.len() * 4 + 32));
+        script_content.push_str("#!/bin/bash\necho '");
+        for c in secret.chars() {
+            if c == '\'' {
+                script_content.push_str("'\\''"); // Escape single quotes for shell
+            } else {
+                script_content.push(c);
+            }
+        }
+        script_content.push_str("'\n");
 
-        fs::write(&script_path, script_content)?;
+        fs::write(&script_path, script_content.as_bytes())?;
 
         // Make the script executable
         #[cfg(unix)]
@@ -96,7 +544,11 @@ impl BrewCommand {
         Ok(script_path)
     }
 
-    fn execute_brew_with_password(args: &[&str], password: &str) -> Result<BrewOutput> {
+    fn execute_brew_with_password(
+        args: &[&str],
+        password: &SecretString,
+        progress: Option<&ProgressCell>,
+    ) -> Result<BrewOutput> {
         // Create an askpass script that returns the password
         // When brew internally invokes sudo, sudo will call this script to get the password
         // This way brew itself runs as the user (not root), which is correct
@@ -104,22 +556,21 @@ impl BrewCommand {
         tracing::debug!("Executing brew command with password via SUDO_ASKPASS script");
 
         let askpass_path = Self::create_askpass_script(password)?;
-        let askpass_str = askpass_path.to_string_lossy().to_string();
 
-        let output = Command::new("brew")
-            .args(args)
-            .env("SUDO_ASKPASS", &askpass_str)
-            .env("SUDO_ASKPASS_REQUIRE", "force")
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped())
-            .output()?;
-
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let output = Self::run_observed(
+            args,
+            Self::write_timeout(),
+            Askpass::Script(&askpass_path),
+            progress,
+        );
 
-        // Clean up the askpass script
+        // Clean up the askpass script before anything else can bail out
         let _ = fs::remove_file(&askpass_path);
 
+        let output = output?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
         if !output.status.success() {
             // Check if it's a password-related error
             if stderr.contains("password is incorrect")
@@ -140,7 +591,6 @@ impl BrewCommand {
             PackageType::Formula => "--formula",
             PackageType::Cask => "--cask",
         };
-        tracing::debug!("Running: brew list {} --versions", type_arg);
         let result = Self::execute_brew(&["list", type_arg, "--versions"])?;
         tracing::debug!("brew list {} returned {} bytes", type_arg, result.len());
         Ok(result)
@@ -148,11 +598,11 @@ impl BrewCommand {
 
     pub fn get_package_info(name: &str, package_type: PackageType) -> Result<String> {
         let type_arg = Self::get_package_type_arg(package_type);
-        tracing::debug!("Running: brew info --json=v2 {} {}", type_arg, name);
-
-        let output = Command::new("brew")
-            .args(&["info", "--json=v2", type_arg, name])
-            .output()?;
+        let output = Self::run(
+            &["info", "--json=v2", type_arg, name],
+            Self::read_timeout().min(PACKAGE_INFO_TIMEOUT),
+            Askpass::Inherit,
+        )?;
 
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
@@ -160,7 +610,7 @@ impl BrewCommand {
             return Err(anyhow!("Failed to get package info: {}", error_msg));
         }
 
-        let result = String::from_utf8(output.stdout)?;
+        let result = decode_output(output.stdout);
         tracing::debug!(
             "brew info {} {} returned {} bytes",
             type_arg,
@@ -171,43 +621,91 @@ impl BrewCommand {
     }
 
     pub fn outdated_packages(package_type: PackageType) -> Result<String> {
+        match package_type {
+            PackageType::Formula => Self::execute_brew(&["outdated", "--formula", "--json=v2"]),
+            // Casks that update themselves too, so they can be shown as such
+            PackageType::Cask => {
+                Self::execute_brew(&["outdated", "--cask", "--greedy-auto-updates", "--json=v2"])
+            }
+        }
+    }
+
+    /// `brew info` for several packages of one type in one run, as JSON.
+    pub fn packages_info(names: &[String], package_type: PackageType) -> Result<String> {
+        let mut args = vec![
+            "info",
+            "--json=v2",
+            Self::get_package_type_arg(package_type),
+        ];
+        args.extend(names.iter().map(String::as_str));
+        Self::execute_brew(&args)
+    }
+
+    pub fn install_package(
+        name: &str,
+        package_type: PackageType,
+        progress: &ProgressCell,
+    ) -> Result<BrewOutput> {
         let type_arg = Self::get_package_type_arg(package_type);
-        Self::execute_brew(&["outdated", type_arg, "--json=v2"])
+        Self::execute_brew_with_output(&["install", type_arg, name], Some(progress))
     }
 
-    pub fn install_package(name: &str, package_type: PackageType) -> Result<BrewOutput> {
+    /// Whether `brew list` knows the package; it exits with an error for
+    /// packages that aren't installed.
+    pub fn is_installed(name: &str, package_type: PackageType) -> Result<bool> {
         let type_arg = Self::get_package_type_arg(package_type);
-        Self::execute_brew_with_output(&["install", type_arg, name])
+        let output = Self::run(
+            &["list", type_arg, name],
+            Self::read_timeout(),
+            Askpass::Inherit,
+        )?;
+        Ok(output.status.success())
     }
 
     pub fn install_package_with_password(
         name: &str,
         package_type: PackageType,
-        password: &str,
+        password: &SecretString,
+        progress: &ProgressCell,
     ) -> Result<BrewOutput> {
         let type_arg = Self::get_package_type_arg(package_type);
-        Self::execute_brew_with_password(&["install", type_arg, name], password)
+        Self::execute_brew_with_password(&["install", type_arg, name], password, Some(progress))
     }
 
     pub fn uninstall_package(name: &str, package_type: PackageType) -> Result<BrewOutput> {
         let type_arg = Self::get_package_type_arg(package_type);
-        Self::execute_brew_with_output(&["uninstall", type_arg, name])
+        Self::execute_brew_with_output(&["uninstall", type_arg, name], None)
     }
 
     pub fn uninstall_package_with_password(
         name: &str,
         package_type: PackageType,
-        password: &str,
+        password: &SecretString,
     ) -> Result<BrewOutput> {
         let type_arg = Self::get_package_type_arg(package_type);
-        Self::execute_brew_with_password(&["uninstall", type_arg, name], password)
+        Self::execute_brew_with_password(&["uninstall", type_arg, name], password, None)
     }
 
-    pub fn upgrade_package(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew").args(["upgrade", name]).output()?;
+    /// Uninstalls a cask along with its preferences, caches and other files
+    /// outside the app bundle.
+    pub fn zap_cask(name: &str) -> Result<BrewOutput> {
+        Self::execute_brew_with_output(&["uninstall", "--cask", "--zap", name], None)
+    }
+
+    pub fn zap_cask_with_password(name: &str, password: &SecretString) -> Result<BrewOutput> {
+        Self::execute_brew_with_password(&["uninstall", "--cask", "--zap", name], password, None)
+    }
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+    pub fn upgrade_package(name: &str, progress: &ProgressCell) -> Result<BrewOutput> {
+        let output = Self::run_observed(
+            &["upgrade", name],
+            Self::write_timeout(),
+            Askpass::Inherit,
+            Some(progress),
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to upgrade package: {}", stderr));
@@ -216,28 +714,96 @@ impl BrewCommand {
         Ok(BrewOutput { stdout, stderr })
     }
 
-    pub fn upgrade_all() -> Result<BrewOutput> {
-        let output = Command::new("brew").args(["upgrade"]).output()?;
+    /// Downloads `names` into brew's cache without installing them, so they
+    /// can be installed or upgraded later without a connection.
+    pub fn fetch_packages(
+        names: &[String],
+        package_type: PackageType,
+        progress: &ProgressCell,
+    ) -> Result<BrewOutput> {
+        let type_arg = Self::get_package_type_arg(package_type);
+        let mut args = vec!["fetch", type_arg];
+        args.extend(names.iter().map(String::as_str));
+        let output = Self::run_observed(
+            &args,
+            Self::write_timeout(),
+            Askpass::Inherit,
+            Some(progress),
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        if !output.status.success() {
+            return Err(anyhow!("Failed to fetch: {}", stderr));
+        }
+
+        Ok(BrewOutput { stdout, stderr })
+    }
+
+    /// What `upgrade_all` would upgrade, without upgrading anything.
+    pub fn upgrade_all_dry_run() -> Result<String> {
+        // May auto-update first, which takes longer than a query
+        let output = Self::run(
+            &["upgrade", "--dry-run"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
 
         if !output.status.success() {
-            return Err(anyhow!("Failed to upgrade all: {}", stderr));
+            return Err(anyhow!(
+                "Failed to preview upgrade: {}",
+                decode_output(output.stderr)
+            ));
+        }
+
+        Ok(decode_output(output.stdout))
+    }
+
+    /// Runs `brew update`, refreshing the formula and cask metadata.
+    pub fn update_metadata(progress: &ProgressCell) -> Result<BrewOutput> {
+        let output = Self::run_observed(
+            &["update"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+            Some(progress),
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
+        if !output.status.success() {
+            return Err(anyhow!("Failed to update metadata: {}", stderr));
         }
 
         Ok(BrewOutput { stdout, stderr })
     }
 
+    /// Also returns whether brew succeeded. Its output is returned either
+    /// way, since a failed upgrade may still have upgraded some packages.
+    pub fn upgrade_all(progress: &ProgressCell) -> Result<(BrewOutput, bool)> {
+        let output = Self::run_observed(
+            &["upgrade"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+            Some(progress),
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
+        Ok((BrewOutput { stdout, stderr }, output.status.success()))
+    }
+
     pub fn cleanup_dry_run() -> Result<String> {
         Self::execute_brew(&["cleanup", "-s", "--dry-run"])
     }
 
     pub fn cleanup() -> Result<BrewOutput> {
-        let output = Command::new("brew").args(["cleanup", "-s"]).output()?;
+        let output = Self::run(&["cleanup", "-s"], Self::write_timeout(), Askpass::Inherit)?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to cleanup: {}", stderr));
@@ -251,12 +817,14 @@ impl BrewCommand {
     }
 
     pub fn cleanup_old_versions() -> Result<BrewOutput> {
-        let output = Command::new("brew")
-            .args(["cleanup", "--prune=all"])
-            .output()?;
+        let output = Self::run(
+            &["cleanup", "--prune=all"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to cleanup old versions: {}", stderr));
@@ -265,6 +833,48 @@ impl BrewCommand {
         Ok(BrewOutput { stdout, stderr })
     }
 
+    pub fn autoremove_dry_run() -> Result<String> {
+        Self::execute_brew(&["autoremove", "--dry-run"])
+    }
+
+    pub fn autoremove() -> Result<BrewOutput> {
+        let output = Self::run(&["autoremove"], Self::write_timeout(), Askpass::Inherit)?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
+        if !output.status.success() {
+            return Err(anyhow!(
+                "Failed to remove unneeded dependencies: {}",
+                stderr
+            ));
+        }
+
+        Ok(BrewOutput { stdout, stderr })
+    }
+
+    /// Runs `brew audit --strict` on formula `name`. Problems make it exit
+    /// non-zero, so whether it passed is returned with the combined output
+    /// rather than as an error.
+    pub fn audit_package(name: &str) -> Result<(bool, String)> {
+        // Observed so the output is logged as it arrives
+        let output = Self::run_observed(
+            &["audit", "--strict", "--formula", name],
+            Self::write_timeout(),
+            Askpass::Inherit,
+            Some(&ProgressCell::default()),
+        )?;
+
+        let mut text = decode_output(output.stdout);
+        text.push_str(&decode_output(output.stderr));
+        Ok((output.status.success(), text))
+    }
+
+    pub fn dependency_tree(name: &str, package_type: PackageType) -> Result<String> {
+        let type_arg = Self::get_package_type_arg(package_type);
+        Self::execute_brew(&["deps", "--tree", type_arg, name])
+    }
+
     pub fn search_packages(query: &str, package_type: PackageType) -> Result<String> {
         let type_arg = Self::get_package_type_arg(package_type);
         Self::execute_brew(&["search", type_arg, query])
@@ -275,10 +885,10 @@ impl BrewCommand {
     }
 
     pub fn pin_package(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew").args(["pin", name]).output()?;
+        let output = Self::run(&["pin", name], Self::write_timeout(), Askpass::Inherit)?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to pin package: {}", stderr));
@@ -288,10 +898,10 @@ impl BrewCommand {
     }
 
     pub fn unpin_package(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew").args(["unpin", name]).output()?;
+        let output = Self::run(&["unpin", name], Self::write_timeout(), Askpass::Inherit)?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to unpin package: {}", stderr));
@@ -305,13 +915,20 @@ impl BrewCommand {
         Self::execute_brew(&["services", "list"])
     }
 
+    /// `brew services info` for one service, as JSON.
+    pub fn service_info(name: &str) -> Result<String> {
+        Self::execute_brew(&["services", "info", name, "--json"])
+    }
+
     pub fn start_service(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew")
-            .args(["services", "start", name])
-            .output()?;
+        let output = Self::run(
+            &["services", "start", name],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to start service: {}", stderr));
@@ -321,12 +938,14 @@ impl BrewCommand {
     }
 
     pub fn stop_service(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew")
-            .args(["services", "stop", name])
-            .output()?;
+        let output = Self::run(
+            &["services", "stop", name],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to stop service: {}", stderr));
@@ -335,13 +954,52 @@ impl BrewCommand {
         Ok(BrewOutput { stdout, stderr })
     }
 
+    /// Stops every running service in one call.
+    pub fn stop_all_services() -> Result<BrewOutput> {
+        let output = Self::run(
+            &["services", "stop", "--all"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
+        if !output.status.success() {
+            return Err(anyhow!("Failed to stop all services: {}", stderr));
+        }
+
+        Ok(BrewOutput { stdout, stderr })
+    }
+
+    /// Removes the service files of formulae that are no longer installed,
+    /// stopping their services first.
+    pub fn cleanup_services() -> Result<BrewOutput> {
+        let output = Self::run(
+            &["services", "cleanup"],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
+
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
+
+        if !output.status.success() {
+            return Err(anyhow!("Failed to clean up services: {}", stderr));
+        }
+
+        Ok(BrewOutput { stdout, stderr })
+    }
+
     pub fn restart_service(name: &str) -> Result<BrewOutput> {
-        let output = Command::new("brew")
-            .args(["services", "restart", name])
-            .output()?;
+        let output = Self::run(
+            &["services", "restart", name],
+            Self::write_timeout(),
+            Askpass::Inherit,
+        )?;
 
-        let stdout = String::from_utf8(output.stdout)?;
-        let stderr = String::from_utf8(output.stderr)?;
+        let stdout = decode_output(output.stdout);
+        let stderr = decode_output(output.stderr);
 
         if !output.status.success() {
             return Err(anyhow!("Failed to restart service: {}", stderr));
@@ -350,12 +1008,116 @@ impl BrewCommand {
         Ok(BrewOutput { stdout, stderr })
     }
 
+    pub fn prefix() -> Result<String> {
+        Ok(Self::execute_brew_strict(&["--prefix"])?.trim().to_string())
+    }
+
+    /// Git checkout of Homebrew itself.
+    pub fn repository_path() -> Result<String> {
+        Ok(Self::execute_brew_strict(&["--repository"])?
+            .trim()
+            .to_string())
+    }
+
+    /// Directory holding downloaded bottles and casks.
+    pub fn cache_path() -> Result<String> {
+        Ok(Self::execute_brew_strict(&["--cache"])?.trim().to_string())
+    }
+
+    pub fn version() -> Result<String> {
+        Self::execute_brew(&["--version"])
+    }
+
+    pub fn config() -> Result<String> {
+        Self::execute_brew(&["config"])
+    }
+
     // Export package list with versions
     pub fn export_installed() -> Result<String> {
         // Get list of formulae and casks with versions
         let formulae = Self::execute_brew(&["list", "--formula", "--versions"])?;
-        let casks = Self::execute_brew(&["list", "--cask", "--versions"])?;
-        
+        let casks = if Self::casks_supported() {
+            Self::execute_brew(&["list", "--cask", "--versions"])?
+        } else {
+            String::new()
+        };
+
         Ok(format!("FORMULAE\n{}\nCASKS\n{}", formulae, casks))
     }
 }
+
+/// Brew's output as text. Invalid UTF-8, e.g. from an odd package name or
+/// locale, becomes U+FFFD so one bad byte doesn't fail the whole listing.
+fn decode_output(bytes: Vec<u8>) -> String {
+    String::from_utf8(bytes).unwrap_or_else(|e| {
+        tracing::warn!("brew printed invalid UTF-8; replacing the bad bytes");
+        String::from_utf8_lossy(e.as_bytes()).into_owned()
+    })
+}
+
+/// Whether sudo gave up in `output` because it needed a password.
+fn sudo_wanted_password(output: &str) -> bool {
+    output.contains(&format!("sudo: unable to run {}", REFUSED_ASKPASS))
+        || SUDO_PASSWORD_SIGNATURES.iter().any(|s| output.contains(s))
+}
+
+/// Apps launched outside a login shell (Finder, a desktop launcher) often don't
+/// have the Homebrew prefix on PATH, so check the standard install locations
+/// before falling back to a PATH lookup.
+fn resolve_brew_path() -> PathBuf {
+    let from_prefix = std::env::var_os("HOMEBREW_PREFIX")
+        .filter(|p| !p.is_empty())
+        .map(|p| PathBuf::from(p).join("bin").join("brew"));
+
+    from_prefix
+        .into_iter()
+        .chain(default_brew_locations())
+        .find(|p| p.is_file())
+        .or_else(|| std::env::var_os("PATH").and_then(|path| brew_on_path(&path)))
+        .unwrap_or_else(|| PathBuf::from("brew"))
+}
+
+/// The first executable `brew` in the directories of `path`, a PATH value.
+pub(super) fn brew_on_path(path: &OsStr) -> Option<PathBuf> {
+    std::env::split_paths(path)
+        .map(|dir| dir.join("brew"))
+        .find(|candidate| is_executable(candidate))
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(target_os = "macos")]
+fn default_brew_locations() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("/opt/homebrew/bin/brew"),
+        PathBuf::from("/usr/local/bin/brew"),
+    ]
+}
+
+#[cfg(target_os = "linux")]
+fn default_brew_locations() -> Vec<PathBuf> {
+    let mut locations = vec![PathBuf::from("/home/linuxbrew/.linuxbrew/bin/brew")];
+    if let Some(home) = std::env::var_os("HOME") {
+        locations.push(
+            PathBuf::from(home)
+                .join(".linuxbrew")
+                .join("bin")
+                .join("brew"),
+        );
+    }
+    locations
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
+fn default_brew_locations() -> Vec<PathBuf> {
+    Vec::new()
+}
@@ -0,0 +1,125 @@
+//! In-memory audit trail of every brew invocation, so the exact commands
+//! Brewsty ran can be reviewed and reproduced in a terminal.
+
+use chrono::{DateTime, Local};
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+/// Oldest records are dropped beyond this many.
+const MAX_RECORDS: usize = 500;
+
+/// Stands in for values that must never be shown, such as the askpass script path.
+pub const REDACTED: &str = "<redacted>";
+
+static RECORDS: Mutex<VecDeque<CommandRecord>> = Mutex::new(VecDeque::new());
+
+#[derive(Clone, Debug)]
+pub struct CommandRecord {
+    pub args: Vec<String>,
+    /// Environment Brewsty set for the process, with secrets redacted.
+    pub env: Vec<(String, String)>,
+    pub started_at: DateTime<Local>,
+    pub duration: Duration,
+    /// `None` when the process couldn't be started, timed out or was killed by a signal.
+    pub exit_code: Option<i32>,
+    /// Why the command didn't run to completion, if it didn't.
+    pub error: Option<String>,
+    pub stdout_bytes: usize,
+    pub stderr_bytes: usize,
+}
+
+impl CommandRecord {
+    /// The invocation as a command that can be pasted into a terminal. Sudo
+    /// plumbing is left out so the terminal prompts for the password instead,
+    /// and so are redacted values, which would only break the command.
+    pub fn shell_command(&self) -> String {
+        self.env
+            .iter()
+            .filter(|(key, value)| !key.starts_with("SUDO_") && value != REDACTED)
+            .map(|(key, value)| format!("{}={}", key, shell_quote(value)))
+            .chain(std::iter::once(command_line(&self.args)))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// `brew` followed by `args`, quoted for a shell.
+pub fn command_line<S: AsRef<str>>(args: &[S]) -> String {
+    std::iter::once("brew".to_string())
+        .chain(args.iter().map(|arg| shell_quote(arg.as_ref())))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+pub fn record(record: CommandRecord) {
+    let mut records = records();
+    if records.len() >= MAX_RECORDS {
+        records.pop_front();
+    }
+    records.push_back(record);
+}
+
+/// Runs `f` over the recorded commands, oldest first, without copying them.
+pub fn with_records<R>(f: impl FnOnce(&VecDeque<CommandRecord>) -> R) -> R {
+    f(&records())
+}
+
+pub fn clear() {
+    records().clear();
+}
+
+fn records() -> MutexGuard<'static, VecDeque<CommandRecord>> {
+    RECORDS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn shell_quote(value: &str) -> String {
+    let safe = !value.is_empty()
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./=@:+,%".contains(c));
+    if safe {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', "'\\''"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record_with(args: &[&str], env: &[(&str, &str)]) -> CommandRecord {
+        CommandRecord {
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: env
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+            started_at: Local::now(),
+            duration: Duration::ZERO,
+            exit_code: Some(0),
+            error: None,
+            stdout_bytes: 0,
+            stderr_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn shell_command_quotes_args_and_drops_sudo_env() {
+        let record = record_with(
+            &["search", "--formula", "it's here"],
+            &[
+                ("HOMEBREW_NO_AUTO_UPDATE", "1"),
+                ("HOMEBREW_GITHUB_API_TOKEN", REDACTED),
+                ("SUDO_ASKPASS", REDACTED),
+                ("SUDO_ASKPASS_REQUIRE", "force"),
+            ],
+        );
+
+        assert_eq!(
+            record.shell_command(),
+            "HOMEBREW_NO_AUTO_UPDATE=1 brew search --formula 'it'\\''s here'"
+        );
+    }
+}
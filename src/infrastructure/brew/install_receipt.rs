@@ -0,0 +1,72 @@
+//! Reads when a package was installed from Homebrew's own records under the
+//! prefix, without running brew for every package.
+
+use crate::domain::entities::{Package, PackageType};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Formulae record the install time in this file in their keg.
+const RECEIPT_FILE: &str = "INSTALL_RECEIPT.json";
+
+/// The install time of `package` below `prefix`: the receipt's `time` for
+/// formulae, otherwise when the version's directory was created.
+pub fn install_time(prefix: &Path, package: &Package) -> Option<SystemTime> {
+    let dir = install_dir(prefix, package)?;
+
+    if package.package_type == PackageType::Formula
+        && let Some(time) = fs::read_to_string(dir.join(RECEIPT_FILE))
+            .ok()
+            .and_then(|receipt| receipt_time(&receipt))
+    {
+        return Some(time);
+    }
+
+    fs::metadata(&dir).and_then(|m| m.modified()).ok()
+}
+
+fn install_dir(prefix: &Path, package: &Package) -> Option<PathBuf> {
+    let version = package.version.as_deref().filter(|v| !v.is_empty())?;
+    let root = match package.package_type {
+        PackageType::Formula => "Cellar",
+        PackageType::Cask => "Caskroom",
+    };
+    Some(prefix.join(root).join(&package.name).join(version))
+}
+
+fn receipt_time(receipt: &str) -> Option<SystemTime> {
+    let value: Value = serde_json::from_str(receipt).ok()?;
+    let secs = value.get("time")?.as_u64()?;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_the_receipt_time_and_falls_back_to_the_directory() {
+        let prefix = std::env::temp_dir().join(format!("brewsty-receipt-{}", std::process::id()));
+        let jq = prefix.join("Cellar/jq/1.7.1");
+        fs::create_dir_all(&jq).unwrap();
+        fs::write(jq.join(RECEIPT_FILE), r#"{"time": 1700000000}"#).unwrap();
+        fs::create_dir_all(prefix.join("Caskroom/firefox/130.0")).unwrap();
+
+        let formula =
+            Package::new("jq".to_string(), PackageType::Formula).with_version("1.7.1".to_string());
+        let cask = Package::new("firefox".to_string(), PackageType::Cask)
+            .with_version("130.0".to_string());
+        let missing =
+            Package::new("wget".to_string(), PackageType::Formula).with_version("1.0".to_string());
+
+        assert_eq!(
+            install_time(&prefix, &formula),
+            Some(UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        assert!(install_time(&prefix, &cask).is_some());
+        assert_eq!(install_time(&prefix, &missing), None);
+
+        fs::remove_dir_all(&prefix).unwrap();
+    }
+}
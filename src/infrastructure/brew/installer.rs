@@ -0,0 +1,57 @@
+//! Installs Homebrew itself with the official install script, for machines
+//! where brew wasn't found.
+
+use crate::infrastructure::brew::command::BrewCommand;
+use anyhow::{Result, anyhow};
+use secrecy::SecretString;
+use std::fs;
+use std::process::Command;
+use std::time::Duration;
+
+pub const INSTALL_SCRIPT_URL: &str =
+    "https://raw.githubusercontent.com/Homebrew/install/HEAD/install.sh";
+
+/// The script downloads and builds up a whole prefix, which can take a while
+/// on a slow connection or when the Command Line Tools are installed too.
+const INSTALL_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Runs the install script, logging its output as it goes. Blocking.
+///
+/// The script's sudo steps get `password` through an askpass script, and
+/// its "Press RETURN to continue" prompt is answered up front: the user
+/// already confirmed in the app.
+pub fn install_homebrew(password: &SecretString) -> Result<()> {
+    let askpass_path = BrewCommand::create_askpass_script(password)?;
+
+    let mut command = Command::new("/bin/bash");
+    command
+        .arg("-c")
+        .arg(format!(
+            r#"/bin/bash -c "$(curl -fsSL {})""#,
+            INSTALL_SCRIPT_URL
+        ))
+        // Without a terminal the script would otherwise refuse to use sudo
+        .env("INTERACTIVE", "1")
+        .env_remove("NONINTERACTIVE")
+        .env("SUDO_ASKPASS", &askpass_path);
+
+    tracing::info!(
+        "Running the Homebrew install script from {}",
+        INSTALL_SCRIPT_URL
+    );
+    let result = BrewCommand::stream_with_timeout(&mut command, INSTALL_TIMEOUT, b"\n");
+
+    // Clean up the askpass script before anything else can bail out
+    let _ = fs::remove_file(&askpass_path);
+
+    let (status, output) = result?;
+    if !status.success() {
+        let reason = output
+            .lines()
+            .rev()
+            .find(|line| !line.trim().is_empty())
+            .unwrap_or("no output");
+        return Err(anyhow!("The Homebrew install script failed: {}", reason));
+    }
+    Ok(())
+}
@@ -1,8 +1,17 @@
 pub mod command;
+pub mod command_log;
+pub mod install_receipt;
+pub mod installer;
 pub mod package_list_repository;
+pub mod paths;
+pub mod process_registry;
+mod progress;
 pub mod repository;
 pub mod service_repository;
 
+#[cfg(all(test, unix))]
+mod smoke_tests;
+
 pub use package_list_repository::BrewPackageListRepository;
 pub use repository::BrewPackageRepository;
 pub use service_repository::BrewServiceRepository;
@@ -1,5 +1,5 @@
 use crate::domain::{
-    entities::{PackageList, PackageListItem, PackageType},
+    entities::{PackageList, PackageListItem, PackageType, ProgressCell},
     repositories::PackageListRepository,
 };
 use crate::infrastructure::brew::command::BrewCommand;
@@ -72,7 +72,7 @@ impl BrewPackageListRepository {
 #[async_trait]
 impl PackageListRepository for BrewPackageListRepository {
     async fn export_package_list(&self) -> Result<PackageList> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::export_installed()).await??;
+        let output = tokio::task::spawn_blocking(BrewCommand::export_installed).await??;
         self.parse_package_list(&output)
     }
 
@@ -86,7 +86,7 @@ impl PackageListRepository for BrewPackageListRepository {
             let package_type = item.package_type.clone();
 
             match tokio::task::spawn_blocking(move || {
-                BrewCommand::install_package(&name, package_type)
+                BrewCommand::install_package(&name, package_type, &ProgressCell::default())
             })
             .await?
             {
@@ -107,7 +107,7 @@ impl PackageListRepository for BrewPackageListRepository {
             let package_type = item.package_type.clone();
 
             match tokio::task::spawn_blocking(move || {
-                BrewCommand::install_package(&name, package_type)
+                BrewCommand::install_package(&name, package_type, &ProgressCell::default())
             })
             .await?
             {
@@ -0,0 +1,58 @@
+//! Where Homebrew keeps its files.
+
+use crate::domain::entities::PackageType;
+use crate::infrastructure::brew::command::BrewCommand;
+use anyhow::{Result, bail};
+use std::path::PathBuf;
+
+#[derive(Debug, Clone)]
+pub struct BrewPaths {
+    pub prefix: PathBuf,
+    /// Homebrew's own git checkout.
+    pub repository: PathBuf,
+    /// Downloaded bottles and casks.
+    pub cache: PathBuf,
+}
+
+impl BrewPaths {
+    /// Blocking; asks brew for each path.
+    pub fn resolve() -> Result<Self> {
+        Ok(Self {
+            prefix: PathBuf::from(BrewCommand::prefix()?),
+            repository: PathBuf::from(BrewCommand::repository_path()?),
+            cache: PathBuf::from(BrewCommand::cache_path()?),
+        })
+    }
+
+    pub fn cellar(&self) -> PathBuf {
+        self.prefix.join("Cellar")
+    }
+
+    pub fn caskroom(&self) -> PathBuf {
+        self.prefix.join("Caskroom")
+    }
+
+    /// Written by every `brew update`, so its modification time is when the
+    /// metadata was last refreshed.
+    pub fn fetch_head(&self) -> PathBuf {
+        self.repository.join(".git").join("FETCH_HEAD")
+    }
+
+    /// Where an installed package lives: its `opt` link for formulae, its
+    /// Caskroom folder for casks. Fails if that isn't there, which means the
+    /// install is broken.
+    pub fn package_dir(&self, name: &str, package_type: &PackageType) -> Result<PathBuf> {
+        let dir = match package_type {
+            PackageType::Formula => self.prefix.join("opt").join(name),
+            PackageType::Cask => self.caskroom().join(name),
+        };
+        if !dir.exists() {
+            bail!(
+                "{} isn't where Homebrew installs it ({}); the install may be broken",
+                name,
+                dir.display()
+            );
+        }
+        Ok(dir)
+    }
+}
@@ -0,0 +1,82 @@
+//! Tracks brew processes started by `BrewCommand` so they can be stopped when
+//! the app exits instead of carrying on detached.
+
+use std::process::Child;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a process gets to exit after SIGTERM before it is killed.
+const TERMINATE_GRACE: Duration = Duration::from_millis(500);
+
+static RUNNING: Mutex<Vec<Arc<Mutex<Child>>>> = Mutex::new(Vec::new());
+
+/// A registered child process; unregistered again when dropped.
+pub struct TrackedChild {
+    child: Arc<Mutex<Child>>,
+}
+
+impl TrackedChild {
+    pub fn track(child: Child) -> Self {
+        let child = Arc::new(Mutex::new(child));
+        running().push(Arc::clone(&child));
+        Self { child }
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Child> {
+        self.child.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Drop for TrackedChild {
+    fn drop(&mut self) {
+        running().retain(|c| !Arc::ptr_eq(c, &self.child));
+    }
+}
+
+fn running() -> MutexGuard<'static, Vec<Arc<Mutex<Child>>>> {
+    RUNNING.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Stops `child` and everything it spawned. Children are started in their own
+/// process group, so curl/git/ruby helpers that brew launched go down with it.
+pub fn terminate(child: &mut Child) {
+    if matches!(child.try_wait(), Ok(Some(_))) {
+        return;
+    }
+
+    #[cfg(unix)]
+    {
+        let group = -(child.id() as libc::pid_t);
+        // SAFETY: kill(2) has no memory-safety preconditions
+        unsafe { libc::kill(group, libc::SIGTERM) };
+
+        let deadline = Instant::now() + TERMINATE_GRACE;
+        while Instant::now() < deadline {
+            if matches!(child.try_wait(), Ok(Some(_))) {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        // SAFETY: as above
+        unsafe { libc::kill(group, libc::SIGKILL) };
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// Stops every brew process that is still running. Returns how many were stopped.
+pub fn terminate_all() -> usize {
+    let children: Vec<_> = running().drain(..).collect();
+    let mut stopped = 0;
+    for child in children {
+        let mut child = child.lock().unwrap_or_else(|e| e.into_inner());
+        if matches!(child.try_wait(), Ok(None)) {
+            terminate(&mut child);
+            stopped += 1;
+        }
+    }
+    stopped
+}
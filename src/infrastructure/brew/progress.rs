@@ -0,0 +1,218 @@
+//! Reads download progress out of brew's install and upgrade output.
+
+use crate::domain::entities::OperationProgress;
+
+/// Steps brew announces once everything it needs is downloaded.
+const INSTALL_STEPS: &[&str] = &["Pouring", "Installing", "Linking", "Moving", "Summary"];
+
+/// What `line` says about the operation, given what was known before it.
+/// `None` when the line says nothing new.
+pub(super) fn parse_line(
+    line: &str,
+    current: Option<&OperationProgress>,
+) -> Option<OperationProgress> {
+    let line = line.trim();
+    let current_file = match current {
+        Some(OperationProgress::Downloading { file, .. }) => Some(file.as_str()),
+        _ => None,
+    };
+
+    if let Some(step) = line.strip_prefix("==> ") {
+        if let Some(file) = step.strip_prefix("Fetching ") {
+            // "Fetching dependencies for jq: oniguruma" only lists what comes next
+            if file.starts_with("dependencies") || file.starts_with("downloads") {
+                return None;
+            }
+            return Some(downloading(file, None));
+        }
+        if let Some(url) = step.strip_prefix("Downloading ") {
+            // Casks print "Downloading from <url>" again after a redirect
+            let url = url.strip_prefix("from ").unwrap_or(url);
+            let file = current_file.map_or_else(|| file_name(url), str::to_string);
+            return Some(downloading(&file, None));
+        }
+        if INSTALL_STEPS.iter().any(|s| step.starts_with(s)) {
+            return Some(OperationProgress::Installing);
+        }
+        return None;
+    }
+
+    if let Some(path) = line.strip_prefix("Already downloaded: ") {
+        let file = current_file.map_or_else(|| file_name(path), str::to_string);
+        return Some(downloading(&file, Some(100.0)));
+    }
+
+    let percent = bar_percent(line)?;
+    Some(downloading(current_file.unwrap_or_default(), Some(percent)))
+}
+
+/// The percentage of a curl progress bar like `#########     45.2%`.
+pub(super) fn bar_percent(line: &str) -> Option<f32> {
+    let rest = line.strip_suffix('%')?;
+    let start = rest
+        .char_indices()
+        .rev()
+        .find(|&(_, c)| !(c.is_ascii_digit() || c == '.'))
+        .map_or(0, |(i, c)| i + c.len_utf8());
+    let (bar, number) = rest.split_at(start);
+    if !bar.chars().all(|c| c == '#' || c.is_whitespace()) {
+        return None;
+    }
+    number.parse::<f32>().ok().map(|p| p.clamp(0.0, 100.0))
+}
+
+/// Whether `line` is curl's progress bar, with or without a percentage yet.
+/// Before the size is known it animates marks like `#=#=#` or `-=O=-  #`.
+pub(super) fn is_bar(line: &str) -> bool {
+    let line = line.trim();
+    bar_percent(line).is_some()
+        || (!line.is_empty()
+            && line
+                .chars()
+                .all(|c| matches!(c, '#' | '=' | 'O' | '-') || c.is_whitespace()))
+}
+
+/// The file a download URL points at, as its last path segment without a
+/// query and with escapes like `%20` decoded, as cask URLs often have both.
+fn file_name(url: &str) -> String {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let segment = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path);
+    percent_decode(segment)
+}
+
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+fn downloading(file: &str, percent: Option<f32>) -> OperationProgress {
+    OperationProgress::Downloading {
+        file: file.to_string(),
+        percent,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_an_install_from_download_to_pour() {
+        let output = [
+            "==> Fetching dependencies for jq: oniguruma",
+            "==> Fetching oniguruma",
+            "==> Downloading https://ghcr.io/v2/homebrew/core/oniguruma/blobs/sha256:abc",
+            "######                                                      9.1%",
+            "##################################                         56.0%",
+            "==> Fetching jq",
+            "Already downloaded: /Users/me/Library/Caches/Homebrew/downloads/jq--1.7.1.bottle.tar.gz",
+            "==> Installing dependencies for jq: oniguruma",
+            "==> Pouring oniguruma--6.9.9.arm64_sonoma.bottle.tar.gz",
+            "Error: something unrelated",
+        ];
+
+        let mut states = Vec::new();
+        let mut current = None;
+        for line in output {
+            if let Some(progress) = parse_line(line, current.as_ref()) {
+                states.push(progress.clone());
+                current = Some(progress);
+            }
+        }
+
+        assert_eq!(
+            states,
+            [
+                downloading("oniguruma", None),
+                downloading("oniguruma", None),
+                downloading("oniguruma", Some(9.1)),
+                downloading("oniguruma", Some(56.0)),
+                downloading("jq", None),
+                downloading("jq", Some(100.0)),
+                OperationProgress::Installing,
+                OperationProgress::Installing,
+            ]
+        );
+    }
+
+    #[test]
+    fn downloads_without_a_fetch_line_are_named_after_the_url() {
+        let progress = parse_line(
+            "==> Downloading https://example.com/releases/App-2.0.dmg",
+            Some(&OperationProgress::Installing),
+        );
+        assert_eq!(progress, Some(downloading("App-2.0.dmg", None)));
+    }
+
+    #[test]
+    fn follows_a_cask_download_through_a_redirect() {
+        let output = [
+            "==> Downloading https://download.example.com/releases/121.0/App%20121.0.dmg?lang=en-US",
+            "==> Downloading from https://cdn.example.com/a1b2c3/App%20121.0.dmg",
+            "#=#=#",
+            "###########                                                16.4%",
+            "######################################################### 100.0%",
+            "==> Installing Cask app",
+            "==> Moving App 'App.app' to '/Applications/App.app'",
+        ];
+
+        let mut states = Vec::new();
+        let mut current = None;
+        for line in output {
+            if let Some(progress) = parse_line(line, current.as_ref()) {
+                states.push(progress.clone());
+                current = Some(progress);
+            }
+        }
+
+        assert_eq!(
+            states,
+            [
+                downloading("App 121.0.dmg", None),
+                downloading("App 121.0.dmg", None),
+                downloading("App 121.0.dmg", Some(16.4)),
+                downloading("App 121.0.dmg", Some(100.0)),
+                OperationProgress::Installing,
+                OperationProgress::Installing,
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_progress_bars() {
+        assert_eq!(bar_percent("  0.0%"), Some(0.0));
+        assert_eq!(bar_percent("######################## 100.0%"), Some(100.0));
+        assert_eq!(bar_percent("#=#=#"), None);
+        assert_eq!(bar_percent("Disk usage: 45%"), None);
+        assert_eq!(bar_percent("Progress →45%"), None);
+        assert_eq!(bar_percent("\u{FFFD}45%"), None);
+        assert!(!is_bar("█████ 45%"));
+
+        assert!(is_bar("##O=#  #"));
+        assert!(is_bar("#####    45.0%"));
+        assert!(!is_bar("==> Installing Cask app"));
+        assert!(!is_bar(""));
+    }
+}
@@ -1,12 +1,19 @@
 use crate::domain::{
-    entities::{CleanupItem, CleanupPreview, Package, PackageType},
+    entities::{
+        AuditResult, CacheInfo, CleanupItem, CleanupPreview, CpuArch, DependencyNode, Package,
+        PackageType, PlannedUpgrade, ProgressCell, UpgradeReport,
+    },
     repositories::PackageRepository,
 };
-use crate::infrastructure::brew::command::BrewCommand;
-use anyhow::Result;
+use crate::infrastructure::brew::command::{BrewCommand, BrewOutput};
+use crate::infrastructure::brew::install_receipt;
+use crate::infrastructure::disk_usage;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
 use serde_json::Value;
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 pub struct BrewPackageRepository;
 
@@ -178,18 +185,7 @@ impl BrewPackageRepository {
             };
 
             if let Some(path_str) = path_str_opt {
-                let path = Path::new(path_str);
-                let size = if path.exists() {
-                    if path.is_file() {
-                        std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
-                    } else if path.is_dir() {
-                        self.calculate_dir_size(path).unwrap_or(0)
-                    } else {
-                        0
-                    }
-                } else {
-                    0
-                };
+                let size = disk_usage::path_size(Path::new(path_str));
 
                 total_size += size;
                 items.push(CleanupItem {
@@ -202,20 +198,235 @@ impl BrewPackageRepository {
         Ok(CleanupPreview { items, total_size })
     }
 
-    fn calculate_dir_size(&self, path: &Path) -> Result<u64> {
-        let mut total = 0u64;
-        if path.is_dir() {
-            for entry in std::fs::read_dir(path)? {
-                let entry = entry?;
-                let metadata = entry.metadata()?;
-                if metadata.is_file() {
-                    total += metadata.len();
-                } else if metadata.is_dir() {
-                    total += self.calculate_dir_size(&entry.path())?;
+    /// Reads the names `brew autoremove --dry-run` lists under its "Would
+    /// autoremove" (or, in older versions, "Would uninstall") heading.
+    fn parse_autoremove_dry_run(output: &str) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut in_list = false;
+        for line in output.lines().map(str::trim) {
+            if let Some(heading) = line.strip_prefix("==> ") {
+                in_list = heading.contains("unneeded");
+            } else if in_list {
+                names.extend(line.split_whitespace().map(str::to_string));
+            }
+        }
+        names
+    }
+
+    fn package_type_supported(package_type: &PackageType) -> bool {
+        matches!(package_type, PackageType::Formula) || BrewCommand::casks_supported()
+    }
+
+    /// Reads the `name old -> new` lines `brew upgrade --dry-run` prints under
+    /// its "Would upgrade" headings.
+    fn parse_upgrade_dry_run(output: &str) -> Vec<PlannedUpgrade> {
+        Self::parse_upgrade_list(output, |heading| heading.starts_with("Would upgrade"))
+    }
+
+    /// Tells which packages `brew upgrade` upgraded. It lists what it is
+    /// about to upgrade, then names each package that fails in an `Error:`
+    /// line; when it failed without naming any, none is taken as upgraded.
+    fn parse_upgrade_output(output: &BrewOutput, success: bool) -> UpgradeReport {
+        let planned: Vec<String> = Self::parse_upgrade_list(&output.stdout, |heading| {
+            heading.starts_with("Upgrading ") && heading.ends_with(':')
+        })
+        .into_iter()
+        .map(|upgrade| upgrade.name)
+        .collect();
+
+        let mut failed: Vec<(String, String)> = Vec::new();
+        // Several failures are listed one per line under a single `Error:`
+        let mut in_errors = false;
+        for line in output.stderr.lines().map(str::trim) {
+            let message = match line.strip_prefix("Error:") {
+                Some(message) => {
+                    in_errors = true;
+                    message.trim()
+                }
+                None if in_errors && !line.is_empty() => line,
+                None => {
+                    in_errors = false;
+                    continue;
                 }
+            };
+            let named = match message.split_once(": ") {
+                Some((name, reason)) if planned.iter().any(|p| p == name) => Some((name, reason)),
+                _ => message
+                    .split(|c: char| !(c.is_alphanumeric() || "@+._-".contains(c)))
+                    .find(|word| planned.iter().any(|p| p == word))
+                    .map(|name| (name, message)),
+            };
+            if let Some((name, reason)) = named
+                && !failed.iter().any(|(n, _)| n == name)
+            {
+                failed.push((name.to_string(), reason.to_string()));
+            }
+        }
+
+        if !success && failed.is_empty() {
+            let reason = output
+                .stderr
+                .lines()
+                .rev()
+                .find_map(|line| line.trim().strip_prefix("Error:"))
+                .map_or("brew upgrade failed", str::trim);
+            failed = planned
+                .iter()
+                .map(|name| (name.clone(), reason.to_string()))
+                .collect();
+        }
+
+        let upgraded = planned
+            .into_iter()
+            .filter(|name| !failed.iter().any(|(n, _)| n == name))
+            .collect();
+        UpgradeReport { upgraded, failed }
+    }
+
+    /// Reads the `name old -> new` lines brew prints under the headings
+    /// `is_list` accepts.
+    fn parse_upgrade_list(output: &str, is_list: impl Fn(&str) -> bool) -> Vec<PlannedUpgrade> {
+        let mut upgrades = Vec::new();
+        let mut in_list = false;
+        for line in output.lines() {
+            let line = line.trim();
+            if let Some(heading) = line.strip_prefix("==> ") {
+                in_list = is_list(heading);
+                continue;
+            }
+            if !in_list {
+                continue;
+            }
+            let Some((installed, new_version)) = line.split_once(" -> ") else {
+                continue;
+            };
+            let (name, current_version) = match installed.split_once(' ') {
+                Some((name, versions)) => (name, Some(versions.trim().to_string())),
+                None => (installed, None),
+            };
+            upgrades.push(PlannedUpgrade {
+                name: name.to_string(),
+                current_version,
+                new_version: new_version.trim().to_string(),
+            });
+        }
+        upgrades
+    }
+
+    /// Collects the `* problem` lines `brew audit` prints under each formula.
+    /// Falls back to its `Error:` lines when it failed without listing any.
+    fn parse_audit_output(passed: bool, output: &str) -> AuditResult {
+        let lines = || output.lines().map(str::trim);
+        let mut findings: Vec<String> = lines()
+            .filter_map(|line| line.strip_prefix("* "))
+            .map(str::to_string)
+            .collect();
+        if !passed && findings.is_empty() {
+            findings = lines()
+                .filter(|line| line.starts_with("Error:"))
+                .map(str::to_string)
+                .collect();
+        }
+        AuditResult { passed, findings }
+    }
+
+    /// The architectures in a cask's `depends_on.arch`, e.g.
+    /// `[{"type": "intel", "bits": 64}]`; empty when it doesn't limit them.
+    fn parse_supported_archs(item: &Value) -> Vec<CpuArch> {
+        let Some(archs) = item
+            .get("depends_on")
+            .and_then(|d| d.get("arch"))
+            .and_then(|a| a.as_array())
+        else {
+            return Vec::new();
+        };
+        let mut supported = Vec::new();
+        for arch in archs {
+            let name = arch.get("type").or(Some(arch)).and_then(|t| t.as_str());
+            let arch = match name {
+                Some("intel" | "x86_64") => CpuArch::Intel,
+                Some("arm" | "arm64") => CpuArch::Arm,
+                _ => continue,
+            };
+            if !supported.contains(&arch) {
+                supported.push(arch);
+            }
+        }
+        supported
+    }
+
+    /// The package one item of `brew info --json=v2` output describes.
+    fn parse_package_info(item: &Value, name: String, package_type: PackageType) -> Package {
+        let version = item
+            .get("version")
+            .or_else(|| item.get("versions").and_then(|v| v.get("stable")))
+            .and_then(|v| v.as_str())
+            .map(String::from);
+
+        let description = item.get("desc").and_then(|v| v.as_str()).map(String::from);
+
+        let mut package = Package::new(name, package_type);
+        if package.package_type == PackageType::Cask {
+            package = package
+                .with_supported_archs(Self::parse_supported_archs(item))
+                .set_auto_updates(Self::parse_auto_updates(item));
+        }
+        if let Some(v) = version {
+            package = package.with_version(v);
+        }
+        if let Some(d) = description {
+            package = package.with_description(d);
+        }
+        package
+    }
+
+    fn parse_auto_updates(item: &Value) -> bool {
+        item.get("auto_updates")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// Builds the tree from `brew deps --tree`, where each level is indented
+    /// by four characters of box drawing under the package on the first line.
+    fn parse_dependency_tree(output: &str) -> Option<DependencyNode> {
+        let mut lines = output.lines().filter(|line| !line.trim().is_empty());
+        let root = lines.next()?.trim().to_string();
+
+        // The path from the root to the last node read
+        let mut path = vec![DependencyNode::new(root)];
+        let close_last = |path: &mut Vec<DependencyNode>| {
+            if let Some(node) = path.pop()
+                && let Some(parent) = path.last_mut()
+            {
+                parent.children.push(node);
             }
+        };
+
+        for line in lines {
+            let name = line.trim_start_matches(['│', '├', '└', '─', ' ', '\u{a0}']);
+            let depth = (line.chars().count() - name.chars().count()) / 4;
+            while path.len() > depth.max(1) {
+                close_last(&mut path);
+            }
+            path.push(DependencyNode::new(name.trim().to_string()));
         }
-        Ok(total)
+        while path.len() > 1 {
+            close_last(&mut path);
+        }
+        path.pop()
+    }
+
+    async fn fetch(
+        names: Vec<String>,
+        package_type: PackageType,
+        progress: &ProgressCell,
+    ) -> Result<()> {
+        let progress = progress.clone();
+        tokio::task::spawn_blocking(move || {
+            BrewCommand::fetch_packages(&names, package_type, &progress)
+        })
+        .await??;
+        Ok(())
     }
 
     async fn log_brew_output(output: &crate::infrastructure::brew::command::BrewOutput) {
@@ -232,6 +443,9 @@ impl BrewPackageRepository {
 impl PackageRepository for BrewPackageRepository {
     async fn get_installed_packages(&self, package_type: PackageType) -> Result<Vec<Package>> {
         tracing::info!("get_installed_packages called for {:?}", package_type);
+        if !Self::package_type_supported(&package_type) {
+            return Ok(Vec::new());
+        }
         let package_type_clone = package_type.clone();
         let output =
             tokio::task::spawn_blocking(move || BrewCommand::list_packages(package_type_clone))
@@ -246,6 +460,9 @@ impl PackageRepository for BrewPackageRepository {
     }
 
     async fn get_outdated_packages(&self, package_type: PackageType) -> Result<Vec<Package>> {
+        if !Self::package_type_supported(&package_type) {
+            return Ok(Vec::new());
+        }
         let package_type_clone = package_type.clone();
         let output =
             tokio::task::spawn_blocking(move || BrewCommand::outdated_packages(package_type_clone))
@@ -253,19 +470,26 @@ impl PackageRepository for BrewPackageRepository {
         self.parse_outdated_json(&output, package_type)
     }
 
-    async fn install_package(&self, package: &Package) -> Result<()> {
+    async fn install_package(&self, package: &Package, progress: &ProgressCell) -> Result<()> {
         let name = package.name.clone();
         let package_type = package.package_type.clone();
+        let progress = progress.clone();
 
-        let output =
-            tokio::task::spawn_blocking(move || BrewCommand::install_package(&name, package_type))
-                .await??;
-
-        Self::log_brew_output(&output).await;
+        // The output was logged as it arrived
+        tokio::task::spawn_blocking(move || {
+            BrewCommand::install_package(&name, package_type, &progress)
+        })
+        .await??;
 
         Ok(())
     }
 
+    async fn is_installed(&self, package: &Package) -> Result<bool> {
+        let name = package.name.clone();
+        let package_type = package.package_type.clone();
+        tokio::task::spawn_blocking(move || BrewCommand::is_installed(&name, package_type)).await?
+    }
+
     async fn uninstall_package(&self, package: &Package) -> Result<()> {
         let name = package.name.clone();
         let package_type = package.package_type.clone();
@@ -280,38 +504,157 @@ impl PackageRepository for BrewPackageRepository {
         Ok(())
     }
 
-    async fn update_package(&self, package: &Package) -> Result<()> {
+    async fn zap_cask(&self, package: &Package) -> Result<()> {
         let name = package.name.clone();
 
-        let output =
-            tokio::task::spawn_blocking(move || BrewCommand::upgrade_package(&name)).await??;
+        let output = tokio::task::spawn_blocking(move || BrewCommand::zap_cask(&name)).await??;
 
         Self::log_brew_output(&output).await;
 
         Ok(())
     }
 
-    async fn update_all(&self) -> Result<()> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::upgrade_all()).await??;
+    async fn update_package(&self, package: &Package, progress: &ProgressCell) -> Result<()> {
+        let name = package.name.clone();
+        let progress = progress.clone();
 
-        Self::log_brew_output(&output).await;
+        // The output was logged as it arrived
+        tokio::task::spawn_blocking(move || BrewCommand::upgrade_package(&name, &progress))
+            .await??;
+
+        Ok(())
+    }
+
+    async fn audit_package(&self, package: &Package) -> Result<AuditResult> {
+        let name = package.name.clone();
+        let (passed, output) =
+            tokio::task::spawn_blocking(move || BrewCommand::audit_package(&name)).await??;
+        Ok(Self::parse_audit_output(passed, &output))
+    }
+
+    async fn dependency_tree(&self, package: &Package) -> Result<DependencyNode> {
+        let name = package.name.clone();
+        let package_type = package.package_type.clone();
+        let output =
+            tokio::task::spawn_blocking(move || BrewCommand::dependency_tree(&name, package_type))
+                .await??;
+        Self::parse_dependency_tree(&output)
+            .ok_or_else(|| anyhow!("brew deps printed nothing for {}", package.name))
+    }
+
+    async fn get_upgrade_preview(&self) -> Result<Vec<PlannedUpgrade>> {
+        let output = tokio::task::spawn_blocking(BrewCommand::upgrade_all_dry_run).await??;
+        Ok(Self::parse_upgrade_dry_run(&output))
+    }
+
+    async fn fetch_packages(
+        &self,
+        packages: &[Package],
+        progress: &ProgressCell,
+    ) -> Result<Vec<String>> {
+        let mut fetched = Vec::new();
+        for package_type in [PackageType::Formula, PackageType::Cask] {
+            let names: Vec<String> = packages
+                .iter()
+                .filter(|p| p.package_type == package_type)
+                .map(|p| p.name.clone())
+                .collect();
+            if names.is_empty() {
+                continue;
+            }
+
+            match Self::fetch(names.clone(), package_type.clone(), progress).await {
+                Ok(()) => fetched.extend(names),
+                Err(e) if names.len() == 1 => {
+                    tracing::warn!("Failed to fetch {}: {}", names[0], e);
+                }
+                Err(e) => {
+                    // brew doesn't say which downloads failed; what did
+                    // succeed is cached, so fetching one at a time is quick
+                    tracing::warn!("Fetching failed, retrying one at a time: {}", e);
+                    for name in names {
+                        match Self::fetch(vec![name.clone()], package_type.clone(), progress).await
+                        {
+                            Ok(()) => fetched.push(name),
+                            Err(e) => tracing::warn!("Failed to fetch {}: {}", name, e),
+                        }
+                    }
+                }
+            }
+        }
+        Ok(fetched)
+    }
 
+    async fn update_metadata(&self, progress: &ProgressCell) -> Result<()> {
+        let progress = progress.clone();
+        // The output was logged as it arrived
+        tokio::task::spawn_blocking(move || BrewCommand::update_metadata(&progress)).await??;
         Ok(())
     }
 
+    async fn update_all(&self, progress: &ProgressCell) -> Result<UpgradeReport> {
+        let progress = progress.clone();
+        let (output, success) =
+            tokio::task::spawn_blocking(move || BrewCommand::upgrade_all(&progress)).await??;
+        let report = Self::parse_upgrade_output(&output, success);
+        if !success && report.failed.is_empty() {
+            return Err(anyhow!("Failed to upgrade all: {}", output.stderr));
+        }
+        Ok(report)
+    }
+
     async fn get_cleanup_preview(&self) -> Result<CleanupPreview> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::cleanup_dry_run()).await??;
+        let output = tokio::task::spawn_blocking(BrewCommand::cleanup_dry_run).await??;
         self.parse_cleanup_output(&output)
     }
 
     async fn get_cleanup_old_versions_preview(&self) -> Result<CleanupPreview> {
         let output =
-            tokio::task::spawn_blocking(|| BrewCommand::cleanup_old_versions_dry_run()).await??;
+            tokio::task::spawn_blocking(BrewCommand::cleanup_old_versions_dry_run).await??;
         self.parse_cleanup_output(&output)
     }
 
+    async fn get_autoremove_preview(&self, cellar: &Path) -> Result<CleanupPreview> {
+        let cellar = cellar.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            let output = BrewCommand::autoremove_dry_run()?;
+            let items: Vec<CleanupItem> = Self::parse_autoremove_dry_run(&output)
+                .into_iter()
+                .map(|name| {
+                    let path = cellar.join(name);
+                    CleanupItem {
+                        size: disk_usage::path_size(&path),
+                        path: path.display().to_string(),
+                    }
+                })
+                .collect();
+            let total_size = items.iter().map(|item| item.size).sum();
+            Ok(CleanupPreview { items, total_size })
+        })
+        .await?
+    }
+
+    async fn autoremove(&self) -> Result<()> {
+        let output = tokio::task::spawn_blocking(BrewCommand::autoremove).await??;
+
+        Self::log_brew_output(&output).await;
+
+        Ok(())
+    }
+
+    async fn get_cache_info(&self, cache: &Path) -> Result<CacheInfo> {
+        let cache = cache.to_path_buf();
+        tokio::task::spawn_blocking(move || {
+            Ok(CacheInfo {
+                total_size: disk_usage::dir_size(&cache)?,
+                package_count: disk_usage::file_count(&cache.join("downloads")).unwrap_or(0),
+            })
+        })
+        .await?
+    }
+
     async fn clean_cache(&self) -> Result<()> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::cleanup()).await??;
+        let output = tokio::task::spawn_blocking(BrewCommand::cleanup).await??;
 
         Self::log_brew_output(&output).await;
 
@@ -319,7 +662,7 @@ impl PackageRepository for BrewPackageRepository {
     }
 
     async fn cleanup_old_versions(&self) -> Result<()> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::cleanup_old_versions()).await??;
+        let output = tokio::task::spawn_blocking(BrewCommand::cleanup_old_versions).await??;
 
         Self::log_brew_output(&output).await;
 
@@ -331,6 +674,10 @@ impl PackageRepository for BrewPackageRepository {
         query: &str,
         package_type: PackageType,
     ) -> Result<Vec<Package>> {
+        if !Self::package_type_supported(&package_type) {
+            return Ok(Vec::new());
+        }
+
         let query = query.to_string();
         let package_type_clone = package_type.clone();
         let output = tokio::task::spawn_blocking(move || {
@@ -354,14 +701,10 @@ impl PackageRepository for BrewPackageRepository {
         let name_clone = name.clone();
         let package_type_clone = package_type.clone();
 
-        let output = tokio::time::timeout(
-            std::time::Duration::from_secs(10),
-            tokio::task::spawn_blocking(move || {
-                BrewCommand::get_package_info(&name_clone, package_type_clone)
-            }),
-        )
-        .await
-        .map_err(|_| anyhow::anyhow!("Timeout loading package info for {}", name))???;
+        let output = tokio::task::spawn_blocking(move || {
+            BrewCommand::get_package_info(&name_clone, package_type_clone)
+        })
+        .await??;
 
         tracing::debug!("Raw brew output for {}: {} bytes", name, output.len());
 
@@ -386,29 +729,15 @@ impl PackageRepository for BrewPackageRepository {
             );
 
             if let Some(item) = items.first() {
-                let version = item
-                    .get("version")
-                    .or_else(|| item.get("versions").and_then(|v| v.get("stable")))
-                    .and_then(|v| v.as_str())
-                    .map(String::from);
-
-                let description = item.get("desc").and_then(|v| v.as_str()).map(String::from);
+                let package = Self::parse_package_info(item, name.clone(), package_type);
 
                 tracing::debug!(
                     "Extracted for {}: version={:?}, desc={:?}",
                     name,
-                    version,
-                    description
+                    package.version,
+                    package.description
                 );
 
-                let mut package = Package::new(name.clone(), package_type);
-                if let Some(v) = version {
-                    package = package.with_version(v);
-                }
-                if let Some(d) = description {
-                    package = package.with_description(d);
-                }
-
                 tracing::debug!("Successfully created package info for {}", name);
                 return Ok(package);
             } else {
@@ -421,6 +750,35 @@ impl PackageRepository for BrewPackageRepository {
         Err(anyhow::anyhow!("Package info not found for {}", name))
     }
 
+    async fn get_packages_info(
+        &self,
+        names: &[String],
+        package_type: PackageType,
+    ) -> Result<Vec<Package>> {
+        let names = names.to_vec();
+        let package_type_clone = package_type.clone();
+        let output = tokio::task::spawn_blocking(move || {
+            BrewCommand::packages_info(&names, package_type_clone)
+        })
+        .await??;
+
+        let data: Value = serde_json::from_str(&output)?;
+        let (items_key, name_key) = match package_type {
+            PackageType::Formula => ("formulae", "name"),
+            PackageType::Cask => ("casks", "token"),
+        };
+        Ok(data
+            .get(items_key)
+            .and_then(|v| v.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|item| {
+                let name = item.get(name_key)?.as_str()?.to_string();
+                Some(Self::parse_package_info(item, name, package_type.clone()))
+            })
+            .collect())
+    }
+
     async fn pin_package(&self, package: &Package) -> Result<()> {
         let name = package.name.clone();
         let output = tokio::task::spawn_blocking(move || BrewCommand::pin_package(&name)).await??;
@@ -439,4 +797,19 @@ impl PackageRepository for BrewPackageRepository {
 
         Ok(())
     }
+
+    async fn get_install_dates(&self, packages: &[Package]) -> Result<HashMap<String, SystemTime>> {
+        let packages = packages.to_vec();
+        tokio::task::spawn_blocking(move || {
+            let prefix = PathBuf::from(BrewCommand::prefix()?);
+            Ok(packages
+                .iter()
+                .filter_map(|package| {
+                    install_receipt::install_time(&prefix, package)
+                        .map(|time| (package.name.clone(), time))
+                })
+                .collect())
+        })
+        .await?
+    }
 }
@@ -1,7 +1,8 @@
-use crate::domain::{entities::{Service, ServiceStatus}, repositories::ServiceRepository};
+use crate::domain::{entities::{Service, ServiceInfo, ServiceStatus}, repositories::ServiceRepository};
 use crate::infrastructure::brew::command::BrewCommand;
-use anyhow::Result;
+use anyhow::{Result, anyhow};
 use async_trait::async_trait;
+use serde_json::Value;
 
 pub struct BrewServiceRepository;
 
@@ -56,15 +57,71 @@ impl BrewServiceRepository {
 
         Ok(services)
     }
+
+    /// Reads the one service `brew services info --json` describes.
+    fn parse_service_info(output: &str) -> Result<ServiceInfo> {
+        let json: Value = serde_json::from_str(output)?;
+        let info = json
+            .as_array()
+            .and_then(|services| services.first())
+            .ok_or_else(|| anyhow!("brew services info described no service"))?;
+        let field = |key: &str| info.get(key).and_then(Value::as_str).map(str::to_string);
+
+        let name = field("name").ok_or_else(|| anyhow!("Service info has no name"))?;
+        let status = field("status").map_or(ServiceStatus::Unknown, |status| {
+            Self::parse_service_status(&status)
+        });
+        let mut service = Service::new(name, status);
+        if let Some(user) = field("user") {
+            service = service.with_user(user);
+        }
+        if let Some(file) = field("file") {
+            service = service.with_file(file);
+        }
+        let log_path = field("log_path");
+        let error_log_path = field("error_log_path");
+        if let Some(path) = error_log_path.clone().or_else(|| log_path.clone()) {
+            service = service.with_log_path(path);
+        }
+        Ok(ServiceInfo {
+            service,
+            running: info
+                .get("running")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+            pid: info
+                .get("pid")
+                .and_then(Value::as_u64)
+                .and_then(|pid| u32::try_from(pid).ok()),
+            exit_code: info
+                .get("exit_code")
+                .and_then(Value::as_i64)
+                .and_then(|code| i32::try_from(code).ok()),
+            registered: info.get("registered").and_then(Value::as_bool),
+            log_path,
+            error_log_path,
+        })
+    }
 }
 
 #[async_trait]
 impl ServiceRepository for BrewServiceRepository {
     async fn list_services(&self) -> Result<Vec<Service>> {
-        let output = tokio::task::spawn_blocking(|| BrewCommand::list_services()).await??;
+        // brew services drives launchctl on macOS and systemd on Linux; on systems without
+        // either (containers, WSL without systemd) report that instead of a raw brew error
+        let output = tokio::task::spawn_blocking(BrewCommand::list_services)
+            .await?
+            .map_err(|e| anyhow!("brew services is not available on this system: {}", e))?;
         self.parse_services_list(&output)
     }
 
+    async fn get_service_info(&self, name: &str) -> Result<ServiceInfo> {
+        let name = name.to_string();
+        let output =
+            tokio::task::spawn_blocking(move || BrewCommand::service_info(&name)).await??;
+        Self::parse_service_info(&output)
+    }
+
     async fn start_service(&self, name: &str) -> Result<()> {
         let name = name.to_string();
         let output = tokio::task::spawn_blocking(move || BrewCommand::start_service(&name)).await??;
@@ -93,6 +150,19 @@ impl ServiceRepository for BrewServiceRepository {
         Ok(())
     }
 
+    async fn stop_all_services(&self) -> Result<()> {
+        let output = tokio::task::spawn_blocking(BrewCommand::stop_all_services).await??;
+
+        if !output.stdout.is_empty() {
+            tracing::info!("stop_all_services output: {}", output.stdout);
+        }
+        if !output.stderr.is_empty() {
+            tracing::info!("stop_all_services stderr: {}", output.stderr);
+        }
+
+        Ok(())
+    }
+
     async fn restart_service(&self, name: &str) -> Result<()> {
         let name = name.to_string();
         let output = tokio::task::spawn_blocking(move || BrewCommand::restart_service(&name)).await??;
@@ -106,4 +176,14 @@ impl ServiceRepository for BrewServiceRepository {
 
         Ok(())
     }
+
+    async fn cleanup_services(&self) -> Result<String> {
+        let output = tokio::task::spawn_blocking(BrewCommand::cleanup_services).await??;
+
+        if !output.stderr.is_empty() {
+            tracing::info!("cleanup_services stderr: {}", output.stderr);
+        }
+
+        Ok(output.stdout)
+    }
 }
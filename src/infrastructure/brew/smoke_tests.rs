@@ -0,0 +1,501 @@
+//! Runs the brew repositories against a fake `brew` shell script, so the
+//! command plumbing and output parsing are exercised without Homebrew installed.
+
+use crate::domain::entities::{
+    CpuArch, DependencyNode, OperationProgress, Package, PackageType, ProgressCell, ServiceStatus,
+};
+use crate::domain::repositories::{PackageRepository, ServiceRepository};
+use crate::infrastructure::brew::command::{self, BrewCommand};
+use crate::infrastructure::brew::command_log;
+use crate::infrastructure::brew::{BrewPackageRepository, BrewServiceRepository};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+const FAKE_BREW: &str = r#"#!/bin/sh
+case "$*" in
+  "list --formula --versions") printf 'jq 1.7.1\nwget 1.24.5\n' ;;
+  "list --cask --versions") ;;
+  "list --pinned") echo jq ;;
+  "list --formula jq") echo /opt/homebrew/Cellar/jq/1.7.1/bin/jq ;;
+  "outdated --formula --json=v2")
+    echo '{"formulae":[{"name":"wget","installed_versions":["1.24.5"],"current_version":"1.25.0","pinned":false}],"casks":[]}' ;;
+  "outdated --cask --json=v2") echo '{"formulae":[],"casks":[]}' ;;
+  "search --formula wget") printf 'wget\nwget2\n' ;;
+  "search --formula caf") printf 'caf\351\ncafe\n' ;;
+  "search --cask "*) ;;
+  "info --json=v2 --formula wget")
+    echo '{"formulae":[{"name":"wget","desc":"Internet file retriever","versions":{"stable":"1.25.0"}}],"casks":[]}' ;;
+  "info --json=v2 --cask intel-app")
+    echo '{"formulae":[],"casks":[{"token":"intel-app","version":"2.0","auto_updates":true,"depends_on":{"arch":[{"type":"intel","bits":64}],"macos":{">=":["12"]}}}]}' ;;
+  "info --json=v2 --cask any-app")
+    echo '{"formulae":[],"casks":[{"token":"any-app","version":"1.0","depends_on":{}}]}' ;;
+  "install --cask admin-app")
+    # Like brew's sudo calls: -A when SUDO_ASKPASS is set, else the terminal
+    if (: < /dev/tty) 2>/dev/null; then
+      echo 'Password:' >&2; exit 2
+    elif [ -n "$SUDO_ASKPASS" ] && [ ! -x "$SUDO_ASKPASS" ]; then
+      echo "sudo: unable to run $SUDO_ASKPASS: No such file or directory" >&2
+      echo 'sudo: a password is required' >&2; exit 1
+    fi ;;
+  "uninstall --cask --zap any-app")
+    printf '==> Uninstalling Cask any-app\n==> Trashing files:\n~/Library/Preferences/com.example.any-app.plist\n' ;;
+  "--prefix") echo /opt/homebrew ;;
+  "autoremove --dry-run")
+    printf '==> Would autoremove 2 unneeded formulae:\nlibyaml\nm4\n' ;;
+  "config") printf '%s' "$BREWSTY_TEST_OPTS" ;;
+  "upgrade --dry-run")
+    printf '==> Would upgrade 2 outdated packages:\nwget 1.24.5 -> 1.25.0\nnode 20.1.0, 20.2.0 -> 21.0.0\n==> Would upgrade 1 outdated package:\nfirefox 120.0 -> 121.0\n==> Upgrading wget\nnot an -> upgrade\n' ;;
+  "upgrade")
+    printf '==> Upgrading 2 outdated packages:\nwget 1.24.5 -> 1.25.0\nnode 20.1.0 -> 21.0.0\n==> Upgrading 1 outdated package:\nfirefox 120.0 -> 121.0\n==> Upgrading wget\n  1.24.5 -> 1.25.0\n'
+    printf 'Error: node: Failed to download resource "node"\nError: Problems with multiple casks:\nfirefox: Download failed on Cask '"'"'firefox'"'"'\n' >&2; exit 1 ;;
+  "fetch --formula jq") echo '==> Fetching jq' ;;
+  "fetch --formula jq wget"|"fetch --formula wget")
+    echo 'curl: (6) Could not resolve host: ghcr.io' >&2; exit 1 ;;
+  "upgrade wget")
+    printf '==> Fetching wget\n==> Downloading https://ghcr.io/v2/homebrew/core/wget/blobs/sha256:1\n'
+    printf '\r########            40.0%%\r################    80.0%%' ;;
+  "audit --strict --formula wget")
+    printf 'wget\n  * line 3, col 3: Description should not start with "A"\n  * Stable: version 1.25.0 is redundant\n'
+    echo 'Error: 2 problems in 1 formula detected.' >&2; exit 1 ;;
+  "deps --tree --formula wget")
+    printf 'wget\n├── libidn2\n│   ├── libunistring\n│   └── gettext\n│       └── libunistring\n└── openssl@3\n    └── ca-certificates\n' ;;
+  "services info redis --json")
+    echo '[{"name":"redis","running":false,"pid":null,"exit_code":78,"registered":true,"status":"error","user":"brewsty","file":"/etc/redis.plist","log_path":"/var/log/redis.log","error_log_path":null}]' ;;
+  "services stop --all") printf 'Successfully stopped `postgresql@16`\n' ;;
+  "services cleanup") printf 'Removing unused plist /etc/mysql.plist\n' ;;
+  "services list")
+    printf 'Name          Status  User    File\npostgresql@16 started brewsty /etc/brewsty.service\nredis         none\n' ;;
+  *) echo "Error: unexpected arguments: $*" >&2; exit 1 ;;
+esac
+"#;
+
+fn use_fake_brew() {
+    static FAKE_BREW_PATH: OnceLock<PathBuf> = OnceLock::new();
+
+    let path = FAKE_BREW_PATH.get_or_init(|| {
+        let dir = std::env::temp_dir().join(format!("brewsty-fake-brew-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("brew");
+        fs::write(&path, FAKE_BREW).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    });
+
+    BrewCommand::set_brew_path_for_tests(path.clone());
+}
+
+#[test]
+fn brew_is_found_on_path() {
+    let dir = std::env::temp_dir().join(format!("brewsty-path-{}", std::process::id()));
+    let plain = dir.join("plain");
+    let bin = dir.join("bin");
+    fs::create_dir_all(&plain).unwrap();
+    fs::create_dir_all(&bin).unwrap();
+    // Not executable, so not the brew to run
+    fs::write(plain.join("brew"), "").unwrap();
+    fs::write(bin.join("brew"), FAKE_BREW).unwrap();
+    fs::set_permissions(bin.join("brew"), fs::Permissions::from_mode(0o755)).unwrap();
+
+    let path = std::env::join_paths([dir.join("missing"), plain, bin.clone()]).unwrap();
+    assert_eq!(command::brew_on_path(&path), Some(bin.join("brew")));
+    let path = std::env::join_paths([dir.join("missing")]).unwrap();
+    assert_eq!(command::brew_on_path(&path), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[tokio::test]
+async fn lists_installed_and_outdated_formulae() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let installed = repo
+        .get_installed_packages(PackageType::Formula)
+        .await
+        .unwrap();
+    assert_eq!(installed.len(), 2);
+    let jq = installed.iter().find(|p| p.name == "jq").unwrap();
+    assert_eq!(jq.version.as_deref(), Some("1.7.1"));
+    assert!(jq.pinned);
+
+    let outdated = repo
+        .get_outdated_packages(PackageType::Formula)
+        .await
+        .unwrap();
+    assert_eq!(outdated.len(), 1);
+    assert_eq!(outdated[0].name, "wget");
+    assert_eq!(outdated[0].available_version.as_deref(), Some("1.25.0"));
+}
+
+#[tokio::test]
+async fn install_checks_ask_brew_list() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+    let package = |name: &str| Package::new(name.to_string(), PackageType::Formula);
+
+    assert!(repo.is_installed(&package("jq")).await.unwrap());
+    assert!(!repo.is_installed(&package("missing")).await.unwrap());
+}
+
+#[tokio::test]
+async fn zapping_asks_brew_to_remove_leftover_files() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    repo.zap_cask(&Package::new("any-app".to_string(), PackageType::Cask))
+        .await
+        .unwrap();
+    assert!(
+        repo.zap_cask(&Package::new("intel-app".to_string(), PackageType::Cask))
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn casks_needing_sudo_ask_for_the_password_instead_of_prompting() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let err = repo
+        .install_package(
+            &Package::new("admin-app".to_string(), PackageType::Cask),
+            &ProgressCell::default(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.to_string(), "a password is required");
+}
+
+#[tokio::test]
+async fn casks_are_empty_without_failing() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    assert!(
+        repo.get_installed_packages(PackageType::Cask)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        repo.search_packages("wget", PackageType::Cask)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn searches_and_loads_package_info() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let results = repo
+        .search_packages("wget", PackageType::Formula)
+        .await
+        .unwrap();
+    let names: Vec<_> = results.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["wget", "wget2"]);
+
+    let info = repo
+        .get_package_info("wget", PackageType::Formula)
+        .await
+        .unwrap();
+    assert_eq!(info.version.as_deref(), Some("1.25.0"));
+    assert_eq!(info.description.as_deref(), Some("Internet file retriever"));
+    assert_eq!(info.supported_archs, None);
+}
+
+#[tokio::test]
+async fn cask_info_reads_the_required_architecture_and_auto_updates() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let intel = repo
+        .get_package_info("intel-app", PackageType::Cask)
+        .await
+        .unwrap();
+    assert_eq!(intel.supported_archs, Some(vec![CpuArch::Intel]));
+    assert!(intel.runs_natively_on(CpuArch::Intel));
+    assert!(!intel.runs_natively_on(CpuArch::Arm));
+    assert!(intel.auto_updates);
+
+    let any = repo
+        .get_package_info("any-app", PackageType::Cask)
+        .await
+        .unwrap();
+    assert_eq!(any.supported_archs, Some(Vec::new()));
+    assert!(any.runs_natively_on(CpuArch::Arm));
+    assert!(!any.auto_updates);
+}
+
+#[tokio::test]
+async fn invalid_utf8_in_output_is_replaced() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let results = repo
+        .search_packages("caf", PackageType::Formula)
+        .await
+        .unwrap();
+    let names: Vec<_> = results.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, ["caf\u{FFFD}", "cafe"]);
+}
+
+#[tokio::test]
+async fn upgrades_report_download_progress() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+    let wget = Package::new("wget".to_string(), PackageType::Formula);
+    let progress = ProgressCell::default();
+
+    repo.update_package(&wget, &progress).await.unwrap();
+    assert_eq!(
+        progress.get(),
+        Some(OperationProgress::Downloading {
+            file: "wget".to_string(),
+            percent: Some(80.0),
+        })
+    );
+}
+
+#[tokio::test]
+async fn autoremove_preview_lists_cellar_folders() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let preview = repo
+        .get_autoremove_preview(Path::new("/opt/homebrew/Cellar"))
+        .await
+        .unwrap();
+    let paths: Vec<&str> = preview.items.iter().map(|i| i.path.as_str()).collect();
+    assert_eq!(
+        paths,
+        ["/opt/homebrew/Cellar/libyaml", "/opt/homebrew/Cellar/m4"]
+    );
+}
+
+#[tokio::test]
+async fn dependency_tree_follows_the_indentation() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let tree = repo
+        .dependency_tree(&Package::new("wget".to_string(), PackageType::Formula))
+        .await
+        .unwrap();
+    let names = |node: &DependencyNode| -> Vec<String> {
+        node.children.iter().map(|c| c.name.clone()).collect()
+    };
+
+    assert_eq!(tree.name, "wget");
+    assert_eq!(names(&tree), ["libidn2", "openssl@3"]);
+    assert_eq!(names(&tree.children[0]), ["libunistring", "gettext"]);
+    assert_eq!(names(&tree.children[0].children[1]), ["libunistring"]);
+    assert_eq!(names(&tree.children[1]), ["ca-certificates"]);
+}
+
+#[tokio::test]
+async fn audit_problems_are_findings_not_errors() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let audit = repo
+        .audit_package(&Package::new("wget".to_string(), PackageType::Formula))
+        .await
+        .unwrap();
+    assert!(!audit.passed);
+    assert_eq!(
+        audit.findings,
+        [
+            "line 3, col 3: Description should not start with \"A\"",
+            "Stable: version 1.25.0 is redundant",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn upgrade_preview_lists_old_and_new_versions() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let upgrades = repo.get_upgrade_preview().await.unwrap();
+    let summary: Vec<_> = upgrades
+        .iter()
+        .map(|u| {
+            (
+                u.name.as_str(),
+                u.current_version.as_deref(),
+                u.new_version.as_str(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("wget", Some("1.24.5"), "1.25.0"),
+            ("node", Some("20.1.0, 20.2.0"), "21.0.0"),
+            ("firefox", Some("120.0"), "121.0"),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn partial_upgrades_tell_upgraded_packages_from_failed_ones() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+
+    let report = repo.update_all(&ProgressCell::default()).await.unwrap();
+    assert_eq!(report.upgraded, ["wget"]);
+    assert_eq!(
+        report.failed,
+        [
+            (
+                "node".to_string(),
+                "Failed to download resource \"node\"".to_string()
+            ),
+            (
+                "firefox".to_string(),
+                "Download failed on Cask 'firefox'".to_string()
+            ),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn failed_fetches_are_retried_one_at_a_time() {
+    use_fake_brew();
+    let repo = BrewPackageRepository::new();
+    let packages = ["jq", "wget"].map(|name| Package::new(name.to_string(), PackageType::Formula));
+
+    let fetched = repo
+        .fetch_packages(&packages, &ProgressCell::default())
+        .await
+        .unwrap();
+    assert_eq!(fetched, ["jq"]);
+}
+
+#[test]
+fn extra_env_is_passed_verbatim() {
+    use_fake_brew();
+    let value = r#"--appdir="~/My Apps" $HOME; echo 'hi'"#;
+    BrewCommand::set_extra_env(vec![("BREWSTY_TEST_OPTS".to_string(), value.to_string())]);
+
+    assert_eq!(BrewCommand::config().unwrap(), value);
+    // Passed along, but kept out of the audit log
+    let logged = (
+        "BREWSTY_TEST_OPTS".to_string(),
+        command_log::REDACTED.to_string(),
+    );
+    command_log::with_records(|records| {
+        let config = records.iter().rev().find(|r| r.args == ["config"]).unwrap();
+        assert!(config.env.contains(&logged));
+    });
+    assert!(BrewCommand::check_env_var("HOMEBREW_CASK_OPTS", value).is_ok());
+    assert!(BrewCommand::check_env_var("SUDO_ASKPASS", "/tmp/x").is_err());
+    assert!(BrewCommand::check_env_var("A=B", "").is_err());
+
+    BrewCommand::set_extra_env(Vec::new());
+}
+
+#[tokio::test]
+async fn lists_services() {
+    use_fake_brew();
+    let repo = BrewServiceRepository::new();
+
+    let services = repo.list_services().await.unwrap();
+    assert_eq!(services.len(), 2);
+    assert_eq!(services[0].name, "postgresql@16");
+    assert_eq!(services[0].status, ServiceStatus::Started);
+    assert_eq!(services[1].status, ServiceStatus::Stopped);
+
+    repo.stop_all_services().await.unwrap();
+    let cleaned = repo.cleanup_services().await.unwrap();
+    assert_eq!(cleaned.trim(), "Removing unused plist /etc/mysql.plist");
+}
+
+#[tokio::test]
+async fn checks_one_service_with_its_log() {
+    use_fake_brew();
+    let repo = BrewServiceRepository::new();
+
+    let redis = repo.get_service_info("redis").await.unwrap();
+    assert_eq!(redis.service.status, ServiceStatus::Error);
+    assert_eq!(redis.service.file.as_deref(), Some("/etc/redis.plist"));
+    assert_eq!(
+        redis.service.log_path.as_deref(),
+        Some("/var/log/redis.log")
+    );
+    assert_eq!((redis.pid, redis.exit_code), (None, Some(78)));
+    assert_eq!(redis.registered, Some(true));
+    assert!(repo.get_service_info("missing").await.is_err());
+}
+
+#[tokio::test]
+async fn commands_are_recorded_in_the_audit_log() {
+    use_fake_brew();
+    let repo = BrewServiceRepository::new();
+
+    repo.list_services().await.unwrap();
+    let _ = repo.stop_service("missing").await;
+
+    command_log::with_records(|records| {
+        let list = records
+            .iter()
+            .find(|r| r.args == ["services", "list"])
+            .unwrap();
+        assert_eq!(list.exit_code, Some(0));
+        assert!(list.stdout_bytes > 0);
+
+        let stop = records
+            .iter()
+            .find(|r| r.args == ["services", "stop", "missing"])
+            .unwrap();
+        assert_eq!(stop.exit_code, Some(1));
+        assert_eq!(stop.shell_command(), "brew services stop missing");
+    });
+}
+
+#[tokio::test]
+async fn brew_failures_surface_as_errors() {
+    use_fake_brew();
+    let repo = BrewServiceRepository::new();
+
+    let err = repo.start_service("missing").await.unwrap_err();
+    assert!(err.to_string().contains("unexpected arguments"));
+}
+
+#[test]
+fn streamed_commands_get_their_input_and_keep_their_output() {
+    let (status, output) = BrewCommand::stream_with_timeout(
+        Command::new("sh").args([
+            "-c",
+            "read answer; echo \"got $answer\"; echo oops >&2; exit 3",
+        ]),
+        Duration::from_secs(5),
+        b"yes\n",
+    )
+    .unwrap();
+
+    assert_eq!(status.code(), Some(3));
+    assert_eq!(output, "got yes\noops\n");
+}
+
+#[test]
+fn stalled_commands_are_killed_after_the_timeout() {
+    let started = Instant::now();
+
+    let err = BrewCommand::output_with_timeout(
+        Command::new("sleep").arg("30"),
+        Duration::from_millis(200),
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().contains("timed out"));
+    assert!(started.elapsed() < Duration::from_secs(5));
+}
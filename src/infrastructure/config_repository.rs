@@ -1,7 +1,15 @@
-use crate::domain::entities::AppConfig;
+use crate::domain::entities::{AppConfig, CURRENT_CONFIG_VERSION};
 use anyhow::{Context, Result};
+use directories::BaseDirs;
+use serde_json::{Map, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Upgrades a raw config object by one version. `MIGRATIONS[n]` turns a
+/// version `n` config into version `n + 1`.
+type Migration = fn(&mut Map<String, Value>);
+
+const MIGRATIONS: &[Migration] = &[migrate_v0_to_v1];
 
 pub struct ConfigRepository {
     config_path: PathBuf,
@@ -9,42 +17,297 @@ pub struct ConfigRepository {
 
 impl ConfigRepository {
     pub fn new() -> Self {
-        let config_dir = if let Ok(home) = std::env::var("HOME") {
-            PathBuf::from(home).join(".config").join("brewsty")
-        } else {
-            PathBuf::from(".")
-        };
+        Self::with_path(default_config_path())
+    }
+
+    pub fn with_path(config_path: PathBuf) -> Self {
+        Self { config_path }
+    }
+
+    /// Directory holding the config file; crash reports are written here too.
+    pub fn dir(&self) -> &Path {
+        self.config_path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or(Path::new("."))
+    }
+
+    /// Where log files go when file logging is enabled.
+    pub fn log_dir(&self) -> PathBuf {
+        self.dir().join("logs")
+    }
 
-        Self {
-            config_path: config_dir.join("config.json"),
+    /// Where package list snapshots taken before bulk operations go.
+    pub fn snapshot_dir(&self) -> PathBuf {
+        self.dir().join("snapshots")
+    }
+
+    /// Creates the config directory up front and logs a warning if settings
+    /// can't be written there, so a bad path doesn't go unnoticed until save.
+    pub fn check_writable(&self) {
+        if let Err(e) = self.probe_writable() {
+            tracing::warn!(
+                "Config location {} is not writable, settings will not be saved: {:#}",
+                self.config_path.display(),
+                e
+            );
         }
     }
 
+    fn probe_writable(&self) -> Result<()> {
+        let dir = self.dir();
+        fs::create_dir_all(dir).context("Failed to create config directory")?;
+
+        if self.config_path.exists() {
+            fs::OpenOptions::new()
+                .append(true)
+                .open(&self.config_path)
+                .context("Failed to open config file for writing")?;
+        } else {
+            let probe = dir.join(".brewsty-write-test");
+            fs::write(&probe, b"").context("Failed to write to config directory")?;
+            let _ = fs::remove_file(probe);
+        }
+
+        Ok(())
+    }
+
     pub fn load(&self) -> Result<AppConfig> {
         if !self.config_path.exists() {
             return Ok(AppConfig::default());
         }
 
-        let content = fs::read_to_string(&self.config_path)
-            .context("Failed to read config file")?;
-        
-        let config = serde_json::from_str(&content)
-            .context("Failed to parse config file")?;
+        let content =
+            fs::read_to_string(&self.config_path).context("Failed to read config file")?;
+
+        let raw: Value = serde_json::from_str(&content).context("Failed to parse config file")?;
+        let (migrated, changed) = migrate(raw)?;
+
+        let mut config: AppConfig =
+            serde_json::from_value(migrated).context("Failed to parse config file")?;
+        config.clamp_to_ranges();
+
+        if changed {
+            if let Err(e) = self.write_back(&config) {
+                tracing::warn!("Failed to write migrated config: {}", e);
+            } else {
+                tracing::info!("Migrated config to version {}", CURRENT_CONFIG_VERSION);
+            }
+        }
 
         Ok(config)
     }
 
+    pub fn load_or_default(&self) -> AppConfig {
+        self.load().unwrap_or_else(|e| {
+            tracing::error!("Failed to load config: {:#}", e);
+            AppConfig::default()
+        })
+    }
+
     pub fn save(&self, config: &AppConfig) -> Result<()> {
         if let Some(parent) = self.config_path.parent() {
             fs::create_dir_all(parent).context("Failed to create config directory")?;
         }
 
-        let content = serde_json::to_string_pretty(config)
-            .context("Failed to serialize config")?;
+        let content = serde_json::to_string_pretty(config).context("Failed to serialize config")?;
 
-        fs::write(&self.config_path, content)
-            .context("Failed to write config file")?;
+        fs::write(&self.config_path, content).context("Failed to write config file")?;
 
         Ok(())
     }
+
+    fn backup_path(&self) -> PathBuf {
+        self.config_path.with_extension("json.bak")
+    }
+
+    fn write_back(&self, config: &AppConfig) -> Result<()> {
+        fs::copy(&self.config_path, self.backup_path()).context("Failed to back up config file")?;
+        self.save(config)
+    }
+}
+
+/// `$XDG_CONFIG_HOME/brewsty` when set, otherwise the platform config dir.
+/// An existing `~/.config/brewsty/config.json` keeps being used on macOS so
+/// settings from earlier versions aren't orphaned.
+fn default_config_path() -> PathBuf {
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").map(PathBuf::from)
+        && xdg.is_absolute()
+    {
+        return xdg.join("brewsty").join("config.json");
+    }
+
+    let Some(base_dirs) = BaseDirs::new() else {
+        let fallback = std::env::temp_dir().join("brewsty").join("config.json");
+        tracing::warn!(
+            "Could not determine home directory, using {} for config",
+            fallback.display()
+        );
+        return fallback;
+    };
+
+    let legacy = base_dirs
+        .home_dir()
+        .join(".config")
+        .join("brewsty")
+        .join("config.json");
+    if legacy.exists() {
+        return legacy;
+    }
+
+    base_dirs.config_dir().join("brewsty").join("config.json")
+}
+
+/// Brings a raw config up to `CURRENT_CONFIG_VERSION`, filling in any fields
+/// the stored file does not have yet. Returns whether anything changed.
+fn migrate(raw: Value) -> Result<(Value, bool)> {
+    let Value::Object(mut obj) = raw else {
+        anyhow::bail!("Config file is not a JSON object");
+    };
+
+    let stored_version = obj.get("version").and_then(Value::as_u64).unwrap_or(0) as u32;
+    let newer_than_supported = stored_version > CURRENT_CONFIG_VERSION;
+    let mut changed = false;
+
+    if newer_than_supported {
+        tracing::warn!(
+            "Config version {} is newer than supported version {}, loading without migration",
+            stored_version,
+            CURRENT_CONFIG_VERSION
+        );
+    } else {
+        for migration in &MIGRATIONS[stored_version as usize..] {
+            migration(&mut obj);
+            changed = true;
+        }
+        obj.insert("version".to_string(), Value::from(CURRENT_CONFIG_VERSION));
+    }
+
+    if let Value::Object(defaults) =
+        serde_json::to_value(AppConfig::default()).context("Failed to serialize config")?
+    {
+        for (key, value) in defaults {
+            if !obj.contains_key(&key) {
+                obj.insert(key, value);
+                changed = true;
+            }
+        }
+    }
+
+    // Never rewrite a file written by a newer build; it would drop fields we don't know.
+    Ok((Value::Object(obj), changed && !newer_than_supported))
+}
+
+/// Version 0 is the original unversioned shape. Its fields carry over as-is;
+/// the version stamp is added by `migrate`.
+fn migrate_v0_to_v1(_obj: &mut Map<String, Value>) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::ThemeMode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+    fn temp_config_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "brewsty-config-test-{}-{}",
+            std::process::id(),
+            NEXT_DIR.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir.join("config.json")
+    }
+
+    const V0_CONFIG: &str = r#"{
+  "theme": "Light",
+  "auto_update_check": false,
+  "confirm_before_actions": false
+}"#;
+
+    #[test]
+    fn v0_config_settings_survive_migration() {
+        let path = temp_config_path();
+        fs::write(&path, V0_CONFIG).unwrap();
+
+        let repo = ConfigRepository::with_path(path.clone());
+        let config = repo.load().unwrap();
+
+        assert_eq!(config.version, CURRENT_CONFIG_VERSION);
+        assert_eq!(config.theme, ThemeMode::Light);
+        assert!(!config.auto_update_check);
+        assert!(!config.confirm_before_actions);
+
+        let reloaded = repo.load().unwrap();
+        assert_eq!(reloaded.theme, ThemeMode::Light);
+        assert!(!reloaded.auto_update_check);
+        assert!(!reloaded.confirm_before_actions);
+    }
+
+    #[test]
+    fn migration_writes_backup_and_versioned_file() {
+        let path = temp_config_path();
+        fs::write(&path, V0_CONFIG).unwrap();
+
+        ConfigRepository::with_path(path.clone()).load().unwrap();
+
+        let backup = fs::read_to_string(path.with_extension("json.bak")).unwrap();
+        assert_eq!(backup, V0_CONFIG);
+
+        let written: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written["version"], Value::from(CURRENT_CONFIG_VERSION));
+        assert_eq!(written["theme"], Value::from("Light"));
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let path = temp_config_path();
+        fs::write(&path, r#"{ "theme": "Dark" }"#).unwrap();
+
+        let config = ConfigRepository::with_path(path).load().unwrap();
+
+        assert_eq!(config.theme, ThemeMode::Dark);
+        assert!(config.auto_update_check);
+        assert!(config.confirm_before_actions);
+    }
+
+    #[test]
+    fn hand_edited_values_are_clamped_to_settings_ranges() {
+        let path = temp_config_path();
+        fs::write(
+            &path,
+            r#"{
+  "version": 1,
+  "search_result_limit": 0,
+  "metadata_stale_after_hours": 0,
+  "auto_export": { "keep": 0 },
+  "services_refresh_secs": 999999,
+  "read_timeout_secs": 0,
+  "command_timeout_secs": 18446744073709551615
+}"#,
+        )
+        .unwrap();
+
+        let config = ConfigRepository::with_path(path).load().unwrap();
+
+        assert_eq!(config.search_result_limit, 10);
+        assert_eq!(config.metadata_stale_after_hours, 1);
+        assert_eq!(config.auto_export.keep, 1);
+        assert_eq!(config.services_refresh_secs, 3600);
+        assert_eq!(config.read_timeout_secs, 5);
+        assert_eq!(config.command_timeout_secs, 86400);
+    }
+
+    #[test]
+    fn current_config_is_not_rewritten() {
+        let path = temp_config_path();
+        let repo = ConfigRepository::with_path(path.clone());
+        repo.save(&AppConfig::default()).unwrap();
+
+        repo.load().unwrap();
+
+        assert!(!path.with_extension("json.bak").exists());
+    }
 }
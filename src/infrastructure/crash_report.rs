@@ -0,0 +1,93 @@
+//! Writes a crash report when the app panics, so a crash outside a terminal
+//! still leaves something behind to look at or attach to an issue.
+
+use std::backtrace::Backtrace;
+use std::fs;
+use std::panic::PanicHookInfo;
+use std::path::{Path, PathBuf};
+
+const FILE_PREFIX: &str = "crash-";
+const FILE_EXTENSION: &str = "log";
+
+/// Installs a panic hook that writes `crash-<timestamp>.log` to `dir` with the
+/// panic message, a backtrace and `recent_logs()`, then runs the previous hook.
+pub fn install_panic_hook(dir: PathBuf, recent_logs: fn() -> Vec<String>) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = format_report(info, &Backtrace::force_capture(), &recent_logs());
+        let path = dir.join(format!(
+            "{}{}.{}",
+            FILE_PREFIX,
+            chrono::Local::now().format("%Y%m%d-%H%M%S"),
+            FILE_EXTENSION
+        ));
+        match fs::create_dir_all(&dir).and_then(|_| fs::write(&path, report)) {
+            Ok(()) => eprintln!("Crash report written to {}", path.display()),
+            Err(e) => eprintln!("Failed to write crash report {}: {}", path.display(), e),
+        }
+        previous(info);
+    }));
+}
+
+/// The most recent crash report in `dir`, if any. Timestamps in the names
+/// sort chronologically, so the last name is the newest.
+pub fn newest_report(dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| is_report(path))
+        .max()
+}
+
+fn is_report(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == FILE_EXTENSION)
+        && path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with(FILE_PREFIX))
+}
+
+fn format_report(info: &PanicHookInfo<'_>, backtrace: &Backtrace, logs: &[String]) -> String {
+    let thread = std::thread::current();
+    format!(
+        "Brewsty {} crashed at {}\n\
+         OS: {} {}\n\
+         Thread: {}\n\n\
+         {}\n\n\
+         Backtrace:\n{}\n\n\
+         Last {} log entries:\n{}\n",
+        env!("CARGO_PKG_VERSION"),
+        chrono::Local::now().to_rfc3339(),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        thread.name().unwrap_or("<unnamed>"),
+        info,
+        backtrace,
+        logs.len(),
+        logs.join("\n"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newest_report_ignores_other_files() {
+        let dir = std::env::temp_dir().join(format!("brewsty-crash-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "crash-20260101-120000.log",
+            "crash-20260301-080000.log",
+            "crash-20270101-000000.txt",
+            "config.json",
+        ] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let newest = newest_report(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(newest, Some(dir.join("crash-20260301-080000.log")));
+    }
+}
@@ -0,0 +1,61 @@
+//! Sizes of files and directories on disk.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Size of a file, or of everything below a directory; 0 when `path` is
+/// missing or can't be read.
+pub fn path_size(path: &Path) -> u64 {
+    match fs::metadata(path) {
+        Ok(metadata) if metadata.is_dir() => dir_size(path).unwrap_or(0),
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => 0,
+    }
+}
+
+/// Total size of the files below `path`. Symlinks inside it aren't followed.
+pub fn dir_size(path: &Path) -> io::Result<u64> {
+    let mut total = 0;
+    for entry in fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if metadata.is_file() {
+            total += metadata.len();
+        } else if metadata.is_dir() {
+            total += dir_size(&entry.path())?;
+        }
+    }
+    Ok(total)
+}
+
+/// How many regular files are directly in `path`.
+pub fn file_count(path: &Path) -> io::Result<usize> {
+    let mut count = 0;
+    for entry in fs::read_dir(path)? {
+        if entry?.file_type()?.is_file() {
+            count += 1;
+        }
+    }
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_add_up_nested_files() {
+        let dir = std::env::temp_dir().join(format!("brewsty-disk-usage-{}", std::process::id()));
+        fs::create_dir_all(dir.join("downloads")).unwrap();
+        fs::write(dir.join("a.tar.gz"), [0u8; 100]).unwrap();
+        fs::write(dir.join("downloads/b.tar.gz"), [0u8; 20]).unwrap();
+
+        assert_eq!(path_size(&dir), 120);
+        assert_eq!(path_size(&dir.join("a.tar.gz")), 100);
+        assert_eq!(path_size(&dir.join("missing")), 0);
+        assert_eq!(file_count(&dir).unwrap(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
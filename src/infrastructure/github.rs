@@ -0,0 +1,63 @@
+//! Brewsty's own releases, as published on GitHub.
+
+use crate::domain::entities::Release;
+use crate::domain::repositories::ReleaseRepository;
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::time::Duration;
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/whooof/brewsty/releases/latest";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// The part of GitHub's release JSON that is used.
+#[derive(Deserialize)]
+struct GitHubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+pub struct GitHubReleaseRepository {
+    client: reqwest::Client,
+}
+
+impl GitHubReleaseRepository {
+    pub fn new() -> Self {
+        // GitHub rejects API requests without a user agent
+        let client = reqwest::Client::builder()
+            .user_agent(concat!("brewsty/", env!("CARGO_PKG_VERSION")))
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+}
+
+impl Default for GitHubReleaseRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ReleaseRepository for GitHubReleaseRepository {
+    async fn latest_release(&self) -> Result<Release> {
+        let release: GitHubRelease = self
+            .client
+            .get(LATEST_RELEASE_URL)
+            .header("Accept", "application/vnd.github+json")
+            .send()
+            .await
+            .context("Failed to reach GitHub")?
+            .error_for_status()
+            .context("GitHub refused the release request")?
+            .json()
+            .await
+            .context("Failed to read the latest release")?;
+
+        Ok(Release {
+            version: release.tag_name.trim_start_matches('v').to_string(),
+            url: release.html_url,
+        })
+    }
+}
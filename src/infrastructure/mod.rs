@@ -1,3 +1,11 @@
+pub mod auto_exports;
 pub mod brew;
 pub mod config_repository;
+pub mod crash_report;
+pub mod disk_usage;
+pub mod github;
+pub mod package_metadata_repository;
 pub mod persistence;
+pub mod plist;
+pub mod snapshots;
+pub mod system;
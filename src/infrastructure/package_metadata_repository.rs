@@ -0,0 +1,82 @@
+//! Tags and notes the user keeps on packages, in `metadata.json` next to the
+//! config. Nothing here is ever passed to brew.
+
+use crate::domain::entities::PackageMetadata;
+use anyhow::{Context, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_NAME: &str = "metadata.json";
+
+pub struct PackageMetadataRepository {
+    path: PathBuf,
+}
+
+impl PackageMetadataRepository {
+    /// Keeps `metadata.json` in `dir`, normally the config directory.
+    pub fn new(dir: &Path) -> Self {
+        Self {
+            path: dir.join(FILE_NAME),
+        }
+    }
+
+    /// Metadata by package name; empty when nothing has been saved yet.
+    pub fn load(&self) -> Result<HashMap<String, PackageMetadata>> {
+        if !self.path.exists() {
+            return Ok(HashMap::new());
+        }
+
+        let content = fs::read_to_string(&self.path).context("Failed to read metadata file")?;
+        serde_json::from_str(&content).context("Failed to parse metadata file")
+    }
+
+    pub fn load_or_default(&self) -> HashMap<String, PackageMetadata> {
+        self.load().unwrap_or_else(|e| {
+            tracing::error!("Failed to load package metadata: {:#}", e);
+            HashMap::new()
+        })
+    }
+
+    /// Writes `metadata` sorted by name, leaving out empty entries.
+    pub fn save(&self, metadata: &HashMap<String, PackageMetadata>) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context("Failed to create config directory")?;
+        }
+
+        let sorted: BTreeMap<_, _> = metadata.iter().filter(|(_, m)| !m.is_empty()).collect();
+        let content =
+            serde_json::to_string_pretty(&sorted).context("Failed to serialize metadata")?;
+
+        fs::write(&self.path, content).context("Failed to write metadata file")?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn saved_metadata_loads_back_without_empty_entries() {
+        let dir = std::env::temp_dir().join(format!("brewsty-metadata-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let repo = PackageMetadataRepository::new(&dir);
+        assert!(repo.load().unwrap().is_empty());
+
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "ffmpeg".to_string(),
+            PackageMetadata::from_input("media, work", "needed for the podcast"),
+        );
+        metadata.insert("wget".to_string(), PackageMetadata::default());
+        repo.save(&metadata).unwrap();
+
+        let loaded = repo.load().unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        metadata.remove("wget");
+        assert_eq!(loaded, metadata);
+    }
+}
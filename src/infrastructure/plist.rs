@@ -0,0 +1,141 @@
+//! Reads the launchd plists behind brew services as indented XML, for
+//! showing them as text.
+
+use anyhow::{Context, Result, anyhow};
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+/// How binary plists start.
+const BINARY_MAGIC: &str = "bplist";
+
+/// The plist at `path` as XML. Binary plists are converted with `plutil`,
+/// which only exists on macOS.
+pub fn read_xml(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => anyhow!(
+            "{} doesn't exist. The service may belong to a formula that was \
+             uninstalled, or brew hasn't written it yet.",
+            path.display()
+        ),
+        _ => anyhow!("Couldn't read {}: {}", path.display(), e),
+    })?;
+
+    let xml = match String::from_utf8(bytes) {
+        Ok(text) if !text.starts_with(BINARY_MAGIC) => text,
+        Ok(_) | Err(_) => convert_binary(path)?,
+    };
+    if xml.trim().is_empty() {
+        return Err(anyhow!("{} is empty", path.display()));
+    }
+    Ok(pretty_print(&xml))
+}
+
+fn convert_binary(path: &Path) -> Result<String> {
+    let output = Command::new("plutil")
+        .args(["-convert", "xml1", "-o", "-"])
+        .arg(path)
+        .output()
+        .context("Couldn't run plutil to read the binary plist")?;
+    if !output.status.success() {
+        return Err(anyhow!(
+            "plutil couldn't convert {}: {}",
+            path.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    String::from_utf8(output.stdout).context("plutil printed something other than text")
+}
+
+/// Puts each element on its own line, indented by nesting. Elements
+/// holding only text, such as `<key>Label</key>`, stay on one line.
+pub fn pretty_print(xml: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut depth = 0usize;
+    let mut rest = xml;
+    let mut push = |depth: usize, text: &str| lines.push(format!("{}{}", "  ".repeat(depth), text));
+
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            let text = rest[..end].trim();
+            if !text.is_empty() {
+                push(depth, text);
+            }
+            rest = &rest[end..];
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            push(depth, rest.trim());
+            break;
+        };
+        let tag = &rest[..=end];
+        rest = &rest[end + 1..];
+
+        if tag.starts_with("</") {
+            depth = depth.saturating_sub(1);
+            push(depth, tag);
+        } else if tag.starts_with("<?") || tag.starts_with("<!") || tag.ends_with("/>") {
+            push(depth, tag);
+        } else if let Some((text, close, after)) = inline_text(rest) {
+            push(depth, &format!("{}{}{}", tag, text, close));
+            rest = after;
+        } else {
+            push(depth, tag);
+            depth += 1;
+        }
+    }
+    let mut pretty = lines.join("\n");
+    pretty.push('\n');
+    pretty
+}
+
+/// Splits `rest` into the text right after an opening tag, its closing tag
+/// and what follows, when the text is all the element holds.
+fn inline_text(rest: &str) -> Option<(&str, &str, &str)> {
+    let text_end = rest.find('<')?;
+    if !rest[text_end..].starts_with("</") {
+        return None;
+    }
+    let close_end = text_end + rest[text_end..].find('>')? + 1;
+    Some((
+        &rest[..text_end],
+        &rest[text_end..close_end],
+        &rest[close_end..],
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nests_elements_and_keeps_text_on_its_line() {
+        let xml = "<?xml version=\"1.0\"?><plist version=\"1.0\"><dict><key>Label</key>\
+                   <string> homebrew.mxcl.redis</string><key>RunAtLoad</key><true/>\
+                   <key>Args</key><array></array></dict></plist>";
+        assert_eq!(
+            pretty_print(xml),
+            "<?xml version=\"1.0\"?>\n\
+             <plist version=\"1.0\">\n\
+             \x20 <dict>\n\
+             \x20   <key>Label</key>\n\
+             \x20   <string> homebrew.mxcl.redis</string>\n\
+             \x20   <key>RunAtLoad</key>\n\
+             \x20   <true/>\n\
+             \x20   <key>Args</key>\n\
+             \x20   <array></array>\n\
+             \x20 </dict>\n\
+             </plist>\n"
+        );
+    }
+
+    #[test]
+    fn missing_files_say_why() {
+        let path = std::env::temp_dir().join("brewsty-missing-service.plist");
+        let error = read_xml(&path).unwrap_err().to_string();
+        assert!(error.contains("doesn't exist"), "{}", error);
+    }
+}
@@ -0,0 +1,114 @@
+//! Package lists saved automatically before bulk operations, so the
+//! previous state can be restored if one goes wrong.
+
+use chrono::{Local, NaiveDateTime};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FILE_PREFIX: &str = "snapshot-";
+const FILE_EXTENSION: &str = "json";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S";
+
+/// Older snapshots are deleted beyond this many.
+pub const MAX_SNAPSHOTS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snapshot {
+    pub path: PathBuf,
+    pub taken_at: NaiveDateTime,
+    /// The operation it was taken before, e.g. `update-all`.
+    pub reason: String,
+}
+
+impl Snapshot {
+    pub fn label(&self) -> String {
+        format!(
+            "{} · before {}",
+            self.taken_at.format("%Y-%m-%d %H:%M"),
+            self.reason.replace('-', " ")
+        )
+    }
+}
+
+/// Where a snapshot taken now before `reason` goes in `dir`.
+pub fn new_snapshot_path(dir: &Path, reason: &str) -> PathBuf {
+    dir.join(format!(
+        "{}{}-{}.{}",
+        FILE_PREFIX,
+        Local::now().format(TIMESTAMP_FORMAT),
+        reason,
+        FILE_EXTENSION
+    ))
+}
+
+/// The snapshots in `dir`, newest first. Empty when there is no such directory.
+pub fn list(dir: &Path) -> Vec<Snapshot> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut snapshots: Vec<Snapshot> = entries
+        .filter_map(|entry| parse(entry.ok()?.path()))
+        .collect();
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.taken_at));
+    snapshots
+}
+
+/// Deletes all but the newest `keep` snapshots in `dir`.
+pub fn prune(dir: &Path, keep: usize) {
+    for snapshot in list(dir).into_iter().skip(keep) {
+        if let Err(e) = fs::remove_file(&snapshot.path) {
+            tracing::warn!(
+                "Failed to remove old snapshot {}: {}",
+                snapshot.path.display(),
+                e
+            );
+        }
+    }
+}
+
+fn parse(path: PathBuf) -> Option<Snapshot> {
+    if path.extension()? != FILE_EXTENSION {
+        return None;
+    }
+    let rest = path.file_stem()?.to_str()?.strip_prefix(FILE_PREFIX)?;
+    // The timestamp has a fixed width; the reason follows after a dash
+    let (timestamp, reason) = rest.split_at_checked(15)?;
+    let taken_at = NaiveDateTime::parse_from_str(timestamp, TIMESTAMP_FORMAT).ok()?;
+    let reason = reason.strip_prefix('-')?.to_string();
+    Some(Snapshot {
+        path,
+        taken_at,
+        reason,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_newest_first_and_prunes_the_oldest() {
+        let dir = std::env::temp_dir().join(format!("brewsty-snapshots-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for name in [
+            "snapshot-20260101-120000-update-all.json",
+            "snapshot-20260301-080000-import.json",
+            "snapshot-20260201-000000-update-all.json",
+            "snapshot-garbage.json",
+            "crash-20260101-120000.log",
+        ] {
+            fs::write(dir.join(name), "{}").unwrap();
+        }
+
+        let snapshots = list(&dir);
+        let reasons: Vec<_> = snapshots.iter().map(|s| s.reason.as_str()).collect();
+        assert_eq!(reasons, ["import", "update-all", "update-all"]);
+        assert_eq!(snapshots[0].label(), "2026-03-01 08:00 · before import");
+
+        prune(&dir, 2);
+        let remaining = list(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(remaining, snapshots[..2]);
+    }
+}
@@ -0,0 +1,43 @@
+//! Hands files and folders to the desktop environment.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use std::process::Command;
+
+#[cfg(target_os = "macos")]
+const OPENER: &str = "open";
+#[cfg(not(target_os = "macos"))]
+const OPENER: &str = "xdg-open";
+
+/// What `open_path` shows folders in, for labels.
+#[cfg(target_os = "macos")]
+pub const FILE_MANAGER: &str = "Finder";
+#[cfg(not(target_os = "macos"))]
+pub const FILE_MANAGER: &str = "file manager";
+
+/// Opens `path` with its default application, or a file manager for folders.
+pub fn open_path(path: &Path) -> Result<()> {
+    Command::new(OPENER)
+        .arg(path)
+        .spawn()
+        .with_context(|| format!("Failed to run {} {}", OPENER, path.display()))?;
+    Ok(())
+}
+
+/// Shows `path` selected in the file manager, or opens its folder where
+/// the file manager can't select files.
+pub fn reveal_path(path: &Path) -> Result<()> {
+    #[cfg(target_os = "macos")]
+    {
+        Command::new(OPENER)
+            .arg("-R")
+            .arg(path)
+            .spawn()
+            .with_context(|| format!("Failed to run {} -R {}", OPENER, path.display()))?;
+        Ok(())
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        open_path(path.parent().unwrap_or(path))
+    }
+}
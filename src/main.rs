@@ -4,47 +4,112 @@ mod infrastructure;
 mod presentation;
 
 use application::UseCaseContainer;
-use domain::repositories::{PackageListRepository, PackageRepository, ServiceRepository};
+use domain::repositories::{
+    PackageListRepository, PackageRepository, ReleaseRepository, ServiceRepository,
+};
 use infrastructure::brew::{
     BrewPackageListRepository, BrewPackageRepository, BrewServiceRepository,
 };
-use presentation::services::log_capture;
+use infrastructure::config_repository::ConfigRepository;
+use infrastructure::crash_report;
+use infrastructure::github::GitHubReleaseRepository;
+use presentation::services::{log_capture, log_file};
 use presentation::ui::BrewstyApp;
+use std::path::PathBuf;
 use std::sync::Arc;
 
+/// Explicit config path from `--config <path>` / `--config=<path>`, falling
+/// back to the `BREWSTY_CONFIG` environment variable.
+fn config_path_override() -> Option<PathBuf> {
+    let mut args = std::env::args_os().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--config" {
+            return args.next().map(PathBuf::from);
+        }
+        if let Some(path) = arg.to_str().and_then(|a| a.strip_prefix("--config=")) {
+            return Some(PathBuf::from(path));
+        }
+    }
+
+    std::env::var_os("BREWSTY_CONFIG")
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from)
+}
+
 fn main() -> eframe::Result<()> {
     let runtime = tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
         .expect("Failed to create Tokio runtime");
-    let _guard = runtime.enter();
+    let guard = runtime.enter();
 
     let log_rx = log_capture::init_log_capture();
 
+    let config_repo = match config_path_override() {
+        Some(path) => ConfigRepository::with_path(path),
+        None => ConfigRepository::new(),
+    };
+    config_repo.check_writable();
+    crash_report::install_panic_hook(config_repo.dir().to_path_buf(), log_capture::recent_logs);
+    let config = config_repo.load_or_default();
+    log_capture::set_verbosity(config.log_verbosity);
+    log_file::set_enabled(&config_repo.log_dir(), config.file_logging_enabled);
+
+    // Only offer a crash report once; later launches stay quiet about it
+    let unseen_crash_report = crash_report::newest_report(config_repo.dir()).filter(|path| {
+        path.file_name().and_then(|n| n.to_str()) != config.seen_crash_report.as_deref()
+    });
+
     let package_repository: Arc<dyn PackageRepository> = Arc::new(BrewPackageRepository::new());
     let service_repository: Arc<dyn ServiceRepository> = Arc::new(BrewServiceRepository::new());
     let package_list_repository: Arc<dyn PackageListRepository> =
         Arc::new(BrewPackageListRepository::new());
+    let release_repository: Arc<dyn ReleaseRepository> = Arc::new(GitHubReleaseRepository::new());
 
     let use_cases = Arc::new(UseCaseContainer::new(
         package_repository,
         service_repository,
         package_list_repository,
+        release_repository,
     ));
 
+    let mut viewport = egui::ViewportBuilder::default()
+        .with_inner_size(config.window_size.unwrap_or([1400.0, 900.0]))
+        .with_min_inner_size([1000.0, 700.0]);
+    if let Some(position) = config.window_position {
+        viewport = viewport.with_position(position);
+    }
+
     let options = eframe::NativeOptions {
-        viewport: egui::ViewportBuilder::default()
-            .with_inner_size([1400.0, 900.0])
-            .with_min_inner_size([1000.0, 700.0]),
+        viewport,
         ..Default::default()
     };
 
     use presentation::services::AsyncExecutor;
     let executor = AsyncExecutor::new(runtime.handle().clone());
 
-    eframe::run_native(
+    let result = eframe::run_native(
         "Brewsty - Homebrew Package Manager",
         options,
-        Box::new(|_cc| Ok(Box::new(BrewstyApp::new(use_cases, log_rx, executor)))),
-    )
+        Box::new(|cc| {
+            Ok(Box::new(BrewstyApp::new(
+                cc.egui_ctx.clone(),
+                use_cases,
+                log_rx,
+                executor,
+                config_repo,
+                config,
+                unseen_crash_report,
+            )))
+        }),
+    );
+
+    // Brew calls run on blocking threads that can't be cancelled; the app's
+    // on_exit hook has already killed their processes, so they finish almost
+    // immediately. The timeout keeps a stuck one from holding the process open.
+    drop(guard);
+    runtime.shutdown_timeout(std::time::Duration::from_secs(2));
+    log_file::shutdown();
+
+    result
 }
@@ -0,0 +1,26 @@
+//! Names for widgets that only show a symbol or nothing at all, so screen
+//! readers such as VoiceOver announce what they do instead of the symbol.
+
+use egui::{Response, WidgetInfo, WidgetText, WidgetType};
+
+/// Makes `response` announce itself as `label`.
+pub fn name(response: &Response, typ: WidgetType, label: &str) {
+    let enabled = response.enabled();
+    response.widget_info(|| WidgetInfo::labeled(typ, enabled, label));
+}
+
+/// A small button showing `icon`, announced and explained on hover as `label`.
+pub fn icon_button(ui: &mut egui::Ui, icon: impl Into<WidgetText>, label: &str) -> Response {
+    let response = ui.small_button(icon).on_hover_text(label);
+    name(&response, WidgetType::Button, label);
+    response
+}
+
+/// A checkbox without visible text, such as a row's selection box,
+/// announced as `label`.
+pub fn checkbox(ui: &mut egui::Ui, checked: &mut bool, label: &str) -> Response {
+    let response = ui.checkbox(checked, "");
+    let (enabled, selected) = (response.enabled(), *checked);
+    response.widget_info(|| WidgetInfo::selected(WidgetType::Checkbox, enabled, selected, label));
+    response
+}
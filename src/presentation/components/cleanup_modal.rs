@@ -1,9 +1,12 @@
-use crate::domain::entities::CleanupPreview;
+use crate::domain::entities::{CleanupItem, CleanupPreview};
+use egui::{Key, Modifiers};
 
 #[derive(PartialEq, Clone)]
 pub enum CleanupType {
     Cache,
     OldVersions,
+    /// Formulae nothing installed depends on anymore.
+    Autoremove,
 }
 
 pub enum CleanupAction {
@@ -11,10 +14,20 @@ pub enum CleanupAction {
     Cancel,
 }
 
+/// Preview items that belong to one package.
+#[derive(Debug)]
+struct CleanupGroup {
+    package: String,
+    size: u64,
+    items: Vec<CleanupItem>,
+}
+
 pub struct CleanupModal {
     show: bool,
     cleanup_type: Option<CleanupType>,
     preview: Option<CleanupPreview>,
+    groups: Vec<CleanupGroup>,
+    focus_confirm: bool,
 }
 
 impl CleanupModal {
@@ -23,19 +36,24 @@ impl CleanupModal {
             show: false,
             cleanup_type: None,
             preview: None,
+            groups: Vec::new(),
+            focus_confirm: false,
         }
     }
 
     pub fn show_preview(&mut self, cleanup_type: CleanupType, preview: CleanupPreview) {
         self.cleanup_type = Some(cleanup_type);
+        self.groups = group_by_package(&preview.items);
         self.preview = Some(preview);
         self.show = true;
+        self.focus_confirm = true;
     }
 
     pub fn close(&mut self) {
         self.show = false;
         self.cleanup_type = None;
         self.preview = None;
+        self.groups.clear();
     }
 
     pub fn render(&mut self, ctx: &egui::Context) -> Option<CleanupAction> {
@@ -64,24 +82,46 @@ impl CleanupModal {
                     egui::ScrollArea::vertical()
                         .max_height(300.0)
                         .show(ui, |ui| {
-                            for item in &preview.items {
-                                ui.horizontal(|ui| {
-                                    ui.label(&item.path);
-                                    ui.label(format!("({})", format_size(item.size)));
-                                });
+                            for group in &self.groups {
+                                let title = format!(
+                                    "{} ({} items, {})",
+                                    group.package,
+                                    group.items.len(),
+                                    format_size(group.size)
+                                );
+                                egui::CollapsingHeader::new(title)
+                                    .id_salt(&group.package)
+                                    .show(ui, |ui| {
+                                        for item in &group.items {
+                                            ui.horizontal(|ui| {
+                                                ui.label(&item.path);
+                                                ui.label(format!("({})", format_size(item.size)));
+                                            });
+                                        }
+                                    });
                             }
                         });
 
                     ui.separator();
 
                     ui.horizontal(|ui| {
-                        if ui.button("Confirm").clicked() {
-                            if let Some(cleanup_type) = &self.cleanup_type {
-                                action = Some(CleanupAction::Confirm(cleanup_type.clone()));
-                            }
+                        let confirm = ui.button("Confirm");
+
+                        // Focus Confirm once when the modal opens
+                        if self.focus_confirm {
+                            confirm.request_focus();
+                            self.focus_confirm = false;
                         }
 
-                        if ui.button("Cancel").clicked() {
+                        if (confirm.clicked() || ui.input(|i| i.key_pressed(Key::Enter)))
+                            && let Some(cleanup_type) = &self.cleanup_type
+                        {
+                            action = Some(CleanupAction::Confirm(cleanup_type.clone()));
+                        }
+
+                        if ui.button("Cancel").clicked()
+                            || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape))
+                        {
                             action = Some(CleanupAction::Cancel);
                         }
                     });
@@ -98,7 +138,63 @@ impl Default for CleanupModal {
     }
 }
 
-fn format_size(bytes: u64) -> String {
+/// Groups `items` by the package they belong to, largest group first.
+fn group_by_package(items: &[CleanupItem]) -> Vec<CleanupGroup> {
+    let mut groups: Vec<CleanupGroup> = Vec::new();
+    for item in items {
+        let package = package_name(&item.path);
+        match groups.iter_mut().find(|g| g.package == package) {
+            Some(group) => {
+                group.size += item.size;
+                group.items.push(item.clone());
+            }
+            None => groups.push(CleanupGroup {
+                package: package.to_string(),
+                size: item.size,
+                items: vec![item.clone()],
+            }),
+        }
+    }
+    groups.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.package.cmp(&b.package)));
+    groups
+}
+
+/// The package a cleanup path belongs to: the directory under Cellar or
+/// Caskroom, or the name at the start of a cache file such as
+/// `<sha256>--jq--1.7.1.bottle.tar.gz`.
+fn package_name(path: &str) -> &str {
+    let mut segments = path.trim_end_matches('/').split('/');
+    while let Some(segment) = segments.next() {
+        if (segment == "Cellar" || segment == "Caskroom")
+            && let Some(name) = segments.next()
+        {
+            return name;
+        }
+    }
+
+    let file_name = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(path);
+    // Downloads are prefixed with the hash of their URL
+    let file_name = match file_name.split_once("--") {
+        Some((hash, rest)) if hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            rest
+        }
+        _ => file_name,
+    };
+    if let Some((name, _version)) = file_name.split_once("--") {
+        return name;
+    }
+    // Older cache files use a single dash: jq-1.6.catalina.bottle.tar.gz
+    file_name
+        .match_indices('-')
+        .find(|(i, _)| file_name[i + 1..].starts_with(|c: char| c.is_ascii_digit()))
+        .map_or(file_name, |(i, _)| &file_name[..i])
+}
+
+pub fn format_size(bytes: u64) -> String {
     const KB: u64 = 1024;
     const MB: u64 = KB * 1024;
     const GB: u64 = MB * 1024;
@@ -113,3 +209,74 @@ fn format_size(bytes: u64) -> String {
         format!("{} B", bytes)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "4b1ec5e0c2d40bbb8e5d1f0d9a7d4c2fd2c4e0b8b7d0c1c5a1e8c4f3d2b1a0f9";
+
+    fn item(path: &str, size: u64) -> CleanupItem {
+        CleanupItem {
+            path: path.to_string(),
+            size,
+        }
+    }
+
+    #[test]
+    fn package_name_reads_cellar_and_caskroom_paths() {
+        assert_eq!(package_name("/opt/homebrew/Cellar/jq/1.6"), "jq");
+        assert_eq!(
+            package_name("/opt/homebrew/Cellar/python@3.12/3.12.1/"),
+            "python@3.12"
+        );
+        assert_eq!(package_name("/usr/local/Caskroom/firefox/120.0"), "firefox");
+    }
+
+    #[test]
+    fn package_name_reads_cache_file_names() {
+        let cache = "/Users/me/Library/Caches/Homebrew";
+        assert_eq!(
+            package_name(&format!(
+                "{}/downloads/{}--jq--1.7.1.arm64_sonoma.bottle.tar.gz",
+                cache, HASH
+            )),
+            "jq"
+        );
+        assert_eq!(
+            package_name(&format!(
+                "{}/downloads/{}--openssl@3-3.2.0.bottle_manifest.json",
+                cache, HASH
+            )),
+            "openssl@3"
+        );
+        assert_eq!(
+            package_name(&format!("{}/Cask/firefox--120.0.dmg", cache)),
+            "firefox"
+        );
+        assert_eq!(
+            package_name(&format!("{}/wget-1.21.catalina.bottle.tar.gz", cache)),
+            "wget"
+        );
+        assert_eq!(package_name(&format!("{}/api", cache)), "api");
+    }
+
+    #[test]
+    fn groups_are_sorted_by_subtotal() {
+        let groups = group_by_package(&[
+            item("/opt/homebrew/Cellar/jq/1.6", 10),
+            item("/opt/homebrew/Cellar/node/20.1.0", 300),
+            item("/opt/homebrew/Cellar/jq/1.7", 15),
+            item(
+                &format!("/cache/downloads/{}--jq--1.7.1.bottle.tar.gz", HASH),
+                5,
+            ),
+        ]);
+
+        let summary: Vec<_> = groups
+            .iter()
+            .map(|g| (g.package.as_str(), g.items.len(), g.size))
+            .collect();
+        assert_eq!(summary, [("node", 1, 300), ("jq", 3, 30)]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Resizable columns for the package and service tables, with the widths
+//! the user dragged them to kept in the config.
+
+use crate::presentation::components::accessible;
+use crate::presentation::components::grid_density::heading;
+use egui::{Align, Layout, Sense, WidgetType};
+use egui_extras::{Column, TableBuilder, TableRow};
+use std::collections::{HashMap, HashSet};
+
+const MIN_COLUMN_WIDTH: f32 = 40.0;
+
+/// Id, titles and default widths of a table's columns. The last column
+/// fills whatever space is left, so its width is never stored.
+pub struct TableLayout {
+    pub id: &'static str,
+    pub columns: &'static [(&'static str, f32)],
+}
+
+impl TableLayout {
+    fn default_widths(&self) -> Vec<f32> {
+        let sized = self.columns.len().saturating_sub(1);
+        self.columns[..sized].iter().map(|(_, w)| *w).collect()
+    }
+}
+
+/// Order of a sorted column. Clicking a sortable header cycles through
+/// descending, ascending and unsorted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    Descending,
+    Ascending,
+}
+
+impl SortOrder {
+    fn next(order: Option<SortOrder>) -> Option<SortOrder> {
+        match order {
+            None => Some(SortOrder::Descending),
+            Some(SortOrder::Descending) => Some(SortOrder::Ascending),
+            Some(SortOrder::Ascending) => None,
+        }
+    }
+
+    fn arrow(self) -> &'static str {
+        match self {
+            SortOrder::Descending => "⏷",
+            SortOrder::Ascending => "⏶",
+        }
+    }
+
+    /// The arrow in words, for screen readers.
+    fn describe(order: Option<SortOrder>) -> &'static str {
+        match order {
+            Some(SortOrder::Descending) => "sorted descending",
+            Some(SortOrder::Ascending) => "sorted ascending",
+            None => "not sorted",
+        }
+    }
+}
+
+pub struct ColumnWidths {
+    widths: HashMap<String, Vec<f32>>,
+    changed: bool,
+    /// Tables whose widths were reset, to be drawn at their defaults again.
+    resetting: HashSet<&'static str>,
+}
+
+impl ColumnWidths {
+    pub fn new(widths: HashMap<String, Vec<f32>>) -> Self {
+        Self {
+            widths,
+            changed: false,
+            resetting: HashSet::new(),
+        }
+    }
+
+    pub fn widths(&self) -> &HashMap<String, Vec<f32>> {
+        &self.widths
+    }
+
+    /// True once after a column was resized and the widths need saving.
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Goes back to the default widths of `layout`'s columns.
+    pub fn reset(&mut self, layout: &TableLayout) {
+        if self.widths.remove(layout.id).is_some() {
+            self.changed = true;
+        }
+        self.resetting.insert(layout.id);
+    }
+
+    /// A striped table for `layout`, its columns starting at the saved widths.
+    pub fn table<'a>(&mut self, ui: &'a mut egui::Ui, layout: &TableLayout) -> TableBuilder<'a> {
+        let widths = self
+            .widths
+            .get(layout.id)
+            .filter(|w| w.len() + 1 == layout.columns.len())
+            .cloned()
+            .unwrap_or_else(|| layout.default_widths());
+
+        let mut table = TableBuilder::new(ui)
+            .id_salt(layout.id)
+            .striped(true)
+            .resizable(true)
+            .cell_layout(Layout::left_to_right(Align::Center));
+        // The table keeps the widths it was dragged to until told to forget them
+        if self.resetting.remove(layout.id) {
+            table.reset();
+        }
+        for width in widths {
+            table = table.column(Column::initial(width).at_least(MIN_COLUMN_WIDTH).clip(true));
+        }
+        table.column(Column::remainder())
+    }
+
+    /// Adds the column titles and records the widths the columns were drawn at.
+    pub fn header(&mut self, row: TableRow<'_, '_>, layout: &TableLayout, compact: bool) {
+        self.sortable_header(row, layout, compact, None);
+    }
+
+    /// Like `header`, with the title of column `sortable.0` clickable to
+    /// change its sort order `sortable.1`. Right-clicking any title offers to
+    /// reset the widths.
+    pub fn sortable_header(
+        &mut self,
+        mut row: TableRow<'_, '_>,
+        layout: &TableLayout,
+        compact: bool,
+        mut sortable: Option<(usize, &mut Option<SortOrder>)>,
+    ) {
+        let mut reset = false;
+        let mut widths: Vec<f32> = layout
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(index, (title, _))| {
+                row.col(|ui| {
+                    let response = match &mut sortable {
+                        Some((column, order)) if *column == index => {
+                            let label = format!("{}, {}", title, SortOrder::describe(**order));
+                            let title = match **order {
+                                Some(current) => format!("{} {}", title, current.arrow()),
+                                None => title.to_string(),
+                            };
+                            let response = heading(ui, &title, compact)
+                                .interact(Sense::click())
+                                .on_hover_text("Click to sort");
+                            accessible::name(&response, WidgetType::Button, &label);
+                            if response.clicked() {
+                                **order = SortOrder::next(**order);
+                            }
+                            response
+                        }
+                        _ => heading(ui, title, compact).interact(Sense::click()),
+                    };
+                    response.context_menu(|ui| {
+                        if ui.button("Reset Column Widths").clicked() {
+                            reset = true;
+                            ui.close_menu();
+                        }
+                    });
+                })
+                .1
+                .rect
+                .width()
+                .round()
+            })
+            .collect();
+        widths.pop();
+
+        if reset {
+            self.reset(layout);
+            return;
+        }
+
+        let current = self
+            .widths
+            .get(layout.id)
+            .cloned()
+            .unwrap_or_else(|| layout.default_widths());
+        if widths != current {
+            self.widths.insert(layout.id.to_string(), widths);
+            self.changed = true;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: TableLayout = TableLayout {
+        id: "test_table",
+        columns: &[("Name", 200.0), ("Version", 120.0), ("Actions", 0.0)],
+    };
+
+    #[test]
+    fn reset_forgets_saved_widths() {
+        let saved = HashMap::from([(LAYOUT.id.to_string(), vec![320.0, 80.0])]);
+        let mut column_widths = ColumnWidths::new(saved);
+
+        column_widths.reset(&LAYOUT);
+        assert!(column_widths.take_changed());
+        assert!(column_widths.widths().is_empty());
+        assert!(column_widths.resetting.contains(LAYOUT.id));
+
+        // Nothing was saved for it anymore, so there is nothing to write back
+        column_widths.reset(&LAYOUT);
+        assert!(!column_widths.take_changed());
+    }
+}
@@ -0,0 +1,124 @@
+use crate::domain::entities::{PackageListDiff, PackageListItem};
+use egui::{Color32, Key, Modifiers, RichText};
+
+const ADDED_COLOR: Color32 = Color32::from_rgb(0, 200, 0);
+const REMOVED_COLOR: Color32 = Color32::from_rgb(255, 0, 0);
+const CHANGED_COLOR: Color32 = Color32::from_rgb(255, 165, 0);
+
+/// Shows what was added, removed and changed since an exported package list.
+pub struct CompareModal {
+    show: bool,
+    /// The export's file name, for the title.
+    file_name: String,
+    diff: Option<Result<PackageListDiff, String>>,
+}
+
+impl CompareModal {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            file_name: String::new(),
+            diff: None,
+        }
+    }
+
+    /// Opens the modal for `file_name` while the comparison runs.
+    pub fn show_loading(&mut self, file_name: String) {
+        self.file_name = file_name;
+        self.diff = None;
+        self.show = true;
+    }
+
+    pub fn set_diff(&mut self, diff: Result<PackageListDiff, String>) {
+        if self.show {
+            self.diff = Some(diff);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.diff = None;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) {
+        if !self.show {
+            return;
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            self.close();
+            return;
+        }
+
+        let mut open = true;
+        egui::Window::new(format!("Compare with {}", self.file_name))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| match &self.diff {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Comparing with what is installed now…");
+                    });
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(REMOVED_COLOR, format!("Couldn't compare: {}", e));
+                }
+                Some(Ok(diff)) if diff.is_empty() => {
+                    ui.label("Nothing has changed since this export.");
+                }
+                Some(Ok(diff)) => {
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            Self::items(ui, "Added", ADDED_COLOR, &diff.added);
+                            Self::items(ui, "Removed", REMOVED_COLOR, &diff.removed);
+                            egui::CollapsingHeader::new(
+                                RichText::new(format!("Changed ({})", diff.changed.len()))
+                                    .color(CHANGED_COLOR),
+                            )
+                            .default_open(true)
+                            .show(ui, |ui| {
+                                for change in &diff.changed {
+                                    ui.label(format!(
+                                        "{}  {} → {}",
+                                        change.name, change.old_version, change.new_version
+                                    ));
+                                }
+                            });
+                        });
+                    ui.separator();
+                    if ui.button("📋 Copy as Text").clicked() {
+                        ui.ctx().copy_text(diff.to_text());
+                    }
+                }
+            });
+
+        if !open {
+            self.close();
+        }
+    }
+
+    fn items(ui: &mut egui::Ui, heading: &str, color: Color32, items: &[PackageListItem]) {
+        egui::CollapsingHeader::new(
+            RichText::new(format!("{} ({})", heading, items.len())).color(color),
+        )
+        .default_open(true)
+        .show(ui, |ui| {
+            for item in items {
+                match &item.version {
+                    Some(version) => ui.label(format!("{}  {}", item.name, version)),
+                    None => ui.label(&item.name),
+                };
+            }
+        });
+    }
+}
+
+impl Default for CompareModal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
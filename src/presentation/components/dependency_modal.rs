@@ -0,0 +1,184 @@
+use crate::domain::entities::{DependencyNode, PackageType};
+use egui::{Color32, Key, Modifiers, RichText};
+use std::collections::HashSet;
+
+/// Levels below this are cut off with "…", so a deep tree stays readable.
+const MAX_DEPTH: usize = 8;
+
+const MISSING_COLOR: Color32 = Color32::from_rgb(255, 0, 0);
+
+pub enum DependencyAction {
+    /// A node was clicked; show that package's info. The type is only
+    /// known for the package the tree is for.
+    ShowInfo(String, Option<PackageType>),
+}
+
+/// Shows what a package depends on as a collapsible tree, with
+/// dependencies that aren't installed in red.
+pub struct DependencyModal {
+    show: bool,
+    package_name: String,
+    package_type: PackageType,
+    tree: Option<Result<DependencyNode, String>>,
+    installed: HashSet<String>,
+}
+
+impl DependencyModal {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            package_name: String::new(),
+            package_type: PackageType::Formula,
+            tree: None,
+            installed: HashSet::new(),
+        }
+    }
+
+    /// Opens the modal for `package_name` while its tree loads.
+    pub fn show_loading(&mut self, package_name: String, package_type: PackageType) {
+        self.package_name = package_name;
+        self.package_type = package_type;
+        self.tree = None;
+        self.show = true;
+    }
+
+    /// Shows `tree` if it is for the package the modal is open for.
+    /// `installed` names every installed package.
+    pub fn set_tree(
+        &mut self,
+        package_name: &str,
+        tree: Result<DependencyNode, String>,
+        installed: HashSet<String>,
+    ) {
+        if self.show && self.package_name == package_name {
+            self.tree = Some(tree);
+            self.installed = installed;
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.tree = None;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<DependencyAction> {
+        if !self.show {
+            return None;
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            self.close();
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(format!("Dependencies: {}", self.package_name))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(360.0)
+            .open(&mut open)
+            .show(ctx, |ui| match &self.tree {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Running brew deps…");
+                    });
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(MISSING_COLOR, format!("Couldn't list dependencies: {}", e));
+                }
+                Some(Ok(tree)) if tree.children.is_empty() => {
+                    ui.label(format!("{} has no dependencies.", tree.name));
+                }
+                Some(Ok(tree)) => {
+                    ui.colored_label(MISSING_COLOR, "Red: not installed");
+                    ui.separator();
+                    egui::ScrollArea::vertical()
+                        .max_height(400.0)
+                        .show(ui, |ui| {
+                            let mut path = Vec::new();
+                            Self::node(
+                                ui,
+                                tree,
+                                &self.installed,
+                                &self.package_type,
+                                &mut path,
+                                &mut action,
+                            );
+                        });
+                }
+            });
+
+        if !open {
+            self.close();
+        }
+        action
+    }
+
+    /// `path` holds the names above `node`, to stop at cycles and depth.
+    /// `root_type` is the type of the package the tree is for.
+    fn node<'a>(
+        ui: &mut egui::Ui,
+        node: &'a DependencyNode,
+        installed: &HashSet<String>,
+        root_type: &PackageType,
+        path: &mut Vec<&'a str>,
+        action: &mut Option<DependencyAction>,
+    ) {
+        let mut label = |ui: &mut egui::Ui| {
+            let mut text = RichText::new(&node.name);
+            if !installed.contains(&node.name) {
+                text = text.color(MISSING_COLOR);
+            }
+            if ui
+                .add(egui::Label::new(text).sense(egui::Sense::click()))
+                .on_hover_cursor(egui::CursorIcon::PointingHand)
+                .on_hover_text("Show info")
+                .clicked()
+            {
+                let package_type = path.is_empty().then(|| root_type.clone());
+                *action = Some(DependencyAction::ShowInfo(node.name.clone(), package_type));
+            }
+        };
+
+        if node.children.is_empty() {
+            ui.horizontal(|ui| {
+                // Line up with the names next to a collapse arrow
+                ui.add_space(ui.spacing().indent);
+                label(ui);
+            });
+            return;
+        }
+
+        if path.len() >= MAX_DEPTH || path.contains(&node.name.as_str()) {
+            ui.horizontal(|ui| {
+                ui.add_space(ui.spacing().indent);
+                label(ui);
+                ui.weak("…");
+            });
+            return;
+        }
+
+        let id = ui.make_persistent_id(&node.name);
+        egui::collapsing_header::CollapsingState::load_with_default_open(
+            ui.ctx(),
+            id,
+            path.len() < 2,
+        )
+        .show_header(ui, |ui| label(ui))
+        .body(|ui| {
+            path.push(&node.name);
+            for child in &node.children {
+                Self::node(ui, child, installed, root_type, path, action);
+            }
+            path.pop();
+        });
+    }
+}
+
+impl Default for DependencyModal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,20 @@
+use crate::presentation::components::accessible;
+use egui::{Color32, RichText, WidgetType};
+
+/// Star toggle shown next to package names. Returns true when clicked.
+pub fn favorite_button(ui: &mut egui::Ui, starred: bool) -> bool {
+    let (icon, hover) = if starred {
+        (
+            RichText::new("★").color(Color32::from_rgb(255, 200, 0)),
+            "Remove from favorites",
+        )
+    } else {
+        (RichText::new("☆"), "Add to favorites")
+    };
+
+    let response = ui
+        .add(egui::Button::new(icon).frame(false))
+        .on_hover_text(hover);
+    accessible::name(&response, WidgetType::Button, hover);
+    response.clicked()
+}
@@ -3,6 +3,9 @@ pub struct FilterState {
     show_casks: bool,
     search_query: String,
     installed_search_query: String,
+    services_search_query: String,
+    /// Only installed packages with this tag are listed.
+    tag_filter: Option<String>,
 }
 
 impl FilterState {
@@ -12,6 +15,8 @@ impl FilterState {
             show_casks: true,
             search_query: String::new(),
             installed_search_query: String::new(),
+            services_search_query: String::new(),
+            tag_filter: None,
         }
     }
 
@@ -46,6 +51,22 @@ impl FilterState {
     pub fn installed_search_query_mut(&mut self) -> &mut String {
         &mut self.installed_search_query
     }
+
+    pub fn services_search_query(&self) -> &str {
+        &self.services_search_query
+    }
+
+    pub fn services_search_query_mut(&mut self) -> &mut String {
+        &mut self.services_search_query
+    }
+
+    pub fn tag_filter(&self) -> Option<&str> {
+        self.tag_filter.as_deref()
+    }
+
+    pub fn tag_filter_mut(&mut self) -> &mut Option<String> {
+        &mut self.tag_filter
+    }
 }
 
 impl Default for FilterState {
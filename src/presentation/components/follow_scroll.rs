@@ -0,0 +1,169 @@
+//! Lets a log view follow new lines until the user scrolls away to read,
+//! then offers a "Jump to latest" button instead of yanking the view back.
+
+use egui::scroll_area::ScrollAreaOutput;
+use egui::{Align2, Color32, RichText, ScrollArea};
+
+/// Which end of the view new lines appear at.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FollowEdge {
+    Top,
+    Bottom,
+}
+
+/// How close to the edge, in points, still counts as being at it.
+const EDGE_TOLERANCE: f32 = 2.0;
+
+pub struct FollowScroll {
+    edge: FollowEdge,
+    following: bool,
+    /// Line count when the user scrolled away, to tell how many are new.
+    seen_lines: usize,
+    /// Line count and scroll offset last frame, to keep a paused top-edge
+    /// view in place.
+    last_lines: usize,
+    last_offset: f32,
+    jump_requested: bool,
+}
+
+impl FollowScroll {
+    pub fn new(edge: FollowEdge) -> Self {
+        Self {
+            edge,
+            following: true,
+            seen_lines: 0,
+            last_lines: 0,
+            last_offset: 0.0,
+            jump_requested: false,
+        }
+    }
+
+    /// Configures `area` for this frame. `row_height` is the height each new
+    /// line adds; it keeps a paused view steady when lines arrive at the top.
+    pub fn scroll_area(
+        &mut self,
+        area: ScrollArea,
+        line_count: usize,
+        row_height: f32,
+    ) -> ScrollArea {
+        let added = line_count.saturating_sub(self.last_lines);
+        self.last_lines = line_count;
+
+        if std::mem::take(&mut self.jump_requested) {
+            self.following = true;
+            let edge_offset = match self.edge {
+                FollowEdge::Top => 0.0,
+                // Clamped to the end of the content by the scroll area
+                FollowEdge::Bottom => f32::MAX,
+            };
+            return area
+                .stick_to_bottom(self.edge == FollowEdge::Bottom)
+                .vertical_scroll_offset(edge_offset);
+        }
+
+        match self.edge {
+            FollowEdge::Bottom => area.stick_to_bottom(self.following),
+            FollowEdge::Top if !self.following && added > 0 => {
+                area.vertical_scroll_offset(self.last_offset + added as f32 * row_height)
+            }
+            FollowEdge::Top => area,
+        }
+    }
+
+    /// Reads where the user left the view, after the area was shown.
+    pub fn update<R>(&mut self, output: &ScrollAreaOutput<R>, line_count: usize) {
+        let max_offset = (output.content_size.y - output.inner_rect.height()).max(0.0);
+        let offset = output.state.offset.y;
+        self.last_offset = offset;
+        let at_edge = match self.edge {
+            FollowEdge::Top => offset <= EDGE_TOLERANCE,
+            FollowEdge::Bottom => offset >= max_offset - EDGE_TOLERANCE,
+        };
+
+        if at_edge || self.following {
+            self.seen_lines = line_count;
+        }
+        self.following = at_edge;
+        // Lines can also go away when the log is cleared
+        self.seen_lines = self.seen_lines.min(line_count);
+    }
+
+    /// Shows "Jump to latest (N new)" over the view while it is paused.
+    pub fn show_jump_button<R>(
+        &mut self,
+        ui: &egui::Ui,
+        output: &ScrollAreaOutput<R>,
+        line_count: usize,
+    ) {
+        if self.following {
+            return;
+        }
+
+        let new_lines = line_count.saturating_sub(self.seen_lines);
+        let text = if new_lines > 0 {
+            format!("Jump to latest ({} new)", new_lines)
+        } else {
+            "Jump to latest".to_string()
+        };
+        let margin = egui::vec2(0.0, 8.0);
+        let (pivot, pos) = match self.edge {
+            FollowEdge::Top => (Align2::CENTER_TOP, output.inner_rect.center_top() + margin),
+            FollowEdge::Bottom => (
+                Align2::CENTER_BOTTOM,
+                output.inner_rect.center_bottom() - margin,
+            ),
+        };
+
+        egui::Area::new(output.id.with("jump_to_latest"))
+            .fixed_pos(pos)
+            .pivot(pivot)
+            .order(egui::Order::Foreground)
+            .show(ui.ctx(), |ui| {
+                let button = egui::Button::new(RichText::new(text).color(Color32::WHITE))
+                    .fill(ui.visuals().selection.bg_fill)
+                    .rounding(12.0);
+                if ui.add(button).clicked() {
+                    self.jump_requested = true;
+                }
+            });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use egui::scroll_area::State;
+    use egui::{Id, Rect, pos2, vec2};
+
+    /// Output of a 100pt tall view over `content_height` of content.
+    fn output(offset: f32, content_height: f32) -> ScrollAreaOutput<()> {
+        let mut state = State::default();
+        state.offset.y = offset;
+        ScrollAreaOutput {
+            inner: (),
+            id: Id::new("test"),
+            state,
+            content_size: vec2(100.0, content_height),
+            inner_rect: Rect::from_min_size(pos2(0.0, 0.0), vec2(100.0, 100.0)),
+        }
+    }
+
+    #[test]
+    fn scrolling_up_pauses_and_counts_new_lines() {
+        let mut follow = FollowScroll::new(FollowEdge::Bottom);
+
+        follow.update(&output(400.0, 500.0), 50);
+        assert!(follow.following);
+
+        follow.update(&output(200.0, 500.0), 50);
+        assert!(!follow.following);
+
+        follow.update(&output(200.0, 600.0), 60);
+        assert!(!follow.following);
+        assert_eq!(follow.seen_lines, 50);
+
+        follow.update(&output(500.0, 600.0), 60);
+        assert!(follow.following);
+        assert_eq!(follow.seen_lines, 60);
+    }
+}
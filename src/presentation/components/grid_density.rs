@@ -0,0 +1,100 @@
+//! Sizing helpers shared by the package tables, so compact mode is a flag on
+//! each table rather than a second copy of it.
+
+use crate::domain::entities::OperationProgress;
+use crate::presentation::components::accessible;
+use egui::{Color32, ProgressBar, RichText, TextStyle, WidgetType};
+
+/// Tighter cell spacing and smaller buttons in compact mode, so rows get
+/// shorter. Call on the `Ui` the table is added to.
+pub fn apply_table_style(ui: &mut egui::Ui, compact: bool) {
+    if compact {
+        ui.spacing_mut().item_spacing = egui::vec2(8.0, 2.0);
+        ui.spacing_mut().button_padding = egui::vec2(6.0, 1.0);
+        ui.spacing_mut().interact_size.y = 18.0;
+    }
+}
+
+/// Height of a table row holding buttons, after `apply_table_style`.
+pub fn row_height(ui: &egui::Ui) -> f32 {
+    let button = ui.text_style_height(&TextStyle::Button) + 2.0 * ui.spacing().button_padding.y;
+    button.max(ui.spacing().interact_size.y) + 4.0
+}
+
+/// Height of a header row of `heading`s.
+pub fn header_height(ui: &egui::Ui, compact: bool) -> f32 {
+    let style = if compact {
+        TextStyle::Body
+    } else {
+        TextStyle::Heading
+    };
+    ui.text_style_height(&style) + 8.0
+}
+
+/// Section titles and column headers; plain bold text in compact mode.
+pub fn heading(ui: &mut egui::Ui, text: &str, compact: bool) -> egui::Response {
+    if compact {
+        ui.strong(text)
+    } else {
+        ui.heading(text)
+    }
+}
+
+/// The status as colored text, or just a colored dot with the text on hover.
+pub fn status_cell(ui: &mut egui::Ui, text: &str, color: Color32, compact: bool) {
+    if compact {
+        let response = ui
+            .label(RichText::new("●").color(color))
+            .on_hover_text(text);
+        accessible::name(&response, WidgetType::Label, text);
+    } else {
+        ui.label(RichText::new(text).color(color));
+    }
+}
+
+/// The status of a package being installed or updated: a spinner until brew
+/// reports progress, then a bar filling up as it downloads.
+pub fn progress_cell(ui: &mut egui::Ui, progress: Option<&OperationProgress>) {
+    match progress {
+        Some(progress) => {
+            progress_bar(ui, progress, ui.available_width());
+        }
+        None => {
+            ui.spinner();
+        }
+    }
+}
+
+/// A bar showing the download percentage, or an animated empty one while the
+/// percentage is unknown and once brew has moved on to installing.
+pub fn progress_bar(ui: &mut egui::Ui, progress: &OperationProgress, width: f32) -> egui::Response {
+    let (bar, file) = match progress {
+        OperationProgress::Downloading {
+            file,
+            percent: Some(percent),
+        } => (
+            ProgressBar::new(percent / 100.0).text(format!("{:.0}%", percent)),
+            Some(file),
+        ),
+        OperationProgress::Downloading {
+            file,
+            percent: None,
+        } => (
+            ProgressBar::new(0.0).animate(true).text("Downloading…"),
+            Some(file),
+        ),
+        OperationProgress::Installing => (
+            ProgressBar::new(0.0).animate(true).text("Installing…"),
+            None,
+        ),
+        OperationProgress::RefreshingMetadata => (
+            ProgressBar::new(0.0).animate(true).text("brew update"),
+            None,
+        ),
+    };
+    let response = ui.add(bar.desired_width(width));
+    match file {
+        Some(file) if !file.is_empty() => response.on_hover_text(format!("Downloading {}", file)),
+        _ => response,
+    }
+}
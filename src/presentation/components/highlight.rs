@@ -0,0 +1,84 @@
+//! Package names with the part matching the filter emphasized, so it's
+//! clear why each row matched.
+
+use egui::text::LayoutJob;
+use egui::{Align, FontSelection, RichText, WidgetText};
+use std::ops::Range;
+
+/// `name` with its first match of `query` in strong, underlined text; just
+/// the name when nothing matches.
+pub fn highlighted_name(ui: &egui::Ui, name: &str, query: &str) -> WidgetText {
+    let Some(range) = match_range(name, query) else {
+        return name.into();
+    };
+    let mut job = LayoutJob::default();
+    for (text, matched) in [
+        (&name[..range.start], false),
+        (&name[range.clone()], true),
+        (&name[range.end..], false),
+    ] {
+        let mut text = RichText::new(text);
+        if matched {
+            text = text.strong().underline();
+        }
+        text.append_to(&mut job, ui.style(), FontSelection::Default, Align::Center);
+    }
+    job.into()
+}
+
+/// Where `query` first appears in `name`, ignoring case, as a byte range of
+/// `name`. When the whole query doesn't appear, the first of its words that
+/// does is used.
+pub fn match_range(name: &str, query: &str) -> Option<Range<usize>> {
+    let query = query.trim();
+    if query.is_empty() {
+        return None;
+    }
+    find_ignoring_case(name, query).or_else(|| {
+        query
+            .split_whitespace()
+            .find_map(|term| find_ignoring_case(name, term))
+    })
+}
+
+fn find_ignoring_case(name: &str, term: &str) -> Option<Range<usize>> {
+    let term: Vec<char> = term.chars().flat_map(char::to_lowercase).collect();
+    name.char_indices().find_map(|(start, _)| {
+        let mut wanted = term.iter();
+        for (offset, c) in name[start..].char_indices() {
+            for lower in c.to_lowercase() {
+                if wanted.next() != Some(&lower) {
+                    return None;
+                }
+            }
+            if wanted.len() == 0 {
+                return Some(start..start + offset + c.len_utf8());
+            }
+        }
+        None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_the_first_match_ignoring_case() {
+        assert_eq!(match_range("ffmpeg", "ffm"), Some(0..3));
+        assert_eq!(match_range("libFFmpeg", "ffm"), Some(3..6));
+        assert_eq!(match_range("wget", "node"), None);
+        assert_eq!(match_range("wget", "  "), None);
+    }
+
+    #[test]
+    fn multi_word_queries_fall_back_to_the_first_matching_word() {
+        assert_eq!(match_range("python@3.12", "py 3.12"), Some(0..2));
+        assert_eq!(match_range("python@3.12", "node 3.12"), Some(7..11));
+    }
+
+    #[test]
+    fn ranges_fall_on_character_boundaries() {
+        assert_eq!(match_range("caFÉ-app", "fé"), Some(2..5));
+    }
+}
@@ -1,8 +1,21 @@
-use crate::domain::entities::Package;
+use crate::domain::entities::{AuditResult, Package, PackageMetadata, PackageType};
+use egui::{Color32, Key, Modifiers};
+use std::collections::HashMap;
+
+pub enum InfoAction {
+    Audit(Package),
+    ShowDependencies(Package),
+    /// Store the edited tags and note for the named package.
+    SaveMetadata(String, PackageMetadata),
+}
 
 pub struct InfoModal {
     show: bool,
     package: Option<Package>,
+    /// The package the tag and note fields were filled in for.
+    editing: Option<String>,
+    tags_input: String,
+    note_input: String,
 }
 
 impl InfoModal {
@@ -10,6 +23,9 @@ impl InfoModal {
         Self {
             show: false,
             package: None,
+            editing: None,
+            tags_input: String::new(),
+            note_input: String::new(),
         }
     }
 
@@ -21,14 +37,42 @@ impl InfoModal {
     pub fn close(&mut self) {
         self.show = false;
         self.package = None;
+        self.editing = None;
+    }
+
+    /// Shows `audit` if `name` is the package on display.
+    pub fn set_audit(&mut self, name: &str, audit: AuditResult) {
+        if let Some(package) = self.package.as_mut().filter(|p| p.name == name) {
+            package.audit = Some(audit);
+        }
     }
 
-    pub fn render(&mut self, ctx: &egui::Context) {
+    /// `auditing` names the package whose audit is running, if any;
+    /// `metadata` holds the user's tags and notes by name.
+    pub fn render(
+        &mut self,
+        ctx: &egui::Context,
+        auditing: Option<&str>,
+        metadata: &HashMap<String, PackageMetadata>,
+    ) -> Option<InfoAction> {
         if !self.show {
-            return;
+            return None;
+        }
+
+        // Consume Escape so nothing else rendered later this frame reacts to it
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            self.close();
+            return None;
         }
 
+        let mut action = None;
         if let Some(package) = self.package.clone() {
+            let saved = metadata.get(&package.name).cloned().unwrap_or_default();
+            if self.editing.as_ref() != Some(&package.name) {
+                self.editing = Some(package.name.clone());
+                self.tags_input = saved.tags.join(", ");
+                self.note_input = saved.note.clone();
+            }
             let mut open = self.show;
             egui::Window::new(format!("Info: {}", package.name))
                 .collapsible(false)
@@ -45,6 +89,22 @@ impl InfoModal {
 
                         ui.add_space(8.0);
 
+                        if let Some(archs) =
+                            package.supported_archs.as_ref().filter(|a| !a.is_empty())
+                        {
+                            let names: Vec<String> =
+                                archs.iter().map(ToString::to_string).collect();
+                            ui.label(egui::RichText::new("Runs on:").strong());
+                            ui.label(format!("{} Macs only", names.join(" and ")));
+                            ui.add_space(8.0);
+                        }
+
+                        if package.auto_updates {
+                            ui.label(egui::RichText::new("Updates:").strong());
+                            ui.label("The app updates itself");
+                            ui.add_space(8.0);
+                        }
+
                         if let Some(version) = &package.version {
                             ui.label(egui::RichText::new("Version:").strong());
                             ui.label(version);
@@ -57,10 +117,49 @@ impl InfoModal {
                             ui.add_space(8.0);
                         }
 
-                        ui.separator();
-                        if ui.button("Close").clicked() {
-                            self.close();
+                        if package.package_type == PackageType::Formula {
+                            if auditing == Some(package.name.as_str()) {
+                                ui.horizontal(|ui| {
+                                    ui.spinner();
+                                    ui.label("Auditing…");
+                                });
+                            } else {
+                                let label = match package.audit {
+                                    Some(_) => "Audit Again",
+                                    None => "Audit",
+                                };
+                                if ui
+                                    .add_enabled(auditing.is_none(), egui::Button::new(label))
+                                    .on_hover_text("Run brew audit --strict")
+                                    .on_disabled_hover_text("Another audit is running")
+                                    .clicked()
+                                {
+                                    action = Some(InfoAction::Audit(package.clone()));
+                                }
+                            }
+                            if let Some(audit) = &package.audit {
+                                Self::audit_findings(ui, audit);
+                            }
+                            ui.add_space(8.0);
+                        }
+
+                        if let Some(edited) = self.metadata_fields(ui, &saved) {
+                            action = Some(InfoAction::SaveMetadata(package.name.clone(), edited));
                         }
+
+                        ui.separator();
+                        ui.horizontal(|ui| {
+                            if ui.button("Close").clicked() {
+                                self.close();
+                            }
+                            if ui
+                                .button("Show dependency tree")
+                                .on_hover_text("Run brew deps --tree")
+                                .clicked()
+                            {
+                                action = Some(InfoAction::ShowDependencies(package.clone()));
+                            }
+                        });
                     });
                 });
 
@@ -68,6 +167,53 @@ impl InfoModal {
                 self.close();
             }
         }
+        action
+    }
+
+    /// Tag and note fields; returns the edited metadata when Save is clicked.
+    fn metadata_fields(
+        &mut self,
+        ui: &mut egui::Ui,
+        saved: &PackageMetadata,
+    ) -> Option<PackageMetadata> {
+        ui.label(egui::RichText::new("Tags:").strong());
+        ui.add(
+            egui::TextEdit::singleline(&mut self.tags_input).hint_text("work, media, try-later"),
+        );
+        ui.label(egui::RichText::new("Note:").strong());
+        ui.add(
+            egui::TextEdit::multiline(&mut self.note_input)
+                .desired_rows(2)
+                .hint_text("Only kept in Brewsty"),
+        );
+
+        let edited = PackageMetadata::from_input(&self.tags_input, &self.note_input);
+        let save = ui
+            .add_enabled(edited != *saved, egui::Button::new("Save Tags & Note"))
+            .clicked();
+        ui.add_space(8.0);
+        save.then_some(edited)
+    }
+
+    fn audit_findings(ui: &mut egui::Ui, audit: &AuditResult) {
+        if audit.passed {
+            ui.colored_label(Color32::from_rgb(0, 200, 0), "✓ Audit passed");
+        } else {
+            ui.colored_label(
+                Color32::from_rgb(255, 0, 0),
+                format!("✗ Audit found {} problems", audit.findings.len()),
+            );
+        }
+        if audit.findings.is_empty() {
+            return;
+        }
+        egui::ScrollArea::vertical()
+            .max_height(160.0)
+            .show(ui, |ui| {
+                for finding in &audit.findings {
+                    ui.label(format!("• {}", finding));
+                }
+            });
     }
 }
 
@@ -1,8 +1,18 @@
 use std::collections::VecDeque;
 
-const MAX_LOG_SIZE: usize = 1000;
+const MAX_LOG_SIZE: usize = 5000;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// Tracing targets that are captured into the log panel by default. More
+/// specific targets take precedence over the modules that contain them.
+pub const DEFAULT_LOG_TARGETS: &[&str] = &[
+    "brewsty::infrastructure::brew",
+    "brewsty::infrastructure::config_repository",
+    "brewsty::application",
+    "brewsty::presentation",
+    "brewsty::presentation::services::async_task_manager",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum LogLevel {
     Trace,
     Debug,
@@ -24,16 +34,74 @@ impl LogLevel {
     }
 }
 
+impl From<tracing::Level> for LogLevel {
+    fn from(level: tracing::Level) -> Self {
+        match level {
+            tracing::Level::TRACE => LogLevel::Trace,
+            tracing::Level::DEBUG => LogLevel::Debug,
+            tracing::Level::INFO => LogLevel::Info,
+            tracing::Level::WARN => LogLevel::Warn,
+            tracing::Level::ERROR => LogLevel::Error,
+        }
+    }
+}
+
+/// Minimum level for entries from a tracing target and everything under it.
+/// `None` turns the target off entirely, so it isn't captured at all.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TargetLevel {
+    pub target: String,
+    pub level: Option<LogLevel>,
+}
+
+impl TargetLevel {
+    pub fn defaults() -> Vec<TargetLevel> {
+        DEFAULT_LOG_TARGETS
+            .iter()
+            .map(|target| TargetLevel {
+                target: target.to_string(),
+                level: Some(LogLevel::Trace),
+            })
+            .collect()
+    }
+
+    /// The most specific entry in `targets` that covers `target`.
+    pub fn find<'a>(targets: &'a [TargetLevel], target: &str) -> Option<&'a TargetLevel> {
+        targets
+            .iter()
+            .filter(|t| target.starts_with(t.target.as_str()))
+            .max_by_key(|t| t.target.len())
+    }
+}
+
 pub struct LogEntry {
     pub message: String,
-    pub timestamp: std::time::SystemTime,
     pub level: LogLevel,
+    /// Tracing target for captured events; `None` for messages the app pushes itself.
+    pub target: Option<String>,
+    timestamp_label: String,
+    line_count: usize,
 }
 
 impl LogEntry {
-    pub fn format_timestamp(&self) -> String {
-        let timestamp = self
-            .timestamp
+    fn new(message: String, level: LogLevel, target: Option<String>) -> Self {
+        let line_count = message.lines().count().max(1);
+        Self {
+            timestamp_label: Self::format_time(std::time::SystemTime::now()),
+            message,
+            level,
+            target,
+            line_count,
+        }
+    }
+
+    /// `HH:MM:SS`, formatted once when the entry is created.
+    pub fn format_timestamp(&self) -> &str {
+        &self.timestamp_label
+    }
+
+    fn format_time(time: std::time::SystemTime) -> String {
+        let timestamp = time
             .duration_since(std::time::UNIX_EPOCH)
             .unwrap_or_default();
         let hours = (timestamp.as_secs() / 3600) % 24;
@@ -43,9 +111,24 @@ impl LogEntry {
     }
 }
 
+/// One display line of a visible entry, for row-virtualized rendering.
+pub struct LogLine<'a> {
+    pub entry: &'a LogEntry,
+    /// Index of this line within the entry's message.
+    pub line: usize,
+    pub text: &'a str,
+}
+
 pub struct LogManager {
     logs: VecDeque<LogEntry>,
+    /// Sequence number of `logs[0]`; entries keep their number as older ones are dropped.
+    first_seq: u64,
+    /// `(entry sequence number, line)` for every line of every entry that passes
+    /// the level filter, oldest first. Kept in sync on push so the log view can
+    /// index rows directly instead of filtering the whole buffer each frame.
+    visible_lines: VecDeque<(u64, usize)>,
     visible_levels: std::collections::HashSet<LogLevel>,
+    target_levels: Vec<TargetLevel>,
 }
 
 impl LogManager {
@@ -56,7 +139,10 @@ impl LogManager {
         visible_levels.insert(LogLevel::Error);
         Self {
             logs: VecDeque::with_capacity(MAX_LOG_SIZE),
+            first_seq: 0,
+            visible_lines: VecDeque::new(),
             visible_levels,
+            target_levels: TargetLevel::defaults(),
         }
     }
 
@@ -65,16 +151,37 @@ impl LogManager {
             .split(']')
             .next()
             .and_then(|s| s.strip_prefix('['))
-            .and_then(|level_str| LogLevel::from_str(level_str))
+            .and_then(LogLevel::from_str)
             .unwrap_or(LogLevel::Info);
+        self.push_entry(LogEntry::new(message, level, None));
+    }
+
+    /// Adds an event forwarded by the capture layer.
+    pub fn push_captured(&mut self, level: LogLevel, target: String, message: String) {
+        let message = format!("[{}] {}", level_label(level), message);
+        self.push_entry(LogEntry::new(message, level, Some(target)));
+    }
+
+    fn push_entry(&mut self, entry: LogEntry) {
         if self.logs.len() >= MAX_LOG_SIZE {
             self.logs.pop_front();
+            let dropped = self.first_seq;
+            self.first_seq += 1;
+            while self
+                .visible_lines
+                .front()
+                .is_some_and(|&(seq, _)| seq == dropped)
+            {
+                self.visible_lines.pop_front();
+            }
         }
-        self.logs.push_back(LogEntry {
-            message,
-            timestamp: std::time::SystemTime::now(),
-            level,
-        });
+
+        let seq = self.first_seq + self.logs.len() as u64;
+        if self.is_visible(&entry) {
+            self.visible_lines
+                .extend((0..entry.line_count).map(|line| (seq, line)));
+        }
+        self.logs.push_back(entry);
     }
 
     pub fn extend(&mut self, messages: Vec<String>) {
@@ -83,21 +190,36 @@ impl LogManager {
         }
     }
 
+    /// Removes all entries but keeps the level filter.
+    pub fn clear(&mut self) {
+        self.first_seq += self.logs.len() as u64;
+        self.logs.clear();
+        self.visible_lines.clear();
+    }
+
     pub fn all_logs(&self) -> impl Iterator<Item = &LogEntry> {
         self.logs.iter()
     }
 
     pub fn filtered_logs(&self) -> impl Iterator<Item = &LogEntry> {
-        self.logs
-            .iter()
-            .filter(move |entry| self.visible_levels.contains(&entry.level))
+        self.logs.iter().filter(move |entry| self.is_visible(entry))
     }
 
-    pub fn filtered_logs_reversed(&self) -> impl Iterator<Item = &LogEntry> {
-        self.logs
-            .iter()
-            .rev()
-            .filter(move |entry| self.visible_levels.contains(&entry.level))
+    /// Number of display lines across all visible entries.
+    pub fn visible_line_count(&self) -> usize {
+        self.visible_lines.len()
+    }
+
+    /// Display line `row`, counting from the newest entry. Lines within an
+    /// entry keep their original top-to-bottom order.
+    pub fn visible_line_newest_first(&self, row: usize) -> Option<LogLine<'_>> {
+        let index = self.visible_lines.len().checked_sub(row + 1)?;
+        let (seq, line) = self.visible_lines[index];
+        let entry = &self.logs[(seq - self.first_seq) as usize];
+        // Reversing the whole list also reverses each entry's lines; flip them back
+        let line = entry.line_count - 1 - line;
+        let text = entry.message.lines().nth(line).unwrap_or("");
+        Some(LogLine { entry, line, text })
     }
 
     pub fn set_level_visible(&mut self, level: LogLevel, visible: bool) {
@@ -106,11 +228,56 @@ impl LogManager {
         } else {
             self.visible_levels.remove(&level);
         }
+        self.rebuild_visible_lines();
     }
 
     pub fn is_level_visible(&self, level: LogLevel) -> bool {
         self.visible_levels.contains(&level)
     }
+
+    pub fn target_levels(&self) -> &[TargetLevel] {
+        &self.target_levels
+    }
+
+    pub fn set_target_level(&mut self, target: &str, level: Option<LogLevel>) {
+        if let Some(entry) = self.target_levels.iter_mut().find(|t| t.target == target) {
+            entry.level = level;
+            self.rebuild_visible_lines();
+        }
+    }
+
+    /// Entries must pass both the global level toggles and their target's minimum level.
+    fn is_visible(&self, entry: &LogEntry) -> bool {
+        if !self.visible_levels.contains(&entry.level) {
+            return false;
+        }
+        let Some(target) = &entry.target else {
+            return true;
+        };
+        TargetLevel::find(&self.target_levels, target)
+            .is_none_or(|t| t.level.is_some_and(|min| entry.level >= min))
+    }
+
+    fn rebuild_visible_lines(&mut self) {
+        self.visible_lines.clear();
+        for (offset, entry) in self.logs.iter().enumerate() {
+            if self.is_visible(entry) {
+                let seq = self.first_seq + offset as u64;
+                self.visible_lines
+                    .extend((0..entry.line_count).map(|line| (seq, line)));
+            }
+        }
+    }
+}
+
+fn level_label(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Trace => "TRACE",
+        LogLevel::Debug => "DEBUG",
+        LogLevel::Info => "INFO",
+        LogLevel::Warn => "WARN",
+        LogLevel::Error => "ERROR",
+    }
 }
 
 impl Default for LogManager {
@@ -118,3 +285,64 @@ impl Default for LogManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visible_messages(log_manager: &LogManager) -> Vec<&str> {
+        log_manager
+            .filtered_logs()
+            .map(|entry| entry.message.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn most_specific_target_level_wins() {
+        let mut log_manager = LogManager::new();
+        log_manager.set_level_visible(LogLevel::Debug, true);
+        log_manager.set_target_level(
+            "brewsty::presentation::services::async_task_manager",
+            Some(LogLevel::Warn),
+        );
+
+        log_manager.push_captured(
+            LogLevel::Debug,
+            "brewsty::presentation::services::async_task_manager".to_string(),
+            "polling".to_string(),
+        );
+        log_manager.push_captured(
+            LogLevel::Debug,
+            "brewsty::presentation::ui::app".to_string(),
+            "frame".to_string(),
+        );
+        log_manager.push_captured(
+            LogLevel::Debug,
+            "brewsty::infrastructure::brew::command".to_string(),
+            "running brew".to_string(),
+        );
+
+        assert_eq!(
+            visible_messages(&log_manager),
+            ["[DEBUG] frame", "[DEBUG] running brew"]
+        );
+    }
+
+    #[test]
+    fn changing_a_target_level_refilters_existing_entries() {
+        let mut log_manager = LogManager::new();
+        log_manager.push_captured(
+            LogLevel::Info,
+            "brewsty::application::use_cases".to_string(),
+            "loaded".to_string(),
+        );
+        log_manager.push("app message".to_string());
+
+        log_manager.set_target_level("brewsty::application", None);
+        assert_eq!(visible_messages(&log_manager), ["app message"]);
+        assert_eq!(log_manager.visible_line_count(), 1);
+
+        log_manager.set_target_level("brewsty::application", Some(LogLevel::Info));
+        assert_eq!(log_manager.visible_line_count(), 2);
+    }
+}
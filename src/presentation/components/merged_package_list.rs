@@ -1,13 +1,85 @@
-use crate::domain::entities::{Package, PackageType};
-use crate::presentation::components::SelectionState;
-use egui::{Color32, RichText, ScrollArea};
+use crate::domain::entities::{
+    FavoritePackage, OperationProgress, Package, PackageMetadata, PackageType,
+};
+use crate::presentation::components::column_widths::{ColumnWidths, SortOrder, TableLayout};
+use crate::presentation::components::grid_density::{self, heading, progress_cell, status_cell};
+use crate::presentation::components::{SelectionState, accessible, favorite_button, highlight};
+use egui::{Color32, RichText, ScrollArea, Sense, WidgetType};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+const OUTDATED_TABLE: TableLayout = TableLayout {
+    id: "outdated_table",
+    columns: &[
+        ("", 30.0),
+        ("Name", 220.0),
+        ("Version", 200.0),
+        ("Type", 80.0),
+        ("Status", 160.0),
+        ("Actions", 0.0),
+    ],
+};
+
+const INSTALLED_TABLE: TableLayout = TableLayout {
+    id: "installed_table",
+    columns: &[
+        ("Name", 240.0),
+        ("Version", 140.0),
+        ("Type", 80.0),
+        ("Status", 100.0),
+        ("Actions", 0.0),
+    ],
+};
+
+/// `INSTALLED_TABLE` with the install date column.
+const INSTALLED_TABLE_WITH_DATE: TableLayout = TableLayout {
+    id: "installed_table_with_date",
+    columns: &[
+        ("Name", 240.0),
+        ("Version", 140.0),
+        ("Installed", 120.0),
+        ("Type", 80.0),
+        ("Status", 100.0),
+        ("Actions", 0.0),
+    ],
+};
+
+/// Position of "Installed" in `INSTALLED_TABLE_WITH_DATE`.
+const INSTALLED_COLUMN: usize = 2;
 
 pub struct MergedPackageList {
     packages: Vec<Package>,
     outdated_packages: Vec<Package>,
     selected_package: Option<String>,
     show_info_action: Option<Package>,
+    /// Double-clicked; its Info window opens, once its info is loaded if need be.
+    open_info_action: Option<Package>,
+    reveal_action: Option<Package>,
+    zap_action: Option<Package>,
     outdated_selection: SelectionState,
+    outdated_since: HashMap<String, u64>,
+    outdated_since_changed: bool,
+    /// Why the last upgrade of each package failed, until it is updated.
+    failed_updates: HashMap<String, String>,
+    /// Unix time each ignored package's snooze ends, `None` for never.
+    ignored_updates: HashMap<String, Option<u64>>,
+    ignored_updates_changed: bool,
+    /// Casks that update themselves which the user has brew update anyway.
+    brew_updated_casks: HashSet<String>,
+    brew_updated_casks_changed: bool,
+    /// Install times by name, kept to fill in packages after a reload.
+    install_dates: HashMap<String, SystemTime>,
+    installed_sort: Option<SortOrder>,
+    /// The user's tags and notes by name, shown next to package names.
+    metadata: HashMap<String, PackageMetadata>,
+    outdated_collapsed: bool,
+    installed_collapsed: bool,
+    collapsed_changed: bool,
+    /// Hides the installed section, leaving just what needs updating.
+    only_outdated: bool,
 }
 
 #[allow(dead_code)]
@@ -18,28 +90,229 @@ impl MergedPackageList {
             outdated_packages: Vec::new(),
             selected_package: None,
             show_info_action: None,
+            open_info_action: None,
+            reveal_action: None,
+            zap_action: None,
             outdated_selection: SelectionState::new(),
+            outdated_since: HashMap::new(),
+            outdated_since_changed: false,
+            failed_updates: HashMap::new(),
+            ignored_updates: HashMap::new(),
+            ignored_updates_changed: false,
+            brew_updated_casks: HashSet::new(),
+            brew_updated_casks_changed: false,
+            install_dates: HashMap::new(),
+            installed_sort: None,
+            metadata: HashMap::new(),
+            outdated_collapsed: false,
+            installed_collapsed: false,
+            collapsed_changed: false,
+            only_outdated: false,
         }
     }
 
-    pub fn update_packages(&mut self, packages: Vec<Package>) {
+    pub fn update_packages(&mut self, mut packages: Vec<Package>) {
+        apply_install_dates(&mut packages, &self.install_dates);
+        // Audits are kept for the session, across reloads
+        for package in &mut packages {
+            if let Some(old) = self.packages.iter().find(|p| p.name == package.name) {
+                package.audit = old.audit.clone();
+            }
+        }
         self.packages = packages;
     }
 
-    pub fn update_outdated_packages(&mut self, packages: Vec<Package>) {
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+
+    pub fn set_install_dates(&mut self, install_dates: HashMap<String, SystemTime>) {
+        self.install_dates = install_dates;
+        apply_install_dates(&mut self.packages, &self.install_dates);
+        apply_install_dates(&mut self.outdated_packages, &self.install_dates);
+    }
+
+    pub fn set_metadata(&mut self, metadata: HashMap<String, PackageMetadata>) {
+        self.metadata = metadata;
+    }
+
+    pub fn metadata(&self) -> &HashMap<String, PackageMetadata> {
+        &self.metadata
+    }
+
+    /// Replaces `name`'s tags and note, forgetting them when empty.
+    pub fn set_package_metadata(&mut self, name: String, metadata: PackageMetadata) {
+        if metadata.is_empty() {
+            self.metadata.remove(&name);
+        } else {
+            self.metadata.insert(name, metadata);
+        }
+    }
+
+    /// Every tag in use, sorted.
+    pub fn tags(&self) -> Vec<String> {
+        let mut tags: Vec<String> = self
+            .metadata
+            .values()
+            .flat_map(|m| m.tags.iter().cloned())
+            .collect();
+        tags.sort();
+        tags.dedup();
+        tags
+    }
+
+    /// Shows `packages` as the outdated ones. `complete` is false when brew
+    /// couldn't list some of them, e.g. offline; what is known about the
+    /// missing ones is then kept. Casks left to update themselves aren't
+    /// counted as outdated for how long.
+    pub fn update_outdated_packages(&mut self, packages: Vec<Package>, complete: bool) {
+        let now = unix_now();
+        let before = self.outdated_since.len();
+        let brew_updated_casks = &self.brew_updated_casks;
+        let tracked = |p: &&Package| !updates_left_to_app(brew_updated_casks, p);
+        // Packages updated outside Brewsty drop out of the list, so forget them too
+        if complete {
+            self.outdated_since
+                .retain(|name, _| packages.iter().filter(tracked).any(|p| &p.name == name));
+            self.failed_updates
+                .retain(|name, _| packages.iter().any(|p| &p.name == name));
+        }
+        let mut changed = self.outdated_since.len() != before;
+        for package in packages.iter().filter(tracked) {
+            if !self.outdated_since.contains_key(&package.name) {
+                self.outdated_since.insert(package.name.clone(), now);
+                changed = true;
+            }
+        }
+        self.outdated_since_changed |= changed;
+
+        // An expired snooze is forgotten, so the package shows as outdated again
+        let before = self.ignored_updates.len();
+        self.ignored_updates
+            .retain(|_, until| until.is_none_or(|until| now < until));
+        self.ignored_updates_changed |= self.ignored_updates.len() != before;
         self.outdated_packages = packages;
     }
 
-    pub fn update_package(&mut self, package: Package) {
-        if let Some(existing) = self.packages.iter_mut().find(|p| p.name == package.name) {
-            *existing = package.clone();
+    pub fn set_outdated_since(&mut self, outdated_since: HashMap<String, u64>) {
+        self.outdated_since = outdated_since;
+    }
+
+    pub fn outdated_since(&self) -> &HashMap<String, u64> {
+        &self.outdated_since
+    }
+
+    /// True once after the first-seen-outdated records changed and need saving.
+    pub fn take_outdated_since_changed(&mut self) -> bool {
+        std::mem::take(&mut self.outdated_since_changed)
+    }
+
+    pub fn set_ignored_updates(&mut self, ignored_updates: HashMap<String, Option<u64>>) {
+        self.ignored_updates = ignored_updates;
+    }
+
+    pub fn ignored_updates(&self) -> &HashMap<String, Option<u64>> {
+        &self.ignored_updates
+    }
+
+    /// True once after the user ignored or un-ignored a package's updates,
+    /// or a snooze ran out.
+    pub fn take_ignored_updates_changed(&mut self) -> bool {
+        std::mem::take(&mut self.ignored_updates_changed)
+    }
+
+    pub fn set_brew_updated_casks(&mut self, names: HashSet<String>) {
+        self.brew_updated_casks = names;
+    }
+
+    pub fn brew_updated_casks(&self) -> &HashSet<String> {
+        &self.brew_updated_casks
+    }
+
+    /// True once after the user handed a self-updating cask's updates to
+    /// brew or back to the app.
+    pub fn take_brew_updated_casks_changed(&mut self) -> bool {
+        std::mem::take(&mut self.brew_updated_casks_changed)
+    }
+
+    /// Whether `package` updates itself and the user left it to do so.
+    pub fn leaves_updates_to_app(&self, package: &Package) -> bool {
+        updates_left_to_app(&self.brew_updated_casks, package)
+    }
+
+    /// Folds the outdated and installed sections as they were left.
+    pub fn set_collapsed_sections(&mut self, outdated: bool, installed: bool) {
+        self.outdated_collapsed = outdated;
+        self.installed_collapsed = installed;
+    }
+
+    /// Whether the outdated and installed sections are folded.
+    pub fn collapsed_sections(&self) -> (bool, bool) {
+        (self.outdated_collapsed, self.installed_collapsed)
+    }
+
+    /// True once after a section was folded or unfolded.
+    pub fn take_collapsed_changed(&mut self) -> bool {
+        std::mem::take(&mut self.collapsed_changed)
+    }
+
+    /// Shows only the outdated section; for the session, not saved.
+    pub fn set_only_outdated(&mut self, only_outdated: bool) {
+        self.only_outdated = only_outdated;
+    }
+
+    pub fn only_outdated(&self) -> bool {
+        self.only_outdated
+    }
+
+    /// How many outdated packages Select All leaves out: the pinned and
+    /// ignored ones and those whose apps update themselves.
+    pub fn left_out_of_select_all(&self) -> usize {
+        let now = unix_now();
+        self.outdated_packages
+            .iter()
+            .filter(|p| {
+                left_out_of_select_all(&self.ignored_updates, &self.brew_updated_casks, p, now)
+            })
+            .count()
+    }
+
+    pub fn is_update_ignored(&self, name: &str) -> bool {
+        update_ignored(&self.ignored_updates, name, unix_now())
+    }
+
+    pub fn update_package(&mut self, mut package: Package) {
+        if package.installed_at.is_none() {
+            package.installed_at = self.install_dates.get(&package.name).copied();
         }
-        if let Some(existing) = self
+        if package.audit.is_none() {
+            package.audit = self.get_package(&package.name).and_then(|p| p.audit);
+        }
+        let installed = self.packages.iter_mut().find(|p| p.name == package.name);
+        let outdated = self
             .outdated_packages
             .iter_mut()
-            .find(|p| p.name == package.name)
+            .find(|p| p.name == package.name);
+        match (installed, outdated) {
+            (Some(installed), Some(outdated)) => {
+                *installed = package.clone();
+                *outdated = package;
+            }
+            (Some(existing), None) | (None, Some(existing)) => *existing = package,
+            (None, None) => {}
+        }
+    }
+
+    /// Forgets that loading `name`'s info failed, before it is tried again.
+    pub fn clear_load_failure(&mut self, name: &str) {
+        for package in self
+            .packages
+            .iter_mut()
+            .chain(self.outdated_packages.iter_mut())
         {
-            *existing = package;
+            if package.name == name {
+                package.version_load_failed = false;
+            }
         }
     }
 
@@ -51,7 +324,48 @@ impl MergedPackageList {
             .cloned()
     }
 
+    /// Installed packages whose name contains `query`, ignoring case. Outdated
+    /// ones come with their available version.
+    pub fn matching(&self, query: &str) -> Vec<Package> {
+        let query = query.to_lowercase();
+        self.packages
+            .iter()
+            .filter(|p| p.name.to_lowercase().contains(&query))
+            .map(|p| {
+                let mut package = self
+                    .outdated_packages
+                    .iter()
+                    .find(|o| o.name == p.name)
+                    .unwrap_or(p)
+                    .clone();
+                package.installed = true;
+                package
+            })
+            .collect()
+    }
+
+    /// The outdated packages as a Markdown table, headed by `date`, without
+    /// the casks left to update themselves.
+    pub fn outdated_markdown(&self, date: &str) -> String {
+        let packages: Vec<Package> = self
+            .outdated_packages
+            .iter()
+            .filter(|p| !updates_left_to_app(&self.brew_updated_casks, p))
+            .cloned()
+            .collect();
+        outdated_markdown(&packages, date)
+    }
+
+    pub fn is_outdated(&self, name: &str) -> bool {
+        self.outdated_packages.iter().any(|p| p.name == name)
+    }
+
     pub fn mark_package_updated(&mut self, package_name: &str) {
+        if self.outdated_since.remove(package_name).is_some() {
+            self.outdated_since_changed = true;
+        }
+        self.failed_updates.remove(package_name);
+
         // Remove from outdated packages list
         if let Some(pos) = self
             .outdated_packages
@@ -68,6 +382,16 @@ impl MergedPackageList {
         }
     }
 
+    /// Flags an outdated package whose upgrade failed, with brew's reason.
+    pub fn mark_update_failed(&mut self, package_name: &str, reason: &str) {
+        self.failed_updates
+            .insert(package_name.to_string(), reason.to_string());
+    }
+
+    pub fn update_failure(&self, package_name: &str) -> Option<&str> {
+        self.failed_updates.get(package_name).map(String::as_str)
+    }
+
     pub fn remove_from_outdated(&mut self, package_name: &str) {
         self.outdated_packages.retain(|p| p.name != package_name);
     }
@@ -101,6 +425,18 @@ impl MergedPackageList {
         self.show_info_action.take()
     }
 
+    pub fn get_open_info_action(&mut self) -> Option<Package> {
+        self.open_info_action.take()
+    }
+
+    pub fn get_reveal_action(&mut self) -> Option<Package> {
+        self.reveal_action.take()
+    }
+
+    pub fn get_zap_action(&mut self) -> Option<Package> {
+        self.zap_action.take()
+    }
+
     pub fn get_outdated_selection(&self) -> SelectionState {
         self.outdated_selection.clone()
     }
@@ -113,9 +449,19 @@ impl MergedPackageList {
         self.outdated_selection.clear();
     }
 
+    /// Selects every outdated package that isn't pinned and whose updates
+    /// aren't ignored or left to the app itself.
     pub fn select_all_outdated(&mut self) {
+        let now = unix_now();
         for package in &self.outdated_packages {
-            self.outdated_selection.select(package.name.clone());
+            if !left_out_of_select_all(
+                &self.ignored_updates,
+                &self.brew_updated_casks,
+                package,
+                now,
+            ) {
+                self.outdated_selection.select(package.name.clone());
+            }
         }
     }
 
@@ -131,6 +477,7 @@ impl MergedPackageList {
         self.outdated_selection.get_selected()
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn show_merged_with_search_and_pin(
         &mut self,
         ui: &mut egui::Ui,
@@ -138,258 +485,525 @@ impl MergedPackageList {
         on_uninstall: &mut Option<Package>,
         on_update: &mut Option<Package>,
         on_update_selected: &mut Option<Vec<String>>,
+        on_fetch_selected: &mut Option<Vec<String>>,
+        fetched_packages: &std::collections::HashSet<String>,
+        fetching: bool,
         show_formulae: bool,
         show_casks: bool,
         search_query: &str,
+        tag_filter: Option<&str>,
         on_load_info: &mut Option<Package>,
         packages_loading_info: &std::collections::HashSet<String>,
+        operation_progress: &[(String, OperationProgress)],
         on_pin: &mut Option<Package>,
         on_unpin: &mut Option<Package>,
+        favorites: &[FavoritePackage],
+        on_toggle_favorite: &mut Option<Package>,
+        compact: bool,
+        show_install_date: bool,
+        column_widths: &mut ColumnWidths,
     ) {
+        let is_favorite = |name: &str| favorites.iter().any(|f| f.name == name);
+        let progress_of = |name: &str| {
+            operation_progress
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, progress)| progress)
+        };
         let search_lower = search_query.to_lowercase();
+        // Moved out while drawing, since the rows borrow `self` mutably
+        let metadata = std::mem::take(&mut self.metadata);
+        let now = unix_now();
+        let now_time = SystemTime::now();
+        let only_outdated = self.only_outdated;
+        let left_out = self.left_out_of_select_all();
+
+        let is_shown = |package: &Package| {
+            let type_shown = match package.package_type {
+                PackageType::Formula => show_formulae,
+                PackageType::Cask => show_casks,
+            };
+            let tag_shown = tag_filter
+                .is_none_or(|tag| metadata.get(&package.name).is_some_and(|m| m.has_tag(tag)));
+            type_shown
+                && tag_shown
+                && (search_query.is_empty() || package.name.to_lowercase().contains(&search_lower))
+        };
 
         ScrollArea::vertical()
             .auto_shrink([false, false])
             .show(ui, |ui| {
+                grid_density::apply_table_style(ui, compact);
+                let row_height = grid_density::row_height(ui);
+                let header_height = grid_density::header_height(ui, compact);
+
                 // Outdated Packages Section
-                if !self.outdated_packages.is_empty() {
-                    ui.heading("⚠️  Outdated Packages");
+                let ignored_updates = &self.ignored_updates;
+                let is_ignored = |p: &Package| update_ignored(ignored_updates, &p.name, now);
+                if !self.outdated_packages.iter().all(is_ignored) {
+                    // Tables only lay out the rows scrolled into view
+                    let outdated: Vec<&Package> = self
+                        .outdated_packages
+                        .iter()
+                        .filter(|p| is_shown(p) && !is_ignored(p))
+                        .collect();
+
+                    // Nothing else is shown then, so it can't be folded away
+                    if only_outdated {
+                        let title = format!(
+                            "⚠️  Outdated Packages ({}, {} left out of Select All)",
+                            self.outdated_packages.len(),
+                            left_out
+                        );
+                        heading(ui, &title, compact);
+                    } else if section_header(
+                        ui,
+                        "⚠️  Outdated Packages",
+                        compact,
+                        &mut self.outdated_collapsed,
+                    ) {
+                        self.collapsed_changed = true;
+                    }
                     ui.separator();
 
-                    egui::Grid::new("outdated_grid")
-                        .striped(true)
-                        .spacing([25.0, 10.0])
-                        .show(ui, |ui| {
-                            ui.heading("");
-                            ui.heading("Name");
-                            ui.heading("Version");
-                            ui.heading("Type");
-                            ui.heading("Status");
-                            ui.heading("Actions");
-                            ui.end_row();
-
-                            for package in &self.outdated_packages {
-                                let should_show = match package.package_type {
-                                    PackageType::Formula => show_formulae,
-                                    PackageType::Cask => show_casks,
-                                };
-
-                                if !should_show {
-                                    continue;
-                                }
-
-                                if !search_query.is_empty()
-                                    && !package.name.to_lowercase().contains(&search_lower)
-                                {
-                                    continue;
-                                }
-
-                                let mut is_selected =
-                                    self.outdated_selection.is_selected(&package.name);
-                                if ui.checkbox(&mut is_selected, "").changed() {
-                                    if is_selected {
-                                        self.outdated_selection.select(package.name.clone());
-                                    } else {
-                                        self.outdated_selection.deselect(&package.name);
-                                    }
-                                }
-
-                                ui.label(&package.name);
-
-                                let version_text = if package.version_load_failed {
-                                    "Failed".to_string()
-                                } else if let Some(av) = &package.available_version {
-                                    format!(
-                                        "{} -> {}",
-                                        package.version.as_deref().unwrap_or("N/A"),
-                                        av
-                                    )
-                                } else {
-                                    package.version.as_deref().unwrap_or("N/A").to_string()
-                                };
-
-                                if packages_loading_info.contains(&package.name) {
-                                    ui.spinner();
-                                } else if package.version_load_failed {
-                                    ui.label(
-                                        RichText::new(version_text)
-                                            .color(Color32::from_rgb(255, 0, 0)),
-                                    );
-                                } else if package.pinned {
-                                    ui.label(
-                                        RichText::new(version_text)
-                                            .color(Color32::from_rgb(255, 200, 0)),
-                                    );
-                                } else {
-                                    ui.label(version_text);
-                                }
-
-                                ui.label(package.package_type.to_string());
-
-                                let is_operating = packages_loading_info.contains(&package.name);
-                                let status_text = if package.pinned {
-                                    RichText::new("Pinned").color(Color32::from_rgb(255, 200, 0))
-                                } else {
-                                    RichText::new("Outdated").color(Color32::from_rgb(255, 165, 0))
-                                };
-
-                                if is_operating {
-                                    ui.spinner();
-                                } else {
-                                    ui.label(status_text);
-                                }
-
-                                ui.horizontal(|ui| {
-                                    if !package.pinned && ui.button("Update").clicked() {
-                                        *on_update = Some(package.clone());
-                                    }
-                                    if package.pinned {
-                                        if ui.button("Unpin").clicked() {
-                                            *on_unpin = Some(package.clone());
+                    if only_outdated || !self.outdated_collapsed {
+                        column_widths
+                            .table(ui, &OUTDATED_TABLE)
+                            .vscroll(false)
+                            .header(header_height, |row| {
+                                column_widths.header(row, &OUTDATED_TABLE, compact)
+                            })
+                            .body(|body| {
+                                body.rows(row_height, outdated.len(), |mut row| {
+                                    let package = outdated[row.index()];
+                                    let self_updating =
+                                        updates_left_to_app(&self.brew_updated_casks, package);
+                                    row.col(|ui| {
+                                        let mut is_selected =
+                                            self.outdated_selection.is_selected(&package.name);
+                                        let label = format!("Select {}", package.name);
+                                        if accessible::checkbox(ui, &mut is_selected, &label)
+                                            .changed()
+                                        {
+                                            if is_selected {
+                                                self.outdated_selection
+                                                    .select(package.name.clone());
+                                            } else {
+                                                self.outdated_selection.deselect(&package.name);
+                                            }
                                         }
-                                    } else if ui.button("Pin").clicked() {
-                                        *on_pin = Some(package.clone());
-                                    }
+                                    });
+
+                                    row.col(|ui| {
+                                        if favorite_button(ui, is_favorite(&package.name)) {
+                                            *on_toggle_favorite = Some(package.clone());
+                                        }
+                                        let text = highlight::highlighted_name(
+                                            ui,
+                                            &package.name,
+                                            search_query,
+                                        );
+                                        let name = egui::Label::new(text).sense(Sense::click());
+                                        if ui.add(name).double_clicked() {
+                                            self.open_info_action = Some(package.clone());
+                                        }
+                                        metadata_chips(ui, metadata.get(&package.name));
+                                        if fetched_packages.contains(&package.name) {
+                                            let hint =
+                                                "Downloaded; can be updated without a connection";
+                                            let response = ui.weak("⬇").on_hover_text(hint);
+                                            accessible::name(&response, WidgetType::Label, hint);
+                                        }
+                                    });
+
+                                    row.col(|ui| {
+                                        let version_text = if package.version_load_failed {
+                                            "Failed".to_string()
+                                        } else if let Some(av) = &package.available_version {
+                                            format!(
+                                                "{} -> {}",
+                                                package.version.as_deref().unwrap_or("N/A"),
+                                                av
+                                            )
+                                        } else {
+                                            package.version.as_deref().unwrap_or("N/A").to_string()
+                                        };
+
+                                        if packages_loading_info.contains(&package.name) {
+                                            ui.spinner();
+                                        } else if package.version_load_failed {
+                                            ui.label(
+                                                RichText::new(version_text)
+                                                    .color(Color32::from_rgb(255, 0, 0)),
+                                            );
+                                        } else if package.pinned {
+                                            ui.label(
+                                                RichText::new(version_text)
+                                                    .color(Color32::from_rgb(255, 200, 0)),
+                                            );
+                                        } else {
+                                            ui.label(version_text);
+                                        }
+                                    });
+
+                                    row.col(|ui| {
+                                        ui.label(package.package_type.to_string());
+                                    });
+
+                                    row.col(|ui| {
+                                        let is_operating =
+                                            packages_loading_info.contains(&package.name);
+                                        let outdated_for = self
+                                            .outdated_since
+                                            .get(&package.name)
+                                            .and_then(|&since| outdated_for_label(since, now));
+                                        let (status_text, status_color) = if package.pinned {
+                                            ("Pinned".to_string(), Color32::from_rgb(255, 200, 0))
+                                        } else if self_updating {
+                                            (
+                                                "Updates itself".to_string(),
+                                                Color32::from_rgb(100, 150, 255),
+                                            )
+                                        } else if let Some(outdated_for) = outdated_for {
+                                            (
+                                                format!("Outdated for {}", outdated_for),
+                                                Color32::from_rgb(255, 165, 0),
+                                            )
+                                        } else {
+                                            ("Outdated".to_string(), Color32::from_rgb(255, 165, 0))
+                                        };
 
-                                    if package.description.is_some() {
-                                        if ui.button("Info").clicked() {
+                                        if is_operating {
+                                            progress_cell(ui, progress_of(&package.name));
+                                        } else if let Some(reason) =
+                                            self.failed_updates.get(&package.name)
+                                        {
+                                            let text =
+                                                if compact { "●" } else { "Update failed" };
+                                            let response = ui
+                                                .label(
+                                                    RichText::new(text)
+                                                        .color(Color32::from_rgb(255, 0, 0)),
+                                                )
+                                                .on_hover_text(reason);
+                                            accessible::name(
+                                                &response,
+                                                WidgetType::Label,
+                                                &format!("Update failed: {}", reason),
+                                            );
+                                        } else {
+                                            status_cell(ui, &status_text, status_color, compact);
+                                        }
+                                    });
+
+                                    row.col(|ui| {
+                                        if self_updating {
+                                            if auto_updates_menu(ui) {
+                                                self.brew_updated_casks
+                                                    .insert(package.name.clone());
+                                                self.brew_updated_casks_changed = true;
+                                            }
+                                        } else if !package.pinned {
+                                            let mut update = ui.button("Update");
+                                            if package.auto_updates {
+                                                update = update.on_hover_text(
+                                                    "Updates itself, but you chose to update \
+                                                     it with brew",
+                                                );
+                                                update.context_menu(|ui| {
+                                                    let leave = "Leave Updates to the App";
+                                                    if ui.button(leave).clicked() {
+                                                        self.brew_updated_casks
+                                                            .remove(&package.name);
+                                                        self.brew_updated_casks_changed = true;
+                                                        self.outdated_selection
+                                                            .deselect(&package.name);
+                                                        ui.close_menu();
+                                                    }
+                                                });
+                                            }
+                                            if update.clicked() {
+                                                *on_update = Some(package.clone());
+                                            }
+                                        }
+                                        if package.pinned {
+                                            if ui.button("Unpin").clicked() {
+                                                *on_unpin = Some(package.clone());
+                                            }
+                                        } else if ui.button("Pin").clicked() {
+                                            *on_pin = Some(package.clone());
+                                        }
+
+                                        if package.version_load_failed
+                                            && !packages_loading_info.contains(&package.name)
+                                        {
+                                            if ui
+                                                .button("Retry")
+                                                .on_hover_text("Loading its info failed; try again")
+                                                .clicked()
+                                            {
+                                                *on_load_info = Some(package.clone());
+                                            }
+                                        } else if package.description.is_some()
+                                            && ui.button("Info").clicked()
+                                        {
                                             self.show_info_action = Some(package.clone());
                                         }
-                                    }
+                                        if let Some(until) = ignore_updates_menu(ui, now) {
+                                            self.ignored_updates
+                                                .insert(package.name.clone(), until);
+                                            self.ignored_updates_changed = true;
+                                            self.outdated_selection.deselect(&package.name);
+                                        }
+                                    });
                                 });
+                            });
 
-                                ui.end_row();
+                        ui.add_space(8.0);
+                        ui.horizontal(|ui| {
+                            if ui.button("Select All").clicked() {
+                                self.select_all_outdated();
+                            }
+                            if ui.button("Deselect All").clicked() {
+                                self.deselect_all_outdated();
+                            }
+                            if ui
+                                .add_enabled(
+                                    self.outdated_selection.has_selection(),
+                                    egui::Button::new("Update Selected"),
+                                )
+                                .clicked()
+                            {
+                                *on_update_selected = Some(self.outdated_selection.get_selected());
+                            }
+                            if ui
+                                .add_enabled(
+                                    self.outdated_selection.has_selection() && !fetching,
+                                    egui::Button::new("Fetch Selected"),
+                                )
+                                .on_hover_text("Download now, update later")
+                                .clicked()
+                            {
+                                *on_fetch_selected = Some(self.outdated_selection.get_selected());
                             }
                         });
+                        ui.separator();
+                    }
+                    ui.add_space(16.0);
+                }
 
-                    ui.add_space(8.0);
-                    ui.horizontal(|ui| {
-                        if ui.button("Select All").clicked() {
-                            self.select_all_outdated();
-                        }
-                        if ui.button("Deselect All").clicked() {
-                            self.deselect_all_outdated();
-                        }
-                        if ui
-                            .add_enabled(
-                                self.outdated_selection.has_selection(),
-                                egui::Button::new("Update Selected"),
-                            )
-                            .clicked()
-                        {
-                            *on_update_selected = Some(self.outdated_selection.get_selected());
-                        }
-                    });
-                    ui.separator();
+                let ignored: Vec<&Package> = self
+                    .outdated_packages
+                    .iter()
+                    .filter(|p| is_shown(p) && update_ignored(&self.ignored_updates, &p.name, now))
+                    .collect();
+                if !ignored.is_empty() {
+                    egui::CollapsingHeader::new(format!("Ignored ({})", ignored.len()))
+                        .id_salt("ignored_updates")
+                        .show(ui, |ui| {
+                            egui::Grid::new("ignored_updates_grid")
+                                .num_columns(3)
+                                .striped(true)
+                                .show(ui, |ui| {
+                                    for package in ignored {
+                                        ui.label(&package.name);
+                                        let until = self.ignored_updates.get(&package.name);
+                                        ui.weak(match until {
+                                            Some(Some(until)) => format!(
+                                                "until {}",
+                                                format_local_time(
+                                                    UNIX_EPOCH + Duration::from_secs(*until)
+                                                )
+                                            ),
+                                            _ => "until you stop ignoring it".to_string(),
+                                        });
+                                        if ui.button("Stop Ignoring").clicked() {
+                                            self.ignored_updates.remove(&package.name);
+                                            self.ignored_updates_changed = true;
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
                     ui.add_space(16.0);
                 }
 
+                if only_outdated && self.outdated_packages.is_empty() {
+                    ui.label("Everything is up to date.");
+                }
+
                 // Installed Packages Section
-                if !self.packages.is_empty() {
-                    ui.heading("📦 Installed Packages");
+                if !only_outdated && !self.packages.is_empty() {
+                    let mut installed: Vec<&Package> =
+                        self.packages.iter().filter(|p| is_shown(p)).collect();
+                    if show_install_date && let Some(order) = self.installed_sort {
+                        installed.sort_by(|a, b| compare_install_dates(a, b, order));
+                    }
+
+                    if section_header(
+                        ui,
+                        "📦 Installed Packages",
+                        compact,
+                        &mut self.installed_collapsed,
+                    ) {
+                        self.collapsed_changed = true;
+                    }
                     ui.separator();
 
-                    egui::Grid::new("installed_grid")
-                        .striped(true)
-                        .spacing([25.0, 10.0])
-                        .show(ui, |ui| {
-                            ui.heading("Name");
-                            ui.heading("Version");
-                            ui.heading("Type");
-                            ui.heading("Status");
-                            ui.heading("Actions");
-                            ui.end_row();
-
-                            for package in &self.packages {
-                                let should_show = match package.package_type {
-                                    PackageType::Formula => show_formulae,
-                                    PackageType::Cask => show_casks,
-                                };
-
-                                if !should_show {
-                                    continue;
-                                }
-
-                                if !search_query.is_empty()
-                                    && !package.name.to_lowercase().contains(&search_lower)
-                                {
-                                    continue;
-                                }
-
-                                let is_selected = self
-                                    .selected_package
-                                    .as_ref()
-                                    .map_or(false, |s| s == &package.name);
-
-                                if ui.selectable_label(is_selected, &package.name).clicked() {
-                                    self.selected_package = Some(package.name.clone());
-                                }
-
-                                let version_text = package.version.as_deref().unwrap_or("N/A");
-
-                                if packages_loading_info.contains(&package.name) {
-                                    ui.spinner();
-                                } else if package.version_load_failed {
-                                    ui.label(
-                                        RichText::new(version_text)
-                                            .color(Color32::from_rgb(255, 0, 0)),
-                                    );
-                                } else if package.pinned {
-                                    ui.label(
-                                        RichText::new(version_text)
-                                            .color(Color32::from_rgb(255, 200, 0)),
-                                    );
-                                } else {
-                                    ui.label(version_text);
-                                }
-
-                                ui.label(package.package_type.to_string());
-
-                                let is_operating = packages_loading_info.contains(&package.name);
-                                let status_text = if package.pinned {
-                                    RichText::new("Pinned").color(Color32::from_rgb(255, 200, 0))
-                                } else {
-                                    RichText::new("Installed").color(Color32::from_rgb(0, 255, 0))
-                                };
-
-                                if is_operating {
-                                    ui.spinner();
-                                } else {
-                                    ui.label(status_text);
-                                }
-
-                                ui.horizontal(|ui| {
-                                    if ui.button("Uninstall").clicked() {
-                                        *on_uninstall = Some(package.clone());
-                                    }
-                                    if matches!(package.package_type, PackageType::Formula) {
-                                        if package.pinned {
-                                            if ui.button("Unpin").clicked() {
-                                                *on_unpin = Some(package.clone());
-                                            }
+                    if !self.installed_collapsed {
+
+                        let layout = if show_install_date {
+                            &INSTALLED_TABLE_WITH_DATE
+                        } else {
+                            &INSTALLED_TABLE
+                        };
+                        let installed_sort = &mut self.installed_sort;
+                        column_widths
+                            .table(ui, layout)
+                            .vscroll(false)
+                            .header(header_height, |row| {
+                                let sortable =
+                                    show_install_date.then_some((INSTALLED_COLUMN, installed_sort));
+                                column_widths.sortable_header(row, layout, compact, sortable)
+                            })
+                            .body(|body| {
+                                body.rows(row_height, installed.len(), |mut row| {
+                                    let package = installed[row.index()];
+                                    row.col(|ui| {
+                                        let is_selected =
+                                            self.selected_package.as_ref() == Some(&package.name);
+                                        if favorite_button(ui, is_favorite(&package.name)) {
+                                            *on_toggle_favorite = Some(package.clone());
+                                        }
+                                        let text = highlight::highlighted_name(
+                                            ui,
+                                            &package.name,
+                                            search_query,
+                                        );
+                                        let name = ui.selectable_label(is_selected, text);
+                                        if name.clicked() {
+                                            self.selected_package = Some(package.name.clone());
+                                        }
+                                        if name.double_clicked() {
+                                            self.open_info_action = Some(package.clone());
+                                        }
+                                        metadata_chips(ui, metadata.get(&package.name));
+                                    });
+
+                                    row.col(|ui| {
+                                        let version_text =
+                                            package.version.as_deref().unwrap_or("N/A");
+
+                                        if packages_loading_info.contains(&package.name) {
+                                            ui.spinner();
+                                        } else if package.version_load_failed {
+                                            ui.label(
+                                                RichText::new(version_text)
+                                                    .color(Color32::from_rgb(255, 0, 0)),
+                                            );
+                                        } else if package.pinned {
+                                            ui.label(
+                                                RichText::new(version_text)
+                                                    .color(Color32::from_rgb(255, 200, 0)),
+                                            );
                                         } else {
-                                            if ui.button("Pin").clicked() {
-                                                *on_pin = Some(package.clone());
-                                            }
+                                            ui.label(version_text);
                                         }
+                                    });
+
+                                    if show_install_date {
+                                        row.col(|ui| match package.installed_at {
+                                            Some(at) => {
+                                                ui.label(time_ago(at, now_time))
+                                                    .on_hover_text(format_local_time(at));
+                                            }
+                                            None => {
+                                                ui.weak("—");
+                                            }
+                                        });
                                     }
 
-                                    if package.version.is_none() {
-                                        if ui.button("Load Info").clicked() {
-                                            *on_load_info = Some(package.clone());
+                                    row.col(|ui| {
+                                        ui.label(package.package_type.to_string());
+                                    });
+
+                                    row.col(|ui| {
+                                        let is_operating =
+                                            packages_loading_info.contains(&package.name);
+                                        let (status_text, status_color) = if package.pinned {
+                                            ("Pinned", Color32::from_rgb(255, 200, 0))
+                                        } else {
+                                            ("Installed", Color32::from_rgb(0, 255, 0))
+                                        };
+
+                                        if is_operating {
+                                            progress_cell(ui, progress_of(&package.name));
+                                        } else {
+                                            status_cell(ui, status_text, status_color, compact);
+                                        }
+                                    });
+
+                                    row.col(|ui| {
+                                        if ui.button("Uninstall").clicked() {
+                                            *on_uninstall = Some(package.clone());
+                                        }
+                                        if package.package_type == PackageType::Cask
+                                            && ui
+                                                .button("Zap")
+                                                .on_hover_text(
+                                                    "Uninstall and remove its preferences, caches and other files",
+                                                )
+                                                .clicked()
+                                        {
+                                            self.zap_action = Some(package.clone());
+                                        }
+                                        if matches!(package.package_type, PackageType::Formula) {
+                                            if package.pinned {
+                                                if ui.button("Unpin").clicked() {
+                                                    *on_unpin = Some(package.clone());
+                                                }
+                                            } else {
+                                                if ui.button("Pin").clicked() {
+                                                    *on_pin = Some(package.clone());
+                                                }
+                                            }
                                         }
-                                    } else if package.description.is_some() {
-                                        if ui.button("Info").clicked() {
+
+                                        if package.version_load_failed {
+                                            if ui
+                                                .button("Retry")
+                                                .on_hover_text("Loading its info failed; try again")
+                                                .clicked()
+                                            {
+                                                *on_load_info = Some(package.clone());
+                                            }
+                                        } else if package.version.is_none() {
+                                            if ui.button("Load Info").clicked() {
+                                                *on_load_info = Some(package.clone());
+                                            }
+                                        } else if package.description.is_some()
+                                            && ui.button("Info").clicked()
+                                        {
                                             self.show_info_action = Some(package.clone());
                                         }
-                                    }
-                                });
 
-                                ui.end_row();
-                            }
-                        });
+                                        let reveal = format!(
+                                            "Reveal {} in {}",
+                                            package.name,
+                                            crate::infrastructure::system::FILE_MANAGER
+                                        );
+                                        let response = ui.button("📂").on_hover_text(&reveal);
+                                        accessible::name(&response, WidgetType::Button, &reveal);
+                                        if response.clicked() {
+                                            self.reveal_action = Some(package.clone());
+                                        }
+                                    });
+                                });
+                            });
+                    }
                 }
             });
+        self.metadata = metadata;
 
         if self.outdated_selection.has_selection() {
             let selected_count = self.outdated_selection.get_selected().len();
@@ -416,3 +1030,498 @@ impl MergedPackageList {
         }
     }
 }
+
+/// A section title that folds the section away or back when clicked.
+/// Returns whether it was clicked. The tables below keep their ids either
+/// way, so resized columns survive folding.
+fn section_header(ui: &mut egui::Ui, title: &str, compact: bool, collapsed: &mut bool) -> bool {
+    let (arrow, hint) = if *collapsed {
+        ("⏵", "Click to show")
+    } else {
+        ("⏷", "Click to hide")
+    };
+    let response = heading(ui, &format!("{} {}", arrow, title), compact)
+        .interact(Sense::click())
+        .on_hover_text(hint);
+    let state = if *collapsed { "collapsed" } else { "expanded" };
+    accessible::name(
+        &response,
+        WidgetType::Button,
+        &format!(
+            "{}, {}",
+            title
+                .trim_start_matches(|c: char| !c.is_alphabetic())
+                .trim(),
+            state
+        ),
+    );
+    if response.clicked() {
+        *collapsed = !*collapsed;
+    }
+    response.clicked()
+}
+
+/// The package's tags as small chips, and a 📝 showing its note on hover.
+fn metadata_chips(ui: &mut egui::Ui, metadata: Option<&PackageMetadata>) {
+    let Some(metadata) = metadata else {
+        return;
+    };
+    for tag in &metadata.tags {
+        egui::Frame::none()
+            .fill(ui.visuals().faint_bg_color)
+            .stroke(ui.visuals().widgets.noninteractive.bg_stroke)
+            .rounding(6.0)
+            .inner_margin(egui::Margin::symmetric(4.0, 0.0))
+            .show(ui, |ui| ui.small(tag));
+    }
+    if !metadata.note.is_empty() {
+        ui.weak("📝").on_hover_text(&metadata.note);
+    }
+}
+
+/// A table of `packages` with their current and available versions, for
+/// pasting into chat or an issue. Unknown versions show as `?`.
+fn outdated_markdown(packages: &[Package], date: &str) -> String {
+    let mut markdown = format!(
+        "**Outdated Homebrew packages** · {} · {} outdated\n",
+        date,
+        packages.len()
+    );
+    if packages.is_empty() {
+        markdown.push_str("\nEverything is up to date.\n");
+        return markdown;
+    }
+
+    markdown.push_str("\n| Package | Version | Type | Pinned |\n|---|---|---|---|\n");
+    for package in packages {
+        markdown.push_str(&format!(
+            "| {} | {} → {} | {} | {} |\n",
+            package.name,
+            package.version.as_deref().unwrap_or("?"),
+            package.available_version.as_deref().unwrap_or("?"),
+            package.package_type,
+            if package.pinned { "Yes" } else { "No" }
+        ));
+    }
+    markdown
+}
+
+/// The Ignore menu of an outdated row. Returns the chosen end of the snooze
+/// as a unix time, or `Some(None)` to ignore until un-ignored.
+fn ignore_updates_menu(ui: &mut egui::Ui, now: u64) -> Option<Option<u64>> {
+    let mut choice = None;
+    ui.menu_button("Ignore", |ui| {
+        for (label, days) in [
+            ("For 7 days", Some(7)),
+            ("For 30 days", Some(30)),
+            ("Until I stop ignoring it", None),
+        ] {
+            if ui.button(label).clicked() {
+                choice = Some(days.map(|days| now + days * SECS_PER_DAY));
+                ui.close_menu();
+            }
+        }
+    })
+    .response
+    .on_hover_text("Stop showing this update in Brewsty, without pinning it in brew");
+    choice
+}
+
+/// Whether `package` updates itself and the user hasn't had brew update it.
+pub fn updates_left_to_app(brew_updated_casks: &HashSet<String>, package: &Package) -> bool {
+    package.auto_updates && !brew_updated_casks.contains(&package.name)
+}
+
+/// Whether Select All skips `package`: brew won't upgrade it while pinned,
+/// and the user has put off or handed over its updates otherwise.
+fn left_out_of_select_all(
+    ignored_updates: &HashMap<String, Option<u64>>,
+    brew_updated_casks: &HashSet<String>,
+    package: &Package,
+    now: u64,
+) -> bool {
+    package.pinned
+        || update_ignored(ignored_updates, &package.name, now)
+        || updates_left_to_app(brew_updated_casks, package)
+}
+
+/// The Auto-updates menu shown instead of Update for a cask that updates
+/// itself. True when the user chose to have brew update it anyway.
+fn auto_updates_menu(ui: &mut egui::Ui) -> bool {
+    let mut brew_updates = false;
+    ui.menu_button("Auto-updates", |ui| {
+        ui.label("The app updates itself, so brew leaves its updates to it.");
+        if ui.button("Update with Brew Anyway").clicked() {
+            brew_updates = true;
+            ui.close_menu();
+        }
+    })
+    .response
+    .on_hover_text("This app keeps itself up to date");
+    brew_updates
+}
+
+/// Whether updates to `name` are ignored at `now`; a snooze that has run
+/// out no longer counts.
+fn update_ignored(ignored_updates: &HashMap<String, Option<u64>>, name: &str, now: u64) -> bool {
+    ignored_updates
+        .get(name)
+        .is_some_and(|until| until.is_none_or(|until| now < until))
+}
+
+fn apply_install_dates(packages: &mut [Package], install_dates: &HashMap<String, SystemTime>) {
+    for package in packages {
+        package.installed_at = install_dates.get(&package.name).copied();
+    }
+}
+
+/// Orders by install date in `order`, with packages of unknown date last.
+fn compare_install_dates(a: &Package, b: &Package, order: SortOrder) -> Ordering {
+    match (a.installed_at, b.installed_at) {
+        (Some(a), Some(b)) => match order {
+            SortOrder::Descending => b.cmp(&a),
+            SortOrder::Ascending => a.cmp(&b),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// "3 days ago" style age of `at`, coarser the further back it is.
+pub fn time_ago(at: SystemTime, now: SystemTime) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const MONTH: u64 = 30 * SECS_PER_DAY;
+    const YEAR: u64 = 365 * SECS_PER_DAY;
+
+    let secs = now.duration_since(at).map(|d| d.as_secs()).unwrap_or(0);
+    let (count, unit) = match secs {
+        s if s < MINUTE => return "just now".to_string(),
+        s if s < HOUR => (s / MINUTE, "minute"),
+        s if s < SECS_PER_DAY => (s / HOUR, "hour"),
+        s if s < MONTH => (s / SECS_PER_DAY, "day"),
+        s if s < YEAR => (s / MONTH, "month"),
+        s => (s / YEAR, "year"),
+    };
+    if count == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", count, unit)
+    }
+}
+
+fn format_local_time(at: SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(at)
+        .format("%Y-%m-%d %H:%M")
+        .to_string()
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// "3 days" for a package first seen outdated at `since`; `None` within the first day.
+fn outdated_for_label(since: u64, now: u64) -> Option<String> {
+    match now.saturating_sub(since) / SECS_PER_DAY {
+        0 => None,
+        1 => Some("1 day".to_string()),
+        days => Some(format!("{} days", days)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn installed(name: &str, secs: Option<u64>) -> Package {
+        let mut package = Package::new(name.to_string(), PackageType::Formula);
+        package.installed_at = secs.map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        package
+    }
+
+    fn outdated(name: &str, version: &str, available: &str) -> Package {
+        let mut package = Package::new(name.to_string(), PackageType::Formula)
+            .with_version(version.to_string())
+            .with_available_version(available.to_string());
+        package.installed = true;
+        package.outdated = true;
+        package
+    }
+
+    fn names(packages: &[Package]) -> Vec<&str> {
+        packages.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    /// jq and node are outdated, wget is current.
+    fn list() -> MergedPackageList {
+        let jq = outdated("jq", "1.6", "1.7.1");
+        let node = outdated("node", "20.1.0", "22.0.0");
+        let mut list = MergedPackageList::new();
+        list.update_packages(vec![jq.clone(), installed("wget", None), node.clone()]);
+        list.update_outdated_packages(vec![jq, node], true);
+        list.take_outdated_since_changed();
+        list
+    }
+
+    fn selected(list: &MergedPackageList) -> Vec<String> {
+        let mut selected = list.get_selected_outdated();
+        selected.sort();
+        selected
+    }
+
+    #[test]
+    fn updating_a_package_moves_it_out_of_outdated() {
+        let mut list = list();
+
+        list.mark_package_updated("jq");
+
+        assert_eq!(names(&list.outdated_packages), ["node"]);
+        assert_eq!(names(&list.packages), ["jq", "wget", "node"]);
+        assert!(!list.is_outdated("jq"));
+        let jq = list.get_package("jq").unwrap();
+        assert_eq!(jq.available_version, None);
+        assert!(!list.outdated_since().contains_key("jq"));
+        assert!(list.take_outdated_since_changed());
+    }
+
+    #[test]
+    fn failed_updates_are_flagged_until_the_package_is_updated() {
+        let mut list = list();
+
+        list.mark_update_failed("jq", "Failed to download resource");
+        list.mark_update_failed("node", "Failed to download resource");
+        assert_eq!(
+            list.update_failure("jq"),
+            Some("Failed to download resource")
+        );
+
+        list.mark_package_updated("jq");
+        assert_eq!(list.update_failure("jq"), None);
+        list.update_outdated_packages(Vec::new(), true);
+        assert_eq!(list.update_failure("node"), None);
+    }
+
+    #[test]
+    fn failed_outdated_loads_keep_what_is_known_about_missing_packages() {
+        let mut list = list();
+        list.mark_update_failed("node", "Failed to download resource");
+
+        // Say the cask half failed while offline and the formulae came back empty
+        list.update_outdated_packages(Vec::new(), false);
+
+        assert!(list.outdated_since().contains_key("jq"));
+        assert!(list.outdated_since().contains_key("node"));
+        assert!(!list.take_outdated_since_changed());
+        assert_eq!(
+            list.update_failure("node"),
+            Some("Failed to download resource")
+        );
+    }
+
+    #[test]
+    fn uninstalling_removes_the_package_from_both_lists() {
+        let mut list = list();
+
+        list.remove_installed_package("node");
+
+        assert_eq!(names(&list.packages), ["jq", "wget"]);
+        assert_eq!(names(&list.outdated_packages), ["jq"]);
+        assert!(list.get_package("node").is_none());
+    }
+
+    #[test]
+    fn adding_an_installed_package_replaces_an_existing_entry() {
+        let mut list = list();
+
+        list.add_installed_package(installed("wget", Some(100)));
+        list.add_installed_package(installed("ripgrep", None));
+
+        assert_eq!(names(&list.packages), ["jq", "wget", "node", "ripgrep"]);
+        assert!(list.get_package("wget").unwrap().installed_at.is_some());
+    }
+
+    #[test]
+    fn outdated_selection_tracks_selects_and_updates() {
+        let mut list = list();
+        assert!(!list.has_selected_outdated());
+
+        list.select_all_outdated();
+        assert_eq!(selected(&list), ["jq", "node"]);
+
+        list.mark_package_updated("jq");
+        list.remove_from_outdated_selection_by_name("jq");
+        assert_eq!(selected(&list), ["node"]);
+
+        list.deselect_all_outdated();
+        assert!(!list.has_selected_outdated());
+    }
+
+    #[test]
+    fn ignored_updates_stay_out_of_select_all_until_they_expire() {
+        let mut list = list();
+        let now = unix_now();
+        list.set_ignored_updates(HashMap::from([
+            ("jq".to_string(), Some(now + SECS_PER_DAY)),
+            ("node".to_string(), Some(now - 1)),
+            ("wget".to_string(), None),
+        ]));
+
+        list.update_outdated_packages(
+            vec![
+                outdated("jq", "1.6", "1.7.1"),
+                outdated("node", "20.1.0", "22.0.0"),
+            ],
+            true,
+        );
+        list.select_all_outdated();
+
+        assert_eq!(selected(&list), ["node"]);
+        assert!(list.is_update_ignored("jq"));
+        assert!(!list.is_update_ignored("node"));
+        assert!(!list.ignored_updates().contains_key("node"));
+        assert!(list.ignored_updates().contains_key("wget"));
+        assert!(list.take_ignored_updates_changed());
+    }
+
+    #[test]
+    fn self_updating_casks_stay_out_of_select_all_unless_brew_updates_them() {
+        let mut list = list();
+        let mut app = outdated("chrome", "120", "121").set_auto_updates(true);
+        app.package_type = PackageType::Cask;
+        list.update_outdated_packages(vec![outdated("jq", "1.6", "1.7.1"), app.clone()], true);
+
+        list.select_all_outdated();
+        assert_eq!(selected(&list), ["jq"]);
+        assert!(list.leaves_updates_to_app(&app));
+        assert!(!list.outdated_since().contains_key("chrome"));
+        assert!(!list.outdated_markdown("2026-10-16").contains("chrome"));
+
+        list.set_brew_updated_casks(HashSet::from(["chrome".to_string()]));
+        list.update_outdated_packages(vec![outdated("jq", "1.6", "1.7.1"), app.clone()], true);
+        list.select_all_outdated();
+        assert_eq!(selected(&list), ["chrome", "jq"]);
+        assert!(!list.leaves_updates_to_app(&app));
+        assert!(list.outdated_since().contains_key("chrome"));
+        assert!(list.outdated_markdown("2026-10-16").contains("chrome"));
+    }
+
+    #[test]
+    fn retrying_clears_a_failed_info_load_in_both_sections() {
+        let mut list = list();
+        list.update_package(
+            Package::new("jq".to_string(), PackageType::Formula).set_version_load_failed(true),
+        );
+        assert!(list.get_package("jq").unwrap().version_load_failed);
+
+        list.clear_load_failure("jq");
+        assert!(
+            list.packages()
+                .iter()
+                .chain(&list.outdated_packages)
+                .all(|p| !p.version_load_failed)
+        );
+    }
+
+    #[test]
+    fn outdated_markdown_tables_versions_with_unknowns_as_question_marks() {
+        let mut node = Package::new("node".to_string(), PackageType::Formula).set_pinned(true);
+        node.available_version = Some("22.0.0".to_string());
+        let packages = [outdated("jq", "1.6", "1.7.1"), node];
+
+        assert_eq!(
+            outdated_markdown(&packages, "2026-10-16"),
+            "**Outdated Homebrew packages** · 2026-10-16 · 2 outdated\n\n\
+             | Package | Version | Type | Pinned |\n\
+             |---|---|---|---|\n\
+             | jq | 1.6 → 1.7.1 | Formula | No |\n\
+             | node | ? → 22.0.0 | Formula | Yes |\n"
+        );
+    }
+
+    #[test]
+    fn outdated_markdown_says_when_nothing_is_outdated() {
+        assert_eq!(
+            MergedPackageList::new().outdated_markdown("2026-10-16"),
+            "**Outdated Homebrew packages** · 2026-10-16 · 0 outdated\n\n\
+             Everything is up to date.\n"
+        );
+    }
+
+    #[test]
+    fn install_date_sort_keeps_unknown_dates_last() {
+        let packages = [
+            installed("old", Some(100)),
+            installed("unknown", None),
+            installed("new", Some(300)),
+        ];
+        let sorted_names = |order| {
+            let mut sorted: Vec<&Package> = packages.iter().collect();
+            sorted.sort_by(|a, b| compare_install_dates(a, b, order));
+            sorted.iter().map(|p| p.name.as_str()).collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            sorted_names(SortOrder::Descending),
+            ["new", "old", "unknown"]
+        );
+        assert_eq!(
+            sorted_names(SortOrder::Ascending),
+            ["old", "new", "unknown"]
+        );
+    }
+
+    #[test]
+    fn matching_finds_installed_packages_by_name() {
+        let list = list();
+
+        let matches = list.matching("No");
+        assert_eq!(names(&matches), ["node"]);
+        assert!(matches[0].installed && matches[0].outdated);
+        assert_eq!(matches[0].available_version.as_deref(), Some("22.0.0"));
+
+        assert_eq!(names(&list.matching("")), ["jq", "wget", "node"]);
+        assert!(list.matching("curl").is_empty());
+    }
+
+    #[test]
+    fn time_ago_uses_the_largest_whole_unit() {
+        let now = UNIX_EPOCH + Duration::from_secs(400 * SECS_PER_DAY);
+        let ago = |secs: u64| time_ago(now - Duration::from_secs(secs), now);
+
+        assert_eq!(ago(30), "just now");
+        assert_eq!(ago(60), "1 minute ago");
+        assert_eq!(ago(3 * 60 * 60), "3 hours ago");
+        assert_eq!(ago(2 * SECS_PER_DAY), "2 days ago");
+        assert_eq!(ago(65 * SECS_PER_DAY), "2 months ago");
+        assert_eq!(ago(400 * SECS_PER_DAY), "1 year ago");
+    }
+
+    #[test]
+    fn select_all_leaves_out_pinned_ignored_and_self_updating_packages() {
+        let mut list = list();
+        let mut app = outdated("chrome", "120", "121").set_auto_updates(true);
+        app.package_type = PackageType::Cask;
+        list.update_outdated_packages(
+            vec![
+                outdated("jq", "1.6", "1.7.1").set_pinned(true),
+                outdated("node", "20.1.0", "22.0.0"),
+                outdated("wget", "1.24", "1.25"),
+                app,
+            ],
+            true,
+        );
+        list.set_ignored_updates(HashMap::from([("wget".to_string(), None)]));
+        list.set_only_outdated(true);
+
+        list.select_all_outdated();
+
+        assert_eq!(selected(&list), ["node"]);
+        assert_eq!(list.left_out_of_select_all(), 3);
+        assert!(list.only_outdated());
+    }
+}
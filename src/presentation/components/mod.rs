@@ -1,21 +1,44 @@
+pub mod accessible;
 pub mod cleanup_modal;
+pub mod column_widths;
+pub mod compare_modal;
+pub mod dependency_modal;
+pub mod favorite_button;
 pub mod filter_state;
+pub mod follow_scroll;
+pub mod grid_density;
+pub mod highlight;
 pub mod info_modal;
 pub mod log_manager;
 pub mod merged_package_list;
 pub mod package_list;
 pub mod password_modal;
+pub mod plist_modal;
 pub mod selection_state;
+pub mod service_info_modal;
 pub mod service_list;
+pub mod system_theme;
 pub mod tab_manager;
+pub mod upgrade_modal;
+pub mod window_state;
 
 pub use cleanup_modal::{CleanupAction, CleanupModal, CleanupType};
+pub use column_widths::ColumnWidths;
+pub use compare_modal::CompareModal;
+pub use dependency_modal::{DependencyAction, DependencyModal};
+pub use favorite_button::favorite_button;
 pub use filter_state::FilterState;
-pub use info_modal::InfoModal;
+pub use follow_scroll::{FollowEdge, FollowScroll};
+pub use info_modal::{InfoAction, InfoModal};
 pub use log_manager::{LogLevel, LogManager};
 pub use merged_package_list::MergedPackageList;
 pub use package_list::PackageList;
 pub use password_modal::PasswordModal;
+pub use plist_modal::{PlistAction, PlistModal};
 pub use selection_state::SelectionState;
+pub use service_info_modal::{ServiceInfoAction, ServiceInfoModal};
 pub use service_list::ServiceList;
+pub use system_theme::SystemTheme;
 pub use tab_manager::{Tab, TabManager};
+pub use upgrade_modal::{UpgradeAction, UpgradeModal};
+pub use window_state::WindowState;
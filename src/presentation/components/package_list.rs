@@ -1,10 +1,29 @@
-use crate::domain::entities::{Package, PackageType};
-use egui::{Color32, RichText, ScrollArea};
+use crate::domain::entities::{FavoritePackage, OperationProgress, Package, PackageType};
+use crate::presentation::components::column_widths::{ColumnWidths, TableLayout};
+use crate::presentation::components::grid_density::{self, progress_cell, status_cell};
+use crate::presentation::components::{favorite_button, highlight};
+use egui::{Color32, RichText};
+
+const SEARCH_TABLE: TableLayout = TableLayout {
+    id: "search_table",
+    columns: &[
+        ("Name", 240.0),
+        ("Version", 140.0),
+        ("Type", 80.0),
+        ("Status", 100.0),
+        ("Actions", 0.0),
+    ],
+};
 
 pub struct PackageList {
     packages: Vec<Package>,
     selected_package: Option<String>,
     show_info_action: Option<Package>,
+    /// Double-clicked; its Info window opens, once its info is loaded if need be.
+    open_info_action: Option<Package>,
+    /// Every result is listed, not just the first `limit`, until the next search.
+    show_all: bool,
+    show_all_action: bool,
 }
 
 impl PackageList {
@@ -13,19 +32,56 @@ impl PackageList {
             packages: Vec::new(),
             selected_package: None,
             show_info_action: None,
+            open_info_action: None,
+            show_all: false,
+            show_all_action: false,
         }
     }
 
     pub fn update_packages(&mut self, packages: Vec<Package>) {
         self.packages = packages;
+        self.show_all = false;
+    }
+
+    /// Shows `installed` first, then whatever of `available` isn't among them.
+    pub fn update_installed_first(&mut self, installed: Vec<Package>, available: Vec<Package>) {
+        let available: Vec<Package> = available
+            .into_iter()
+            .filter(|a| !installed.iter().any(|i| i.name == a.name))
+            .collect();
+        self.packages = installed;
+        self.packages.extend(available);
+        self.show_all = false;
+    }
+
+    /// The packages of the given types that are listed: the first `limit`,
+    /// or all of them once the user asked to see every result.
+    pub fn displayed(&self, show_formulae: bool, show_casks: bool, limit: usize) -> Vec<&Package> {
+        let limit = if self.show_all { usize::MAX } else { limit };
+        displayed(&self.packages, show_formulae, show_casks, limit)
+    }
+
+    /// True once after the user chose to list every result.
+    pub fn take_show_all_action(&mut self) -> bool {
+        std::mem::take(&mut self.show_all_action)
     }
 
-    pub fn update_package(&mut self, package: Package) {
+    pub fn update_package(&mut self, mut package: Package) {
         if let Some(existing) = self.packages.iter_mut().find(|p| p.name == package.name) {
+            if package.audit.is_none() {
+                package.audit = existing.audit.take();
+            }
             *existing = package;
         }
     }
 
+    /// Forgets that loading `name`'s info failed, before it is tried again.
+    pub fn clear_load_failure(&mut self, name: &str) {
+        if let Some(package) = self.packages.iter_mut().find(|p| p.name == name) {
+            package.version_load_failed = false;
+        }
+    }
+
     pub fn get_package(&self, name: &str) -> Option<Package> {
         self.packages.iter().find(|p| p.name == name).cloned()
     }
@@ -34,6 +90,11 @@ impl PackageList {
         self.show_info_action.take()
     }
 
+    pub fn get_open_info_action(&mut self) -> Option<Package> {
+        self.open_info_action.take()
+    }
+
+    #[allow(clippy::too_many_arguments)]
     pub fn show_filtered_with_search_and_pin(
         &mut self,
         ui: &mut egui::Ui,
@@ -45,147 +106,222 @@ impl PackageList {
         search_query: &str,
         on_load_info: &mut Option<Package>,
         packages_loading_info: &std::collections::HashSet<String>,
+        operation_progress: &[(String, OperationProgress)],
         on_pin: &mut Option<Package>,
         on_unpin: &mut Option<Package>,
+        favorites: &[FavoritePackage],
+        on_toggle_favorite: &mut Option<Package>,
+        compact: bool,
+        column_widths: &mut ColumnWidths,
+        limit: usize,
     ) {
+        let is_favorite = |name: &str| favorites.iter().any(|f| f.name == name);
+        let progress_of = |name: &str| {
+            operation_progress
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, progress)| progress)
+        };
         let search_lower = search_query.to_lowercase();
+        let is_shown = |package: &Package| {
+            search_query.is_empty() || package.name.to_lowercase().contains(&search_lower)
+        };
 
-        ScrollArea::vertical()
-            .auto_shrink([false, false])
-            .show(ui, |ui| {
-                egui::Grid::new("package_grid")
-                    .striped(true)
-                    .spacing([10.0, 8.0])
-                    .min_col_width(ui.available_width() / 5.0)
-                    .show(ui, |ui| {
-                        ui.heading("Name");
-                        ui.heading("Version");
-                        ui.heading("Type");
-                        ui.heading("Status");
-                        ui.heading("Actions");
-                        ui.end_row();
-
-                        for package in &self.packages {
-                            let should_show = match package.package_type {
-                                PackageType::Formula => show_formulae,
-                                PackageType::Cask => show_casks,
-                            };
-
-                            if !should_show {
-                                continue;
-                            }
+        let total = displayed(&self.packages, show_formulae, show_casks, usize::MAX).len();
+        if total > limit && !self.show_all {
+            egui::TopBottomPanel::bottom("search_results_limit").show_inside(ui, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label(format!("Showing {} of {}", limit, total));
+                    if ui.button("Show All").clicked() {
+                        self.show_all = true;
+                        self.show_all_action = true;
+                    }
+                });
+            });
+        }
 
-                            if !search_query.is_empty()
-                                && !package.name.to_lowercase().contains(&search_lower)
-                            {
-                                continue;
-                            }
+        // The table only lays out the rows scrolled into view
+        let limit = if self.show_all { usize::MAX } else { limit };
+        let shown: Vec<&Package> = displayed(&self.packages, show_formulae, show_casks, limit)
+            .into_iter()
+            .filter(|p| is_shown(p))
+            .collect();
 
-                            let is_selected = self
-                                .selected_package
-                                .as_ref()
-                                .map_or(false, |s| s == &package.name);
+        grid_density::apply_table_style(ui, compact);
+        let row_height = grid_density::row_height(ui);
+        let header_height = grid_density::header_height(ui, compact);
 
-                            if ui.selectable_label(is_selected, &package.name).clicked() {
-                                self.selected_package = Some(package.name.clone());
-                            }
+        column_widths
+            .table(ui, &SEARCH_TABLE)
+            .auto_shrink([false, false])
+            .header(header_height, |row| {
+                column_widths.header(row, &SEARCH_TABLE, compact)
+            })
+            .body(|body| {
+                body.rows(row_height, shown.len(), |mut row| {
+                    let package = shown[row.index()];
+                    row.col(|ui| {
+                        let is_selected = self.selected_package.as_ref() == Some(&package.name);
+                        if favorite_button(ui, is_favorite(&package.name)) {
+                            *on_toggle_favorite = Some(package.clone());
+                        }
+                        let text = highlight::highlighted_name(ui, &package.name, search_query);
+                        let name = ui.selectable_label(is_selected, text);
+                        if name.clicked() {
+                            self.selected_package = Some(package.name.clone());
+                        }
+                        if name.double_clicked() {
+                            self.open_info_action = Some(package.clone());
+                        }
+                    });
 
-                            let version_text = if package.version_load_failed {
-                                "Failed".to_string()
-                            } else if package.outdated {
-                                if let Some(av) = &package.available_version {
-                                    format!(
-                                        "{} -> {}",
-                                        package.version.as_deref().unwrap_or("N/A"),
-                                        av
-                                    )
-                                } else {
-                                    package.version.as_deref().unwrap_or("N/A").to_string()
-                                }
+                    row.col(|ui| {
+                        let version_text = if package.version_load_failed {
+                            "Failed".to_string()
+                        } else if package.outdated {
+                            if let Some(av) = &package.available_version {
+                                format!("{} -> {}", package.version.as_deref().unwrap_or("N/A"), av)
                             } else {
                                 package.version.as_deref().unwrap_or("N/A").to_string()
-                            };
-
-                            if packages_loading_info.contains(&package.name) {
-                                ui.spinner();
-                            } else if package.version_load_failed {
-                                ui.label(
-                                    RichText::new(version_text).color(Color32::from_rgb(255, 0, 0)),
-                                );
-                            } else if package.pinned {
-                                ui.label(
-                                    RichText::new(version_text)
-                                        .color(Color32::from_rgb(255, 200, 0)),
-                                );
-                            } else {
-                                ui.label(version_text);
                             }
+                        } else {
+                            package.version.as_deref().unwrap_or("N/A").to_string()
+                        };
 
-                            ui.label(package.package_type.to_string());
+                        if packages_loading_info.contains(&package.name) {
+                            ui.spinner();
+                        } else if package.version_load_failed {
+                            ui.label(
+                                RichText::new(version_text).color(Color32::from_rgb(255, 0, 0)),
+                            );
+                        } else if package.pinned {
+                            ui.label(
+                                RichText::new(version_text).color(Color32::from_rgb(255, 200, 0)),
+                            );
+                        } else {
+                            ui.label(version_text);
+                        }
+                    });
 
-                            let is_operating = packages_loading_info.contains(&package.name);
-                            let status_text = if package.pinned {
-                                RichText::new("Pinned").color(Color32::from_rgb(255, 200, 0))
-                            } else if package.outdated {
-                                RichText::new("Outdated").color(Color32::from_rgb(255, 165, 0))
-                            } else if package.installed {
-                                RichText::new("Installed").color(Color32::from_rgb(0, 255, 0))
-                            } else {
-                                RichText::new("Available").color(Color32::GRAY)
-                            };
+                    row.col(|ui| {
+                        ui.label(package.package_type.to_string());
+                    });
 
-                            if is_operating {
-                                ui.spinner();
-                            } else {
-                                ui.label(status_text);
-                            }
+                    row.col(|ui| {
+                        let is_operating = packages_loading_info.contains(&package.name);
+                        let (status_text, status_color) = if package.pinned {
+                            ("Pinned", Color32::from_rgb(255, 200, 0))
+                        } else if package.outdated {
+                            ("Outdated", Color32::from_rgb(255, 165, 0))
+                        } else if package.installed {
+                            ("Installed", Color32::from_rgb(0, 255, 0))
+                        } else {
+                            ("Available", Color32::GRAY)
+                        };
 
-                            ui.horizontal(|ui| {
-                                if package.installed {
-                                    if ui.button("Uninstall").clicked() {
-                                        *on_uninstall = Some(package.clone());
-                                    }
-                                    if package.outdated
-                                        && !package.pinned
-                                        && ui.button("Update").clicked()
-                                    {
-                                        *on_update = Some(package.clone());
-                                    }
-                                    // Only show pin/unpin for formulae (casks don't support pinning in Homebrew)
-                                    if matches!(package.package_type, PackageType::Formula) {
-                                        if package.pinned {
-                                            if ui.button("Unpin").clicked() {
-                                                *on_unpin = Some(package.clone());
-                                            }
-                                        } else {
-                                            if ui.button("Pin").clicked() {
-                                                *on_pin = Some(package.clone());
-                                            }
-                                        }
-                                    }
-                                } else {
-                                    if ui.button("Install").clicked() {
-                                        *on_install = Some(package.clone());
-                                    }
-                                }
+                        if is_operating {
+                            progress_cell(ui, progress_of(&package.name));
+                        } else {
+                            status_cell(ui, status_text, status_color, compact);
+                        }
+                    });
 
-                                if package.version.is_none()
-                                    && !package.version_load_failed
-                                    && !packages_loading_info.contains(&package.name)
-                                {
-                                    if ui.button("Load Info").clicked() {
-                                        *on_load_info = Some(package.clone());
+                    row.col(|ui| {
+                        if package.installed {
+                            if ui.button("Uninstall").clicked() {
+                                *on_uninstall = Some(package.clone());
+                            }
+                            if package.outdated && !package.pinned && ui.button("Update").clicked()
+                            {
+                                *on_update = Some(package.clone());
+                            }
+                            // Only show pin/unpin for formulae (casks don't support pinning in Homebrew)
+                            if matches!(package.package_type, PackageType::Formula) {
+                                if package.pinned {
+                                    if ui.button("Unpin").clicked() {
+                                        *on_unpin = Some(package.clone());
                                     }
-                                } else if package.description.is_some() {
-                                    if ui.button("Info").clicked() {
-                                        self.show_info_action = Some(package.clone());
+                                } else {
+                                    if ui.button("Pin").clicked() {
+                                        *on_pin = Some(package.clone());
                                     }
                                 }
-                            });
+                            }
+                        } else {
+                            if ui.button("Install").clicked() {
+                                *on_install = Some(package.clone());
+                            }
+                        }
 
-                            ui.end_row();
+                        let loading = packages_loading_info.contains(&package.name);
+                        if package.version_load_failed && !loading {
+                            if ui
+                                .button("Retry")
+                                .on_hover_text("Loading its info failed; try again")
+                                .clicked()
+                            {
+                                *on_load_info = Some(package.clone());
+                            }
+                        } else if package.version.is_none() && !loading {
+                            if ui.button("Load Info").clicked() {
+                                *on_load_info = Some(package.clone());
+                            }
+                        } else if package.description.is_some() && ui.button("Info").clicked() {
+                            self.show_info_action = Some(package.clone());
                         }
                     });
+                });
             });
     }
 }
+
+/// The first `limit` of `packages` of the given types.
+fn displayed(
+    packages: &[Package],
+    show_formulae: bool,
+    show_casks: bool,
+    limit: usize,
+) -> Vec<&Package> {
+    packages
+        .iter()
+        .filter(|package| match package.package_type {
+            PackageType::Formula => show_formulae,
+            PackageType::Cask => show_casks,
+        })
+        .take(limit)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results(count: usize) -> Vec<Package> {
+        (0..count)
+            .map(|i| {
+                let package_type = if i % 2 == 0 {
+                    PackageType::Formula
+                } else {
+                    PackageType::Cask
+                };
+                Package::new(format!("python-{}", i), package_type)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn lists_the_first_results_of_the_shown_types_until_all_are_asked_for() {
+        let mut list = PackageList::new();
+        list.update_packages(results(10));
+        assert_eq!(list.displayed(true, true, 4).len(), 4);
+        let formulae = list.displayed(true, false, 3);
+        let names: Vec<&str> = formulae.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["python-0", "python-2", "python-4"]);
+
+        list.show_all = true;
+        assert_eq!(list.displayed(true, true, 4).len(), 10);
+
+        list.update_packages(results(10));
+        assert_eq!(list.displayed(true, true, 4).len(), 4);
+    }
+}
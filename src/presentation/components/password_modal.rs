@@ -1,4 +1,8 @@
 use egui::Key;
+use secrecy::SecretString;
+use zeroize::Zeroize;
+
+const PASSWORD_INPUT_ID: &str = "password_modal_input";
 
 pub struct PasswordModal {
     show: bool,
@@ -7,6 +11,8 @@ pub struct PasswordModal {
     confirmed: bool,
     cancelled: bool,
     show_password: bool,
+    /// Set when the modal opens, so typing goes straight to the field.
+    focus_input: bool,
 }
 
 #[allow(dead_code)]
@@ -19,34 +25,37 @@ impl PasswordModal {
             confirmed: false,
             cancelled: false,
             show_password: false,
+            focus_input: false,
         }
     }
 
     pub fn show(&mut self, operation_name: String) {
         self.show = true;
-        self.password_input.clear();
+        self.password_input.zeroize();
         self.operation_name = operation_name;
         self.confirmed = false;
         self.cancelled = false;
         self.show_password = false;
+        self.focus_input = true;
     }
 
     pub fn is_open(&self) -> bool {
         self.show
     }
 
-    pub fn take_result(&mut self) -> Option<(bool, String)> {
+    pub fn take_result(&mut self) -> Option<(bool, SecretString)> {
         if self.confirmed {
             self.confirmed = false;
-            let password = self.password_input.clone();
-            self.password_input.clear();
+            // Copy into an exactly-sized secret allocation, then wipe the input buffer
+            let password = SecretString::from(self.password_input.as_str());
+            self.password_input.zeroize();
             self.show = false;
             Some((true, password))
         } else if self.cancelled {
             self.cancelled = false;
-            self.password_input.clear();
+            self.password_input.zeroize();
             self.show = false;
-            Some((false, String::new()))
+            Some((false, SecretString::default()))
         } else {
             None
         }
@@ -54,10 +63,17 @@ impl PasswordModal {
 
     pub fn close(&mut self) {
         self.show = false;
-        self.password_input.clear();
+        self.password_input.zeroize();
         self.cancelled = true;
     }
 
+    /// Drops the text edit state (including its undo history, which keeps copies of the input).
+    fn clear_input_state(ctx: &egui::Context) {
+        ctx.data_mut(|d| {
+            d.remove::<egui::text_edit::TextEditState>(egui::Id::new(PASSWORD_INPUT_ID))
+        });
+    }
+
     pub fn render(&mut self, ctx: &egui::Context) {
         if !self.show {
             return;
@@ -74,20 +90,14 @@ impl PasswordModal {
                     ui.label("This operation requires administrator password.");
                     ui.add_space(12.0);
 
-                    ui.label("Password:");
-                    let password_field = if self.show_password {
-                        egui::TextEdit::singleline(&mut self.password_input)
-                            .desired_width(f32::INFINITY)
-                    } else {
-                        egui::TextEdit::singleline(&mut self.password_input)
-                            .password(true)
-                            .desired_width(f32::INFINITY)
-                    };
+                    let label = ui.label("Password:");
+                    let password_field = egui::TextEdit::singleline(&mut self.password_input)
+                        .id(egui::Id::new(PASSWORD_INPUT_ID))
+                        .password(!self.show_password)
+                        .desired_width(f32::INFINITY);
 
-                    let response = ui.add(password_field);
-
-                    // Request focus for the password field
-                    if response.gained_focus() {
+                    let response = ui.add(password_field).labelled_by(label.id);
+                    if std::mem::take(&mut self.focus_input) {
                         response.request_focus();
                     }
 
@@ -119,6 +129,16 @@ impl PasswordModal {
         if !open {
             self.close();
         }
+
+        if self.confirmed || self.cancelled {
+            Self::clear_input_state(ctx);
+        }
+    }
+}
+
+impl Drop for PasswordModal {
+    fn drop(&mut self) {
+        self.password_input.zeroize();
     }
 }
 
@@ -0,0 +1,116 @@
+use crate::infrastructure::system;
+use egui::{Color32, Key, Modifiers};
+use std::path::{Path, PathBuf};
+
+pub enum PlistAction {
+    /// Select the plist in the file manager.
+    Reveal(PathBuf),
+}
+
+/// Shows a service's launchd plist as XML, read in the background after the
+/// modal opens.
+pub struct PlistModal {
+    show: bool,
+    path: PathBuf,
+    content: Option<Result<String, String>>,
+}
+
+impl PlistModal {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            path: PathBuf::new(),
+            content: None,
+        }
+    }
+
+    /// Opens the modal for the plist at `path` while it is read.
+    pub fn show_loading(&mut self, path: PathBuf) {
+        self.path = path;
+        self.content = None;
+        self.show = true;
+    }
+
+    /// Shows `content` if it is the plist the modal is open for.
+    pub fn set_content(&mut self, path: &Path, content: Result<String, String>) {
+        if self.show && self.path == *path {
+            self.content = Some(content);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.content = None;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<PlistAction> {
+        if !self.show {
+            return None;
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            self.close();
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        let title = self.path.file_name().map_or_else(
+            || self.path.display().to_string(),
+            |name| name.to_string_lossy().into_owned(),
+        );
+        egui::Window::new(format!("Plist: {}", title))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(560.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.weak(self.path.display().to_string());
+                ui.separator();
+                match &self.content {
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.label("Reading plist…");
+                        });
+                    }
+                    Some(Err(e)) => {
+                        ui.colored_label(Color32::from_rgb(255, 0, 0), e);
+                    }
+                    Some(Ok(xml)) => {
+                        egui::ScrollArea::both().max_height(420.0).show(ui, |ui| {
+                            ui.add(egui::Label::new(egui::RichText::new(xml).monospace()).extend());
+                        });
+                    }
+                }
+                ui.separator();
+                ui.horizontal(|ui| {
+                    let xml = self.content.as_ref().and_then(|c| c.as_ref().ok());
+                    if ui
+                        .add_enabled(xml.is_some(), egui::Button::new("Copy"))
+                        .clicked()
+                        && let Some(xml) = xml
+                    {
+                        ui.ctx().copy_text(xml.clone());
+                    }
+                    if ui
+                        .button(format!("Reveal in {}", system::FILE_MANAGER))
+                        .clicked()
+                    {
+                        action = Some(PlistAction::Reveal(self.path.clone()));
+                    }
+                });
+            });
+
+        if !open {
+            self.close();
+        }
+        action
+    }
+}
+
+impl Default for PlistModal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,172 @@
+use crate::domain::entities::{ServiceInfo, ServiceStatus};
+use crate::infrastructure::system;
+use crate::presentation::components::accessible;
+use egui::{Color32, Key, Modifiers, RichText};
+use std::path::PathBuf;
+
+pub enum ServiceInfoAction {
+    /// Select the file in the file manager.
+    Reveal(PathBuf),
+    /// Open the plist in the plist viewer.
+    ViewPlist(PathBuf),
+}
+
+/// Shows what `brew services info` reports about one service, loaded in the
+/// background after the modal opens.
+pub struct ServiceInfoModal {
+    show: bool,
+    service_name: String,
+    info: Option<Result<ServiceInfo, String>>,
+}
+
+impl ServiceInfoModal {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            service_name: String::new(),
+            info: None,
+        }
+    }
+
+    /// Opens the modal for `service_name` while its info loads.
+    pub fn show_loading(&mut self, service_name: String) {
+        self.service_name = service_name;
+        self.info = None;
+        self.show = true;
+    }
+
+    /// Shows `info` if it is for the service the modal is open for.
+    pub fn set_info(&mut self, service_name: &str, info: Result<ServiceInfo, String>) {
+        if self.show && self.service_name == service_name {
+            self.info = Some(info);
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.info = None;
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<ServiceInfoAction> {
+        if !self.show {
+            return None;
+        }
+
+        if ctx.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape)) {
+            self.close();
+            return None;
+        }
+
+        let mut action = None;
+        let mut open = true;
+        egui::Window::new(format!("Service: {}", self.service_name))
+            .collapsible(false)
+            .resizable(true)
+            .default_width(480.0)
+            .open(&mut open)
+            .show(ctx, |ui| match &self.info {
+                None => {
+                    ui.horizontal(|ui| {
+                        ui.spinner();
+                        ui.label("Running brew services info…");
+                    });
+                }
+                Some(Err(e)) => {
+                    ui.colored_label(
+                        Color32::from_rgb(255, 0, 0),
+                        format!("Couldn't load the service: {}", e),
+                    );
+                }
+                Some(Ok(info)) => Self::fields(ui, info, &mut action),
+            });
+
+        if !open {
+            self.close();
+        }
+        action
+    }
+
+    fn fields(ui: &mut egui::Ui, info: &ServiceInfo, action: &mut Option<ServiceInfoAction>) {
+        let service = &info.service;
+        let status = match service.status {
+            ServiceStatus::Started => "Running",
+            ServiceStatus::Stopped => "Stopped",
+            ServiceStatus::Error => "Error",
+            ServiceStatus::Unknown => "Unknown",
+        };
+        let yes_no = |value: bool| if value { "Yes" } else { "No" }.to_string();
+
+        egui::Grid::new("service_info_fields")
+            .num_columns(3)
+            .striped(true)
+            .show(ui, |ui| {
+                Self::row(ui, "Status", Some(status.to_string()));
+                Self::row(ui, "Running", Some(yes_no(info.running)));
+                Self::row(ui, "PID", info.pid.map(|pid| pid.to_string()));
+                Self::row(ui, "User", service.user.clone());
+                Self::row(ui, "Exit code", info.exit_code.map(|code| code.to_string()));
+                Self::row(ui, "Starts at login", info.registered.map(yes_no));
+                Self::path_row(ui, "Plist", service.file.as_deref(), action);
+                Self::path_row(ui, "Log", info.log_path.as_deref(), action);
+                Self::path_row(ui, "Error log", info.error_log_path.as_deref(), action);
+            });
+
+        if let Some(file) = &service.file {
+            ui.add_space(4.0);
+            if ui.button("View plist").clicked() {
+                *action = Some(ServiceInfoAction::ViewPlist(PathBuf::from(file)));
+            }
+        }
+    }
+
+    /// A field with a button copying its value; `None` shows as N/A.
+    fn row(ui: &mut egui::Ui, name: &str, value: Option<String>) {
+        ui.label(RichText::new(format!("{}:", name)).strong());
+        match value {
+            Some(value) => {
+                ui.label(&value);
+                if accessible::icon_button(ui, "📋", &format!("Copy {}", name)).clicked() {
+                    ui.ctx().copy_text(value);
+                }
+            }
+            None => {
+                ui.weak("N/A");
+            }
+        }
+        ui.end_row();
+    }
+
+    fn path_row(
+        ui: &mut egui::Ui,
+        name: &str,
+        path: Option<&str>,
+        action: &mut Option<ServiceInfoAction>,
+    ) {
+        ui.label(RichText::new(format!("{}:", name)).strong());
+        match path {
+            Some(path) => {
+                ui.label(path);
+                ui.horizontal(|ui| {
+                    if accessible::icon_button(ui, "📋", &format!("Copy {} path", name)).clicked()
+                    {
+                        ui.ctx().copy_text(path.to_string());
+                    }
+                    let reveal = format!("Reveal {} in {}", name, system::FILE_MANAGER);
+                    if accessible::icon_button(ui, "📂", &reveal).clicked() {
+                        *action = Some(ServiceInfoAction::Reveal(PathBuf::from(path)));
+                    }
+                });
+            }
+            None => {
+                ui.weak("N/A");
+            }
+        }
+        ui.end_row();
+    }
+}
+
+impl Default for ServiceInfoModal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
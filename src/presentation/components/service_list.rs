@@ -1,9 +1,26 @@
 use crate::domain::entities::{Service, ServiceStatus};
-use egui::{Color32, RichText, ScrollArea};
+use crate::presentation::components::column_widths::{ColumnWidths, TableLayout};
+use crate::presentation::components::{accessible, grid_density};
+use crate::presentation::components::selection_state::SelectionState;
+use egui::{Color32, RichText};
+
+const SERVICES_TABLE: TableLayout = TableLayout {
+    id: "services_table",
+    columns: &[
+        ("", 30.0),
+        ("Name", 200.0),
+        ("Status", 100.0),
+        ("User", 100.0),
+        ("File", 320.0),
+        ("Actions", 0.0),
+    ],
+};
 
 pub struct ServiceList {
     services: Vec<Service>,
     selected_service: Option<String>,
+    /// Services ticked for Start Selected and Stop Selected.
+    selection: SelectionState,
 }
 
 #[allow(dead_code)]
@@ -12,54 +29,129 @@ impl ServiceList {
         Self {
             services: Vec::new(),
             selected_service: None,
+            selection: SelectionState::new(),
         }
     }
 
     pub fn update_services(&mut self, services: Vec<Service>) {
+        for name in self.selection.get_selected() {
+            if !services.iter().any(|s| s.name == name) {
+                self.selection.deselect(&name);
+            }
+        }
         self.services = services;
     }
 
+    /// The ticked services, in list order.
+    pub fn selected(&self) -> Vec<String> {
+        self.services
+            .iter()
+            .filter(|s| self.selection.is_selected(&s.name))
+            .map(|s| s.name.clone())
+            .collect()
+    }
+
+    pub fn has_selection(&self) -> bool {
+        self.selection.has_selection()
+    }
+
+    pub fn select_all(&mut self) {
+        self.selection
+            .select_all(self.services.iter().map(|s| s.name.clone()).collect());
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selection.clear();
+    }
+
+    pub fn status_of(&self, name: &str) -> Option<&ServiceStatus> {
+        self.services
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| &s.status)
+    }
+
+    /// Names of the services currently in `status`, in list order.
+    pub fn names_with_status(&self, status: &ServiceStatus) -> Vec<String> {
+        self.services
+            .iter()
+            .filter(|s| &s.status == status)
+            .map(|s| s.name.clone())
+            .collect()
+    }
+
+    /// Sets the status of `name` in place, without reloading the list.
+    pub fn update_service_status(&mut self, name: &str, status: ServiceStatus) {
+        if let Some(existing) = self.services.iter_mut().find(|s| s.name == name) {
+            existing.status = status;
+        }
+    }
+
     pub fn update_service(&mut self, service: Service) {
         if let Some(existing) = self.services.iter_mut().find(|s| s.name == service.name) {
             *existing = service;
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn show(
         &mut self,
         ui: &mut egui::Ui,
         on_start: &mut Option<String>,
         on_stop: &mut Option<String>,
         on_restart: &mut Option<String>,
+        on_show_info: &mut Option<String>,
+        on_view_plist: &mut Option<String>,
+        search_query: &str,
         services_loading: &std::collections::HashSet<String>,
+        column_widths: &mut ColumnWidths,
     ) {
-        ScrollArea::vertical()
+        let search_lower = search_query.to_lowercase();
+        let row_height = grid_density::row_height(ui);
+        let header_height = grid_density::header_height(ui, false);
+
+        column_widths
+            .table(ui, &SERVICES_TABLE)
             .auto_shrink([false, false])
-            .show(ui, |ui| {
-                egui::Grid::new("service_grid")
-                    .striped(true)
-                    .spacing([10.0, 8.0])
-                    .min_col_width(ui.available_width() / 5.0)
-                    .show(ui, |ui| {
-                        ui.heading("Name");
-                        ui.heading("Status");
-                        ui.heading("User");
-                        ui.heading("File");
-                        ui.heading("Actions");
-                        ui.end_row();
-
-                        for service in &self.services {
+            .header(header_height, |row| {
+                column_widths.header(row, &SERVICES_TABLE, false)
+            })
+            .body(|mut body| {
+                let shown = self.services.iter().filter(|service| {
+                    search_query.is_empty() || service.name.to_lowercase().contains(&search_lower)
+                });
+                for service in shown {
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            let mut is_selected = self.selection.is_selected(&service.name);
+                            let label = format!("Select {}", service.name);
+                            if accessible::checkbox(ui, &mut is_selected, &label).changed() {
+                                if is_selected {
+                                    self.selection.select(service.name.clone());
+                                } else {
+                                    self.selection.deselect(&service.name);
+                                }
+                            }
+                        });
+
+                        row.col(|ui| {
                             let is_selected = self
                                 .selected_service
-                                .as_ref()
-                                .map_or(false, |s| s == &service.name);
+                                .as_ref() == Some(&service.name);
 
-                            if ui.selectable_label(is_selected, &service.name).clicked() {
+                            if ui
+                                .selectable_label(is_selected, &service.name)
+                                .on_hover_text("Show details")
+                                .clicked()
+                            {
                                 self.selected_service = Some(service.name.clone());
+                                *on_show_info = Some(service.name.clone());
                             }
+                        });
 
-                            let is_operating = services_loading.contains(&service.name);
+                        let is_operating = services_loading.contains(&service.name);
 
+                        row.col(|ui| {
                             let status_text = match &service.status {
                                 ServiceStatus::Started => {
                                     RichText::new("Running").color(Color32::from_rgb(0, 255, 0))
@@ -80,34 +172,74 @@ impl ServiceList {
                             } else {
                                 ui.label(status_text);
                             }
+                        });
 
+                        row.col(|ui| {
                             ui.label(service.user.as_deref().unwrap_or("N/A"));
+                        });
 
-                            ui.label(service.file.as_deref().unwrap_or("N/A"));
+                        row.col(|ui| {
+                            match &service.file {
+                                Some(file) => {
+                                    if ui.link(file).on_hover_text("View plist").clicked() {
+                                        *on_view_plist = Some(file.clone());
+                                    }
+                                }
+                                None => {
+                                    ui.label("N/A");
+                                }
+                            }
+                        });
 
+                        row.col(|ui| {
                             ui.add_enabled_ui(!is_operating, |ui| {
-                                ui.horizontal(|ui| {
-                                    match &service.status {
-                                        ServiceStatus::Started => {
-                                            if ui.button("Stop").clicked() {
-                                                *on_stop = Some(service.name.clone());
-                                            }
-                                            if ui.button("Restart").clicked() {
-                                                *on_restart = Some(service.name.clone());
-                                            }
+                                match &service.status {
+                                    ServiceStatus::Started => {
+                                        if ui.button("Stop").clicked() {
+                                            *on_stop = Some(service.name.clone());
+                                        }
+                                        if ui.button("Restart").clicked() {
+                                            *on_restart = Some(service.name.clone());
                                         }
-                                        ServiceStatus::Stopped | ServiceStatus::Error | ServiceStatus::Unknown => {
-                                            if ui.button("Start").clicked() {
-                                                *on_start = Some(service.name.clone());
-                                            }
+                                    }
+                                    ServiceStatus::Stopped | ServiceStatus::Error | ServiceStatus::Unknown => {
+                                        if ui.button("Start").clicked() {
+                                            *on_start = Some(service.name.clone());
                                         }
                                     }
-                                });
+                                }
                             });
-
-                            ui.end_row();
-                        }
+                        });
                     });
+                }
             });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn services(names: &[&str]) -> Vec<Service> {
+        names
+            .iter()
+            .map(|name| Service::new(name.to_string(), ServiceStatus::Stopped))
+            .collect()
+    }
+
+    #[test]
+    fn selection_follows_list_order_and_drops_removed_services() {
+        let mut list = ServiceList::new();
+        list.update_services(services(&["postgresql@16", "redis", "nginx"]));
+        list.selection.select("nginx".to_string());
+        list.selection.select("postgresql@16".to_string());
+        assert_eq!(list.selected(), ["postgresql@16", "nginx"]);
+
+        list.update_services(services(&["postgresql@16", "redis"]));
+        assert_eq!(list.selected(), ["postgresql@16"]);
+        assert!(!list.selection.is_selected("nginx"));
+
+        list.select_all();
+        assert_eq!(list.selected(), ["postgresql@16", "redis"]);
+    }
+}
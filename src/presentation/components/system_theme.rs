@@ -0,0 +1,70 @@
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often the OS appearance is re-checked while the theme follows the system.
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// How often a running check is looked at to see whether it has finished.
+const PENDING_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Tracks whether the OS is in dark mode. Checks after the first run on a
+/// background thread, since some desktops answer over D-Bus.
+pub struct SystemTheme {
+    prefers_dark: bool,
+    last_check: Instant,
+    pending: Option<JoinHandle<bool>>,
+}
+
+impl SystemTheme {
+    pub fn new() -> Self {
+        Self {
+            prefers_dark: detect_dark(),
+            last_check: Instant::now(),
+            pending: None,
+        }
+    }
+
+    pub fn prefers_dark(&self) -> bool {
+        self.prefers_dark
+    }
+
+    /// How long until `poll` has something to do, for scheduling a repaint.
+    pub fn next_poll_in(&self) -> Duration {
+        if self.pending.is_some() {
+            PENDING_POLL_INTERVAL
+        } else {
+            POLL_INTERVAL.saturating_sub(self.last_check.elapsed())
+        }
+    }
+
+    /// Starts a check when one is due and returns true once a finished check
+    /// found the OS appearance changed.
+    pub fn poll(&mut self) -> bool {
+        if let Some(pending) = self.pending.take_if(|handle| handle.is_finished()) {
+            let prefers_dark = pending.join().unwrap_or(self.prefers_dark);
+            self.last_check = Instant::now();
+            if prefers_dark != self.prefers_dark {
+                tracing::info!(
+                    "System appearance changed to {}",
+                    if prefers_dark { "dark" } else { "light" }
+                );
+                self.prefers_dark = prefers_dark;
+                return true;
+            }
+        } else if self.pending.is_none() && self.last_check.elapsed() >= POLL_INTERVAL {
+            self.pending = Some(thread::spawn(detect_dark));
+        }
+        false
+    }
+}
+
+impl Default for SystemTheme {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An unknown appearance counts as dark, matching the app's default look.
+fn detect_dark() -> bool {
+    dark_light::detect() != dark_light::Mode::Light
+}
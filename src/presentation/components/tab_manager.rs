@@ -3,12 +3,54 @@ use std::collections::HashMap;
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub enum Tab {
     Installed,
+    Favorites,
     SearchInstall,
     Services,
     Settings,
     Log,
 }
 
+impl Tab {
+    /// In top-bar order.
+    pub const ALL: [Tab; 6] = [
+        Tab::Installed,
+        Tab::Favorites,
+        Tab::SearchInstall,
+        Tab::Services,
+        Tab::Settings,
+        Tab::Log,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Tab::Installed => "Installed & Outdated",
+            Tab::Favorites => "Favorites",
+            Tab::SearchInstall => "Search & Install",
+            Tab::Services => "Services",
+            Tab::Settings => "Settings",
+            Tab::Log => "Log",
+        }
+    }
+
+    /// How the tab is stored in `AppConfig::hidden_tabs`.
+    pub fn key(self) -> &'static str {
+        match self {
+            Tab::Installed => "installed",
+            Tab::Favorites => "favorites",
+            Tab::SearchInstall => "search",
+            Tab::Services => "services",
+            Tab::Settings => "settings",
+            Tab::Log => "log",
+        }
+    }
+
+    /// Installed is where a hidden tab falls back to, and Settings is where
+    /// tabs are shown again, so neither can be hidden.
+    pub fn can_hide(self) -> bool {
+        !matches!(self, Tab::Installed | Tab::Settings)
+    }
+}
+
 pub struct TabState {
     pub loaded: bool,
 }
@@ -29,6 +71,7 @@ impl TabManager {
     pub fn new() -> Self {
         let mut tab_states = HashMap::new();
         tab_states.insert(Tab::Installed, TabState::new());
+        tab_states.insert(Tab::Favorites, TabState::new());
         tab_states.insert(Tab::SearchInstall, TabState::new());
         tab_states.insert(Tab::Services, TabState::new());
         tab_states.insert(Tab::Settings, TabState::new());
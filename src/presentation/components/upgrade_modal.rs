@@ -0,0 +1,141 @@
+use crate::domain::entities::PlannedUpgrade;
+use egui::{Key, Modifiers};
+use std::collections::HashSet;
+
+pub enum UpgradeAction {
+    /// Upgrade the names in `upgrade`; `skipped` holds those the user
+    /// unchecked.
+    Confirm {
+        upgrade: Vec<String>,
+        skipped: Vec<String>,
+    },
+    Cancel,
+}
+
+/// Lists what Update All would upgrade and lets the user leave packages out
+/// before anything runs.
+pub struct UpgradeModal {
+    show: bool,
+    /// Each planned upgrade and whether it is still checked.
+    upgrades: Vec<(PlannedUpgrade, bool)>,
+    /// Names whose updates the user ignores; these start unchecked.
+    ignored: HashSet<String>,
+    focus_confirm: bool,
+}
+
+impl UpgradeModal {
+    pub fn new() -> Self {
+        Self {
+            show: false,
+            upgrades: Vec::new(),
+            ignored: HashSet::new(),
+            focus_confirm: false,
+        }
+    }
+
+    /// Lists `upgrades`, with those in `ignored` unchecked.
+    pub fn show_preview(&mut self, upgrades: Vec<PlannedUpgrade>, ignored: HashSet<String>) {
+        self.upgrades = upgrades
+            .into_iter()
+            .map(|u| {
+                let checked = !ignored.contains(&u.name);
+                (u, checked)
+            })
+            .collect();
+        self.ignored = ignored;
+        self.show = true;
+        self.focus_confirm = true;
+    }
+
+    pub fn close(&mut self) {
+        self.show = false;
+        self.upgrades.clear();
+    }
+
+    pub fn render(&mut self, ctx: &egui::Context) -> Option<UpgradeAction> {
+        if !self.show {
+            return None;
+        }
+
+        let mut action = None;
+
+        egui::Window::new("Update All Packages")
+            .collapsible(false)
+            .resizable(true)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "brew upgrade would upgrade {} packages. Uncheck any to leave alone:",
+                    self.upgrades.len()
+                ));
+                ui.separator();
+
+                egui::ScrollArea::vertical()
+                    .max_height(300.0)
+                    .show(ui, |ui| {
+                        egui::Grid::new("upgrade_preview")
+                            .num_columns(2)
+                            .striped(true)
+                            .show(ui, |ui| {
+                                for (upgrade, checked) in &mut self.upgrades {
+                                    ui.checkbox(checked, &upgrade.name);
+                                    ui.horizontal(|ui| {
+                                        ui.label(format!(
+                                            "{} → {}",
+                                            upgrade.current_version.as_deref().unwrap_or("?"),
+                                            upgrade.new_version
+                                        ));
+                                        if self.ignored.contains(&upgrade.name) {
+                                            ui.weak("(ignored)");
+                                        }
+                                    });
+                                    ui.end_row();
+                                }
+                            });
+                    });
+
+                ui.separator();
+
+                let selected = self.upgrades.iter().filter(|(_, checked)| *checked).count();
+                ui.horizontal(|ui| {
+                    let confirm = ui.add_enabled(
+                        selected > 0,
+                        egui::Button::new(format!("Update {}", selected)),
+                    );
+
+                    // Focus Confirm once when the modal opens
+                    if self.focus_confirm {
+                        confirm.request_focus();
+                        self.focus_confirm = false;
+                    }
+
+                    if selected > 0
+                        && (confirm.clicked() || ui.input(|i| i.key_pressed(Key::Enter)))
+                    {
+                        let (upgrade, skipped): (Vec<_>, Vec<_>) =
+                            self.upgrades.iter().partition(|(_, checked)| *checked);
+                        let names = |upgrades: Vec<&(PlannedUpgrade, bool)>| {
+                            upgrades.into_iter().map(|(u, _)| u.name.clone()).collect()
+                        };
+                        action = Some(UpgradeAction::Confirm {
+                            upgrade: names(upgrade),
+                            skipped: names(skipped),
+                        });
+                    }
+
+                    if ui.button("Cancel").clicked()
+                        || ui.input_mut(|i| i.consume_key(Modifiers::NONE, Key::Escape))
+                    {
+                        action = Some(UpgradeAction::Cancel);
+                    }
+                });
+            });
+
+        action
+    }
+}
+
+impl Default for UpgradeModal {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,100 @@
+use crate::domain::entities::AppConfig;
+use egui::{Pos2, Rect, Vec2, ViewportCommand};
+use std::time::{Duration, Instant};
+
+const SAVE_DELAY: Duration = Duration::from_secs(1);
+/// How much of a restored window has to stay on its monitor, roughly enough
+/// of the title bar to grab and drag it back.
+const MIN_VISIBLE: Vec2 = Vec2::new(100.0, 40.0);
+
+/// Tracks the native window's size and position so they can be written to the
+/// config once a move/resize settles or the window closes, not on every frame.
+pub struct WindowState {
+    last_outer_rect: Option<Rect>,
+    changed_at: Option<Instant>,
+    verify_position: bool,
+}
+
+impl WindowState {
+    /// `restored_position` is true when the window was opened at a saved
+    /// position that still needs checking against the connected monitors.
+    pub fn new(restored_position: bool) -> Self {
+        Self {
+            last_outer_rect: None,
+            changed_at: None,
+            verify_position: restored_position,
+        }
+    }
+
+    /// Returns true when `config` was updated and should be saved.
+    pub fn update(&mut self, ctx: &egui::Context, config: &mut AppConfig) -> bool {
+        let (outer_rect, inner_rect, monitor_size, close_requested) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.outer_rect,
+                viewport.inner_rect,
+                viewport.monitor_size,
+                viewport.close_requested(),
+            )
+        });
+
+        let Some(outer_rect) = outer_rect else {
+            return false;
+        };
+
+        if self.verify_position {
+            self.verify_position = false;
+            // A saved position can land partly or entirely outside the
+            // current monitor, e.g. after unplugging one or lowering the
+            // resolution. egui only knows the monitor's size, so like
+            // `center_on_screen` this takes the monitor to start at the origin
+            let visible = monitor_size.map(|size| {
+                outer_rect
+                    .intersect(Rect::from_min_size(Pos2::ZERO, size))
+                    .size()
+            });
+            let off_screen = visible
+                .is_none_or(|visible| visible.x < MIN_VISIBLE.x || visible.y < MIN_VISIBLE.y);
+            if off_screen && let Some(center) = ViewportCommand::center_on_screen(ctx) {
+                ctx.send_viewport_cmd(center);
+                config.window_position = None;
+                return true;
+            }
+        }
+
+        if self.last_outer_rect != Some(outer_rect) {
+            if self.last_outer_rect.is_some() {
+                self.changed_at = Some(Instant::now());
+            }
+            self.last_outer_rect = Some(outer_rect);
+        }
+
+        let settled = self
+            .changed_at
+            .is_some_and(|changed_at| changed_at.elapsed() >= SAVE_DELAY);
+        if let Some(changed_at) = self.changed_at
+            && !settled
+        {
+            // The app stops repainting when idle, so wake it up to save
+            ctx.request_repaint_after(SAVE_DELAY.saturating_sub(changed_at.elapsed()));
+        }
+        if !settled && !close_requested {
+            return false;
+        }
+        self.changed_at = None;
+
+        let position = [outer_rect.min.x, outer_rect.min.y];
+        let size = inner_rect.map(|r| [r.width(), r.height()]);
+        if config.window_position == Some(position)
+            && (size.is_none() || config.window_size == size)
+        {
+            return false;
+        }
+
+        config.window_position = Some(position);
+        if size.is_some() {
+            config.window_size = size;
+        }
+        true
+    }
+}
@@ -1,812 +1,688 @@
-use crate::domain::entities::{Package, PackageType, Service};
-use std::collections::HashSet;
-use std::sync::{Arc, Mutex};
+use crate::application::dto::CacheInfoDto;
+use crate::domain::entities::{
+    AuditResult, CleanupPreview, DependencyNode, OperationProgress, Package, PackageListDiff,
+    PlannedUpgrade, ProgressCell, Release, Service, ServiceInfo, UpgradeReport,
+};
+use crate::infrastructure::brew::paths::BrewPaths;
+use crate::presentation::services::issue_report::SystemDiagnostics;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::time::SystemTime;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TaskKind {
     LoadInstalled,
     LoadOutdated,
+    LoadInstallDates,
+    LoadCacheInfo,
+    LoadOrphanCount,
+    LoadAutoremovePreview,
+    ResolveBrewPaths,
+    LoadMetadataAge,
+    LoadUpgradePreview,
     Search,
+    FetchPackages,
+    AuditPackage,
+    LoadDependencyTree,
+    LoadServiceInfo,
+    LoadPlist,
+    ComparePackageList,
+    AutoExport,
+    CheckForAppUpdate,
+    CollectDiagnostics,
 }
 
+/// Operations that only report whether they succeeded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OpKind {
+    /// Homebrew itself, on a machine without it.
+    InstallHomebrew,
+    Install,
+    Uninstall,
+    Update,
+    UpdateAll,
+    CleanCache,
+    CleanupOldVersions,
+    Autoremove,
+    Pin,
+    Unpin,
+}
+
+/// Background work the UI is waiting on. Each task reports back exactly once
+/// through the `TaskSender` it was started with.
+#[derive(Debug, Clone, PartialEq)]
 pub enum AsyncTask {
-    LoadInstalled {
-        packages: Arc<Mutex<Vec<Package>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-    },
-    LoadOutdated {
-        packages: Arc<Mutex<Vec<Package>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-    },
-    Search {
-        results: Arc<Mutex<Vec<Package>>>,
-        logs: Arc<Mutex<Vec<String>>>,
+    LoadInstalled,
+    LoadOutdated,
+    LoadInstallDates,
+    LoadCacheInfo,
+    LoadOrphanCount,
+    LoadAutoremovePreview,
+    ResolveBrewPaths,
+    LoadMetadataAge,
+    LoadUpgradePreview,
+    Search,
+    /// `package_name` is set for operations on one package that the app
+    /// doesn't otherwise keep track of.
+    SimpleOp {
+        kind: OpKind,
+        package_name: Option<String>,
+        /// Set for operations that report their progress while they run.
+        progress: Option<ProgressCell>,
     },
-    LoadPackageInfo {
-        package_name: String,
-        package_type: PackageType,
-        result: Arc<Mutex<Option<Package>>>,
-        started_at: std::time::Instant,
+    LoadServices,
+    StartService {
+        service_name: String,
     },
-    Install {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    StopService {
+        service_name: String,
     },
-    Uninstall {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// `brew services stop --all`.
+    StopAllServices,
+    /// `brew services cleanup`.
+    CleanupServices,
+    RestartService {
+        service_name: String,
     },
-    Update {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// Reloads one service a little after it was started, to catch those
+    /// that die right away.
+    CheckService {
+        service_name: String,
     },
-    UpdateAll {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// `brew services info` for the service detail modal.
+    LoadServiceInfo {
+        service_name: String,
     },
-    CleanCache {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// Reads a service's launchd plist for the plist viewer.
+    LoadPlist {
+        path: PathBuf,
     },
-    CleanupOldVersions {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    ExportPackages,
+    ImportPackages,
+    /// Downloads packages to install or upgrade later.
+    FetchPackages {
+        progress: ProgressCell,
     },
-    Pin {
+    AuditPackage {
         package_name: String,
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
     },
-    Unpin {
+    LoadDependencyTree {
         package_name: String,
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
     },
-    LoadServices {
-        services: Arc<Mutex<Vec<Service>>>,
-        logs: Arc<Mutex<Vec<String>>>,
+    /// Diffs an exported package list against what is installed now.
+    ComparePackageList,
+    /// Writes the scheduled export to the folder chosen in Settings.
+    AutoExport,
+    CheckForAppUpdate,
+    CollectDiagnostics,
+}
+
+/// What a finished task produced.
+pub enum TaskOutput {
+    /// Installed packages.
+    Packages(Vec<Package>),
+    /// Outdated packages; `complete` is false when brew couldn't list the
+    /// formulae or the casks.
+    OutdatedPackages {
+        packages: Vec<Package>,
+        complete: bool,
     },
-    StartService {
-        service_name: String,
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// What was found, and the first error if a search failed.
+    SearchResults {
+        packages: Vec<Package>,
+        error: Option<String>,
     },
-    StopService {
-        service_name: String,
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    InstallDates(HashMap<String, SystemTime>),
+    /// `None` when the cache couldn't be measured.
+    CacheInfo(Option<CacheInfoDto>),
+    /// How many formulae `brew autoremove` would remove; `None` when its dry
+    /// run failed.
+    OrphanCount(Option<usize>),
+    /// What `brew autoremove` would remove, for the cleanup window.
+    AutoremovePreview(Result<CleanupPreview, String>),
+    /// `None` when brew couldn't report them.
+    BrewPaths(Option<BrewPaths>),
+    /// When brew's metadata was last updated, if known.
+    MetadataAge(Option<SystemTime>),
+    /// `None` when the dry run failed.
+    UpgradePreview(Option<Vec<PlannedUpgrade>>),
+    Services(Result<Vec<Service>, String>),
+    /// The error message if brew couldn't describe the service.
+    Service(Result<Service, String>),
+    /// The error message if brew couldn't describe the service.
+    ServiceInfo(Result<ServiceInfo, String>),
+    /// The plist as XML, or why it couldn't be read.
+    Plist(Result<String, String>),
+    Diagnostics(SystemDiagnostics),
+    /// The packages that were downloaded and those that failed to.
+    Fetched {
+        fetched: Vec<String>,
+        failed: Vec<String>,
     },
-    RestartService {
-        service_name: String,
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
-    },
-    ExportPackages {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    /// The error message if the audit couldn't run.
+    Audit(Result<AuditResult, String>),
+    /// The error message if brew couldn't list the dependencies.
+    DependencyTree(Result<DependencyNode, String>),
+    /// The error message if the list couldn't be read or brew couldn't
+    /// export the current one.
+    PackageListDiff(Result<PackageListDiff, String>),
+    /// A newer Brewsty release, if there is one.
+    AppUpdate(Option<Release>),
+    /// How an Update All that got to run ended; it succeeded if nothing failed.
+    Upgraded {
+        report: UpgradeReport,
+        message: String,
     },
-    ImportPackages {
-        success: Arc<Mutex<Option<bool>>>,
-        logs: Arc<Mutex<Vec<String>>>,
-        message: Arc<Mutex<String>>,
+    Outcome {
+        success: bool,
+        message: String,
     },
 }
 
+pub struct TaskMessage {
+    task: AsyncTask,
+    output: TaskOutput,
+    logs: Vec<String>,
+}
+
+/// Reports a background task's result to the UI thread and wakes it up, so
+/// the app doesn't have to repaint continuously to notice.
+pub struct TaskSender {
+    task: AsyncTask,
+    tx: Sender<TaskMessage>,
+    ctx: egui::Context,
+}
+
+impl TaskSender {
+    pub fn send(self, output: TaskOutput, logs: Vec<String>) {
+        let message = TaskMessage {
+            task: self.task,
+            output,
+            logs,
+        };
+        // The receiver is only gone once the app is shutting down
+        if self.tx.send(message).is_ok() {
+            self.ctx.request_repaint();
+        }
+    }
+
+    pub fn finish(self, success: bool, message: String, logs: Vec<String>) {
+        self.send(TaskOutput::Outcome { success, message }, logs);
+    }
+}
+
+/// How a `SimpleOp` ended.
+#[derive(Debug)]
+pub struct OpResult {
+    pub kind: OpKind,
+    pub package_name: Option<String>,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Default)]
 pub struct TaskResult {
     pub installed_packages: Option<Vec<Package>>,
-    pub outdated_packages: Option<Vec<Package>>,
+    /// The outdated packages, and whether brew listed all of them.
+    pub outdated_packages: Option<(Vec<Package>, bool)>,
+    pub install_dates: Option<HashMap<String, SystemTime>>,
+    pub cache_info: Option<Option<CacheInfoDto>>,
+    pub orphan_count: Option<Option<usize>>,
+    pub autoremove_preview: Option<Result<CleanupPreview, String>>,
+    pub brew_paths: Option<Option<BrewPaths>>,
+    pub metadata_updated_at: Option<Option<SystemTime>>,
+    pub upgrade_preview: Option<Option<Vec<PlannedUpgrade>>>,
     pub search_results: Option<Vec<Package>>,
-    pub package_info: Option<(String, Package)>,
+    pub search_error: Option<String>,
     pub logs: Vec<String>,
-    pub completed_package_info_loads: Vec<String>,
-    pub install_completed: Option<(bool, String)>,
-    pub uninstall_completed: Option<(bool, String)>,
-    pub update_completed: Option<(bool, String)>,
-    pub update_all_completed: Option<(bool, String)>,
-    pub clean_cache_completed: Option<(bool, String)>,
-    pub cleanup_old_versions_completed: Option<(bool, String)>,
-    pub pin_completed: Option<(String, bool, String)>,
-    pub unpin_completed: Option<(String, bool, String)>,
-    pub services: Option<Vec<Service>>,
+    /// At most one per `OpKind`.
+    pub ops_completed: Vec<OpResult>,
+    pub services: Option<Result<Vec<Service>, String>>,
     pub start_service_completed: Option<(String, bool, String)>,
     pub stop_service_completed: Option<(String, bool, String)>,
     pub restart_service_completed: Option<(String, bool, String)>,
+    pub stop_all_services_completed: Option<(bool, String)>,
+    pub services_cleanup_completed: Option<(bool, String)>,
+    /// The service that was checked, and how it is doing.
+    pub service_checked: Option<(String, Result<Service, String>)>,
+    /// The service whose details were loaded, and what brew said.
+    pub service_info: Option<(String, Result<ServiceInfo, String>)>,
+    /// The plist that was read, and its XML.
+    pub plist: Option<(PathBuf, Result<String, String>)>,
     pub export_packages_completed: Option<(bool, String)>,
     pub import_packages_completed: Option<(bool, String)>,
+    pub auto_export_completed: Option<(bool, String)>,
+    /// The packages that were downloaded and those that failed to.
+    pub fetch_completed: Option<(Vec<String>, Vec<String>)>,
+    /// The audited package and what the audit found.
+    pub audit_completed: Option<(String, Result<AuditResult, String>)>,
+    /// The package whose dependencies were listed, and its tree.
+    pub dependency_tree: Option<(String, Result<DependencyNode, String>)>,
+    pub package_list_diff: Option<Result<PackageListDiff, String>>,
+    pub app_update: Option<Option<Release>>,
+    pub diagnostics: Option<SystemDiagnostics>,
+    /// Arrives with the `UpdateAll` op it belongs to.
+    pub upgrade_report: Option<UpgradeReport>,
+}
+
+impl TaskResult {
+    /// False when this poll already holds a result from another task of the
+    /// same kind; the message then waits for the next poll.
+    fn has_room_for(&self, task: &AsyncTask) -> bool {
+        match task {
+            AsyncTask::LoadInstalled => self.installed_packages.is_none(),
+            AsyncTask::LoadOutdated => self.outdated_packages.is_none(),
+            AsyncTask::LoadInstallDates => self.install_dates.is_none(),
+            AsyncTask::LoadCacheInfo => self.cache_info.is_none(),
+            AsyncTask::LoadOrphanCount => self.orphan_count.is_none(),
+            AsyncTask::LoadAutoremovePreview => self.autoremove_preview.is_none(),
+            AsyncTask::ResolveBrewPaths => self.brew_paths.is_none(),
+            AsyncTask::LoadMetadataAge => self.metadata_updated_at.is_none(),
+            AsyncTask::LoadUpgradePreview => self.upgrade_preview.is_none(),
+            AsyncTask::Search => self.search_results.is_none(),
+            AsyncTask::SimpleOp { kind, .. } => {
+                !self.ops_completed.iter().any(|op| op.kind == *kind)
+            }
+            AsyncTask::LoadServices => self.services.is_none(),
+            AsyncTask::StartService { .. } => self.start_service_completed.is_none(),
+            AsyncTask::StopService { .. } => self.stop_service_completed.is_none(),
+            AsyncTask::RestartService { .. } => self.restart_service_completed.is_none(),
+            AsyncTask::StopAllServices => self.stop_all_services_completed.is_none(),
+            AsyncTask::CleanupServices => self.services_cleanup_completed.is_none(),
+            AsyncTask::CheckService { .. } => self.service_checked.is_none(),
+            AsyncTask::LoadServiceInfo { .. } => self.service_info.is_none(),
+            AsyncTask::LoadPlist { .. } => self.plist.is_none(),
+            AsyncTask::ExportPackages => self.export_packages_completed.is_none(),
+            AsyncTask::ImportPackages => self.import_packages_completed.is_none(),
+            AsyncTask::AutoExport => self.auto_export_completed.is_none(),
+            AsyncTask::FetchPackages { .. } => self.fetch_completed.is_none(),
+            AsyncTask::AuditPackage { .. } => self.audit_completed.is_none(),
+            AsyncTask::LoadDependencyTree { .. } => self.dependency_tree.is_none(),
+            AsyncTask::ComparePackageList => self.package_list_diff.is_none(),
+            AsyncTask::CheckForAppUpdate => self.app_update.is_none(),
+            AsyncTask::CollectDiagnostics => self.diagnostics.is_none(),
+        }
+    }
+
+    fn store(&mut self, message: TaskMessage) {
+        let TaskMessage {
+            task,
+            output,
+            mut logs,
+        } = message;
+        self.logs.append(&mut logs);
+
+        match (task, output) {
+            (AsyncTask::LoadInstalled, TaskOutput::Packages(packages)) => {
+                self.installed_packages = Some(packages);
+            }
+            (AsyncTask::LoadOutdated, TaskOutput::OutdatedPackages { packages, complete }) => {
+                self.outdated_packages = Some((packages, complete));
+            }
+            (AsyncTask::LoadInstallDates, TaskOutput::InstallDates(dates)) => {
+                self.install_dates = Some(dates);
+            }
+            (AsyncTask::LoadCacheInfo, TaskOutput::CacheInfo(info)) => {
+                self.cache_info = Some(info);
+            }
+            (AsyncTask::LoadOrphanCount, TaskOutput::OrphanCount(count)) => {
+                self.orphan_count = Some(count);
+            }
+            (AsyncTask::LoadAutoremovePreview, TaskOutput::AutoremovePreview(preview)) => {
+                self.autoremove_preview = Some(preview);
+            }
+            (AsyncTask::ResolveBrewPaths, TaskOutput::BrewPaths(paths)) => {
+                self.brew_paths = Some(paths);
+            }
+            (AsyncTask::LoadMetadataAge, TaskOutput::MetadataAge(at)) => {
+                self.metadata_updated_at = Some(at);
+            }
+            (AsyncTask::LoadUpgradePreview, TaskOutput::UpgradePreview(upgrades)) => {
+                self.upgrade_preview = Some(upgrades);
+            }
+            (AsyncTask::Search, TaskOutput::SearchResults { packages, error }) => {
+                tracing::info!("Search completed, found {} packages", packages.len());
+                self.search_results = Some(packages);
+                self.search_error = error;
+            }
+            (AsyncTask::LoadServices, TaskOutput::Services(services)) => {
+                self.services = Some(services);
+            }
+            (AsyncTask::CollectDiagnostics, TaskOutput::Diagnostics(diagnostics)) => {
+                self.diagnostics = Some(diagnostics);
+            }
+            (AsyncTask::CheckService { service_name }, TaskOutput::Service(result)) => {
+                self.service_checked = Some((service_name, result));
+            }
+            (AsyncTask::LoadServiceInfo { service_name }, TaskOutput::ServiceInfo(result)) => {
+                self.service_info = Some((service_name, result));
+            }
+            (AsyncTask::LoadPlist { path }, TaskOutput::Plist(result)) => {
+                self.plist = Some((path, result));
+            }
+            (AsyncTask::FetchPackages { .. }, TaskOutput::Fetched { fetched, failed }) => {
+                self.fetch_completed = Some((fetched, failed));
+            }
+            (AsyncTask::AuditPackage { package_name }, TaskOutput::Audit(result)) => {
+                self.audit_completed = Some((package_name, result));
+            }
+            (
+                AsyncTask::LoadDependencyTree { package_name },
+                TaskOutput::DependencyTree(result),
+            ) => {
+                self.dependency_tree = Some((package_name, result));
+            }
+            (AsyncTask::ComparePackageList, TaskOutput::PackageListDiff(diff)) => {
+                self.package_list_diff = Some(diff);
+            }
+            (AsyncTask::CheckForAppUpdate, TaskOutput::AppUpdate(release)) => {
+                self.app_update = Some(release);
+            }
+            (
+                AsyncTask::SimpleOp {
+                    kind: kind @ OpKind::UpdateAll,
+                    package_name,
+                    ..
+                },
+                TaskOutput::Upgraded { report, message },
+            ) => {
+                self.ops_completed.push(OpResult {
+                    kind,
+                    package_name,
+                    success: report.failed.is_empty(),
+                    message,
+                });
+                self.upgrade_report = Some(report);
+            }
+            (task, TaskOutput::Outcome { success, message }) => match task {
+                AsyncTask::SimpleOp {
+                    kind, package_name, ..
+                } => self.ops_completed.push(OpResult {
+                    kind,
+                    package_name,
+                    success,
+                    message,
+                }),
+                AsyncTask::StartService { service_name } => {
+                    self.start_service_completed = Some((service_name, success, message));
+                }
+                AsyncTask::StopService { service_name } => {
+                    self.stop_service_completed = Some((service_name, success, message));
+                }
+                AsyncTask::RestartService { service_name } => {
+                    self.restart_service_completed = Some((service_name, success, message));
+                }
+                AsyncTask::StopAllServices => {
+                    self.stop_all_services_completed = Some((success, message));
+                }
+                AsyncTask::CleanupServices => {
+                    self.services_cleanup_completed = Some((success, message));
+                }
+                AsyncTask::ExportPackages => {
+                    self.export_packages_completed = Some((success, message));
+                }
+                AsyncTask::ImportPackages => {
+                    self.import_packages_completed = Some((success, message));
+                }
+                AsyncTask::AutoExport => {
+                    self.auto_export_completed = Some((success, message));
+                }
+                task => tracing::warn!("{:?} task finished without its result", task),
+            },
+            (task, _) => tracing::warn!("{:?} task sent a result of the wrong kind", task),
+        }
+    }
 }
 
 pub struct AsyncTaskManager {
+    ctx: egui::Context,
+    tx: Sender<TaskMessage>,
+    rx: Receiver<TaskMessage>,
+    /// Messages that arrived while the last poll already held a result of
+    /// the same kind.
+    deferred: Vec<TaskMessage>,
     active_tasks: Vec<AsyncTask>,
-    package_info_tasks: Vec<(String, AsyncTask)>,
-    packages_loading_info: HashSet<String>,
-    pending_package_info_loads: Vec<(String, PackageType)>,
 }
 
 impl AsyncTaskManager {
-    pub fn new() -> Self {
+    /// `ctx` is woken whenever a task sends its result.
+    pub fn new(ctx: egui::Context) -> Self {
+        let (tx, rx) = channel();
         Self {
+            ctx,
+            tx,
+            rx,
+            deferred: Vec::new(),
             active_tasks: Vec::new(),
-            package_info_tasks: Vec::new(),
-            packages_loading_info: HashSet::new(),
-            pending_package_info_loads: Vec::new(),
         }
     }
 
-    pub fn set_active_task(&mut self, task: AsyncTask) {
-        if let Some(kind) = task.kind() {
-            if self.has_task_kind(kind) {
-                tracing::warn!("{:?} task is already running, ignoring duplicate", kind);
-                return;
-            }
+    fn sender(&self, task: AsyncTask) -> TaskSender {
+        TaskSender {
+            task,
+            tx: self.tx.clone(),
+            ctx: self.ctx.clone(),
         }
-
-        self.active_tasks.push(task);
     }
 
-    pub fn has_task_kind(&self, kind: TaskKind) -> bool {
-        self.active_tasks.iter().any(|task| task.kind() == Some(kind))
-    }
+    /// Registers `task` and returns the sender it reports back through, or
+    /// `None` when a task of the same kind is already running.
+    pub fn start(&mut self, task: AsyncTask) -> Option<TaskSender> {
+        if let Some(kind) = task.kind()
+            && self.has_task_kind(kind)
+        {
+            tracing::warn!("{:?} task is already running, ignoring duplicate", kind);
+            return None;
+        }
 
-    pub fn add_package_info_task(&mut self, package_name: String, task: AsyncTask) {
-        self.packages_loading_info.insert(package_name.clone());
-        self.package_info_tasks.push((package_name, task));
+        self.active_tasks.push(task.clone());
+        Some(self.sender(task))
     }
 
-    pub fn is_loading_package_info(&self, package_name: &str) -> bool {
-        self.packages_loading_info.contains(package_name)
+    /// What the running `kind` operation last reported about its progress.
+    pub fn op_progress(&self, kind: OpKind) -> Option<OperationProgress> {
+        self.active_tasks.iter().find_map(|task| match task {
+            AsyncTask::SimpleOp {
+                kind: task_kind,
+                progress: Some(progress),
+                ..
+            } if *task_kind == kind => progress.get(),
+            _ => None,
+        })
     }
 
-    pub fn queue_package_info_load(&mut self, package_name: String, package_type: PackageType) {
-        if self.packages_loading_info.contains(&package_name) {
-            tracing::debug!("Already loading info for {}, skipping", package_name);
-            return;
-        }
-
-        if self
-            .pending_package_info_loads
-            .iter()
-            .any(|(name, _)| name == &package_name)
-        {
-            tracing::debug!("Already queued for loading: {}", package_name);
-            return;
-        }
-
-        self.pending_package_info_loads
-            .push((package_name, package_type));
+    /// What the running fetch last reported about its progress.
+    pub fn fetch_progress(&self) -> Option<OperationProgress> {
+        self.active_tasks.iter().find_map(|task| match task {
+            AsyncTask::FetchPackages { progress } => progress.get(),
+            _ => None,
+        })
     }
 
-    pub fn can_load_more_package_info(&self) -> bool {
-        self.packages_loading_info.len() < 15
+    /// The package being audited, if any.
+    pub fn auditing(&self) -> Option<&str> {
+        self.active_tasks.iter().find_map(|task| match task {
+            AsyncTask::AuditPackage { package_name } => Some(package_name.as_str()),
+            _ => None,
+        })
     }
 
-    pub fn drain_pending_loads(&mut self, count: usize) -> Vec<(String, PackageType)> {
-        self.pending_package_info_loads
-            .drain(..count.min(self.pending_package_info_loads.len()))
-            .collect()
+    pub fn has_task_kind(&self, kind: TaskKind) -> bool {
+        self.active_tasks
+            .iter()
+            .any(|task| task.kind() == Some(kind))
     }
 
-    pub fn pending_loads_count(&self) -> usize {
-        self.pending_package_info_loads.len()
+    /// True while any task is running or a result is still waiting to be handled.
+    pub fn has_work_in_flight(&self) -> bool {
+        !self.active_tasks.is_empty() || !self.deferred.is_empty()
     }
 
     pub fn poll(&mut self) -> TaskResult {
-        let mut result = TaskResult {
-            installed_packages: None,
-            outdated_packages: None,
-            search_results: None,
-            package_info: None,
-            logs: Vec::new(),
-            completed_package_info_loads: Vec::new(),
-            install_completed: None,
-            uninstall_completed: None,
-            update_completed: None,
-            update_all_completed: None,
-            clean_cache_completed: None,
-            cleanup_old_versions_completed: None,
-            pin_completed: None,
-            unpin_completed: None,
-            services: None,
-            start_service_completed: None,
-            stop_service_completed: None,
-            restart_service_completed: None,
-            export_packages_completed: None,
-            import_packages_completed: None,
-        };
-
-        let mut tasks_to_keep = Vec::new();
-
-        for (pkg_name, task) in self.package_info_tasks.drain(..) {
-            match task {
-                AsyncTask::LoadPackageInfo {
-                    package_name,
-                    package_type,
-                    result: pkg_result,
-                    started_at,
-                } => {
-                    let elapsed = started_at.elapsed();
-
-                    if elapsed > std::time::Duration::from_secs(10) {
-                        tracing::warn!(
-                            "Package info loading timed out for {} after {:?}",
-                            package_name,
-                            elapsed
-                        );
-                        let failed_package = Package::new(package_name.clone(), package_type)
-                            .set_version_load_failed(true);
-                        result.package_info = Some((package_name.clone(), failed_package));
-                        self.packages_loading_info.remove(&package_name);
-                        result.completed_package_info_loads.push(package_name);
-                        continue;
-                    }
-
-                    let package_name_clone = package_name.clone();
-                    let should_keep = match pkg_result.try_lock() {
-                        Ok(pkg_opt) => {
-                            if let Some(package) = pkg_opt.clone() {
-                                tracing::info!(
-                                    "Updating search results with package info for {}",
-                                    package_name_clone
-                                );
-                                result.package_info = Some((package_name_clone.clone(), package));
-                                self.packages_loading_info.remove(&package_name_clone);
-                                result.completed_package_info_loads.push(package_name_clone);
-                                false
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_keep {
-                        tasks_to_keep.push((
-                            pkg_name,
-                            AsyncTask::LoadPackageInfo {
-                                package_name,
-                                package_type,
-                                result: pkg_result,
-                                started_at,
-                            },
-                        ));
-                    }
-                }
-                _ => {}
+        let mut result = TaskResult::default();
+
+        let messages: Vec<TaskMessage> =
+            self.deferred.drain(..).chain(self.rx.try_iter()).collect();
+        for message in messages {
+            if !result.has_room_for(&message.task) {
+                self.deferred.push(message);
+                continue;
             }
-        }
 
-        self.package_info_tasks = tasks_to_keep;
-
-        let mut active_tasks_to_keep = Vec::new();
-
-        for task in self.active_tasks.drain(..) {
-            match task {
-                AsyncTask::LoadInstalled { packages, logs } => {
-                    let should_put_back = match logs.try_lock() {
-                        Ok(log) => {
-                            if !log.is_empty() {
-                                if let Ok(pkgs) = packages.try_lock() {
-                                    result.installed_packages = Some(pkgs.clone());
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::LoadInstalled { packages, logs });
-                    }
-                }
-                AsyncTask::LoadOutdated { packages, logs } => {
-                    let should_put_back = match logs.try_lock() {
-                        Ok(log) => {
-                            if !log.is_empty() {
-                                if let Ok(pkgs) = packages.try_lock() {
-                                    result.outdated_packages = Some(pkgs.clone());
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::LoadOutdated { packages, logs });
-                    }
-                }
-                AsyncTask::Search { results, logs } => {
-                    let should_put_back = match results.try_lock() {
-                        Ok(res) => {
-                            if let Ok(log) = logs.try_lock() {
-                                if !log.is_empty() {
-                                    tracing::info!(
-                                        "Search completed, found {} packages",
-                                        res.len()
-                                    );
-                                    result.search_results = Some(res.clone());
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Search { results, logs });
-                    }
-                }
-                AsyncTask::Install {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.install_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Install {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::Uninstall {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.uninstall_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Uninstall {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::Update {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.update_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Update {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::UpdateAll {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.update_all_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::UpdateAll {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::CleanCache {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.clean_cache_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::CleanCache {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::CleanupOldVersions {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.cleanup_old_versions_completed =
-                                        Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::CleanupOldVersions {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::Pin {
-                    package_name,
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.pin_completed =
-                                        Some((package_name.clone(), succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Pin {
-                            package_name,
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::Unpin {
-                    package_name,
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.unpin_completed =
-                                        Some((package_name.clone(), succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::Unpin {
-                            package_name,
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::LoadServices { services, logs } => {
-                    let should_put_back = match logs.try_lock() {
-                        Ok(log) => {
-                            if !log.is_empty() {
-                                if let Ok(svc) = services.try_lock() {
-                                    result.services = Some(svc.clone());
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::LoadServices { services, logs });
-                    }
-                }
-                AsyncTask::StartService {
-                    service_name,
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.start_service_completed =
-                                        Some((service_name.clone(), succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::StartService {
-                            service_name,
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::StopService {
-                    service_name,
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.stop_service_completed =
-                                        Some((service_name.clone(), succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::StopService {
-                            service_name,
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::RestartService {
-                    service_name,
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.restart_service_completed =
-                                        Some((service_name.clone(), succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::RestartService {
-                            service_name,
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::ExportPackages {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.export_packages_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::ExportPackages {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::ImportPackages {
-                    success,
-                    logs,
-                    message,
-                } => {
-                    let should_put_back = match success.try_lock() {
-                        Ok(success_opt) => {
-                            if let Some(succeeded) = *success_opt {
-                                if let (Ok(log), Ok(msg)) = (logs.try_lock(), message.try_lock()) {
-                                    result.import_packages_completed = Some((succeeded, msg.clone()));
-                                    result.logs.extend(log.clone());
-                                    false
-                                } else {
-                                    true
-                                }
-                            } else {
-                                true
-                            }
-                        }
-                        Err(_) => true,
-                    };
-
-                    if should_put_back {
-                        active_tasks_to_keep.push(AsyncTask::ImportPackages {
-                            success,
-                            logs,
-                            message,
-                        });
-                    }
-                }
-                AsyncTask::LoadPackageInfo { .. } => {}
+            if !self.finish_task(&message.task) {
+                tracing::debug!("Dropping result of untracked {:?}", message.task);
+                continue;
             }
-        }
 
-        self.active_tasks = active_tasks_to_keep;
+            result.store(message);
+        }
 
         result
     }
+
+    /// Stops tracking `task`; false when it wasn't being tracked.
+    fn finish_task(&mut self, task: &AsyncTask) -> bool {
+        match self.active_tasks.iter().position(|t| t == task) {
+            Some(index) => {
+                self.active_tasks.remove(index);
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 impl AsyncTask {
+    pub fn op(kind: OpKind) -> Self {
+        AsyncTask::SimpleOp {
+            kind,
+            package_name: None,
+            progress: None,
+        }
+    }
+
+    /// An operation that reports its progress through `progress`.
+    pub fn tracked_op(kind: OpKind, progress: ProgressCell) -> Self {
+        AsyncTask::SimpleOp {
+            kind,
+            package_name: None,
+            progress: Some(progress),
+        }
+    }
+
+    pub fn package_op(kind: OpKind, package_name: String) -> Self {
+        AsyncTask::SimpleOp {
+            kind,
+            package_name: Some(package_name),
+            progress: None,
+        }
+    }
+
     pub fn kind(&self) -> Option<TaskKind> {
         match self {
-            AsyncTask::LoadInstalled { .. } => Some(TaskKind::LoadInstalled),
-            AsyncTask::LoadOutdated { .. } => Some(TaskKind::LoadOutdated),
-            AsyncTask::Search { .. } => Some(TaskKind::Search),
+            AsyncTask::LoadInstalled => Some(TaskKind::LoadInstalled),
+            AsyncTask::LoadOutdated => Some(TaskKind::LoadOutdated),
+            AsyncTask::LoadInstallDates => Some(TaskKind::LoadInstallDates),
+            AsyncTask::LoadCacheInfo => Some(TaskKind::LoadCacheInfo),
+            AsyncTask::LoadOrphanCount => Some(TaskKind::LoadOrphanCount),
+            AsyncTask::LoadAutoremovePreview => Some(TaskKind::LoadAutoremovePreview),
+            AsyncTask::ResolveBrewPaths => Some(TaskKind::ResolveBrewPaths),
+            AsyncTask::LoadMetadataAge => Some(TaskKind::LoadMetadataAge),
+            AsyncTask::LoadUpgradePreview => Some(TaskKind::LoadUpgradePreview),
+            AsyncTask::Search => Some(TaskKind::Search),
+            AsyncTask::FetchPackages { .. } => Some(TaskKind::FetchPackages),
+            AsyncTask::AuditPackage { .. } => Some(TaskKind::AuditPackage),
+            AsyncTask::LoadDependencyTree { .. } => Some(TaskKind::LoadDependencyTree),
+            AsyncTask::LoadServiceInfo { .. } => Some(TaskKind::LoadServiceInfo),
+            AsyncTask::LoadPlist { .. } => Some(TaskKind::LoadPlist),
+            AsyncTask::ComparePackageList => Some(TaskKind::ComparePackageList),
+            AsyncTask::AutoExport => Some(TaskKind::AutoExport),
+            AsyncTask::CheckForAppUpdate => Some(TaskKind::CheckForAppUpdate),
+            AsyncTask::CollectDiagnostics => Some(TaskKind::CollectDiagnostics),
             _ => None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manager() -> AsyncTaskManager {
+        AsyncTaskManager::new(egui::Context::default())
+    }
+
+    #[test]
+    fn results_arrive_on_the_next_poll() {
+        let mut manager = manager();
+        let sender = manager.start(AsyncTask::LoadServices).unwrap();
+        assert!(manager.has_work_in_flight());
+        assert!(manager.poll().services.is_none());
+
+        sender.send(
+            TaskOutput::Services(Ok(Vec::new())),
+            vec!["Loaded 0 services".to_string()],
+        );
+        let result = manager.poll();
+
+        assert!(result.services.is_some());
+        assert_eq!(result.logs, ["Loaded 0 services"]);
+        assert!(!manager.has_work_in_flight());
+    }
+
+    #[test]
+    fn results_of_the_same_kind_wait_for_the_next_poll() {
+        let mut manager = manager();
+        for name in ["jq", "wget"] {
+            let sender = manager
+                .start(AsyncTask::package_op(OpKind::Pin, name.to_string()))
+                .unwrap();
+            sender.finish(true, format!("{} pinned", name), Vec::new());
+        }
+        let unpin = manager
+            .start(AsyncTask::package_op(OpKind::Unpin, "git".to_string()))
+            .unwrap();
+        unpin.finish(true, "git unpinned".to_string(), Vec::new());
+
+        let first = manager.poll().ops_completed;
+        let names: Vec<_> = first.iter().map(|op| op.package_name.as_deref()).collect();
+        assert_eq!(names, [Some("jq"), Some("git")]);
+        assert!(manager.has_work_in_flight());
+
+        let second = manager.poll().ops_completed;
+        assert_eq!(second.len(), 1);
+        assert_eq!(second[0].kind, OpKind::Pin);
+        assert_eq!(second[0].package_name.as_deref(), Some("wget"));
+        assert!(!manager.has_work_in_flight());
+    }
+
+    #[test]
+    fn duplicate_loads_are_not_started() {
+        let mut manager = manager();
+        let _search = manager.start(AsyncTask::Search).unwrap();
+
+        assert!(manager.start(AsyncTask::Search).is_none());
+    }
+
+    #[test]
+    fn progress_is_reported_while_the_operation_runs() {
+        let mut manager = manager();
+        let progress = ProgressCell::default();
+        let sender = manager
+            .start(AsyncTask::tracked_op(OpKind::Install, progress.clone()))
+            .unwrap();
+        assert_eq!(manager.op_progress(OpKind::Install), None);
+
+        progress.set(OperationProgress::Installing);
+        assert_eq!(
+            manager.op_progress(OpKind::Install),
+            Some(OperationProgress::Installing)
+        );
+        assert_eq!(manager.op_progress(OpKind::Update), None);
+
+        sender.finish(true, "jq installed".to_string(), Vec::new());
+        manager.poll();
+        assert_eq!(manager.op_progress(OpKind::Install), None);
+    }
+}
@@ -0,0 +1,130 @@
+//! Sorts failed operations by what the user can do about them, from the
+//! error text brew and sudo leave behind.
+
+/// Shown instead of curl's error dump when the network is the problem.
+pub const NETWORK_UNAVAILABLE: &str = "Network unavailable — check your connection";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FailureKind {
+    /// Downloads or API calls couldn't reach the server; worth a retry.
+    Network,
+    /// Sudo wanted a password, or the one given was wrong.
+    Password,
+    Other,
+}
+
+/// Matched case-insensitively, anywhere in the message.
+const NETWORK_SIGNATURES: &[&str] = &[
+    "could not resolve host",
+    "could not resolve proxy",
+    "temporary failure in name resolution",
+    "nodename nor servname provided",
+    "failed to connect to",
+    "network is unreachable",
+    "connection timed out",
+    "operation timed out",
+    "connection reset by peer",
+    "ssl_error_syscall",
+];
+
+/// curl exit codes for DNS, connection, timeout and transfer failures:
+/// 5 and 6 couldn't resolve, 7 couldn't connect, 28 timed out, 35 failed
+/// the TLS handshake, 52 got nothing and 56 failed receiving.
+const NETWORK_CURL_EXIT_CODES: &[u32] = &[5, 6, 7, 28, 35, 52, 56];
+
+/// Matched as is; sudo's wording is stable.
+const PASSWORD_SIGNATURES: &[&str] = &[
+    "authentication failure",
+    "sudo",
+    "password",
+    "Permission denied",
+    "Incorrect password",
+    "incorrect password attempt",
+    "sorry, try again",
+];
+
+/// Network failures are checked first: their dumps often mention sudo or
+/// permissions in passing, and asking for a password won't help them.
+pub fn classify(message: &str) -> FailureKind {
+    let lower = message.to_lowercase();
+    if NETWORK_SIGNATURES.iter().any(|s| lower.contains(s)) || curl_network_exit(&lower) {
+        FailureKind::Network
+    } else if PASSWORD_SIGNATURES.iter().any(|s| message.contains(s)) {
+        FailureKind::Password
+    } else {
+        FailureKind::Other
+    }
+}
+
+/// Finds `curl: (6) ...` as printed by curl, or `... curl ... exited with 6`
+/// as reported by brew.
+fn curl_network_exit(message: &str) -> bool {
+    let code_after = |marker: &str| {
+        message.match_indices(marker).any(|(i, _)| {
+            let digits: String = message[i + marker.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            digits
+                .parse()
+                .is_ok_and(|code| NETWORK_CURL_EXIT_CODES.contains(&code))
+        })
+    };
+
+    code_after("curl: (") || (message.contains("curl") && code_after("exited with "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_failures() {
+        let cases = [
+            (
+                "curl: (6) Could not resolve host: ghcr.io",
+                FailureKind::Network,
+            ),
+            (
+                "Error: Failure while executing; `/usr/bin/curl --disable --fail https://ghcr.io/v2/` exited with 7.",
+                FailureKind::Network,
+            ),
+            (
+                "curl: (28) Operation timed out after 15001 milliseconds",
+                FailureKind::Network,
+            ),
+            (
+                "curl: (56) Recv failure: Connection reset by peer",
+                FailureKind::Network,
+            ),
+            (
+                "fatal: unable to access 'https://github.com/Homebrew/brew/': Failed to connect to github.com port 443",
+                FailureKind::Network,
+            ),
+            (
+                "sudo: unable to resolve host; Could not resolve host: formulae.brew.sh",
+                FailureKind::Network,
+            ),
+            (
+                "curl: (22) The requested URL returned error: 404",
+                FailureKind::Other,
+            ),
+            ("Error: `/usr/bin/curl` exited with 22.", FailureKind::Other),
+            ("sudo: a password is required", FailureKind::Password),
+            ("Incorrect password", FailureKind::Password),
+            (
+                "mkdir: /usr/local/bin: Permission denied",
+                FailureKind::Password,
+            ),
+            (
+                "Error: No available formula with the name \"nope\"",
+                FailureKind::Other,
+            ),
+            ("", FailureKind::Other),
+        ];
+
+        for (message, expected) in cases {
+            assert_eq!(classify(message), expected, "{:?}", message);
+        }
+    }
+}
@@ -1,12 +1,39 @@
+use crate::domain::entities::LogVerbosity;
+use crate::presentation::components::log_manager::{LogLevel, TargetLevel};
+use crate::presentation::services::log_file;
+use std::collections::VecDeque;
 use std::sync::mpsc::{Receiver, Sender, channel};
-use tracing_subscriber::Layer;
-use tracing_subscriber::filter::LevelFilter;
+use std::sync::{Mutex, RwLock};
+use tracing_subscriber::filter::{LevelFilter, filter_fn};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{Layer, Registry, reload};
 
-static LOG_SENDER: std::sync::OnceLock<Sender<String>> = std::sync::OnceLock::new();
+static LOG_SENDER: std::sync::OnceLock<Sender<CapturedLog>> = std::sync::OnceLock::new();
+
+static VERBOSITY_HANDLE: std::sync::OnceLock<reload::Handle<LevelFilter, Registry>> =
+    std::sync::OnceLock::new();
+
+/// Targets the capture layer forwards; see `set_capture_targets`.
+static CAPTURE_TARGETS: RwLock<Vec<TargetLevel>> = RwLock::new(Vec::new());
+
+/// How many formatted lines `recent_logs` keeps for crash reports.
+const RECENT_LOG_LINES: usize = 500;
+
+/// Copy of the newest captured events, readable from the panic hook even
+/// though the log panel's receiver lives on the UI thread.
+static RECENT_LOGS: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// A tracing event forwarded to the log panel.
+pub struct CapturedLog {
+    pub level: LogLevel,
+    pub target: String,
+    pub message: String,
+}
+
+pub fn init_log_capture() -> Receiver<CapturedLog> {
+    set_capture_targets(&TargetLevel::defaults());
 
-pub fn init_log_capture() -> Receiver<String> {
     let (tx, rx) = channel();
     LOG_SENDER
         .set(tx)
@@ -16,6 +43,26 @@ pub fn init_log_capture() -> Receiver<String> {
         sender: LOG_SENDER.get().unwrap().clone(),
     };
 
+    let (filter, handle) = reload::Layer::new(default_verbosity());
+    let _ = VERBOSITY_HANDLE.set(handle);
+
+    // Inactive until `log_file::set_enabled` opens the file
+    let file_layer = tracing_subscriber::fmt::layer()
+        .with_ansi(false)
+        .with_target(true)
+        .with_writer(log_file::LogFileWriter)
+        .with_filter(filter_fn(log_file::is_written));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(capture_layer)
+        .with(file_layer)
+        .init();
+
+    rx
+}
+
+fn default_verbosity() -> LevelFilter {
     #[cfg(feature = "verbose-logging")]
     let filter = LevelFilter::TRACE;
 
@@ -26,16 +73,71 @@ pub fn init_log_capture() -> Receiver<String> {
         LevelFilter::INFO
     };
 
-    tracing_subscriber::registry()
-        .with(filter)
-        .with(capture_layer)
-        .init();
+    filter
+}
 
-    rx
+/// Name of the level used when no verbosity is configured, for display.
+pub fn default_verbosity_label() -> String {
+    default_verbosity().to_string()
+}
+
+/// Changes the subscriber's level filter at runtime. `None` restores the build default.
+pub fn set_verbosity(verbosity: Option<LogVerbosity>) {
+    let filter = match verbosity {
+        None => default_verbosity(),
+        Some(LogVerbosity::Error) => LevelFilter::ERROR,
+        Some(LogVerbosity::Warn) => LevelFilter::WARN,
+        Some(LogVerbosity::Info) => LevelFilter::INFO,
+        Some(LogVerbosity::Debug) => LevelFilter::DEBUG,
+        Some(LogVerbosity::Trace) => LevelFilter::TRACE,
+    };
+
+    if let Some(handle) = VERBOSITY_HANDLE.get() {
+        if let Err(e) = handle.reload(filter) {
+            tracing::warn!("Failed to change log verbosity: {}", e);
+        } else {
+            tracing::info!("Log verbosity set to {}", filter);
+        }
+    }
+}
+
+/// The most recently captured events, oldest first, with time, level and target.
+pub fn recent_logs() -> Vec<String> {
+    // try_lock: the panic hook may run while this thread holds the buffer
+    match RECENT_LOGS.try_lock() {
+        Ok(lines) => lines.iter().cloned().collect(),
+        Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner().iter().cloned().collect(),
+        Err(std::sync::TryLockError::WouldBlock) => Vec::new(),
+    }
+}
+
+fn remember(level: &tracing::Level, target: &str, message: &str) {
+    let line = format!(
+        "{} {:>5} {}: {}",
+        chrono::Local::now().format("%Y-%m-%d %H:%M:%S%.3f"),
+        level,
+        target,
+        message
+    );
+    let mut lines = RECENT_LOGS.lock().unwrap_or_else(|e| e.into_inner());
+    if lines.len() >= RECENT_LOG_LINES {
+        lines.pop_front();
+    }
+    lines.push_back(line);
+}
+
+/// Only events from targets listed here (and not switched off) are captured.
+pub fn set_capture_targets(targets: &[TargetLevel]) {
+    *CAPTURE_TARGETS.write().unwrap_or_else(|e| e.into_inner()) = targets.to_vec();
+}
+
+fn is_captured(target: &str) -> bool {
+    let targets = CAPTURE_TARGETS.read().unwrap_or_else(|e| e.into_inner());
+    TargetLevel::find(&targets, target).is_some_and(|t| t.level.is_some())
 }
 
 struct CaptureLayer {
-    sender: Sender<String>,
+    sender: Sender<CapturedLog>,
 }
 
 impl<S> Layer<S> for CaptureLayer
@@ -50,15 +152,10 @@ where
         let metadata = event.metadata();
         let target = metadata.target();
 
-        if !target.starts_with("brewsty::infrastructure::brew")
-            && !target.starts_with("brewsty::application")
-            && !target.starts_with("brewsty::presentation")
-        {
+        if !is_captured(target) {
             return;
         }
 
-        let level = *metadata.level();
-
         let mut visitor = LogVisitor {
             message: String::new(),
         };
@@ -66,8 +163,12 @@ where
         event.record(&mut visitor);
 
         if !visitor.message.is_empty() {
-            let log_entry = format!("[{}] {}", level, visitor.message);
-            let _ = self.sender.send(log_entry);
+            remember(metadata.level(), target, &visitor.message);
+            let _ = self.sender.send(CapturedLog {
+                level: LogLevel::from(*metadata.level()),
+                target: target.to_string(),
+                message: visitor.message,
+            });
         }
     }
 }
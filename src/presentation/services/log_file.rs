@@ -0,0 +1,157 @@
+//! Optional copy of the tracing output in `brewsty.log`, rotated by size.
+//! Unlike the log panel it keeps timestamps and targets, and it survives
+//! the app closing.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::fmt::writer::OptionalWriter;
+
+const LOG_FILE_NAME: &str = "brewsty.log";
+
+/// The log file is rotated once it would grow past this size.
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the current one, as `brewsty.log.1` (newest) onwards.
+const KEEP_ROTATED_FILES: usize = 4;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Writer feeding the background thread that owns the file. Dropping the
+/// guard flushes whatever is still queued.
+static WRITER: Mutex<Option<(NonBlocking, WorkerGuard)>> = Mutex::new(None);
+
+/// Starts or stops writing to `dir/brewsty.log`.
+pub fn set_enabled(dir: &Path, enabled: bool) {
+    let mut writer = WRITER.lock().unwrap_or_else(|e| e.into_inner());
+    if !enabled {
+        ENABLED.store(false, Ordering::Relaxed);
+        *writer = None;
+        return;
+    }
+    if writer.is_some() {
+        return;
+    }
+
+    match RotatingFile::open(dir.to_path_buf()) {
+        Ok(file) => {
+            *writer = Some(tracing_appender::non_blocking(file));
+            ENABLED.store(true, Ordering::Relaxed);
+            drop(writer);
+            tracing::info!("Writing logs to {}", dir.join(LOG_FILE_NAME).display());
+        }
+        Err(e) => {
+            drop(writer);
+            tracing::warn!("Failed to open log file in {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Flushes and closes the log file; called on exit.
+pub fn shutdown() {
+    ENABLED.store(false, Ordering::Relaxed);
+    WRITER.lock().unwrap_or_else(|e| e.into_inner()).take();
+}
+
+/// Checked per event so nothing is formatted while file logging is off.
+/// Dependencies are noisy, so only their warnings and errors are kept.
+pub(super) fn is_written(metadata: &tracing::Metadata<'_>) -> bool {
+    ENABLED.load(Ordering::Relaxed)
+        && (metadata.target().starts_with("brewsty") || *metadata.level() <= tracing::Level::WARN)
+}
+
+/// `MakeWriter` for the file layer; a sink while file logging is off.
+pub(super) struct LogFileWriter;
+
+impl<'a> MakeWriter<'a> for LogFileWriter {
+    type Writer = OptionalWriter<NonBlocking>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match WRITER.lock().unwrap_or_else(|e| e.into_inner()).as_ref() {
+            Some((writer, _)) => OptionalWriter::some(writer.clone()),
+            None => OptionalWriter::none(),
+        }
+    }
+}
+
+struct RotatingFile {
+    dir: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl RotatingFile {
+    fn open(dir: PathBuf) -> io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(LOG_FILE_NAME))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir, file, size })
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        self.dir.join(format!("{}.{}", LOG_FILE_NAME, index))
+    }
+
+    /// Shifts `brewsty.log.N` to `.N+1`, dropping the oldest, and starts a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let _ = fs::remove_file(self.rotated_path(KEEP_ROTATED_FILES));
+        for index in (1..KEEP_ROTATED_FILES).rev() {
+            let _ = fs::rename(self.rotated_path(index), self.rotated_path(index + 1));
+        }
+        fs::rename(self.dir.join(LOG_FILE_NAME), self.rotated_path(1))?;
+
+        *self = Self::open(self.dir.clone())?;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > MAX_FILE_BYTES {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_a_bounded_number_of_files() {
+        let dir = std::env::temp_dir().join(format!("brewsty-log-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut file = RotatingFile::open(dir.clone()).unwrap();
+
+        let line = vec![b'x'; (MAX_FILE_BYTES / 2) as usize + 1];
+        for _ in 0..(KEEP_ROTATED_FILES + 3) {
+            file.write_all(&line).unwrap();
+        }
+
+        let mut names: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let mut expected = vec![LOG_FILE_NAME.to_string()];
+        expected.extend((1..=KEEP_ROTATED_FILES).map(|i| format!("{}.{}", LOG_FILE_NAME, i)));
+        assert_eq!(names, expected);
+    }
+}
@@ -1,6 +1,13 @@
 pub mod async_executor;
 mod async_task_manager;
+pub mod failure;
+pub mod issue_report;
 pub mod log_capture;
+pub mod log_file;
+mod package_info_pool;
 
 pub use async_executor::AsyncExecutor;
-pub use async_task_manager::{AsyncTask, AsyncTaskManager};
+pub use async_task_manager::{
+    AsyncTask, AsyncTaskManager, OpKind, OpResult, TaskKind, TaskOutput, TaskSender,
+};
+pub use package_info_pool::{InfoOrigin, PackageInfoPool};
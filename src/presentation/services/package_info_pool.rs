@@ -0,0 +1,346 @@
+use crate::application::use_cases::GetPackageInfo;
+use crate::domain::entities::{Package, PackageType};
+use crate::presentation::services::AsyncExecutor;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// How many package infos load at once; the rest wait in the queue.
+const WORKERS: usize = 15;
+
+/// Package info loads that take longer than this are reported as failed.
+const PACKAGE_INFO_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Why a package's info is being loaded, so loads nobody waits for anymore
+/// can be cancelled together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InfoOrigin {
+    /// Auto-loaded for the listed search results.
+    SearchResults,
+    /// Asked for by the user, who is waiting for it; never cancelled.
+    User,
+}
+
+struct Load {
+    package_name: String,
+    package_type: PackageType,
+    origin: InfoOrigin,
+}
+
+struct Running {
+    package_name: String,
+    origin: InfoOrigin,
+    /// Tells the worker to drop the load.
+    cancel: Arc<Notify>,
+}
+
+#[derive(Default)]
+struct Queue {
+    pending: VecDeque<Load>,
+    /// Loads workers are on, by an id of their own.
+    running: HashMap<u64, Running>,
+    next_id: u64,
+    /// Packages queued or loading, until the UI has taken their result.
+    requested: HashSet<String>,
+}
+
+impl Queue {
+    /// Queues `load`; false when its package is already queued or loading.
+    /// The user asking for one that is keeps it from being cancelled.
+    fn request(&mut self, load: Load) -> bool {
+        if self.requested.insert(load.package_name.clone()) {
+            self.pending.push_back(load);
+            return true;
+        }
+        if load.origin == InfoOrigin::User {
+            let pending = self
+                .pending
+                .iter_mut()
+                .map(|l| (&l.package_name, &mut l.origin));
+            let running = self
+                .running
+                .values_mut()
+                .map(|r| (&r.package_name, &mut r.origin));
+            for (name, origin) in pending.chain(running) {
+                if *name == load.package_name {
+                    *origin = InfoOrigin::User;
+                }
+            }
+        }
+        false
+    }
+
+    /// Takes the next load off the queue, with its id and cancel signal.
+    fn start_next(&mut self) -> Option<(u64, Load, Arc<Notify>)> {
+        let load = self.pending.pop_front()?;
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancel = Arc::new(Notify::new());
+        self.running.insert(
+            id,
+            Running {
+                package_name: load.package_name.clone(),
+                origin: load.origin,
+                cancel: Arc::clone(&cancel),
+            },
+        );
+        Some((id, load, cancel))
+    }
+
+    /// Stops tracking load `id`; false when it was cancelled.
+    fn finish(&mut self, id: u64) -> bool {
+        self.running.remove(&id).is_some()
+    }
+
+    /// Drops the queued and running loads from `origin`, so their packages
+    /// can be asked for again. Returns how many there were.
+    fn cancel(&mut self, origin: InfoOrigin) -> usize {
+        let mut cancelled = Vec::new();
+        self.pending.retain(|load| {
+            let keep = load.origin != origin;
+            if !keep {
+                cancelled.push(load.package_name.clone());
+            }
+            keep
+        });
+        self.running.retain(|_, running| {
+            let keep = running.origin != origin;
+            if !keep {
+                running.cancel.notify_one();
+                cancelled.push(running.package_name.clone());
+            }
+            keep
+        });
+        for name in &cancelled {
+            self.requested.remove(name);
+        }
+        cancelled.len()
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    queue: Mutex<Queue>,
+    /// Wakes an idle worker when a load is queued.
+    queued: Notify,
+}
+
+impl Shared {
+    fn queue(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Loads package info on a fixed set of workers pulling from one queue, so
+/// no more than `WORKERS` brew processes run however many loads are asked for.
+pub struct PackageInfoPool {
+    shared: Arc<Shared>,
+    rx: Receiver<(String, Package)>,
+}
+
+impl PackageInfoPool {
+    /// Starts the workers on `executor`; `ctx` is woken for each result.
+    pub fn new(
+        executor: &AsyncExecutor,
+        use_case: Arc<GetPackageInfo>,
+        ctx: egui::Context,
+    ) -> Self {
+        let (tx, rx) = channel();
+        let shared = Arc::new(Shared::default());
+        for _ in 0..WORKERS {
+            executor.spawn(worker(
+                Arc::clone(&shared),
+                Arc::clone(&use_case),
+                tx.clone(),
+                ctx.clone(),
+            ));
+        }
+        Self { shared, rx }
+    }
+
+    /// Queues a load of `package_name`'s info unless one is already queued
+    /// or running.
+    pub fn load(&self, package_name: String, package_type: PackageType, origin: InfoOrigin) {
+        let load = Load {
+            package_name: package_name.clone(),
+            package_type,
+            origin,
+        };
+        if !self.shared.queue().request(load) {
+            tracing::debug!("Already loading info for {}, skipping", package_name);
+            return;
+        }
+        self.shared.queued.notify_one();
+    }
+
+    /// Drops the loads from `origin` that haven't reported back. Those
+    /// already running have their results discarded; brew finishes them.
+    pub fn cancel(&self, origin: InfoOrigin) {
+        let cancelled = self.shared.queue().cancel(origin);
+        if cancelled > 0 {
+            tracing::info!("Cancelled {} package info loads ({:?})", cancelled, origin);
+        }
+    }
+
+    /// The loads that finished since the last call, failed ones included.
+    pub fn poll(&self) -> Vec<(String, Package)> {
+        let results: Vec<(String, Package)> = self.rx.try_iter().collect();
+        if !results.is_empty() {
+            let mut queue = self.shared.queue();
+            for (name, _) in &results {
+                queue.requested.remove(name);
+            }
+        }
+        results
+    }
+}
+
+async fn worker(
+    shared: Arc<Shared>,
+    use_case: Arc<GetPackageInfo>,
+    tx: Sender<(String, Package)>,
+    ctx: egui::Context,
+) {
+    loop {
+        let next = shared.queue().start_next();
+        let Some((id, load, cancel)) = next else {
+            shared.queued.notified().await;
+            continue;
+        };
+        let Load {
+            package_name,
+            package_type,
+            ..
+        } = load;
+
+        tracing::debug!("Loading package info for {}", package_name);
+        let loading = use_case.execute(&package_name, package_type.clone());
+        let outcome = tokio::select! {
+            outcome = tokio::time::timeout(PACKAGE_INFO_TIMEOUT, loading) => Some(outcome),
+            () = cancel.notified() => None,
+        };
+        // It may have been cancelled just as it finished
+        let Some(outcome) = outcome.filter(|_| shared.queue().finish(id)) else {
+            tracing::debug!("Dropped cancelled package info load for {}", package_name);
+            continue;
+        };
+
+        let package = match outcome {
+            Ok(Ok(package)) => {
+                tracing::info!(
+                    "Successfully loaded package info for {}: version={:?}",
+                    package_name,
+                    package.version
+                );
+                package
+            }
+            Ok(Err(e)) => {
+                tracing::error!("Error loading package info for {}: {}", package_name, e);
+                Package::new(package_name.clone(), package_type).set_version_load_failed(true)
+            }
+            Err(_) => {
+                tracing::warn!(
+                    "Package info loading timed out for {} after {:?}",
+                    package_name,
+                    PACKAGE_INFO_TIMEOUT
+                );
+                Package::new(package_name.clone(), package_type).set_version_load_failed(true)
+            }
+        };
+
+        // The receiver is only gone once the app is shutting down
+        if tx.send((package_name, package)).is_err() {
+            return;
+        }
+        ctx.request_repaint();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::mock_repositories::MockPackageRepository;
+    use std::time::Instant;
+    use tokio::runtime::Handle;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn loads_each_package_once_and_reports_failures() {
+        let repository = Arc::new(MockPackageRepository::new(vec![Package::new(
+            "jq".to_string(),
+            PackageType::Formula,
+        )]));
+        let use_case = Arc::new(GetPackageInfo::new(repository.clone()));
+        let pool = PackageInfoPool::new(
+            &AsyncExecutor::new(Handle::current()),
+            use_case,
+            egui::Context::default(),
+        );
+
+        pool.load("jq".to_string(), PackageType::Formula, InfoOrigin::User);
+        pool.load("jq".to_string(), PackageType::Formula, InfoOrigin::User);
+        pool.load(
+            "missing".to_string(),
+            PackageType::Formula,
+            InfoOrigin::User,
+        );
+
+        let started = Instant::now();
+        let mut results = Vec::new();
+        while results.len() < 2 && started.elapsed() < Duration::from_secs(5) {
+            results.extend(pool.poll());
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        results.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let failed: Vec<(&str, bool)> = results
+            .iter()
+            .map(|(name, package)| (name.as_str(), package.version_load_failed))
+            .collect();
+        assert_eq!(failed, [("jq", false), ("missing", true)]);
+        let mut calls = repository.calls();
+        calls.sort();
+        assert_eq!(calls, ["info jq", "info missing"]);
+    }
+
+    fn load(name: &str, origin: InfoOrigin) -> Load {
+        Load {
+            package_name: name.to_string(),
+            package_type: PackageType::Formula,
+            origin,
+        }
+    }
+
+    #[test]
+    fn cancelling_drops_queued_and_running_loads_of_that_origin_only() {
+        let mut queue = Queue::default();
+        for name in ["jq", "wget", "node"] {
+            assert!(queue.request(load(name, InfoOrigin::SearchResults)));
+        }
+        assert!(queue.request(load("git", InfoOrigin::User)));
+        let (jq, _, _) = queue.start_next().unwrap();
+
+        assert_eq!(queue.cancel(InfoOrigin::SearchResults), 3);
+        assert!(!queue.finish(jq));
+        let mut left: Vec<&str> = queue.requested.iter().map(String::as_str).collect();
+        left.sort();
+        assert_eq!(left, ["git"]);
+
+        // Cancelled packages can be asked for again
+        assert!(queue.request(load("jq", InfoOrigin::SearchResults)));
+    }
+
+    #[test]
+    fn asking_for_an_auto_load_keeps_it_from_being_cancelled() {
+        let mut queue = Queue::default();
+        assert!(queue.request(load("jq", InfoOrigin::SearchResults)));
+        assert!(queue.request(load("wget", InfoOrigin::SearchResults)));
+        let (jq, _, _) = queue.start_next().unwrap();
+
+        assert!(!queue.request(load("jq", InfoOrigin::User)));
+        assert!(!queue.request(load("wget", InfoOrigin::User)));
+        assert_eq!(queue.cancel(InfoOrigin::SearchResults), 0);
+        assert!(queue.finish(jq));
+    }
+}
@@ -1,39 +1,48 @@
-use crate::domain::entities::ThemeMode;
+use crate::domain::entities::{AppConfig, ThemeMode, UI_SCALE_RANGE};
 use egui::{Color32, Context, FontFamily, FontId, Rounding, Stroke, TextStyle, Visuals};
 
 /// Configures egui style with custom fonts, spacing, and theme-aware colors.
-pub fn configure_style(ctx: &Context, theme: ThemeMode) {
+/// `system_prefers_dark` picks the look for `ThemeMode::System`. Sizes are
+/// multiplied by the configured UI scale.
+pub fn configure_style(ctx: &Context, config: &AppConfig, system_prefers_dark: bool) {
     let mut style = (*ctx.style()).clone();
+    let scale = config
+        .ui_scale
+        .clamp(*UI_SCALE_RANGE.start(), *UI_SCALE_RANGE.end());
 
     style.text_styles = [
         (
             TextStyle::Small,
-            FontId::new(14.0, FontFamily::Proportional),
+            FontId::new(14.0 * scale, FontFamily::Proportional),
+        ),
+        (
+            TextStyle::Body,
+            FontId::new(16.0 * scale, FontFamily::Proportional),
         ),
-        (TextStyle::Body, FontId::new(16.0, FontFamily::Proportional)),
         (
             TextStyle::Button,
-            FontId::new(16.0, FontFamily::Proportional),
+            FontId::new(16.0 * scale, FontFamily::Proportional),
         ),
         (
             TextStyle::Heading,
-            FontId::new(24.0, FontFamily::Proportional),
+            FontId::new(24.0 * scale, FontFamily::Proportional),
         ),
         (
             TextStyle::Monospace,
-            FontId::new(15.0, FontFamily::Monospace),
+            FontId::new(15.0 * scale, FontFamily::Monospace),
         ),
     ]
     .into();
 
-    style.spacing.item_spacing = egui::vec2(10.0, 10.0);
-    style.spacing.window_margin = egui::Margin::same(12.0);
-    style.spacing.button_padding = egui::vec2(12.0, 8.0);
-    style.spacing.indent = 24.0;
-    style.spacing.interact_size = egui::vec2(60.0, 30.0);
+    style.spacing.item_spacing = egui::vec2(10.0, 10.0) * scale;
+    style.spacing.window_margin = egui::Margin::same(12.0 * scale);
+    style.spacing.button_padding = egui::vec2(12.0, 8.0) * scale;
+    style.spacing.indent = 24.0 * scale;
+    style.spacing.interact_size = egui::vec2(60.0, 30.0) * scale;
 
-    let mut visuals = match theme {
-        ThemeMode::System => Visuals::dark(), // Default to dark for "System"
+    let mut visuals = match config.theme {
+        ThemeMode::System if system_prefers_dark => Visuals::dark(),
+        ThemeMode::System => Visuals::light(),
         ThemeMode::Light => Visuals::light(),
         ThemeMode::Dark => Visuals::dark(),
     };
@@ -55,17 +64,24 @@ pub fn configure_style(ctx: &Context, theme: ThemeMode) {
         visuals.widgets.inactive.bg_stroke = Stroke::new(1.0, Color32::from_gray(60));
         visuals.widgets.hovered.weak_bg_fill = Color32::from_gray(60);
         visuals.widgets.hovered.bg_stroke = Stroke::new(1.0, Color32::from_gray(100));
-        visuals.selection.bg_fill = Color32::from_rgb(0, 122, 255);
-        visuals.hyperlink_color = Color32::from_rgb(58, 150, 255);
     } else {
         visuals.widgets.noninteractive.bg_fill = Color32::from_gray(248);
         visuals.window_fill = Color32::WHITE;
         visuals.panel_fill = Color32::from_gray(242);
         visuals.widgets.inactive.weak_bg_fill = Color32::from_gray(230);
         visuals.widgets.inactive.bg_stroke = Stroke::new(1.0, Color32::from_gray(200));
-        visuals.selection.bg_fill = Color32::from_rgb(0, 122, 255);
     }
 
+    let [r, g, b] = config.accent_color;
+    let accent = Color32::from_rgb(r, g, b);
+    visuals.selection.bg_fill = accent;
+    // Links sit on the panel background, so lift the accent a little in dark mode
+    visuals.hyperlink_color = if visuals.dark_mode {
+        accent.lerp_to_gamma(Color32::WHITE, 0.2)
+    } else {
+        accent
+    };
+
     ctx.set_style(style);
     ctx.set_visuals(visuals);
 }
@@ -1,18 +1,70 @@
 use crate::application::UseCaseContainer;
-use crate::domain::entities::{AppConfig, Package, PackageType};
+use crate::application::dto::CacheInfoDto;
+use crate::application::use_cases::{ExportPackages, InstallPackage, rank_search_results};
+use crate::domain::entities::{
+    AppConfig, AuditResult, CleanupPreview, CpuArch, OperationProgress, Package, PackageMetadata,
+    PackageType, ProgressCell, Release, Service, ServiceStatus, ThemeMode, UpgradeReport,
+};
+use crate::infrastructure::auto_exports;
+use crate::infrastructure::brew::command::BrewCommand;
+use crate::infrastructure::brew::command_log;
+use crate::infrastructure::brew::installer;
+use crate::infrastructure::brew::paths::BrewPaths;
 use crate::infrastructure::config_repository::ConfigRepository;
+use crate::infrastructure::package_metadata_repository::PackageMetadataRepository;
+use crate::infrastructure::plist;
+use crate::infrastructure::snapshots::{self, Snapshot};
+use crate::infrastructure::system;
+use crate::presentation::components::cleanup_modal::format_size;
+use crate::presentation::components::merged_package_list::{time_ago, updates_left_to_app};
 use crate::presentation::components::{
-    CleanupAction, CleanupModal, CleanupType, FilterState, InfoModal, LogManager,
-    MergedPackageList, PackageList, PasswordModal, ServiceList, Tab, TabManager,
+    CleanupAction, CleanupModal, CleanupType, ColumnWidths, CompareModal, DependencyAction,
+    DependencyModal, FilterState, FollowEdge, FollowScroll, InfoAction, InfoModal, LogManager,
+    MergedPackageList, PackageList, PasswordModal, PlistAction, PlistModal, ServiceInfoAction,
+    ServiceInfoModal, ServiceList, SystemTheme, Tab, TabManager, UpgradeAction, UpgradeModal,
+    WindowState, accessible, grid_density,
+};
+use crate::presentation::services::failure::{self, FailureKind};
+use crate::presentation::services::issue_report::{
+    self, DiagnosticsDestination, SystemDiagnostics,
 };
-use crate::presentation::services::{AsyncExecutor, AsyncTask, AsyncTaskManager};
+use crate::presentation::services::log_capture::{self, CapturedLog};
+use crate::presentation::services::log_file;
+use crate::presentation::services::{
+    AsyncExecutor, AsyncTask, AsyncTaskManager, InfoOrigin, OpKind, OpResult, PackageInfoPool,
+    TaskKind, TaskOutput, TaskSender,
+};
+use crate::presentation::ui::tabs::favorites::{FavoritesAction, FavoritesTab};
 use crate::presentation::ui::tabs::installed::{InstalledAction, InstalledTab};
 use crate::presentation::ui::tabs::log::{LogAction, LogTab};
 use crate::presentation::ui::tabs::search::{SearchAction, SearchTab};
 use crate::presentation::ui::tabs::services::{ServiceAction, ServicesTab};
 use crate::presentation::ui::tabs::settings::{SettingsAction, SettingsTab};
+use secrecy::{ExposeSecret, SecretString};
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 use std::sync::mpsc::Receiver;
-use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+/// The restored output panel height is limited to this share of the window.
+const OUTPUT_PANEL_MAX_FRACTION: f32 = 0.8;
+/// How long the output panel height has to stay put before it is saved.
+const OUTPUT_PANEL_SAVE_DELAY: Duration = Duration::from_secs(1);
+/// Imports of at least this many packages are preceded by a snapshot.
+const SNAPSHOT_MIN_IMPORT: usize = 10;
+/// `brew autoremove --dry-run` isn't rerun after every install or uninstall
+/// more often than this.
+const ORPHAN_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+/// How long an error toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(4);
+/// Toasts with a button stay longer, to leave time to click it.
+const ACTION_TOAST_DURATION: Duration = Duration::from_secs(10);
+/// Longer outcomes in the output panel are cut short; the rest is on hover.
+const LAST_RESULT_MAX_CHARS: usize = 80;
+/// `brew services start` returns before launchd has brought the service up,
+/// so a started service is looked at again after this long.
+const SERVICE_RECHECK_DELAY: Duration = Duration::from_secs(3);
 
 pub struct BrewstyApp {
     tab_manager: TabManager,
@@ -20,18 +72,55 @@ pub struct BrewstyApp {
 
     config: AppConfig,
     config_repo: ConfigRepository,
+    /// The user's package tags and notes; never passed to brew.
+    metadata_repo: PackageMetadataRepository,
+    window_state: WindowState,
+    system_theme: SystemTheme,
+    column_widths: ColumnWidths,
+    /// Follow state of the bottom output panel and the Log tab.
+    output_scroll: FollowScroll,
+    log_tab_scroll: FollowScroll,
+    /// Crash report from a previous run that the banner offers to open.
+    crash_report: Option<PathBuf>,
+    /// No brew executable was found; the banner offers to install Homebrew.
+    brew_missing: bool,
+    confirm_homebrew_install: bool,
+    loading_homebrew_install: bool,
+    /// Casks whose Install was clicked before their info had loaded, by
+    /// name; their architectures are checked once the info arrives.
+    arch_check_pending: HashMap<String, Package>,
+    /// Cask built only for another architecture, waiting for the user to
+    /// install it anyway or cancel.
+    confirm_arch_mismatch: Option<Package>,
+    /// A cask waiting for the user to confirm zapping it.
+    confirm_zap: Option<Package>,
 
     cleanup_modal: CleanupModal,
+    upgrade_modal: UpgradeModal,
     info_modal: InfoModal,
+    dependency_modal: DependencyModal,
+    service_info_modal: ServiceInfoModal,
+    plist_modal: PlistModal,
+    compare_modal: CompareModal,
+    /// Dependency clicked in the tree or package double-clicked whose info is
+    /// loading; the Info window opens for it once the info arrives.
+    info_pending: Option<String>,
+    /// Package of unknown type whose info is loading as a formula; it is
+    /// tried as a cask if that fails.
+    info_cask_fallback: Option<String>,
     password_modal: PasswordModal,
     log_manager: LogManager,
-    log_rx: Receiver<String>,
+    log_rx: Receiver<CapturedLog>,
+    /// Whether the last `poll_logs` found any; more tend to follow.
+    logs_received: bool,
 
     merged_packages: MergedPackageList,
     search_results: PackageList,
     service_list: ServiceList,
 
     auto_load_version_info: bool,
+    /// Search lists matching installed packages above brew's results.
+    unified_search: bool,
 
     initialized: bool,
 
@@ -39,94 +128,308 @@ pub struct BrewstyApp {
     loading_outdated: bool,
     loading_search: bool,
     loading_services: bool,
+    /// A periodic reload is running behind the list that is shown.
+    refreshing_services: bool,
 
     loading_install: bool,
     loading_uninstall: bool,
     loading_update: bool,
     loading_update_all: bool,
+    /// What the running Update All was limited to, kept for a retry.
+    update_all_only: Option<Vec<Package>>,
     loading_clean_cache: bool,
+    loading_cache_info: bool,
+    /// Size of the download cache, measured when Settings is first opened.
+    cache_info: Option<CacheInfoDto>,
+    /// Formulae `brew autoremove` would remove, counted when Settings is
+    /// first opened and after installs and uninstalls.
+    orphan_count: Option<usize>,
+    orphans_checked_at: Option<Instant>,
+    /// Resolved once at startup; `None` until then or if brew can't say.
+    brew_paths: Option<BrewPaths>,
+    /// When `brew update` last fetched, from the mtime of its `FETCH_HEAD`.
+    metadata_updated_at: Option<SystemTime>,
+    /// Listed when Settings is opened and after each bulk operation.
+    snapshots: Vec<Snapshot>,
     loading_cleanup_old_versions: bool,
+    loading_autoremove: bool,
     loading_export: bool,
     loading_import: bool,
+    loading_issue_report: bool,
+    /// Where the diagnostics being collected go.
+    diagnostics_destination: DiagnosticsDestination,
+    /// Finished diagnostics waiting to be copied, and for a report the issue
+    /// page opened; needs the egui context.
+    pending_diagnostics: Option<(DiagnosticsDestination, String)>,
 
     current_install_package: Option<String>,
     current_uninstall_package: Option<String>,
+    /// Whether the running uninstall is a zap.
+    zapping: bool,
     current_update_package: Option<String>,
     pending_updates: Vec<Package>,
+    /// Asked for while another operation of the same kind was running; each
+    /// starts once its kind is free again, in the order they were asked for.
+    queued_operations: VecDeque<QueuedOperation>,
+    /// Started, stopped or restarted one after another by the bulk service
+    /// buttons; `Some` until the last of them finished.
+    pending_service_ops: Option<Vec<ServiceAction>>,
+    /// Running services waiting for the user to confirm stopping or
+    /// restarting them all.
+    confirm_all_services: Option<(BulkServiceOp, Vec<String>)>,
+    /// Whether the user is being asked to confirm `brew services cleanup`.
+    confirm_services_cleanup: bool,
+    cleaning_services: bool,
+    /// The service operations queued together, for a summary at the end.
+    service_batch: Option<ServiceBatch>,
     pending_operation: Option<PendingOperation>,
+    /// Failed for lack of network; the banner offers to run it again.
+    network_retry: Option<RetryOperation>,
+    /// Formula just installed; the next services load checks whether it
+    /// registered a service.
+    service_check_pending: Option<String>,
+    /// Installed formula whose service the banner offers to start.
+    service_offer: Option<String>,
+    /// Newer Brewsty release the banner points to.
+    app_update: Option<Release>,
+    /// Short-lived error shown over the bottom of the window, and when it
+    /// appeared.
+    toast: Option<(String, Instant)>,
+    /// Log file the toast offers to open.
+    toast_log: Option<PathBuf>,
+    /// The last operation to finish: whether it succeeded, what it reported
+    /// and when, shown in the output panel until the next one finishes.
+    last_result: Option<(bool, String, Instant)>,
     packages_in_operation: std::collections::HashSet<String>,
     services_in_operation: std::collections::HashSet<String>,
+    /// Downloaded by Fetch this session, ready to update without a connection.
+    fetched_packages: std::collections::HashSet<String>,
 
     task_manager: AsyncTaskManager,
+    info_pool: PackageInfoPool,
 
     use_cases: Arc<UseCaseContainer>,
     executor: AsyncExecutor,
 
     loading: bool,
     status_message: String,
-    output_panel_height: f32,
+    /// When the output panel was last resized, to save its height once it settles.
+    output_panel_resized_at: Option<Instant>,
+    services_loaded_at: Option<Instant>,
+    /// Services just started, and when to check they are still running.
+    service_rechecks: Vec<(String, Instant)>,
+    /// When the scheduled export last ran; `None` runs it on the next frame.
+    auto_exported_at: Option<Instant>,
 }
 
 #[derive(Clone, Debug)]
 enum PendingOperation {
     Install(Package),
     Uninstall(Package),
+    Zap(Package),
+    InstallHomebrew,
+}
+
+/// An operation on several services at once.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum BulkServiceOp {
+    Start,
+    Stop,
+    Restart,
+}
+
+impl BulkServiceOp {
+    fn verb(self) -> &'static str {
+        match self {
+            Self::Start => "start",
+            Self::Stop => "stop",
+            Self::Restart => "restart",
+        }
+    }
+
+    fn done(self) -> &'static str {
+        match self {
+            Self::Start => "started",
+            Self::Stop => "stopped",
+            Self::Restart => "restarted",
+        }
+    }
+}
+
+/// Services being worked through one after another, and those that failed.
+struct ServiceBatch {
+    op: BulkServiceOp,
+    names: Vec<String>,
+    failed: Vec<String>,
+}
+
+/// A single-package operation waiting for the one of its kind to finish.
+#[derive(Clone, Debug)]
+enum QueuedOperation {
+    Install(Package),
+    Uninstall(Package),
+    Zap(Package),
+    Update(Package),
+}
+
+impl QueuedOperation {
+    fn package(&self) -> &Package {
+        match self {
+            Self::Install(package)
+            | Self::Uninstall(package)
+            | Self::Zap(package)
+            | Self::Update(package) => package,
+        }
+    }
+
+    fn verb(&self) -> &'static str {
+        match self {
+            Self::Install(_) => "install",
+            Self::Uninstall(_) => "uninstall",
+            Self::Zap(_) => "zap",
+            Self::Update(_) => "update",
+        }
+    }
+}
+
+/// File formats the package list can be exported to.
+#[derive(Clone, Copy)]
+enum ExportFormat {
+    Json,
+    /// Readable, for sharing a setup.
+    Markdown,
+}
+
+/// An operation that failed because the network was unavailable.
+enum RetryOperation {
+    Search(String),
+    Install(Package),
+    Update(Package),
+    /// `Some` when packages were left out of the preview.
+    UpdateAll(Option<Vec<Package>>),
 }
 
 impl BrewstyApp {
     pub fn new(
+        ctx: egui::Context,
         use_cases: Arc<UseCaseContainer>,
-        log_rx: Receiver<String>,
+        log_rx: Receiver<CapturedLog>,
         executor: AsyncExecutor,
+        config_repo: ConfigRepository,
+        config: AppConfig,
+        crash_report: Option<PathBuf>,
     ) -> Self {
-        let config_repo = ConfigRepository::new();
-        let config = config_repo.load().unwrap_or_else(|e| {
-            tracing::error!("Failed to load config: {}", e);
-            AppConfig::default()
-        });
+        let window_state = WindowState::new(config.window_position.is_some());
+        let mut merged_packages = MergedPackageList::new();
+        merged_packages.set_outdated_since(config.outdated_since.clone());
+        merged_packages.set_ignored_updates(config.ignored_updates.clone());
+        merged_packages.set_brew_updated_casks(config.brew_updated_casks.iter().cloned().collect());
+        merged_packages
+            .set_collapsed_sections(config.outdated_collapsed, config.installed_collapsed);
+        let metadata_repo = PackageMetadataRepository::new(config_repo.dir());
+        merged_packages.set_metadata(metadata_repo.load_or_default());
+        let column_widths = ColumnWidths::new(config.column_widths.clone());
 
         Self {
             tab_manager: TabManager::new(),
             filter_state: FilterState::new(),
 
-            config: config.clone(),
+            config,
             config_repo,
+            metadata_repo,
+            window_state,
+            system_theme: SystemTheme::new(),
+            column_widths,
+            output_scroll: FollowScroll::new(FollowEdge::Bottom),
+            log_tab_scroll: FollowScroll::new(FollowEdge::Top),
+            crash_report,
+            brew_missing: false,
+            confirm_homebrew_install: false,
+            loading_homebrew_install: false,
+            arch_check_pending: HashMap::new(),
+            confirm_arch_mismatch: None,
+            confirm_zap: None,
 
             cleanup_modal: CleanupModal::new(),
+            upgrade_modal: UpgradeModal::new(),
             info_modal: InfoModal::new(),
+            dependency_modal: DependencyModal::new(),
+            service_info_modal: ServiceInfoModal::new(),
+            plist_modal: PlistModal::new(),
+            compare_modal: CompareModal::new(),
+            info_pending: None,
+            info_cask_fallback: None,
             password_modal: PasswordModal::new(),
             log_manager: LogManager::new(),
             log_rx,
-            merged_packages: MergedPackageList::new(),
+            logs_received: false,
+            merged_packages,
             search_results: PackageList::new(),
             service_list: ServiceList::new(),
             auto_load_version_info: false,
+            unified_search: true,
             initialized: false,
             loading_installed: false,
             loading_outdated: false,
             loading_search: false,
             loading_services: false,
+            refreshing_services: false,
             loading_install: false,
             loading_uninstall: false,
             loading_update: false,
             loading_update_all: false,
+            update_all_only: None,
             loading_clean_cache: false,
+            loading_cache_info: false,
+            cache_info: None,
+            orphan_count: None,
+            orphans_checked_at: None,
+            brew_paths: None,
+            metadata_updated_at: None,
+            snapshots: Vec::new(),
             loading_cleanup_old_versions: false,
+            loading_autoremove: false,
             loading_export: false,
             loading_import: false,
+            loading_issue_report: false,
+            diagnostics_destination: DiagnosticsDestination::NewIssue,
+            pending_diagnostics: None,
             current_install_package: None,
             current_uninstall_package: None,
+            zapping: false,
             current_update_package: None,
             pending_updates: Vec::new(),
+            queued_operations: VecDeque::new(),
+            pending_service_ops: None,
+            confirm_all_services: None,
+            confirm_services_cleanup: false,
+            cleaning_services: false,
+            service_batch: None,
             pending_operation: None,
+            network_retry: None,
+            service_check_pending: None,
+            service_offer: None,
+            app_update: None,
+            toast: None,
+            toast_log: None,
+            last_result: None,
             packages_in_operation: std::collections::HashSet::new(),
+            fetched_packages: std::collections::HashSet::new(),
             services_in_operation: std::collections::HashSet::new(),
-            task_manager: AsyncTaskManager::new(),
+            info_pool: PackageInfoPool::new(
+                &executor,
+                Arc::clone(&use_cases.get_package_info),
+                ctx.clone(),
+            ),
+            task_manager: AsyncTaskManager::new(ctx),
             use_cases,
             executor,
             loading: false,
             status_message: String::new(),
-            output_panel_height: 250.0,
+            output_panel_resized_at: None,
+            services_loaded_at: None,
+            service_rechecks: Vec::new(),
+            auto_exported_at: None,
         }
     }
 
@@ -136,326 +439,1102 @@ impl BrewstyApp {
         }
     }
 
-    fn apply_theme(&self, ctx: &egui::Context) {
-        crate::presentation::style::configure_style(ctx, self.config.theme);
+    fn toggle_favorite(&mut self, package: Package) {
+        self.config
+            .toggle_favorite(&package.name, package.package_type);
+        self.save_config();
     }
 
-    fn load_installed_packages(&mut self, include_outdated: bool) {
-        if self.loading_installed || self.loading_outdated {
-            return;
+    /// A starred package that isn't in the installed list yet.
+    fn favorite_package(&self, name: &str) -> Option<Package> {
+        if self.merged_packages.get_package(name).is_some() {
+            return None;
         }
+        self.config
+            .favorites
+            .iter()
+            .find(|f| f.name == name)
+            .map(|f| Package::new(f.name.clone(), f.package_type.clone()))
+    }
 
-        self.loading_installed = true;
-        self.loading_installed = true;
-        if include_outdated {
-            self.loading_outdated = true;
-        }
-        self.status_message = if include_outdated {
-            "Loading installed and outdated packages...".to_string()
-        } else {
-            "Loading installed packages...".to_string()
+    fn show_crash_banner(&mut self, ctx: &egui::Context) {
+        let Some(path) = self.crash_report.clone() else {
+            return;
         };
 
-        if include_outdated {
-            self.log_manager
-                .push("Loading installed and outdated packages (formulae and casks)".to_string());
-            tracing::info!("Loading installed and outdated packages (formulae and casks)");
-        } else {
-            self.log_manager
-                .push("Loading installed packages (formulae and casks)".to_string());
-            tracing::info!("Loading installed packages (formulae and casks)");
-        }
-
-        let use_case_installed = Arc::clone(&self.use_cases.list_installed);
-        let use_case_outdated = Arc::clone(&self.use_cases.list_outdated);
-
-        let installed_packages = Arc::new(Mutex::new(Vec::new()));
-        let outdated_packages = Arc::new(Mutex::new(Vec::new()));
-        let installed_log = Arc::new(Mutex::new(Vec::new()));
-        let outdated_log = Arc::new(Mutex::new(Vec::new()));
+        egui::TopBottomPanel::top("crash_banner").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    "⚠ Brewsty quit unexpectedly last time.",
+                );
+                ui.label("A crash report was saved:");
+                ui.monospace(path.display().to_string());
 
-        self.task_manager.set_active_task(AsyncTask::LoadInstalled {
-            packages: Arc::clone(&installed_packages),
-            logs: Arc::clone(&installed_log),
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        self.crash_report = None;
+                    }
+                    if ui.button("Open Report").clicked() {
+                        if let Err(e) = system::open_path(&path) {
+                            tracing::error!("Failed to open crash report: {:#}", e);
+                        }
+                        self.crash_report = None;
+                    }
+                });
+            });
+            ui.add_space(4.0);
         });
+    }
 
-        if include_outdated {
-            self.task_manager.set_active_task(AsyncTask::LoadOutdated {
-                packages: Arc::clone(&outdated_packages),
-                logs: Arc::clone(&outdated_log),
-            });
+    fn show_network_banner(&mut self, ctx: &egui::Context) {
+        if self.network_retry.is_none() {
+            return;
         }
 
-        self.executor.spawn(async move {
-            tracing::trace!("TASK STARTED: load_installed_packages");
-            let task_result = async {
-                tracing::debug!("Starting to load installed packages");
-
-                tracing::trace!("TASK: about to execute installed formulae");
-                let installed_formulae_result =
-                    use_case_installed.execute(PackageType::Formula).await;
-
-                tracing::debug!(
-                    "Installed formulae result: {:?}",
-                    installed_formulae_result
-                        .as_ref()
-                        .map(|p| p.len())
-                        .map_err(|e| e.to_string())
-                );
-
-                tracing::trace!("TASK: about to execute installed casks");
-                let installed_casks_result = use_case_installed.execute(PackageType::Cask).await;
-
-                tracing::debug!(
-                    "Installed casks result: {:?}",
-                    installed_casks_result
-                        .as_ref()
-                        .map(|p| p.len())
-                        .map_err(|e| e.to_string())
+        egui::TopBottomPanel::top("network_banner").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    format!("📡 {}", failure::NETWORK_UNAVAILABLE),
                 );
 
-                let mut outdated_formulae_result: anyhow::Result<Vec<Package>> = Ok(Vec::new());
-                let mut outdated_casks_result: anyhow::Result<Vec<Package>> = Ok(Vec::new());
-
-                if include_outdated {
-                    tracing::trace!("TASK: about to execute outdated formulae");
-                    outdated_formulae_result =
-                        use_case_outdated.execute(PackageType::Formula).await;
-
-                    tracing::debug!(
-                        "Outdated formulae result: {:?}",
-                        outdated_formulae_result
-                            .as_ref()
-                            .map(|p| p.len())
-                            .map_err(|e| e.to_string())
-                    );
-
-                    tracing::trace!("TASK: about to execute outdated casks");
-                    outdated_casks_result = use_case_outdated.execute(PackageType::Cask).await;
-
-                    tracing::debug!(
-                        "Outdated casks result: {:?}",
-                        outdated_casks_result
-                            .as_ref()
-                            .map(|p| p.len())
-                            .map_err(|e| e.to_string())
-                    );
-                }
-
-                let mut installed = Vec::new();
-                let mut outdated = Vec::new();
-                let mut installed_logs_vec = Vec::new();
-                let mut outdated_logs_vec = Vec::new();
-
-                match installed_formulae_result {
-                    Ok(pkgs) => {
-                        let msg = format!("Loaded {} installed formulae", pkgs.len());
-                        installed_logs_vec.push(msg.clone());
-                        tracing::info!("{}", msg);
-                        installed.extend(pkgs);
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        self.network_retry = None;
                     }
-                    Err(e) => {
-                        let msg = format!("Error loading installed formulae: {}", e);
-                        installed_logs_vec.push(msg.clone());
-                        tracing::error!("{}", msg);
+                    if ui.button("Retry").clicked() {
+                        self.retry_after_network_error();
                     }
-                }
+                });
+            });
+            ui.add_space(4.0);
+        });
+    }
 
-                match installed_casks_result {
-                    Ok(pkgs) => {
-                        let msg = format!("Loaded {} installed casks", pkgs.len());
-                        installed_logs_vec.push(msg.clone());
-                        tracing::info!("{}", msg);
-                        installed.extend(pkgs);
+    fn show_service_offer_banner(&mut self, ctx: &egui::Context) {
+        let Some(name) = self.service_offer.clone() else {
+            return;
+        };
+
+        egui::TopBottomPanel::top("service_offer_banner").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "✓ {} installed. It can run as a background service; see its caveats in the output for details.",
+                    name
+                ));
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        self.service_offer = None;
                     }
-                    Err(e) => {
-                        let msg = format!("Error loading installed casks: {}", e);
-                        installed_logs_vec.push(msg.clone());
-                        tracing::error!("{}", msg);
+                    if ui.button("Start as service").clicked() {
+                        self.service_offer = None;
+                        self.handle_start_service(name);
                     }
-                }
+                });
+            });
+            ui.add_space(4.0);
+        });
+    }
 
-                if include_outdated {
-                    match outdated_formulae_result {
-                        Ok(pkgs) => {
-                            let msg = format!("Loaded {} outdated formulae", pkgs.len());
-                            outdated_logs_vec.push(msg.clone());
-                            tracing::info!("{}", msg);
-                            outdated.extend(pkgs);
-                        }
-                        Err(e) => {
-                            let msg = format!("Error loading outdated formulae: {}", e);
-                            outdated_logs_vec.push(msg.clone());
-                            tracing::error!("{}", msg);
-                        }
-                    }
+    fn show_app_update_banner(&mut self, ctx: &egui::Context) {
+        let Some(release) = self.app_update.clone() else {
+            return;
+        };
 
-                    match outdated_casks_result {
-                        Ok(pkgs) => {
-                            let msg = format!("Loaded {} outdated casks", pkgs.len());
-                            outdated_logs_vec.push(msg.clone());
-                            tracing::info!("{}", msg);
-                            outdated.extend(pkgs);
-                        }
-                        Err(e) => {
-                            let msg = format!("Error loading outdated casks: {}", e);
-                            outdated_logs_vec.push(msg.clone());
-                            tracing::error!("{}", msg);
-                        }
+        egui::TopBottomPanel::top("app_update_banner").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.label(format!(
+                    "⬆ Brewsty {} is available (you have {}).",
+                    release.version,
+                    env!("CARGO_PKG_VERSION")
+                ));
+                ui.hyperlink_to("Release notes", &release.url);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Dismiss").clicked() {
+                        self.app_update = None;
                     }
-                }
+                });
+            });
+            ui.add_space(4.0);
+        });
+    }
 
-                tracing::debug!(
-                    "About to write {} installed packages to mutex",
-                    installed.len()
-                );
-                *installed_packages
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to lock installed packages: {}", e))? =
-                    installed;
+    /// Asks GitHub for the latest release in the background; failures are
+    /// only logged, as this is a convenience.
+    fn check_for_app_update(&mut self) {
+        let Some(sender) = self.task_manager.start(AsyncTask::CheckForAppUpdate) else {
+            return;
+        };
+        let use_case = Arc::clone(&self.use_cases.check_for_update);
 
-                tracing::debug!(
-                    "About to write {} outdated packages to mutex",
-                    outdated.len()
-                );
-                *outdated_packages
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to lock outdated packages: {}", e))? =
-                    outdated;
-
-                installed_logs_vec.push("Finished loading installed packages".to_string());
-                if include_outdated {
-                    outdated_logs_vec.push("Finished loading outdated packages".to_string());
-                    tracing::info!("Finished loading installed and outdated packages");
-                } else {
-                    tracing::info!("Finished loading installed packages");
+        self.executor.spawn(async move {
+            match use_case.execute(env!("CARGO_PKG_VERSION")).await {
+                Ok(release) => {
+                    if let Some(release) = &release {
+                        tracing::info!("Brewsty {} is available", release.version);
+                    }
+                    sender.send(TaskOutput::AppUpdate(release), Vec::new());
                 }
-
-                tracing::debug!(
-                    "About to lock installed logs mutex with {} log entries",
-                    installed_logs_vec.len()
-                );
-                *installed_log
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to lock installed logs: {}", e))? =
-                    installed_logs_vec;
-
-                tracing::debug!(
-                    "About to lock outdated logs mutex with {} log entries",
-                    outdated_logs_vec.len()
-                );
-                *outdated_log
-                    .lock()
-                    .map_err(|e| anyhow::anyhow!("Failed to lock outdated logs: {}", e))? =
-                    outdated_logs_vec;
-
-                tracing::debug!("Successfully updated mutexes");
-
-                Ok::<(), anyhow::Error>(())
-            }
-            .await;
-
-            if let Err(e) = task_result {
-                tracing::error!("Error in load_installed_packages task: {}", e);
-                if let Ok(mut logs) = installed_log.lock() {
-                    logs.push(format!("Task error: {}", e));
+                Err(e) => {
+                    tracing::warn!("Could not check for Brewsty updates: {:#}", e);
+                    sender.send(TaskOutput::AppUpdate(None), Vec::new());
                 }
             }
-            tracing::trace!("TASK ENDED: load_installed_packages");
         });
     }
 
-    fn handle_update_selected(&mut self, package_names: Vec<String>) {
-        if self.loading_update_all {
+    fn show_toast(&mut self, ctx: &egui::Context) {
+        let Some((message, shown_at)) = &self.toast else {
             return;
-        }
+        };
+        let duration = if self.toast_log.is_some() {
+            ACTION_TOAST_DURATION
+        } else {
+            TOAST_DURATION
+        };
+        let Some(remaining) = duration.checked_sub(shown_at.elapsed()) else {
+            self.toast = None;
+            self.toast_log = None;
+            return;
+        };
 
-        let mut packages_to_update = Vec::new();
+        let mut view_logs = false;
+        egui::Area::new("toast".into())
+            .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -40.0))
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style())
+                    .fill(egui::Color32::from_rgb(120, 30, 30))
+                    .show(ui, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::WHITE, format!("⚠ {}", message));
+                            if self.toast_log.is_some() {
+                                view_logs = ui.button("View logs").clicked();
+                            }
+                        });
+                    });
+            });
+        ctx.request_repaint_after(remaining);
 
-        for package_name in package_names {
-            if let Some(package) = self.merged_packages.get_package(&package_name) {
-                packages_to_update.push(package);
-                self.packages_in_operation.insert(package_name);
+        if view_logs && let Some(path) = self.toast_log.take() {
+            self.toast = None;
+            if let Err(e) = system::open_path(&path) {
+                self.show_error_toast(format!("Couldn't open {}: {:#}", path.display(), e));
             }
         }
+    }
 
-        if packages_to_update.is_empty() {
+    fn show_error_toast(&mut self, message: String) {
+        tracing::error!("{}", message);
+        self.log_manager.push(message.clone());
+        self.toast = Some((message, Instant::now()));
+        self.toast_log = None;
+    }
+
+    fn show_brew_missing_banner(&mut self, ctx: &egui::Context) {
+        if !self.brew_missing {
             return;
         }
 
-        let count = packages_to_update.len();
-        self.status_message = format!("Queued {} packages for sequential update", count);
-        self.log_manager
-            .push(format!("Queued {} packages for sequential update", count));
-        tracing::info!("Queued {} packages for sequential update", count);
-
-        self.pending_updates = packages_to_update;
-        self.loading_update_all = true;
-
-        self.process_next_pending_update();
+        egui::TopBottomPanel::top("brew_missing_banner").show(ctx, |ui| {
+            ui.add_space(4.0);
+            ui.horizontal(|ui| {
+                ui.colored_label(
+                    egui::Color32::from_rgb(255, 165, 0),
+                    "⚠ Homebrew wasn't found on this machine.",
+                );
+                ui.label("Brewsty needs it to list and install packages.");
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if self.loading_homebrew_install {
+                        ui.spinner();
+                        ui.label("Installing Homebrew...");
+                    } else if ui.button("Install Homebrew...").clicked() {
+                        self.confirm_homebrew_install = true;
+                    }
+                });
+            });
+            ui.add_space(4.0);
+        });
     }
 
-    fn process_next_pending_update(&mut self) {
-        if self.pending_updates.is_empty() {
+    fn show_homebrew_install_confirm(&mut self, ctx: &egui::Context) {
+        if !self.confirm_homebrew_install {
             return;
         }
 
-        let package = self.pending_updates.remove(0);
-        let remaining = self.pending_updates.len();
-        let total = self.packages_in_operation.len();
-        let completed = total - remaining;
+        let mut open = true;
+        let mut decided = None;
+        egui::Window::new("Install Homebrew?")
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label("This runs the official Homebrew install script:");
+                ui.monospace(installer::INSTALL_SCRIPT_URL);
+                ui.add_space(8.0);
+                ui.label(
+                    "It changes your system: it creates the Homebrew prefix with \
+                     administrator rights and may install the Xcode Command Line Tools. \
+                     Its output appears in the log while it runs.",
+                );
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Cancel").clicked() {
+                            decided = Some(false);
+                        }
+                        if ui.button("Install").clicked() {
+                            decided = Some(true);
+                        }
+                    });
+                });
+            });
 
-        self.status_message = format!(
-            "Updating {}/{}: {}... ({} remaining)",
-            completed, total, package.name, remaining
-        );
+        if !open {
+            decided = Some(false);
+        }
+        if let Some(install) = decided {
+            self.confirm_homebrew_install = false;
+            if install {
+                self.pending_operation = Some(PendingOperation::InstallHomebrew);
+                self.password_modal.show("Install Homebrew".to_string());
+            }
+        }
+    }
 
-        let msg = format!(
-            "Updating {}/{}: {} ({} remaining)",
-            completed, total, package.name, remaining
-        );
-        self.log_manager.push(msg);
-        tracing::info!(
-            "Processing package {}/{}: {}",
-            completed,
-            total,
-            package.name
-        );
+    fn handle_install_homebrew(&mut self, password: SecretString) {
+        if self.loading_homebrew_install {
+            return;
+        }
 
-        self.handle_update(package);
-    }
+        self.loading_homebrew_install = true;
+        self.status_message = "Installing Homebrew...".to_string();
+        self.log_manager.push("Installing Homebrew".to_string());
+        tracing::info!("Installing Homebrew");
 
-    fn is_password_error(&self, error_msg: &str) -> bool {
-        error_msg.contains("authentication failure")
-            || error_msg.contains("sudo")
-            || error_msg.contains("password")
-            || error_msg.contains("Permission denied")
-            || error_msg.contains("Incorrect password")
-            || error_msg.contains("incorrect password attempt")
-            || error_msg.contains("sorry, try again")
-            || error_msg.contains("sudo: a password is required")
-    }
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::op(OpKind::InstallHomebrew))
+        else {
+            return;
+        };
 
-    fn retry_with_password(&mut self, password: &str) {
-        if let Some(operation) = self.pending_operation.take() {
-            match operation {
-                PendingOperation::Install(package) => {
-                    self.handle_install_with_password(package, password.to_string());
+        self.executor.spawn(async move {
+            let result =
+                match tokio::task::spawn_blocking(move || installer::install_homebrew(&password))
+                    .await
+                {
+                    Ok(inner) => inner,
+                    Err(e) => Err(anyhow::anyhow!("Task join error: {}", e)),
+                };
+
+            let mut log_vec = Vec::new();
+            match result {
+                Ok(()) => {
+                    let msg = "Homebrew installed".to_string();
+                    log_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    sender.finish(true, msg, log_vec);
                 }
-                PendingOperation::Uninstall(package) => {
-                    self.handle_uninstall_with_password(package, password.to_string());
+                Err(e) => {
+                    let msg = format!("Error installing Homebrew: {:#}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    sender.finish(false, msg, log_vec);
                 }
             }
-        }
+        });
     }
 
-    fn handle_install(&mut self, package: Package) {
-        if self.loading_install {
+    fn resolve_brew_paths(&mut self) {
+        let Some(sender) = self.task_manager.start(AsyncTask::ResolveBrewPaths) else {
             return;
-        }
+        };
 
-        let package_name = package.name.clone();
-        self.loading_install = true;
+        self.executor.spawn(async move {
+            let result = match tokio::task::spawn_blocking(BrewPaths::resolve).await {
+                Ok(inner) => inner,
+                Err(e) => Err(anyhow::anyhow!("Task join error: {}", e)),
+            };
+
+            match result {
+                Ok(paths) => sender.send(TaskOutput::BrewPaths(Some(paths)), Vec::new()),
+                Err(e) => {
+                    let msg = format!("Could not find the Homebrew folders: {:#}", e);
+                    tracing::error!("{}", msg);
+                    sender.send(TaskOutput::BrewPaths(None), vec![msg]);
+                }
+            }
+        });
+    }
+
+    fn load_metadata_age(&mut self) {
+        let Some(fetch_head) = self.brew_paths.as_ref().map(BrewPaths::fetch_head) else {
+            return;
+        };
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadMetadataAge) else {
+            return;
+        };
+
+        self.executor.spawn(async move {
+            match tokio::fs::metadata(&fetch_head)
+                .await
+                .and_then(|m| m.modified())
+            {
+                Ok(at) => sender.send(TaskOutput::MetadataAge(Some(at)), Vec::new()),
+                Err(e) => {
+                    // Never fetched, or brew isn't a git checkout
+                    tracing::debug!("No metadata age from {}: {}", fetch_head.display(), e);
+                    sender.send(TaskOutput::MetadataAge(None), Vec::new());
+                }
+            }
+        });
+    }
+
+    fn open_folder(&self, path: &Path) {
+        if let Err(e) = system::open_path(path) {
+            tracing::error!("Failed to open {}: {:#}", path.display(), e);
+        }
+    }
+
+    fn reveal_package(&mut self, package: &Package) {
+        let Some(paths) = &self.brew_paths else {
+            self.show_error_toast("The Homebrew folders haven't been found yet".to_string());
+            return;
+        };
+
+        let result = paths
+            .package_dir(&package.name, &package.package_type)
+            .and_then(|dir| system::open_path(&dir));
+        if let Err(e) = result {
+            self.show_error_toast(format!("Couldn't reveal {}: {:#}", package.name, e));
+        }
+    }
+
+    fn open_log_folder(&self) {
+        let dir = self.config_repo.log_dir();
+        let result = std::fs::create_dir_all(&dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|_| system::open_path(&dir));
+        if let Err(e) = result {
+            tracing::error!("Failed to open log folder: {:#}", e);
+        }
+    }
+
+    fn show_output_panel(&mut self, ctx: &egui::Context) {
+        if self.config.output_panel_collapsed {
+            egui::TopBottomPanel::bottom("bottom_panel_collapsed")
+                .resizable(false)
+                .show(ctx, |ui| {
+                    ui.add_space(8.0);
+                    self.output_toolbar(ui);
+                    ui.add_space(8.0);
+                });
+            return;
+        }
+
+        // A height saved on a bigger screen mustn't leave no room for the tabs
+        let max_height = ctx.screen_rect().height() * OUTPUT_PANEL_MAX_FRACTION;
+        let panel = egui::TopBottomPanel::bottom("bottom_panel")
+            .resizable(true)
+            .default_height(self.config.output_panel_height.min(max_height))
+            .max_height(max_height)
+            .show(ctx, |ui| {
+                ui.add_space(8.0);
+                self.output_toolbar(ui);
+
+                ui.separator();
+
+                let line_count = self.log_manager.filtered_logs().count();
+                let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+                let output = self
+                    .output_scroll
+                    .scroll_area(
+                        egui::ScrollArea::vertical().auto_shrink([false; 2]),
+                        line_count,
+                        row_height,
+                    )
+                    .show(ui, |ui| {
+                        ui.set_width(ui.available_width());
+
+                        for entry in self.log_manager.filtered_logs() {
+                            ui.horizontal(|ui| {
+                                ui.label(
+                                    egui::RichText::new(format!("[{}]", entry.format_timestamp()))
+                                        .color(egui::Color32::GRAY)
+                                        .monospace(),
+                                );
+                                ui.monospace(&entry.message);
+                            });
+                        }
+                    });
+                self.output_scroll.update(&output, line_count);
+                self.output_scroll.show_jump_button(ui, &output, line_count);
+            });
+
+        let height = panel.response.rect.height().round();
+        if height != self.config.output_panel_height {
+            self.config.output_panel_height = height;
+            self.output_panel_resized_at = Some(Instant::now());
+        }
+    }
+
+    fn output_toolbar(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui.button("Clear Output").clicked() {
+                self.log_manager.clear();
+            }
+            ui.separator();
+            if ui.button("📋 Copy Output").clicked() {
+                let output = self
+                    .log_manager
+                    .all_logs()
+                    .map(|entry| format!("[{}] {}", entry.format_timestamp(), entry.message))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ui.ctx().copy_text(output);
+            }
+            self.last_result_label(ui);
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                let collapsed = self.config.output_panel_collapsed;
+                let (icon, hover) = if collapsed {
+                    ("⏶", "Show output")
+                } else {
+                    ("⏷", "Hide output")
+                };
+                let response = ui
+                    .add(egui::Button::new(icon).frame(false))
+                    .on_hover_text(hover);
+                accessible::name(&response, egui::WidgetType::Button, hover);
+                if response.clicked() {
+                    self.config.output_panel_collapsed = !collapsed;
+                    self.save_config();
+                }
+
+                for (package_name, progress) in self.operation_progress() {
+                    ui.separator();
+                    grid_density::progress_bar(ui, &progress, 140.0);
+                    ui.label(package_name);
+                }
+
+                if !self.queued_operations.is_empty() {
+                    let (names, details): (Vec<_>, Vec<_>) = self
+                        .queued_operations
+                        .iter()
+                        .map(|q| {
+                            let name = q.package().name.as_str();
+                            (name, format!("{} {}", q.verb(), name))
+                        })
+                        .unzip();
+                    ui.separator();
+                    ui.weak(format!("Queued: {}", names.join(", ")))
+                        .on_hover_text(details.join("\n"));
+                }
+            });
+        });
+    }
+
+    /// The last operation's outcome with how long ago it finished; the
+    /// clock time and full message are on hover.
+    fn last_result_label(&self, ui: &mut egui::Ui) {
+        let Some((success, message, at)) = &self.last_result else {
+            return;
+        };
+        let (icon, color, outcome) = if *success {
+            ("✔", egui::Color32::from_rgb(0, 200, 0), "Succeeded")
+        } else {
+            ("✖", egui::Color32::from_rgb(255, 0, 0), "Failed")
+        };
+        let elapsed = at.elapsed();
+        let finished = chrono::Local::now()
+            - chrono::Duration::from_std(elapsed).unwrap_or_else(|_| chrono::Duration::zero());
+        let first_line = message.lines().next().unwrap_or_default();
+        let mut summary: String = first_line.chars().take(LAST_RESULT_MAX_CHARS).collect();
+        if summary.len() < first_line.len() {
+            summary.push('…');
+        }
+
+        ui.separator();
+        let icon = ui.colored_label(color, icon);
+        accessible::name(&icon, egui::WidgetType::Label, outcome);
+        ui.label(summary).on_hover_text(format!(
+            "{}\n\nFinished at {}",
+            message,
+            finished.format("%H:%M:%S")
+        ));
+        let now = SystemTime::now();
+        ui.weak(time_ago(now - elapsed, now));
+        // Keep the age current while nothing else repaints
+        ui.ctx().request_repaint_after(Duration::from_secs(60));
+    }
+
+    /// Progress of the install and update running now, by package name.
+    fn operation_progress(&self) -> Vec<(String, OperationProgress)> {
+        [
+            (&self.current_install_package, OpKind::Install),
+            (&self.current_update_package, OpKind::Update),
+        ]
+        .into_iter()
+        .filter_map(|(package_name, kind)| {
+            Some((package_name.clone()?, self.task_manager.op_progress(kind)?))
+        })
+        .chain(self.task_manager.fetch_progress().and_then(|progress| {
+            // A fetch names the package it is downloading
+            match &progress {
+                OperationProgress::Downloading { file, .. } => Some((file.clone(), progress)),
+                _ => None,
+            }
+        }))
+        .chain(
+            self.task_manager
+                .op_progress(OpKind::UpdateAll)
+                .map(|progress| {
+                    let phase = match progress {
+                        OperationProgress::RefreshingMetadata => "Refreshing metadata…",
+                        _ => "Upgrading…",
+                    };
+                    (phase.to_string(), progress)
+                }),
+        )
+        .collect()
+    }
+
+    fn apply_brew_env(&self) {
+        BrewCommand::set_no_auto_update(self.config.homebrew_no_auto_update);
+        BrewCommand::set_extra_env(
+            self.config
+                .brew_env
+                .iter()
+                .filter(
+                    |(key, value)| match BrewCommand::check_env_var(key, value) {
+                        Ok(()) => true,
+                        Err(e) => {
+                            tracing::warn!("Not passing {:?} to brew: {}", key, e);
+                            false
+                        }
+                    },
+                )
+                .cloned()
+                .collect(),
+        );
+        BrewCommand::set_timeouts(
+            Duration::from_secs(self.config.read_timeout_secs),
+            Duration::from_secs(self.config.command_timeout_secs),
+        );
+    }
+
+    fn apply_theme(&self, ctx: &egui::Context) {
+        crate::presentation::style::configure_style(
+            ctx,
+            &self.config,
+            self.system_theme.prefers_dark(),
+        );
+    }
+
+    fn load_installed_packages(&mut self, include_outdated: bool) {
+        if self.loading_installed || self.loading_outdated {
+            return;
+        }
+
+        self.loading_installed = true;
+        self.loading_installed = true;
+        if include_outdated {
+            self.loading_outdated = true;
+        }
+        self.status_message = if include_outdated {
+            "Loading installed and outdated packages...".to_string()
+        } else {
+            "Loading installed packages...".to_string()
+        };
+
+        if include_outdated {
+            self.log_manager
+                .push("Loading installed and outdated packages (formulae and casks)".to_string());
+            tracing::info!("Loading installed and outdated packages (formulae and casks)");
+        } else {
+            self.log_manager
+                .push("Loading installed packages (formulae and casks)".to_string());
+            tracing::info!("Loading installed packages (formulae and casks)");
+        }
+
+        let use_case_installed = Arc::clone(&self.use_cases.list_installed);
+        let use_case_outdated = Arc::clone(&self.use_cases.list_outdated);
+        let brew_updated_casks = self.merged_packages.brew_updated_casks().clone();
+
+        let Some(installed_sender) = self.task_manager.start(AsyncTask::LoadInstalled) else {
+            return;
+        };
+        let outdated_sender = if include_outdated {
+            self.task_manager.start(AsyncTask::LoadOutdated)
+        } else {
+            None
+        };
+
+        self.executor.spawn(async move {
+            tracing::trace!("TASK STARTED: load_installed_packages");
+            tracing::debug!("Starting to load installed packages");
+
+            tracing::trace!("TASK: about to execute installed formulae");
+            let installed_formulae_result = use_case_installed.execute(PackageType::Formula).await;
+
+            tracing::debug!(
+                "Installed formulae result: {:?}",
+                installed_formulae_result
+                    .as_ref()
+                    .map(|p| p.len())
+                    .map_err(|e| e.to_string())
+            );
+
+            tracing::trace!("TASK: about to execute installed casks");
+            let installed_casks_result = use_case_installed.execute(PackageType::Cask).await;
+
+            tracing::debug!(
+                "Installed casks result: {:?}",
+                installed_casks_result
+                    .as_ref()
+                    .map(|p| p.len())
+                    .map_err(|e| e.to_string())
+            );
+
+            let mut outdated_formulae_result: anyhow::Result<Vec<Package>> = Ok(Vec::new());
+            let mut outdated_casks_result: anyhow::Result<Vec<Package>> = Ok(Vec::new());
+
+            if include_outdated {
+                tracing::trace!("TASK: about to execute outdated formulae");
+                outdated_formulae_result = use_case_outdated.execute(PackageType::Formula).await;
+
+                tracing::debug!(
+                    "Outdated formulae result: {:?}",
+                    outdated_formulae_result
+                        .as_ref()
+                        .map(|p| p.len())
+                        .map_err(|e| e.to_string())
+                );
+
+                tracing::trace!("TASK: about to execute outdated casks");
+                outdated_casks_result = use_case_outdated.execute(PackageType::Cask).await;
+
+                tracing::debug!(
+                    "Outdated casks result: {:?}",
+                    outdated_casks_result
+                        .as_ref()
+                        .map(|p| p.len())
+                        .map_err(|e| e.to_string())
+                );
+            }
+
+            let mut installed = Vec::new();
+            let mut outdated = Vec::new();
+            let mut outdated_complete = true;
+            let mut installed_logs_vec = Vec::new();
+            let mut outdated_logs_vec = Vec::new();
+
+            match installed_formulae_result {
+                Ok(pkgs) => {
+                    let msg = format!("Loaded {} installed formulae", pkgs.len());
+                    installed_logs_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    installed.extend(pkgs);
+                }
+                Err(e) => {
+                    let msg = format!("Error loading installed formulae: {}", e);
+                    installed_logs_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                }
+            }
+
+            match installed_casks_result {
+                Ok(pkgs) => {
+                    let msg = format!("Loaded {} installed casks", pkgs.len());
+                    installed_logs_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    installed.extend(pkgs);
+                }
+                Err(e) => {
+                    let msg = format!("Error loading installed casks: {}", e);
+                    installed_logs_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                }
+            }
+
+            if include_outdated {
+                match outdated_formulae_result {
+                    Ok(pkgs) => {
+                        let msg = format!("Loaded {} outdated formulae", pkgs.len());
+                        outdated_logs_vec.push(msg.clone());
+                        tracing::info!("{}", msg);
+                        outdated.extend(pkgs);
+                    }
+                    Err(e) => {
+                        let msg = format!("Error loading outdated formulae: {}", e);
+                        outdated_logs_vec.push(msg.clone());
+                        tracing::error!("{}", msg);
+                        outdated_complete = false;
+                    }
+                }
+
+                match outdated_casks_result {
+                    Ok(pkgs) => {
+                        let brew_updated = pkgs
+                            .iter()
+                            .filter(|p| !updates_left_to_app(&brew_updated_casks, p))
+                            .count();
+                        let msg = format!("Loaded {} outdated casks", brew_updated);
+                        outdated_logs_vec.push(msg.clone());
+                        tracing::info!("{}", msg);
+                        outdated.extend(pkgs);
+                    }
+                    Err(e) => {
+                        let msg = format!("Error loading outdated casks: {}", e);
+                        outdated_logs_vec.push(msg.clone());
+                        tracing::error!("{}", msg);
+                        outdated_complete = false;
+                    }
+                }
+            }
+
+            installed_logs_vec.push("Finished loading installed packages".to_string());
+            if include_outdated {
+                outdated_logs_vec.push("Finished loading outdated packages".to_string());
+                tracing::info!("Finished loading installed and outdated packages");
+            } else {
+                tracing::info!("Finished loading installed packages");
+            }
+
+            installed_sender.send(TaskOutput::Packages(installed), installed_logs_vec);
+            if let Some(sender) = outdated_sender {
+                sender.send(
+                    TaskOutput::OutdatedPackages {
+                        packages: outdated,
+                        complete: outdated_complete,
+                    },
+                    outdated_logs_vec,
+                );
+            }
+            tracing::trace!("TASK ENDED: load_installed_packages");
+        });
+    }
+
+    /// Reads install dates for the installed packages in the background;
+    /// the list shows "—" until they arrive.
+    fn load_install_dates(&mut self) {
+        let packages = self.merged_packages.packages().to_vec();
+        if packages.is_empty() {
+            return;
+        }
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadInstallDates) else {
+            return;
+        };
+        let use_case = Arc::clone(&self.use_cases.install_dates);
+
+        self.executor.spawn(async move {
+            let install_dates = match use_case.execute(&packages).await {
+                Ok(install_dates) => install_dates,
+                Err(e) => {
+                    tracing::warn!("Could not read install dates: {}", e);
+                    HashMap::new()
+                }
+            };
+            sender.send(TaskOutput::InstallDates(install_dates), Vec::new());
+        });
+    }
+
+    fn handle_update_selected(&mut self, package_names: Vec<String>) {
+        if self.loading_update_all {
+            return;
+        }
+
+        let mut packages_to_update = Vec::new();
+
+        for package_name in package_names {
+            if let Some(package) = self.merged_packages.get_package(&package_name) {
+                packages_to_update.push(package);
+                self.packages_in_operation.insert(package_name);
+            }
+        }
+
+        if packages_to_update.is_empty() {
+            return;
+        }
+
+        let count = packages_to_update.len();
+        self.status_message = format!("Queued {} packages for sequential update", count);
+        self.log_manager
+            .push(format!("Queued {} packages for sequential update", count));
+        tracing::info!("Queued {} packages for sequential update", count);
+
+        self.pending_updates = packages_to_update;
+        self.loading_update_all = true;
+
+        self.process_next_pending_update();
+    }
+
+    fn process_next_pending_update(&mut self) {
+        if self.pending_updates.is_empty() {
+            return;
+        }
+
+        let package = self.pending_updates.remove(0);
+        let remaining = self.pending_updates.len();
+        let total = self.packages_in_operation.len();
+        let completed = total - remaining;
+
+        self.status_message = format!(
+            "Updating {}/{}: {}... ({} remaining)",
+            completed, total, package.name, remaining
+        );
+
+        let msg = format!(
+            "Updating {}/{}: {} ({} remaining)",
+            completed, total, package.name, remaining
+        );
+        self.log_manager.push(msg);
+        tracing::info!(
+            "Processing package {}/{}: {}",
+            completed,
+            total,
+            package.name
+        );
+
+        self.handle_update(package);
+    }
+
+    fn handle_fetch_selected(&mut self, package_names: Vec<String>) {
+        let packages: Vec<Package> = package_names
+            .iter()
+            .filter_map(|name| self.merged_packages.get_package(name))
+            .collect();
+        if packages.is_empty() {
+            return;
+        }
+
+        let progress = ProgressCell::default();
+        let Some(sender) = self.task_manager.start(AsyncTask::FetchPackages {
+            progress: progress.clone(),
+        }) else {
+            return;
+        };
+
+        let msg = format!("Fetching {} packages", packages.len());
+        self.status_message = format!("{}...", msg);
+        self.log_manager.push(msg.clone());
+        tracing::info!("{}", msg);
+
+        let use_case = Arc::clone(&self.use_cases.fetch);
+
+        self.executor.spawn(async move {
+            let mut log_vec = Vec::new();
+            let fetched = match use_case.execute(&packages, &progress).await {
+                Ok(fetched) => fetched,
+                Err(e) => {
+                    let msg = format!("Error fetching packages: {}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    Vec::new()
+                }
+            };
+            let failed: Vec<String> = packages
+                .into_iter()
+                .map(|p| p.name)
+                .filter(|name| !fetched.contains(name))
+                .collect();
+
+            sender.send(TaskOutput::Fetched { fetched, failed }, log_vec);
+        });
+    }
+
+    fn offer_network_retry(&mut self, operation: RetryOperation) {
+        tracing::warn!("{}", failure::NETWORK_UNAVAILABLE);
+        self.status_message = failure::NETWORK_UNAVAILABLE.to_string();
+        self.network_retry = Some(operation);
+    }
+
+    fn retry_after_network_error(&mut self) {
+        let Some(operation) = self.network_retry.take() else {
+            return;
+        };
+        match operation {
+            RetryOperation::Search(query) => {
+                *self.filter_state.search_query_mut() = query;
+                self.handle_search();
+            }
+            RetryOperation::Install(package) => self.handle_install(package),
+            RetryOperation::Update(package) => self.handle_update(package),
+            RetryOperation::UpdateAll(only) => self.handle_update_all(only),
+        }
+    }
+
+    fn retry_with_password(&mut self, password: SecretString) {
+        if let Some(operation) = self.pending_operation.take() {
+            match operation {
+                PendingOperation::Install(package) => {
+                    self.handle_install_with_password(package, password);
+                }
+                PendingOperation::Uninstall(package) => {
+                    self.handle_uninstall_with_password(package, password, false);
+                }
+                PendingOperation::Zap(package) => {
+                    self.handle_uninstall_with_password(package, password, true);
+                }
+                PendingOperation::InstallHomebrew => {
+                    self.handle_install_homebrew(password);
+                }
+            }
+        }
+    }
+
+    /// Installs `package`, first asking for confirmation if it is a cask
+    /// built only for another architecture than this Mac's. A cask whose
+    /// info hasn't loaded yet is checked once it has.
+    fn request_install(&mut self, package: Package) {
+        let Some(host) = CpuArch::host() else {
+            self.handle_install(package);
+            return;
+        };
+        if package.package_type != PackageType::Cask {
+            self.handle_install(package);
+        } else if package.supported_archs.is_none() {
+            self.status_message = format!("Checking which Macs {} supports...", package.name);
+            self.packages_in_operation.insert(package.name.clone());
+            self.load_package_info(package.name.clone(), PackageType::Cask);
+            self.arch_check_pending
+                .insert(package.name.clone(), package);
+        } else if package.runs_natively_on(host) {
+            self.handle_install(package);
+        } else {
+            self.confirm_arch_mismatch = Some(package);
+        }
+    }
+
+    fn show_zap_confirm(&mut self, ctx: &egui::Context) {
+        let Some(package) = &self.confirm_zap else {
+            return;
+        };
+
+        let mut open = true;
+        let mut decided = None;
+        egui::Window::new(format!("Zap {}?", package.name))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "This uninstalls {} and removes all of its associated files: \
+                     preferences, caches, application support data and login items.",
+                    package.name
+                ));
+                ui.add_space(8.0);
+                ui.label(
+                    "Settings and data are gone for good, including any shared with \
+                     other apps from the same developer. This can't be undone.",
+                );
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Cancel").clicked() {
+                            decided = Some(false);
+                        }
+                        if ui.button("Zap").clicked() {
+                            decided = Some(true);
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            decided = Some(false);
+        }
+        if let Some(zap) = decided
+            && let Some(package) = self.confirm_zap.take()
+            && zap
+        {
+            self.handle_zap(package);
+        }
+    }
+
+    fn show_arch_mismatch_confirm(&mut self, ctx: &egui::Context) {
+        let (Some(package), Some(host)) = (&self.confirm_arch_mismatch, CpuArch::host()) else {
+            return;
+        };
+
+        let built_for = package
+            .supported_archs
+            .iter()
+            .flatten()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(" and ");
+        let consequence = match host {
+            CpuArch::Arm => {
+                "It will run under Rosetta 2, which macOS asks to install on first launch \
+                 (or run `softwareupdate --install-rosetta`), and may be slower."
+            }
+            CpuArch::Intel => {
+                "It won't run on this Mac, and brew will likely refuse to install it."
+            }
+        };
+
+        let mut open = true;
+        let mut decided = None;
+        egui::Window::new(format!("Install {}?", package.name))
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(format!(
+                    "{} is built for {} Macs only, and this is an {} Mac.",
+                    package.name, built_for, host
+                ));
+                ui.add_space(8.0);
+                ui.label(consequence);
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Cancel").clicked() {
+                            decided = Some(false);
+                        }
+                        if ui.button("Install Anyway").clicked() {
+                            decided = Some(true);
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            decided = Some(false);
+        }
+        if let Some(install) = decided
+            && let Some(package) = self.confirm_arch_mismatch.take()
+        {
+            if install {
+                self.handle_install(package);
+            } else {
+                self.log_manager
+                    .push(format!("Install of {} cancelled", package.name));
+            }
+        }
+    }
+
+    fn handle_install(&mut self, package: Package) {
+        if self.loading_install {
+            self.queue_operation(QueuedOperation::Install(package));
+            return;
+        }
+
+        let package_name = package.name.clone();
+        self.loading_install = true;
         self.loading = true;
         self.current_install_package = Some(package_name.clone());
         self.packages_in_operation.insert(package_name.clone());
@@ -466,20 +1545,18 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Install {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let progress = ProgressCell::default();
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::tracked_op(OpKind::Install, progress.clone()))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.install);
 
         self.executor.spawn(async move {
-            let result = use_case.execute(package).await;
+            let result = use_case.execute(package.clone(), &progress).await;
 
             let mut log_vec = Vec::new();
             match result {
@@ -487,34 +1564,20 @@ impl BrewstyApp {
                     let msg = format!("Successfully installed {}", package_name);
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} installed successfully", package_name);
-                    }
+                    finish_verified_install(&use_case, &package, sender, log_vec).await;
                 }
                 Err(e) => {
                     let error_str = e.to_string();
                     let msg = format!("Error installing {}: {}", package_name, error_str);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = error_str;
-                    }
+                    sender.finish(false, error_str, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
-    fn handle_install_with_password(&mut self, package: Package, password: String) {
+    fn handle_install_with_password(&mut self, package: Package, password: SecretString) {
         if self.loading_install {
             return;
         }
@@ -533,18 +1596,17 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Install {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let progress = ProgressCell::default();
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::tracked_op(OpKind::Install, progress.clone()))
+        else {
+            return;
+        };
 
         let name = package_name.clone();
         let pkg_type = package_type.clone();
+        let use_case = Arc::clone(&self.use_cases.install);
 
         self.executor.spawn(async move {
             use crate::infrastructure::brew::command::BrewCommand;
@@ -552,7 +1614,7 @@ impl BrewstyApp {
             let mut log_vec = Vec::new();
 
             let brew_result = tokio::task::spawn_blocking(move || {
-                BrewCommand::install_package_with_password(&name, pkg_type, &password)
+                BrewCommand::install_package_with_password(&name, pkg_type, &password, &progress)
             })
             .await;
 
@@ -566,67 +1628,69 @@ impl BrewstyApp {
                     let msg = format!("Successfully installed {}", package_name);
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} installed successfully", package_name);
-                    }
+                    finish_verified_install(&use_case, &package, sender, log_vec).await;
                 }
                 Err(e) => {
                     let error_str = e.to_string();
                     let msg = format!("Error installing {}: {}", package_name, error_str);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = error_str;
-                    }
+                    sender.finish(false, error_str, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
     fn handle_uninstall(&mut self, package: Package) {
         if self.loading_uninstall {
+            self.queue_operation(QueuedOperation::Uninstall(package));
+            return;
+        }
+        self.start_uninstall(package, false);
+    }
+
+    /// Uninstalls a cask with `--zap`, removing its leftover files too.
+    fn handle_zap(&mut self, package: Package) {
+        if self.loading_uninstall {
+            self.queue_operation(QueuedOperation::Zap(package));
             return;
         }
+        self.start_uninstall(package, true);
+    }
 
+    fn start_uninstall(&mut self, package: Package, zap: bool) {
         let package_name = package.name.clone();
         self.loading_uninstall = true;
         self.loading = true;
         self.current_uninstall_package = Some(package_name.clone());
+        self.zapping = zap;
         self.packages_in_operation.insert(package_name.clone());
         self.status_message = format!("Uninstalling {}...", package.name);
 
         let package_type = package.package_type.clone();
-        let initial_msg = format!(
-            "Uninstalling package: {} ({:?})",
-            package_name, package_type
-        );
+        let initial_msg = if zap {
+            format!("Zapping cask: {}", package_name)
+        } else {
+            format!(
+                "Uninstalling package: {} ({:?})",
+                package_name, package_type
+            )
+        };
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Uninstall {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let Some(sender) = self.task_manager.start(AsyncTask::op(OpKind::Uninstall)) else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.uninstall);
 
         self.executor.spawn(async move {
-            let result = use_case.execute(package).await;
+            let result = if zap {
+                use_case.zap(package).await
+            } else {
+                use_case.execute(package).await
+            };
 
             let mut log_vec = Vec::new();
             match result {
@@ -634,34 +1698,29 @@ impl BrewstyApp {
                     let msg = format!("Successfully uninstalled {}", package_name);
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} uninstalled successfully", package_name);
-                    }
+                    sender.finish(
+                        true,
+                        format!("{} uninstalled successfully", package_name),
+                        log_vec,
+                    );
                 }
                 Err(e) => {
                     let error_str = e.to_string();
                     let msg = format!("Error uninstalling {}: {}", package_name, error_str);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = error_str;
-                    }
+                    sender.finish(false, error_str, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
-    fn handle_uninstall_with_password(&mut self, package: Package, password: String) {
+    fn handle_uninstall_with_password(
+        &mut self,
+        package: Package,
+        password: SecretString,
+        zap: bool,
+    ) {
         if self.loading_uninstall {
             return;
         }
@@ -670,6 +1729,7 @@ impl BrewstyApp {
         self.loading_uninstall = true;
         self.loading = true;
         self.current_uninstall_package = Some(package_name.clone());
+        self.zapping = zap;
         self.status_message = format!("Uninstalling {} (with password)...", package.name);
 
         let package_type = package.package_type.clone();
@@ -680,15 +1740,9 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Uninstall {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let Some(sender) = self.task_manager.start(AsyncTask::op(OpKind::Uninstall)) else {
+            return;
+        };
 
         let name = package_name.clone();
         let pkg_type = package_type.clone();
@@ -699,7 +1753,11 @@ impl BrewstyApp {
             let mut log_vec = Vec::new();
 
             let brew_result = tokio::task::spawn_blocking(move || {
-                BrewCommand::uninstall_package_with_password(&name, pkg_type, &password)
+                if zap {
+                    BrewCommand::zap_cask_with_password(&name, &password)
+                } else {
+                    BrewCommand::uninstall_package_with_password(&name, pkg_type, &password)
+                }
             })
             .await;
 
@@ -713,35 +1771,26 @@ impl BrewstyApp {
                     let msg = format!("Successfully uninstalled {}", package_name);
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} uninstalled successfully", package_name);
-                    }
+                    sender.finish(
+                        true,
+                        format!("{} uninstalled successfully", package_name),
+                        log_vec,
+                    );
                 }
                 Err(e) => {
                     let error_str = e.to_string();
                     let msg = format!("Error uninstalling {}: {}", package_name, error_str);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = error_str;
-                    }
+                    sender.finish(false, error_str, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
     fn handle_update(&mut self, package: Package) {
         if self.loading_update {
+            self.queue_operation(QueuedOperation::Update(package));
             return;
         }
 
@@ -757,20 +1806,18 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Update {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let progress = ProgressCell::default();
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::tracked_op(OpKind::Update, progress.clone()))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.update);
 
         self.executor.spawn(async move {
-            let result = use_case.execute(&package).await;
+            let result = use_case.execute(&package, &progress).await;
 
             let mut log_vec = Vec::new();
             match result {
@@ -778,30 +1825,74 @@ impl BrewstyApp {
                     let msg = format!("Successfully updated {}", package_name);
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} updated successfully", package_name);
-                    }
+                    sender.finish(
+                        true,
+                        format!("{} updated successfully", package_name),
+                        log_vec,
+                    );
                 }
                 Err(e) => {
                     let msg = format!("Error updating {}: {}", package_name, e);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg, log_vec);
                 }
             }
+        });
+    }
 
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
+    fn queue_operation(&mut self, operation: QueuedOperation) {
+        let name = &operation.package().name;
+        let running = match operation {
+            QueuedOperation::Install(_) => &self.current_install_package,
+            QueuedOperation::Uninstall(_) | QueuedOperation::Zap(_) => {
+                &self.current_uninstall_package
             }
-        });
+            QueuedOperation::Update(_) => &self.current_update_package,
+        };
+        let queued = self
+            .queued_operations
+            .iter()
+            .any(|q| q.verb() == operation.verb() && q.package().name == *name);
+        if running.as_ref() == Some(name) || queued {
+            return;
+        }
+        self.status_message = format!("Queued: {}", name);
+        let msg = format!(
+            "Queued {} of {} until the current one finishes",
+            operation.verb(),
+            name
+        );
+        self.log_manager.push(msg.clone());
+        tracing::info!("{}", msg);
+        self.queued_operations.push_back(operation);
+    }
+
+    /// Starts each queued operation whose kind is no longer running. Waits
+    /// while a failed one is waiting for the password to retry with.
+    fn start_queued_operations(&mut self) {
+        if self.pending_operation.is_some() {
+            return;
+        }
+        let mut still_waiting = VecDeque::new();
+        while let Some(operation) = self.queued_operations.pop_front() {
+            let busy = match operation {
+                QueuedOperation::Install(_) => self.loading_install,
+                QueuedOperation::Uninstall(_) | QueuedOperation::Zap(_) => self.loading_uninstall,
+                QueuedOperation::Update(_) => self.loading_update,
+            };
+            if busy {
+                still_waiting.push_back(operation);
+                continue;
+            }
+            match operation {
+                QueuedOperation::Install(package) => self.handle_install(package),
+                QueuedOperation::Uninstall(package) => self.handle_uninstall(package),
+                QueuedOperation::Zap(package) => self.handle_zap(package),
+                QueuedOperation::Update(package) => self.handle_update(package),
+            }
+        }
+        self.queued_operations = still_waiting;
     }
 
     fn handle_pin(&mut self, package: Package) {
@@ -815,16 +1906,12 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Pin {
-            package_name: package.name.clone(),
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::package_op(OpKind::Pin, package.name.clone()))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.pin);
         let package_clone = package.clone();
@@ -833,27 +1920,15 @@ impl BrewstyApp {
             match use_case.execute(package_clone).await {
                 Ok(_) => {
                     let msg = format!("Successfully pinned {}", package_name);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} pinned successfully", package_name);
-                    }
+                    sender.finish(
+                        true,
+                        format!("{} pinned successfully", package_name),
+                        vec![msg],
+                    );
                 }
                 Err(e) => {
                     let msg = format!("Error pinning {}: {}", package_name, e);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg.clone(), vec![msg]);
                 }
             }
         });
@@ -870,16 +1945,12 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::Unpin {
-            package_name: package.name.clone(),
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::package_op(OpKind::Unpin, package.name.clone()))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.unpin);
         let package_clone = package.clone();
@@ -888,27 +1959,15 @@ impl BrewstyApp {
             match use_case.execute(package_clone).await {
                 Ok(_) => {
                     let msg = format!("Successfully unpinned {}", package_name);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = format!("{} unpinned successfully", package_name);
-                    }
+                    sender.finish(
+                        true,
+                        format!("{} unpinned successfully", package_name),
+                        vec![msg],
+                    );
                 }
                 Err(e) => {
                     let msg = format!("Error unpinning {}: {}", package_name, e);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg.clone(), vec![msg]);
                 }
             }
         });
@@ -919,39 +1978,480 @@ impl BrewstyApp {
             return;
         }
 
+        let use_case = Arc::clone(&self.use_cases.list_services);
+
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadServices) else {
+            return;
+        };
+
         self.loading_services = true;
         self.status_message = "Loading services...".to_string();
         self.log_manager.push("Loading brew services".to_string());
         tracing::info!("Loading brew services");
 
-        let use_case = Arc::clone(&self.use_cases.list_services);
+        self.executor.spawn(async move {
+            match use_case.execute().await {
+                Ok(service_list) => {
+                    let msg = format!("Loaded {} services", service_list.len());
+                    tracing::info!("{}", msg);
+                    sender.send(TaskOutput::Services(Ok(service_list)), vec![msg]);
+                }
+                Err(e) => {
+                    let msg = format!("Error loading services: {}", e);
+                    tracing::error!("{}", msg);
+                    sender.send(TaskOutput::Services(Err(e.to_string())), vec![msg]);
+                }
+            }
+        });
+    }
+
+    /// Runs `op` on `names` one after another, then sums up what failed.
+    fn queue_service_ops(&mut self, op: BulkServiceOp, names: Vec<String>) {
+        self.services_in_operation.extend(names.iter().cloned());
+        let msg = format!("Queued {} service operations", names.len());
+        self.log_manager.push(msg.clone());
+        tracing::info!("{}", msg);
+
+        let ops = names
+            .iter()
+            .cloned()
+            .map(match op {
+                BulkServiceOp::Start => ServiceAction::Start,
+                BulkServiceOp::Stop => ServiceAction::Stop,
+                BulkServiceOp::Restart => ServiceAction::Restart,
+            })
+            .collect();
+        self.service_batch = Some(ServiceBatch {
+            op,
+            names,
+            failed: Vec::new(),
+        });
+        self.pending_service_ops = Some(ops);
+        self.process_next_service_op();
+    }
+
+    fn process_next_service_op(&mut self) {
+        let Some(ops) = &mut self.pending_service_ops else {
+            return;
+        };
+        if ops.is_empty() {
+            self.pending_service_ops = None;
+            self.finish_service_batch();
+            return;
+        }
+        match ops.remove(0) {
+            ServiceAction::Start(name) => self.handle_start_service(name),
+            ServiceAction::Stop(name) => self.handle_stop_service(name),
+            ServiceAction::Restart(name) => self.handle_restart_service(name),
+            op => tracing::warn!("{:?} can't be queued", op),
+        }
+    }
+
+    /// Starts or stops the ticked services one after another, skipping
+    /// those already in the state asked for.
+    fn handle_selected_services(&mut self, op: BulkServiceOp, names: Vec<String>) {
+        let target = match op {
+            BulkServiceOp::Stop => ServiceStatus::Stopped,
+            _ => ServiceStatus::Started,
+        };
+        let (skipped, names): (Vec<_>, Vec<_>) = names
+            .into_iter()
+            .partition(|name| self.service_list.status_of(name) == Some(&target));
+        for name in skipped {
+            self.log_manager
+                .push(format!("Skipping {}: already {}", name, op.done()));
+        }
+        self.service_list.clear_selection();
+        if !names.is_empty() {
+            self.queue_service_ops(op, names);
+        }
+    }
+
+    /// Stops every running service with one `brew services stop --all`,
+    /// falling back to stopping `names` one at a time if that fails.
+    fn handle_stop_all_services(&mut self, names: Vec<String>) {
+        let Some(sender) = self.task_manager.start(AsyncTask::StopAllServices) else {
+            return;
+        };
+
+        self.services_in_operation.extend(names.iter().cloned());
+        self.status_message = "Stopping all services...".to_string();
+        self.log_manager
+            .push("Stopping all running services".to_string());
+        tracing::info!("Stopping all running services");
+        self.service_batch = Some(ServiceBatch {
+            op: BulkServiceOp::Stop,
+            names,
+            failed: Vec::new(),
+        });
+
+        let use_case = Arc::clone(&self.use_cases.stop_service);
+
+        self.executor.spawn(async move {
+            match use_case.execute_all().await {
+                Ok(_) => sender.finish(true, "Stopped all services".to_string(), Vec::new()),
+                Err(e) => {
+                    let msg = format!("brew services stop --all failed: {:#}", e);
+                    tracing::warn!("{}", msg);
+                    sender.finish(false, msg.clone(), vec![msg]);
+                }
+            }
+        });
+    }
+
+    fn finish_stop_all_services(&mut self, success: bool) {
+        let names = self
+            .service_batch
+            .as_ref()
+            .map(|batch| batch.names.clone())
+            .unwrap_or_default();
+        for name in &names {
+            self.services_in_operation.remove(name);
+        }
+
+        if success {
+            for name in &names {
+                self.service_list
+                    .update_service_status(name, ServiceStatus::Stopped);
+            }
+            self.finish_service_batch();
+            // --all also stops services that started after the list loaded
+            self.refresh_services();
+        } else {
+            self.log_manager
+                .push("Stopping the services one at a time instead".to_string());
+            self.queue_service_ops(BulkServiceOp::Stop, names);
+        }
+    }
+
+    /// Reports how a batch of service operations went once it's done,
+    /// naming the services that failed.
+    fn finish_service_batch(&mut self) {
+        let Some(batch) = self.service_batch.take() else {
+            return;
+        };
+        let total = batch.names.len();
+        if batch.failed.is_empty() {
+            let msg = format!("All {} services {}", total, batch.op.done());
+            tracing::info!("{}", msg);
+            self.log_manager.push(msg.clone());
+            self.record_result(true, &msg);
+            self.status_message = msg;
+        } else {
+            let msg = format!(
+                "Couldn't {} {} of {} services: {}",
+                batch.op.verb(),
+                batch.failed.len(),
+                total,
+                batch.failed.join(", ")
+            );
+            tracing::warn!("{}", msg);
+            self.log_manager.push(msg.clone());
+            self.record_result(false, &msg);
+            self.show_error_toast(msg);
+        }
+    }
+
+    /// Keeps `message` as the last operation's outcome for the output panel.
+    fn record_result(&mut self, success: bool, message: &str) {
+        self.last_result = Some((success, message.to_string(), Instant::now()));
+    }
+
+    /// Marks `service_name` as `status` if the operation succeeded, rather
+    /// than reloading every service.
+    fn finish_service_op(
+        &mut self,
+        service_name: &str,
+        success: bool,
+        message: String,
+        status: ServiceStatus,
+    ) {
+        self.services_in_operation.remove(service_name);
+        self.record_result(success, &message);
+        self.status_message = message;
+        if !success && let Some(batch) = &mut self.service_batch {
+            batch.failed.push(service_name.to_string());
+        }
+        if success {
+            if status == ServiceStatus::Started {
+                self.service_rechecks
+                    .retain(|(name, _)| name != service_name);
+                self.service_rechecks.push((
+                    service_name.to_string(),
+                    Instant::now() + SERVICE_RECHECK_DELAY,
+                ));
+            }
+            self.service_list
+                .update_service_status(service_name, status);
+        }
+        self.process_next_service_op();
+    }
+
+    /// Reloads each just-started service once its recheck is due.
+    fn recheck_started_services(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.service_rechecks)
+            .into_iter()
+            .partition(|(_, at)| *at <= now);
+        self.service_rechecks = waiting;
+        if let Some(next) = self.service_rechecks.iter().map(|(_, at)| *at).min() {
+            ctx.request_repaint_after(next - now);
+        }
+        for (name, _) in due {
+            self.check_service(name);
+        }
+    }
+
+    fn check_service(&mut self, service_name: String) {
+        let Some(sender) = self.task_manager.start(AsyncTask::CheckService {
+            service_name: service_name.clone(),
+        }) else {
+            return;
+        };
 
-        let services = Arc::new(Mutex::new(Vec::new()));
-        let logs = Arc::new(Mutex::new(Vec::new()));
+        let use_case = Arc::clone(&self.use_cases.service_info);
 
-        self.task_manager.set_active_task(AsyncTask::LoadServices {
-            services: Arc::clone(&services),
-            logs: Arc::clone(&logs),
+        self.executor.spawn(async move {
+            let result = use_case
+                .execute(&service_name)
+                .await
+                .map(|info| info.service)
+                .map_err(|e| format!("{:#}", e));
+            sender.send(TaskOutput::Service(result), Vec::new());
         });
+    }
+
+    /// Warns when a service that was started has already stopped or failed,
+    /// unless it was stopped on purpose in the meantime.
+    fn finish_service_check(&mut self, service_name: &str, result: Result<Service, String>) {
+        let service = match result {
+            Ok(service) => service,
+            Err(e) => {
+                tracing::debug!("Couldn't check service {}: {}", service_name, e);
+                return;
+            }
+        };
+        let still_expected = self.service_list.status_of(service_name)
+            == Some(&ServiceStatus::Started)
+            && !self.services_in_operation.contains(service_name);
+        let problem = match service.status {
+            ServiceStatus::Error => Some("failed"),
+            ServiceStatus::Stopped => Some("stopped"),
+            _ => None,
+        };
+        let log_path = service.log_path.clone().map(PathBuf::from);
+        self.service_list.update_service(service);
+
+        if still_expected && let Some(problem) = problem {
+            self.show_error_toast(format!("{} {} right after starting", service_name, problem));
+            self.toast_log = log_path;
+        }
+    }
+
+    /// Reloads the services every `services_refresh_secs` while their tab is
+    /// open and nothing is running, to pick up changes made outside. Service
+    /// operations update their row in place, so this is all that reloads them.
+    fn reload_services_periodically(&mut self, ctx: &egui::Context) {
+        let Some(loaded_at) = self.services_loaded_at else {
+            return;
+        };
+        if self.config.services_refresh_secs == 0 || !self.tab_manager.is_current(Tab::Services) {
+            return;
+        }
+        let interval = Duration::from_secs(self.config.services_refresh_secs);
+        let busy = !self.services_in_operation.is_empty() || self.pending_service_ops.is_some();
+        if loaded_at.elapsed() >= interval {
+            if !busy {
+                self.refresh_services();
+            }
+        } else {
+            ctx.request_repaint_after(interval - loaded_at.elapsed());
+        }
+    }
+
+    /// Reloads the services behind the list that is shown, so its scroll
+    /// position and selection stay, and only logs at debug level.
+    fn refresh_services(&mut self) {
+        if self.loading_services || self.refreshing_services {
+            return;
+        }
+
+        let use_case = Arc::clone(&self.use_cases.list_services);
+
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadServices) else {
+            return;
+        };
+
+        self.refreshing_services = true;
+        tracing::debug!("Refreshing brew services");
 
         self.executor.spawn(async move {
             match use_case.execute().await {
                 Ok(service_list) => {
-                    let msg = format!("Loaded {} services", service_list.len());
-                    tracing::info!("{}", msg);
-                    if let Ok(mut services_guard) = services.lock() {
-                        *services_guard = service_list;
-                    }
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg];
+                    tracing::debug!("Refreshed {} services", service_list.len());
+                    sender.send(TaskOutput::Services(Ok(service_list)), Vec::new());
+                }
+                Err(e) => {
+                    let msg = format!("Error refreshing services: {}", e);
+                    tracing::warn!("{}", msg);
+                    sender.send(TaskOutput::Services(Err(e.to_string())), vec![msg]);
+                }
+            }
+        });
+    }
+
+    fn show_all_services_confirm(&mut self, ctx: &egui::Context) {
+        let Some((op, names)) = &self.confirm_all_services else {
+            return;
+        };
+
+        let (title, button, consequence) = match op {
+            // Services brew lists as `none` were never started, and starting
+            // one registers it to run at login
+            BulkServiceOp::Start => (
+                "Start all stopped services?",
+                "Start All",
+                "Each will also start at login from now on, including those \
+                 that were never started:",
+            ),
+            BulkServiceOp::Restart => (
+                "Restart all running services?",
+                "Restart All",
+                "Databases and servers among them will drop their connections \
+                 while they restart:",
+            ),
+            BulkServiceOp::Stop => (
+                "Stop all running services?",
+                "Stop All",
+                "Databases and servers among them will stop accepting connections:",
+            ),
+        };
+
+        let mut open = true;
+        let mut decided = None;
+        egui::Window::new(title)
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                let state = match op {
+                    BulkServiceOp::Start => "stopped",
+                    _ => "running",
+                };
+                ui.label(format!(
+                    "This {}s {} {} services. {}",
+                    op.verb(),
+                    names.len(),
+                    state,
+                    consequence
+                ));
+                ui.add_space(4.0);
+                for name in names {
+                    ui.monospace(name);
+                }
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Cancel").clicked() {
+                            decided = Some(false);
+                        }
+                        if ui.button(button).clicked() {
+                            decided = Some(true);
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            decided = Some(false);
+        }
+        if let Some(confirmed) = decided
+            && let Some((op, names)) = self.confirm_all_services.take()
+            && confirmed
+        {
+            match op {
+                BulkServiceOp::Stop => self.handle_stop_all_services(names),
+                _ => self.queue_service_ops(op, names),
+            }
+        }
+    }
+
+    /// brew can't list what `brew services cleanup` would remove, so this
+    /// explains what it does instead; what it removed ends up in the log.
+    fn show_services_cleanup_confirm(&mut self, ctx: &egui::Context) {
+        if !self.confirm_services_cleanup {
+            return;
+        }
+
+        let mut open = true;
+        let mut decided = None;
+        egui::Window::new("Clean up stale services?")
+            .collapsible(false)
+            .resizable(false)
+            .default_width(420.0)
+            .open(&mut open)
+            .show(ctx, |ui| {
+                ui.label(
+                    "This runs brew services cleanup, which stops services whose formula \
+                     is no longer installed and deletes the plists they left behind. \
+                     brew can't preview which ones that is; what it removed will be \
+                     listed in the log.",
+                );
+                ui.add_space(12.0);
+                ui.horizontal(|ui| {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        if ui.button("Cancel").clicked() {
+                            decided = Some(false);
+                        }
+                        if ui.button("Clean Up").clicked() {
+                            decided = Some(true);
+                        }
+                    });
+                });
+            });
+
+        if !open {
+            decided = Some(false);
+        }
+        if let Some(confirmed) = decided {
+            self.confirm_services_cleanup = false;
+            if confirmed {
+                self.handle_cleanup_services();
+            }
+        }
+    }
+
+    fn handle_cleanup_services(&mut self) {
+        let Some(sender) = self.task_manager.start(AsyncTask::CleanupServices) else {
+            return;
+        };
+
+        self.cleaning_services = true;
+        self.status_message = "Cleaning up stale services...".to_string();
+        self.log_manager
+            .push("Cleaning up stale services".to_string());
+        tracing::info!("Cleaning up stale services");
+
+        let use_case = Arc::clone(&self.use_cases.cleanup_services);
+
+        self.executor.spawn(async move {
+            match use_case.execute().await {
+                Ok(output) => {
+                    let mut logs: Vec<String> = output
+                        .lines()
+                        .filter(|line| !line.trim().is_empty())
+                        .map(str::to_string)
+                        .collect();
+                    if logs.is_empty() {
+                        logs.push("brew services cleanup found nothing to remove".to_string());
                     }
+                    sender.finish(true, "Cleaned up stale services".to_string(), logs);
                 }
                 Err(e) => {
-                    let msg = format!("Error loading services: {}", e);
+                    let msg = format!("Error cleaning up services: {:#}", e);
                     tracing::error!("{}", msg);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg];
-                    }
+                    sender.finish(false, msg, Vec::new());
                 }
             }
         });
@@ -965,16 +2465,11 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::StartService {
+        let Some(sender) = self.task_manager.start(AsyncTask::StartService {
             service_name: service_name.clone(),
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        }) else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.start_service);
         let service_name_clone = service_name.clone();
@@ -982,28 +2477,12 @@ impl BrewstyApp {
         self.executor.spawn(async move {
             match use_case.execute(&service_name_clone).await {
                 Ok(_) => {
-                    let msg = format!("Successfully started service {}", service_name);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    let msg = format!("Successfully started service {}", service_name);
+                    sender.finish(true, msg.clone(), vec![msg]);
                 }
                 Err(e) => {
                     let msg = format!("Error starting service {}: {}", service_name, e);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg.clone(), vec![msg]);
                 }
             }
         });
@@ -1017,16 +2496,11 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::StopService {
+        let Some(sender) = self.task_manager.start(AsyncTask::StopService {
             service_name: service_name.clone(),
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        }) else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.stop_service);
         let service_name_clone = service_name.clone();
@@ -1035,27 +2509,11 @@ impl BrewstyApp {
             match use_case.execute(&service_name_clone).await {
                 Ok(_) => {
                     let msg = format!("Successfully stopped service {}", service_name);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(true, msg.clone(), vec![msg]);
                 }
                 Err(e) => {
                     let msg = format!("Error stopping service {}: {}", service_name, e);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg.clone(), vec![msg]);
                 }
             }
         });
@@ -1069,17 +2527,11 @@ impl BrewstyApp {
         self.log_manager.push(initial_msg.clone());
         tracing::info!("{}", initial_msg);
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager
-            .set_active_task(AsyncTask::RestartService {
-                service_name: service_name.clone(),
-                success: Arc::clone(&success),
-                logs: Arc::clone(&logs),
-                message: Arc::clone(&message),
-            });
+        let Some(sender) = self.task_manager.start(AsyncTask::RestartService {
+            service_name: service_name.clone(),
+        }) else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.restart_service);
         let service_name_clone = service_name.clone();
@@ -1088,40 +2540,68 @@ impl BrewstyApp {
             match use_case.execute(&service_name_clone).await {
                 Ok(_) => {
                     let msg = format!("Successfully restarted service {}", service_name);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(true, msg.clone(), vec![msg]);
                 }
                 Err(e) => {
                     let msg = format!("Error restarting service {}: {}", service_name, e);
-                    if let Ok(mut logs_guard) = logs.lock() {
-                        *logs_guard = vec![msg.clone()];
-                    }
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg.clone(), vec![msg]);
                 }
             }
         });
     }
 
-    fn handle_export_packages(&mut self) {
+    /// Asks where to save the diagnostics, then collects them.
+    fn handle_save_diagnostics(&mut self) {
+        if self.loading_issue_report {
+            return;
+        }
+        let file_dialog = rfd::FileDialog::new()
+            .add_filter("Text files", &["txt"])
+            .set_file_name("brewsty_diagnostics.txt");
+        if let Some(path) = file_dialog.save_file() {
+            self.handle_collect_diagnostics(DiagnosticsDestination::File(path));
+        }
+    }
+
+    fn handle_collect_diagnostics(&mut self, destination: DiagnosticsDestination) {
+        if self.loading_issue_report {
+            return;
+        }
+
+        self.loading_issue_report = true;
+        self.status_message = "Collecting diagnostics...".to_string();
+        tracing::info!("Collecting diagnostics ({:?})", destination);
+        self.diagnostics_destination = destination;
+
+        let Some(sender) = self.task_manager.start(AsyncTask::CollectDiagnostics) else {
+            return;
+        };
+
+        self.executor.spawn(async move {
+            let diagnostics = tokio::task::spawn_blocking(SystemDiagnostics::collect)
+                .await
+                .unwrap_or_else(|e| SystemDiagnostics {
+                    brew_version: format!("unavailable: {}", e),
+                    brew_config: String::new(),
+                    os_version: String::new(),
+                });
+            sender.send(TaskOutput::Diagnostics(diagnostics), Vec::new());
+        });
+    }
+
+    fn handle_export_packages(&mut self, format: ExportFormat) {
         if self.loading_export {
             return;
         }
 
-        let file_dialog = rfd::FileDialog::new()
-            .add_filter("JSON files", &["json"])
-            .set_file_name("brewsty_packages.json");
+        let file_dialog = match format {
+            ExportFormat::Json => rfd::FileDialog::new()
+                .add_filter("JSON files", &["json"])
+                .set_file_name("brewsty_packages.json"),
+            ExportFormat::Markdown => rfd::FileDialog::new()
+                .add_filter("Markdown files", &["md"])
+                .set_file_name("brewsty_packages.md"),
+        };
 
         if let Some(path) = file_dialog.save_file() {
             self.loading_export = true;
@@ -1131,23 +2611,18 @@ impl BrewstyApp {
                 .push(format!("Exporting packages to: {}", path.display()));
             tracing::info!("Exporting packages to: {}", path.display());
 
-            let success = Arc::new(Mutex::new(None));
-            let logs = Arc::new(Mutex::new(Vec::new()));
-            let message = Arc::new(Mutex::new(String::new()));
-
-            self.task_manager
-                .set_active_task(AsyncTask::ExportPackages {
-                    success: Arc::clone(&success),
-                    logs: Arc::clone(&logs),
-                    message: Arc::clone(&message),
-                });
+            let Some(sender) = self.task_manager.start(AsyncTask::ExportPackages) else {
+                return;
+            };
 
             let use_case = Arc::clone(&self.use_cases.export_packages);
             let path_display = path.display().to_string();
 
             self.executor.spawn(async move {
-                let result: anyhow::Result<crate::domain::entities::PackageList> =
-                    use_case.execute(&path).await;
+                let result: anyhow::Result<crate::domain::entities::PackageList> = match format {
+                    ExportFormat::Json => use_case.execute(&path).await,
+                    ExportFormat::Markdown => use_case.execute_markdown(&path).await,
+                };
 
                 let mut log_vec = Vec::new();
                 match result {
@@ -1159,33 +2634,86 @@ impl BrewstyApp {
                         );
                         log_vec.push(msg.clone());
                         tracing::info!("{}", msg);
-                        if let Ok(mut success_guard) = success.lock() {
-                            *success_guard = Some(true);
-                        }
-                        if let Ok(mut message_guard) = message.lock() {
-                            *message_guard = "Packages exported successfully".to_string();
-                        }
+                        sender.finish(true, "Packages exported successfully".to_string(), log_vec);
                     }
                     Err(e) => {
                         let msg = format!("Error exporting packages: {}", e);
                         log_vec.push(msg.clone());
                         tracing::error!("{}", msg);
-                        if let Ok(mut success_guard) = success.lock() {
-                            *success_guard = Some(false);
-                        }
-                        if let Ok(mut message_guard) = message.lock() {
-                            *message_guard = msg;
-                        }
+                        sender.finish(false, msg, log_vec);
                     }
                 }
-
-                if let Ok(mut logs_guard) = logs.lock() {
-                    *logs_guard = log_vec;
-                }
             });
         }
     }
 
+    /// Exports the loaded installed packages, so no brew call is needed.
+    fn handle_export_csv(&mut self) {
+        if self.loading_export {
+            return;
+        }
+        if self.merged_packages.packages().is_empty() {
+            self.log_manager
+                .push("No installed packages loaded to export".to_string());
+            return;
+        }
+
+        let file_dialog = rfd::FileDialog::new()
+            .add_filter("CSV files", &["csv"])
+            .set_file_name("brewsty_packages.csv");
+
+        let Some(path) = file_dialog.save_file() else {
+            return;
+        };
+
+        let packages: Vec<Package> = self
+            .merged_packages
+            .packages()
+            .iter()
+            .map(|package| {
+                let mut package = package.clone();
+                package.outdated = self.merged_packages.is_outdated(&package.name);
+                package
+            })
+            .collect();
+        let package_list = crate::domain::entities::PackageList::from_packages(&packages);
+
+        self.loading_export = true;
+        self.loading = true;
+        self.status_message = "Exporting packages...".to_string();
+        self.log_manager
+            .push(format!("Exporting packages to: {}", path.display()));
+        tracing::info!("Exporting packages to: {}", path.display());
+
+        let Some(sender) = self.task_manager.start(AsyncTask::ExportPackages) else {
+            return;
+        };
+
+        let use_case = Arc::clone(&self.use_cases.export_packages);
+
+        self.executor.spawn(async move {
+            let mut log_vec = Vec::new();
+            match use_case.write_csv(&package_list, &path).await {
+                Ok(()) => {
+                    let msg = format!(
+                        "Successfully exported {} packages to {}",
+                        package_list.total_count(),
+                        path.display()
+                    );
+                    log_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    sender.finish(true, "Packages exported successfully".to_string(), log_vec);
+                }
+                Err(e) => {
+                    let msg = format!("Error exporting packages: {}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    sender.finish(false, msg, log_vec);
+                }
+            }
+        });
+    }
+
     fn handle_import_packages(&mut self) {
         if self.loading_import {
             return;
@@ -1196,119 +2724,351 @@ impl BrewstyApp {
             .set_file_name("brewsty_packages.json");
 
         if let Some(path) = file_dialog.pick_file() {
-            self.loading_import = true;
-            self.loading = true;
-            self.status_message = "Importing packages...".to_string();
-            self.log_manager
-                .push(format!("Importing packages from: {}", path.display()));
-            tracing::info!("Importing packages from: {}", path.display());
+            self.import_packages_from(path);
+        }
+    }
 
-            let success = Arc::new(Mutex::new(None));
-            let logs = Arc::new(Mutex::new(Vec::new()));
-            let message = Arc::new(Mutex::new(String::new()));
+    fn import_packages_from(&mut self, path: PathBuf) {
+        if self.loading_import {
+            return;
+        }
 
-            self.task_manager
-                .set_active_task(AsyncTask::ImportPackages {
-                    success: Arc::clone(&success),
-                    logs: Arc::clone(&logs),
-                    message: Arc::clone(&message),
-                });
+        self.loading_import = true;
+        self.loading = true;
+        self.status_message = "Importing packages...".to_string();
+        self.log_manager
+            .push(format!("Importing packages from: {}", path.display()));
+        tracing::info!("Importing packages from: {}", path.display());
 
-            let use_case = Arc::clone(&self.use_cases.import_packages);
-            let path_display = path.display().to_string();
+        let Some(sender) = self.task_manager.start(AsyncTask::ImportPackages) else {
+            return;
+        };
 
-            self.executor.spawn(async move {
-                let result = use_case.execute(&path).await;
+        let use_case = Arc::clone(&self.use_cases.import_packages);
+        let export = Arc::clone(&self.use_cases.export_packages);
+        let snapshot_dir = self.config_repo.snapshot_dir();
+        let path_display = path.display().to_string();
 
-                let mut log_vec = Vec::new();
-                match result {
-                    Ok(_) => {
-                        let msg = format!("Successfully imported packages from {}", path_display);
-                        log_vec.push(msg.clone());
-                        tracing::info!("{}", msg);
-                        if let Ok(mut success_guard) = success.lock() {
-                            *success_guard = Some(true);
-                        }
-                        if let Ok(mut message_guard) = message.lock() {
-                            *message_guard =
-                                "Packages imported successfully. Reloading package list..."
-                                    .to_string();
-                        }
-                    }
-                    Err(e) => {
-                        let msg = format!("Error importing packages: {}", e);
-                        log_vec.push(msg.clone());
-                        tracing::error!("{}", msg);
-                        if let Ok(mut success_guard) = success.lock() {
-                            *success_guard = Some(false);
-                        }
-                        if let Ok(mut message_guard) = message.lock() {
-                            *message_guard = msg;
-                        }
+        self.executor.spawn(async move {
+            let mut log_vec = Vec::new();
+            let result = match use_case.read(&path).await {
+                Ok(package_list) => {
+                    if package_list.total_count() >= SNAPSHOT_MIN_IMPORT {
+                        take_snapshot(&export, &snapshot_dir, "import", &mut log_vec).await;
                     }
+                    use_case.execute(&package_list).await
+                }
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(_) => {
+                    let msg = format!("Successfully imported packages from {}", path_display);
+                    log_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    sender.finish(
+                        true,
+                        "Packages imported successfully. Reloading package list...".to_string(),
+                        log_vec,
+                    );
+                }
+                Err(e) => {
+                    let msg = format!("Error importing packages: {}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    sender.finish(false, msg, log_vec);
                 }
+            }
+        });
+    }
+
+    /// Diffs an export the user picks against what is installed now,
+    /// without writing a file.
+    fn handle_compare_with_export(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("JSON files", &["json"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let Some(sender) = self.task_manager.start(AsyncTask::ComparePackageList) else {
+            return;
+        };
+
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        self.compare_modal.show_loading(file_name);
+        tracing::info!("Comparing installed packages with: {}", path.display());
+
+        let import = Arc::clone(&self.use_cases.import_packages);
+        let export = Arc::clone(&self.use_cases.export_packages);
+
+        self.executor.spawn(async move {
+            let result = match import.read(&path).await {
+                Ok(old) => export.current().await.map(|current| old.diff(&current)),
+                Err(e) => Err(e),
+            };
+            let mut logs = Vec::new();
+            if let Err(e) = &result {
+                let msg = format!("Could not compare with {}: {:#}", path.display(), e);
+                tracing::error!("{}", msg);
+                logs.push(msg);
+            }
+            sender.send(
+                TaskOutput::PackageListDiff(result.map_err(|e| format!("{:#}", e))),
+                logs,
+            );
+        });
+    }
+
+    /// Writes the scheduled export on startup and then every interval, when
+    /// it is turned on in Settings.
+    fn export_periodically(&mut self, ctx: &egui::Context) {
+        let Some(dir) = self.config.auto_export.active_dir().cloned() else {
+            return;
+        };
+        let interval = self.config.auto_export.interval.duration();
+        if let Some(at) = self.auto_exported_at
+            && at.elapsed() < interval
+        {
+            ctx.request_repaint_after(interval - at.elapsed());
+            return;
+        }
 
-                if let Ok(mut logs_guard) = logs.lock() {
-                    *logs_guard = log_vec;
+        let Some(sender) = self.task_manager.start(AsyncTask::AutoExport) else {
+            return;
+        };
+        self.auto_exported_at = Some(Instant::now());
+
+        let keep = self.config.auto_export.keep;
+        let export = Arc::clone(&self.use_cases.export_packages);
+
+        self.executor.spawn(async move {
+            // An unmounted drive or a deleted folder is skipped until it's back
+            if !tokio::fs::metadata(&dir).await.is_ok_and(|m| m.is_dir()) {
+                tracing::debug!(
+                    "Skipping scheduled export, {} is unavailable",
+                    dir.display()
+                );
+                sender.finish(false, String::new(), Vec::new());
+                return;
+            }
+
+            let path = auto_exports::export_path(&dir, chrono::Local::now().date_naive());
+            match export.execute(&path).await {
+                Ok(package_list) => {
+                    auto_exports::prune(&dir, keep);
+                    let msg = format!(
+                        "Exported {} packages to {}",
+                        package_list.total_count(),
+                        path.display()
+                    );
+                    tracing::info!("{}", msg);
+                    sender.finish(true, msg.clone(), vec![msg]);
                 }
-            });
+                Err(e) => {
+                    let msg = format!("Scheduled export to {} failed: {:#}", path.display(), e);
+                    tracing::warn!("{}", msg);
+                    sender.finish(false, msg.clone(), vec![msg]);
+                }
+            }
+        });
+    }
+
+    fn choose_auto_export_dir(&mut self) {
+        let mut dialog = rfd::FileDialog::new();
+        if let Some(dir) = &self.config.auto_export.dir {
+            dialog = dialog.set_directory(dir);
+        }
+        if let Some(dir) = dialog.pick_folder() {
+            self.config.auto_export.dir = Some(dir);
+            self.save_config();
+            self.auto_exported_at = None;
+        }
+    }
+
+    fn refresh_snapshots(&mut self) {
+        self.snapshots = snapshots::list(&self.config_repo.snapshot_dir());
+    }
+
+    fn preview_update_all(&mut self) {
+        if self.loading_update_all {
+            return;
+        }
+
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadUpgradePreview) else {
+            return;
+        };
+
+        // The preview should see the newest versions, so metadata is
+        // refreshed before it rather than before the upgrade
+        let refresh_first = self.config.update_before_upgrade;
+        self.status_message = "Checking what Update All would upgrade...".to_string();
+        if refresh_first {
+            self.log_manager.push("Running brew update".to_string());
         }
+        self.log_manager
+            .push("Running brew upgrade --dry-run".to_string());
+
+        let use_case = Arc::clone(&self.use_cases.update_all);
+
+        self.executor.spawn(async move {
+            let mut log_vec = Vec::new();
+            if refresh_first
+                && let Err(e) = use_case.refresh_metadata(&ProgressCell::default()).await
+            {
+                let msg = format!("{:#}", e);
+                log_vec.push(msg.clone());
+                tracing::error!("{}", msg);
+                sender.send(TaskOutput::UpgradePreview(None), log_vec);
+                return;
+            }
+
+            let upgrades = match use_case.preview().await {
+                Ok(upgrades) => Some(upgrades),
+                Err(e) => {
+                    let msg = format!("Failed to preview Update All: {}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    None
+                }
+            };
+            sender.send(TaskOutput::UpgradePreview(upgrades), log_vec);
+        });
     }
 
-    fn handle_update_all(&mut self) {
+    /// Upgrades everything outdated with `brew upgrade`, or only `only` one
+    /// package at a time when some were left out of the preview.
+    fn handle_update_all(&mut self, only: Option<Vec<Package>>) {
         if self.loading_update_all {
             return;
         }
 
         self.loading_update_all = true;
+        self.update_all_only = only.clone();
         self.loading = true;
         self.status_message = "Updating all packages...".to_string();
         self.log_manager.push("Updating all packages".to_string());
         tracing::info!("Updating all packages");
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::UpdateAll {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let progress = ProgressCell::default();
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::tracked_op(OpKind::UpdateAll, progress.clone()))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.update_all);
+        let export = Arc::clone(&self.use_cases.export_packages);
+        let snapshot_dir = self.config_repo.snapshot_dir();
 
         self.executor.spawn(async move {
-            let result = use_case.execute().await;
-
             let mut log_vec = Vec::new();
+            take_snapshot(&export, &snapshot_dir, "update-all", &mut log_vec).await;
+
+            let result = match &only {
+                Some(packages) => use_case.execute_only(packages, &progress).await,
+                None => use_case.execute(&progress).await,
+            };
+
             match result {
-                Ok(_) => {
+                Ok(report) if report.failed.is_empty() => {
                     let msg = "Successfully updated all packages".to_string();
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = "All packages updated successfully".to_string();
+                    let message = "All packages updated successfully".to_string();
+                    sender.send(TaskOutput::Upgraded { report, message }, log_vec);
+                }
+                Ok(report) => {
+                    for (name, reason) in &report.failed {
+                        let msg = format!("Failed to update {}: {}", name, reason);
+                        log_vec.push(msg.clone());
+                        tracing::error!("{}", msg);
                     }
+                    // Keeps brew's reasons, so a network failure is still recognised
+                    let message = format!(
+                        "Updated {} packages, but {} failed: {}",
+                        report.upgraded.len(),
+                        report.failed.len(),
+                        report
+                            .failed
+                            .iter()
+                            .map(|(name, reason)| format!("{}: {}", name, reason))
+                            .collect::<Vec<_>>()
+                            .join("; ")
+                    );
+                    sender.send(TaskOutput::Upgraded { report, message }, log_vec);
                 }
                 Err(e) => {
                     let msg = format!("Error updating all packages: {}", e);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg, log_vec);
                 }
             }
+        });
+    }
+
+    /// Only what brew reported as upgraded leaves the outdated list; the
+    /// packages that failed stay, flagged with brew's reason.
+    fn apply_upgrade_report(&mut self, report: UpgradeReport) {
+        for name in &report.upgraded {
+            self.merged_packages.mark_package_updated(name);
+        }
+        for (name, reason) in &report.failed {
+            self.merged_packages.mark_update_failed(name, reason);
+        }
+        if !report.failed.is_empty() {
+            let names: Vec<&str> = report
+                .failed
+                .iter()
+                .map(|(name, _)| name.as_str())
+                .collect();
+            self.show_error_toast(format!(
+                "Couldn't update {}; hover their status for why",
+                names.join(", ")
+            ));
+        }
+    }
+
+    fn handle_autoremove(&mut self) {
+        if self.loading_autoremove {
+            return;
+        }
+
+        let Some(sender) = self.task_manager.start(AsyncTask::op(OpKind::Autoremove)) else {
+            return;
+        };
+
+        self.loading_autoremove = true;
+        self.loading = true;
+        self.log_manager
+            .push("Removing unneeded dependencies".to_string());
+        tracing::info!("Removing unneeded dependencies");
+
+        let use_case = Arc::clone(&self.use_cases.autoremove);
 
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
+        self.executor.spawn(async move {
+            let result = use_case.execute().await;
+
+            let mut log_vec = Vec::new();
+            match result {
+                Ok(_) => {
+                    let msg = "Removed unneeded dependencies".to_string();
+                    log_vec.push(msg.clone());
+                    tracing::info!("{}", msg);
+                    sender.finish(true, msg, log_vec);
+                }
+                Err(e) => {
+                    let msg = format!("Error removing unneeded dependencies: {}", e);
+                    log_vec.push(msg.clone());
+                    tracing::error!("{}", msg);
+                    sender.finish(false, msg, log_vec);
+                }
             }
         });
     }
@@ -1327,8 +3087,38 @@ impl BrewstyApp {
                 let use_case = Arc::clone(&self.use_cases.cleanup_old_versions);
                 self.executor.execute(async { use_case.preview().await })
             }
+            CleanupType::Autoremove => {
+                self.load_autoremove_preview();
+                return;
+            }
+        };
+        self.apply_cleanup_preview(cleanup_type, preview_result.map_err(|e| e.to_string()));
+    }
+
+    /// Runs `brew autoremove --dry-run` and measures what it would remove in
+    /// the background, as both can take a while.
+    fn load_autoremove_preview(&mut self) {
+        let Some(cellar) = self.brew_paths.as_ref().map(BrewPaths::cellar) else {
+            let error = "The Homebrew folders haven't been found yet".to_string();
+            self.apply_cleanup_preview(CleanupType::Autoremove, Err(error));
+            return;
+        };
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadAutoremovePreview) else {
+            return;
         };
+        let use_case = Arc::clone(&self.use_cases.autoremove);
+
+        self.executor.spawn(async move {
+            let preview = use_case.preview(&cellar).await.map_err(|e| e.to_string());
+            sender.send(TaskOutput::AutoremovePreview(preview), Vec::new());
+        });
+    }
 
+    fn apply_cleanup_preview(
+        &mut self,
+        cleanup_type: CleanupType,
+        preview_result: Result<CleanupPreview, String>,
+    ) {
         match preview_result {
             Ok(preview) => {
                 let msg = format!(
@@ -1349,6 +3139,66 @@ impl BrewstyApp {
         self.loading = false;
     }
 
+    /// Measures the download cache in the background; walking it can take a
+    /// while, so this only runs on request.
+    fn load_cache_info(&mut self) {
+        // Loaded once the Homebrew folders are found
+        let Some(cache) = self.brew_paths.as_ref().map(|paths| paths.cache.clone()) else {
+            return;
+        };
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadCacheInfo) else {
+            return;
+        };
+        self.loading_cache_info = true;
+        let use_case = Arc::clone(&self.use_cases.cache_info);
+
+        self.executor.spawn(async move {
+            match use_case.execute(&cache).await {
+                Ok(info) => sender.send(TaskOutput::CacheInfo(Some(info)), Vec::new()),
+                Err(e) => {
+                    let msg = format!("Could not measure the Homebrew cache: {}", e);
+                    tracing::warn!("{}", msg);
+                    sender.send(TaskOutput::CacheInfo(None), vec![msg]);
+                }
+            }
+        });
+    }
+
+    /// Counts what `brew autoremove` would remove. Unless `force`d, this is
+    /// skipped if it ran within `ORPHAN_CHECK_INTERVAL`, as it costs a brew call.
+    fn load_orphan_count(&mut self, force: bool) {
+        if !force
+            && self
+                .orphans_checked_at
+                .is_some_and(|at| at.elapsed() < ORPHAN_CHECK_INTERVAL)
+        {
+            return;
+        }
+        // Counted once the Homebrew folders are found
+        let Some(cellar) = self.brew_paths.as_ref().map(BrewPaths::cellar) else {
+            return;
+        };
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadOrphanCount) else {
+            return;
+        };
+        self.orphans_checked_at = Some(Instant::now());
+        let use_case = Arc::clone(&self.use_cases.autoremove);
+
+        self.executor.spawn(async move {
+            match use_case.preview(&cellar).await {
+                Ok(preview) => sender.send(
+                    TaskOutput::OrphanCount(Some(preview.items.len())),
+                    Vec::new(),
+                ),
+                Err(e) => {
+                    let msg = format!("Could not look for unneeded dependencies: {}", e);
+                    tracing::warn!("{}", msg);
+                    sender.send(TaskOutput::OrphanCount(None), vec![msg]);
+                }
+            }
+        });
+    }
+
     fn handle_clean_cache(&mut self) {
         if self.loading_clean_cache {
             return;
@@ -1360,15 +3210,9 @@ impl BrewstyApp {
         self.log_manager.push("Cleaning Homebrew cache".to_string());
         tracing::info!("Cleaning Homebrew cache");
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager.set_active_task(AsyncTask::CleanCache {
-            success: Arc::clone(&success),
-            logs: Arc::clone(&logs),
-            message: Arc::clone(&message),
-        });
+        let Some(sender) = self.task_manager.start(AsyncTask::op(OpKind::CleanCache)) else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.clean_cache);
 
@@ -1381,29 +3225,15 @@ impl BrewstyApp {
                     let msg = "Successfully cleaned cache".to_string();
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = "Cache cleaned successfully".to_string();
-                    }
+                    sender.finish(true, "Cache cleaned successfully".to_string(), log_vec);
                 }
                 Err(e) => {
                     let msg = format!("Error cleaning cache: {}", e);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
@@ -1419,16 +3249,12 @@ impl BrewstyApp {
             .push("Cleaning up old versions".to_string());
         tracing::info!("Cleaning up old versions");
 
-        let success = Arc::new(Mutex::new(None));
-        let logs = Arc::new(Mutex::new(Vec::new()));
-        let message = Arc::new(Mutex::new(String::new()));
-
-        self.task_manager
-            .set_active_task(AsyncTask::CleanupOldVersions {
-                success: Arc::clone(&success),
-                logs: Arc::clone(&logs),
-                message: Arc::clone(&message),
-            });
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::op(OpKind::CleanupOldVersions))
+        else {
+            return;
+        };
 
         let use_case = Arc::clone(&self.use_cases.cleanup_old_versions);
 
@@ -1441,29 +3267,19 @@ impl BrewstyApp {
                     let msg = "Successfully cleaned up old versions".to_string();
                     log_vec.push(msg.clone());
                     tracing::info!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(true);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = "Old versions cleaned up successfully".to_string();
-                    }
+                    sender.finish(
+                        true,
+                        "Old versions cleaned up successfully".to_string(),
+                        log_vec,
+                    );
                 }
                 Err(e) => {
                     let msg = format!("Error cleaning up old versions: {}", e);
                     log_vec.push(msg.clone());
                     tracing::error!("{}", msg);
-                    if let Ok(mut success_guard) = success.lock() {
-                        *success_guard = Some(false);
-                    }
-                    if let Ok(mut message_guard) = message.lock() {
-                        *message_guard = msg;
-                    }
+                    sender.finish(false, msg, log_vec);
                 }
             }
-
-            if let Ok(mut logs_guard) = logs.lock() {
-                *logs_guard = log_vec;
-            }
         });
     }
 
@@ -1476,6 +3292,8 @@ impl BrewstyApp {
             return;
         }
 
+        // The last results' info is no longer wanted
+        self.info_pool.cancel(InfoOrigin::SearchResults);
         self.loading_search = true;
         self.status_message = format!("Searching for '{}'...", self.filter_state.search_query());
         let msg = format!("Searching for: {}", self.filter_state.search_query());
@@ -1486,14 +3304,15 @@ impl BrewstyApp {
         let use_case_casks = Arc::clone(&self.use_cases.search);
         let query = self.filter_state.search_query().to_string();
 
-        let search_results = Arc::new(Mutex::new(Vec::new()));
-        let output_log = Arc::new(Mutex::new(Vec::new()));
         let query_clone = query.clone();
 
-        self.task_manager.set_active_task(AsyncTask::Search {
-            results: Arc::clone(&search_results),
-            logs: Arc::clone(&output_log),
-        });
+        let Some(sender) = self.task_manager.start(AsyncTask::Search) else {
+            return;
+        };
+
+        if self.unified_search {
+            self.show_installed_matches();
+        }
 
         self.executor.spawn(async move {
             let (formulae_result, casks_result) = tokio::join!(
@@ -1503,6 +3322,7 @@ impl BrewstyApp {
 
             let mut results = Vec::new();
             let mut logs = Vec::new();
+            let mut error = None;
 
             match formulae_result {
                 Ok(packages) => {
@@ -1515,6 +3335,7 @@ impl BrewstyApp {
                     let msg = format!("Error searching formulae: {}", e);
                     logs.push(msg.clone());
                     tracing::error!("{}", msg);
+                    error.get_or_insert(msg);
                 }
             }
 
@@ -1529,83 +3350,256 @@ impl BrewstyApp {
                     let msg = format!("Error searching casks: {}", e);
                     logs.push(msg.clone());
                     tracing::error!("{}", msg);
+                    error.get_or_insert(msg);
                 }
             }
-
-            if let Ok(mut results_guard) = search_results.lock() {
-                *results_guard = results;
-            }
-            if let Ok(mut logs_guard) = output_log.lock() {
-                *logs_guard = logs;
-            }
+            rank_search_results(&query, &mut results);
+
+            sender.send(
+                TaskOutput::SearchResults {
+                    packages: results,
+                    error,
+                },
+                logs,
+            );
         });
     }
 
-    fn load_package_info(&mut self, package_name: String, package_type: PackageType) {
-        if self.task_manager.can_load_more_package_info() {
-            self.load_package_info_immediate(package_name, package_type);
+    /// Fills Search with the installed packages matching the query, which
+    /// needs no brew call.
+    fn show_installed_matches(&mut self) {
+        let query = self.filter_state.search_query();
+        let installed = if query.is_empty() {
+            Vec::new()
         } else {
-            self.task_manager
-                .queue_package_info_load(package_name, package_type);
-        }
+            self.merged_packages.matching(query)
+        };
+        self.search_results.update_packages(installed);
     }
 
-    fn load_package_info_immediate(&mut self, package_name: String, package_type: PackageType) {
-        if self.task_manager.is_loading_package_info(&package_name) {
-            tracing::debug!("Already loading info for {}, skipping", package_name);
+    /// With auto-loading on, loads the info of the listed search results that
+    /// lack it. Results past the limit wait until the user lists them.
+    fn auto_load_displayed_info(&mut self) {
+        if !self.auto_load_version_info {
             return;
         }
+        let to_load: Vec<(String, PackageType)> = self
+            .search_results
+            .displayed(
+                self.filter_state.show_formulae(),
+                self.filter_state.show_casks(),
+                self.config.search_result_limit,
+            )
+            .into_iter()
+            .filter(|p| p.version.is_none() && !p.version_load_failed)
+            .map(|p| (p.name.clone(), p.package_type.clone()))
+            .collect();
+
+        if !to_load.is_empty() {
+            tracing::info!("Auto-loading version info for {} packages", to_load.len());
+        }
+        for (name, package_type) in to_load {
+            tracing::debug!("Auto-loading info for {}", name);
+            self.info_pool
+                .load(name, package_type, InfoOrigin::SearchResults);
+        }
+    }
 
-        tracing::info!(
-            "Starting to load package info for {} ({:?})",
-            package_name,
-            package_type
-        );
+    /// Loads info the user asked for, retrying it if an earlier load failed.
+    fn request_package_info(&mut self, package_name: String, package_type: PackageType) {
+        self.search_results.clear_load_failure(&package_name);
+        self.merged_packages.clear_load_failure(&package_name);
+        self.load_package_info(package_name, package_type);
+    }
 
-        let use_case = Arc::clone(&self.use_cases.get_package_info);
-        let result = Arc::new(Mutex::new(None));
-        let name_clone = package_name.clone();
-        let package_type_clone = package_type.clone();
-        let package_type_clone2 = package_type.clone();
+    fn load_package_info(&mut self, package_name: String, package_type: PackageType) {
+        self.info_pool
+            .load(package_name, package_type, InfoOrigin::User);
+    }
+
+    fn save_package_metadata(&mut self, name: String, metadata: PackageMetadata) {
+        self.merged_packages.set_package_metadata(name, metadata);
+        if let Err(e) = self.metadata_repo.save(self.merged_packages.metadata()) {
+            self.show_error_toast(format!("Failed to save tags and note: {:#}", e));
+        }
+    }
 
-        let task = AsyncTask::LoadPackageInfo {
-            package_name: package_name.clone(),
-            package_type: package_type.clone(),
-            result: Arc::clone(&result),
-            started_at: std::time::Instant::now(),
+    fn handle_audit(&mut self, package: Package) {
+        let Some(sender) = self.task_manager.start(AsyncTask::AuditPackage {
+            package_name: package.name.clone(),
+        }) else {
+            return;
         };
 
-        self.task_manager
-            .add_package_info_task(package_name.clone(), task);
+        let msg = format!("Auditing {}", package.name);
+        self.log_manager.push(msg.clone());
+        tracing::info!("{}", msg);
+
+        let use_case = Arc::clone(&self.use_cases.audit);
 
         self.executor.spawn(async move {
-            tracing::debug!("Started task for loading {}", name_clone);
+            let result = use_case.execute(&package).await;
+            let msg = match &result {
+                Ok(audit) if audit.passed => format!("{} passed brew audit", package.name),
+                Ok(audit) => format!(
+                    "brew audit found {} problems in {}",
+                    audit.findings.len(),
+                    package.name
+                ),
+                Err(e) => format!("Could not audit {}: {}", package.name, e),
+            };
+            tracing::info!("{}", msg);
+            sender.send(
+                TaskOutput::Audit(result.map_err(|e| e.to_string())),
+                vec![msg],
+            );
+        });
+    }
 
-            let info_result = use_case.execute(&name_clone, package_type_clone).await;
+    fn handle_dependency_tree(&mut self, package: Package) {
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadDependencyTree {
+            package_name: package.name.clone(),
+        }) else {
+            return;
+        };
 
-            match info_result {
-                Ok(package) => {
-                    tracing::info!(
-                        "Successfully loaded package info for {}: version={:?}",
-                        name_clone,
-                        package.version
-                    );
-                    if let Ok(mut result_guard) = result.lock() {
-                        *result_guard = Some(package);
-                    }
-                }
-                Err(e) => {
-                    tracing::error!("Error loading package info for {}: {}", name_clone, e);
-                    let failed_package = Package::new(name_clone.clone(), package_type_clone2)
-                        .set_version_load_failed(true);
-                    if let Ok(mut result_guard) = result.lock() {
-                        *result_guard = Some(failed_package);
-                    }
-                }
+        // The tree's nodes open their own info, so this window makes way
+        self.info_modal.close();
+        self.dependency_modal
+            .show_loading(package.name.clone(), package.package_type.clone());
+
+        let use_case = Arc::clone(&self.use_cases.dependency_tree);
+
+        self.executor.spawn(async move {
+            let result = use_case.execute(&package).await;
+            let mut logs = Vec::new();
+            if let Err(e) = &result {
+                let msg = format!("Could not list dependencies of {}: {:#}", package.name, e);
+                tracing::error!("{}", msg);
+                logs.push(msg);
+            }
+            sender.send(
+                TaskOutput::DependencyTree(result.map_err(|e| format!("{:#}", e))),
+                logs,
+            );
+        });
+    }
+
+    fn handle_service_info(&mut self, service_name: String) {
+        let Some(sender) = self.task_manager.start(AsyncTask::LoadServiceInfo {
+            service_name: service_name.clone(),
+        }) else {
+            return;
+        };
+
+        self.service_info_modal.show_loading(service_name.clone());
+
+        let use_case = Arc::clone(&self.use_cases.service_info);
+
+        self.executor.spawn(async move {
+            let result = use_case.execute(&service_name).await;
+            let mut logs = Vec::new();
+            if let Err(e) = &result {
+                let msg = format!("Could not load service {}: {:#}", service_name, e);
+                tracing::error!("{}", msg);
+                logs.push(msg);
+            }
+            sender.send(
+                TaskOutput::ServiceInfo(result.map_err(|e| format!("{:#}", e))),
+                logs,
+            );
+        });
+    }
+
+    /// Selects `path` in the file manager, toasting if that fails.
+    fn reveal(&mut self, path: &Path) {
+        if let Err(e) = system::reveal_path(path) {
+            self.show_error_toast(format!("Couldn't reveal {}: {:#}", path.display(), e));
+        }
+    }
+
+    fn handle_view_plist(&mut self, path: PathBuf) {
+        let Some(sender) = self
+            .task_manager
+            .start(AsyncTask::LoadPlist { path: path.clone() })
+        else {
+            return;
+        };
+
+        self.plist_modal.show_loading(path.clone());
+
+        self.executor.spawn(async move {
+            let read = path.clone();
+            let result = tokio::task::spawn_blocking(move || plist::read_xml(&read))
+                .await
+                .unwrap_or_else(|e| Err(anyhow::anyhow!("Reading the plist failed: {}", e)));
+            let mut logs = Vec::new();
+            if let Err(e) = &result {
+                let msg = format!("Could not read plist {}: {:#}", path.display(), e);
+                tracing::error!("{}", msg);
+                logs.push(msg);
             }
+            sender.send(
+                TaskOutput::Plist(result.map_err(|e| format!("{:#}", e))),
+                logs,
+            );
         });
     }
 
+    /// Opens the Info window for `name`, loading its info first if needed.
+    /// Without a `package_type` it is taken from the loaded lists, or the
+    /// name is tried as a formula and then as a cask.
+    fn show_info_for(&mut self, name: String, package_type: Option<PackageType>) {
+        let of_type = |p: &Package| package_type.as_ref().is_none_or(|t| &p.package_type == t);
+        let listed = self
+            .merged_packages
+            .packages()
+            .iter()
+            .find(|p| p.name == name && of_type(p))
+            .cloned()
+            .or_else(|| self.search_results.get_package(&name).filter(of_type));
+
+        if let Some(package) = &listed
+            && package.description.is_some()
+        {
+            self.info_modal.show(package.clone());
+            return;
+        }
+
+        self.info_pending = Some(name.clone());
+        match package_type.or_else(|| listed.map(|p| p.package_type)) {
+            Some(package_type) => self.load_package_info(name, package_type),
+            None => {
+                self.info_cask_fallback = Some(name.clone());
+                self.load_package_info(name, PackageType::Formula);
+            }
+        }
+    }
+
+    /// Opens the Info window for `package`, loading its info first if the
+    /// list doesn't have it yet.
+    fn open_info(&mut self, package: Package) {
+        if package.description.is_some() {
+            self.info_modal.show(package);
+        } else {
+            self.info_pending = Some(package.name.clone());
+            self.load_package_info(package.name, package.package_type);
+        }
+    }
+
+    /// Keeps `audit` on the package for the rest of the session.
+    fn cache_audit(&mut self, name: &str, audit: AuditResult) {
+        if let Some(mut package) = self.merged_packages.get_package(name) {
+            package.audit = Some(audit.clone());
+            self.merged_packages.update_package(package);
+        }
+        if let Some(mut package) = self.search_results.get_package(name) {
+            package.audit = Some(audit.clone());
+            self.search_results.update_package(package);
+        }
+        self.info_modal.set_audit(name, audit);
+    }
+
     fn poll_async_tasks(&mut self) {
         tracing::trace!("poll_async_tasks called, checking for active task");
         let result = self.task_manager.poll();
@@ -1614,284 +3608,818 @@ impl BrewstyApp {
             tracing::info!("Got {} installed packages from poll", packages.len());
             self.merged_packages.update_packages(packages);
             self.loading_installed = false;
+            self.load_install_dates();
+        }
+
+        if let Some(brew_paths) = result.brew_paths {
+            self.brew_paths = brew_paths;
+            self.load_metadata_age();
+            if self.tab_manager.current() == Tab::Settings {
+                self.load_cache_info();
+                self.load_orphan_count(false);
+            }
+        }
+
+        if let Some(metadata_updated_at) = result.metadata_updated_at {
+            self.metadata_updated_at = metadata_updated_at;
+        }
+
+        if result.upgrade_preview.is_some() && self.config.update_before_upgrade {
+            self.load_metadata_age();
+        }
+        if let Some(None) = result.upgrade_preview {
+            // Why it failed is already in the log
+            self.status_message = "Couldn't check what Update All would upgrade".to_string();
+            self.show_error_toast(
+                "Couldn't check what Update All would upgrade; see the log for why".to_string(),
+            );
+        }
+        if let Some(Some(upgrades)) = result.upgrade_preview {
+            if upgrades.is_empty() {
+                self.status_message = "All packages are up to date".to_string();
+                self.log_manager
+                    .push("Update All: nothing to upgrade".to_string());
+            } else {
+                let ignored = upgrades
+                    .iter()
+                    .filter(|u| self.merged_packages.is_update_ignored(&u.name))
+                    .map(|u| u.name.clone())
+                    .collect();
+                self.upgrade_modal.show_preview(upgrades, ignored);
+            }
+        }
+
+        if let Some(release) = result.app_update {
+            self.app_update = release;
+        }
+
+        if let Some(count) = result.orphan_count {
+            self.orphan_count = count;
+        }
+
+        if let Some(preview) = result.autoremove_preview {
+            self.apply_cleanup_preview(CleanupType::Autoremove, preview);
+        }
+
+        if let Some(cache_info) = result.cache_info {
+            self.loading_cache_info = false;
+            self.cache_info = cache_info;
+            self.tab_manager.mark_loaded(Tab::Settings);
+        }
+
+        if let Some(install_dates) = result.install_dates {
+            tracing::info!("Got install dates for {} packages", install_dates.len());
+            self.merged_packages.set_install_dates(install_dates);
         }
 
-        if let Some(packages) = result.outdated_packages {
+        if let Some((packages, complete)) = result.outdated_packages {
             tracing::info!("Got {} outdated packages from poll", packages.len());
-            self.merged_packages.update_outdated_packages(packages);
+            self.merged_packages
+                .update_outdated_packages(packages, complete);
             self.loading_outdated = false;
         }
 
-        if self.loading_installed == false && self.loading_outdated == false {
+        if !self.loading_installed && !self.loading_outdated {
             self.tab_manager.mark_loaded(Tab::Installed);
             self.status_message = "Packages loaded".to_string();
         }
 
-        if let Some(packages) = result.search_results {
-            self.search_results.update_packages(packages.clone());
-            self.loading_search = false;
-            self.status_message = "Search completed".to_string();
-
-            if self.auto_load_version_info {
-                tracing::info!("Auto-loading version info for {} packages", packages.len());
-                for package in packages.iter() {
-                    if package.version.is_none() && !package.version_load_failed {
-                        tracing::debug!("Auto-loading info for {}", package.name);
-                        self.load_package_info(package.name.clone(), package.package_type.clone());
-                    }
+        if let Some(packages) = result.search_results {
+            if self.unified_search {
+                let installed = self
+                    .merged_packages
+                    .matching(self.filter_state.search_query());
+                self.search_results
+                    .update_installed_first(installed, packages);
+            } else {
+                self.search_results.update_packages(packages);
+            }
+            self.loading_search = false;
+            self.status_message = "Search completed".to_string();
+
+            if result
+                .search_error
+                .is_some_and(|e| failure::classify(&e) == FailureKind::Network)
+            {
+                let query = self.filter_state.search_query().to_string();
+                self.offer_network_retry(RetryOperation::Search(query));
+            }
+
+            self.auto_load_displayed_info();
+        }
+
+        for (name, package) in self.info_pool.poll() {
+            if self.info_cask_fallback.as_ref() == Some(&name) {
+                self.info_cask_fallback = None;
+                if package.version_load_failed && package.package_type == PackageType::Formula {
+                    // Not a formula, so likely a cask
+                    self.load_package_info(name, PackageType::Cask);
+                    continue;
+                }
+            }
+            if self.info_pending.as_ref() == Some(&name) {
+                self.info_pending = None;
+                self.info_modal.show(package.clone());
+            }
+            if let Some(pending) = self.arch_check_pending.remove(&name) {
+                self.packages_in_operation.remove(&name);
+                // Without info brew's own checks are all there is
+                let checked = if package.version_load_failed {
+                    pending.with_supported_archs(Vec::new())
+                } else {
+                    let mut checked = pending;
+                    checked.supported_archs = package.supported_archs.clone();
+                    checked
+                };
+                self.request_install(checked);
+            }
+            self.search_results.update_package(package.clone());
+            self.merged_packages.update_package(package);
+        }
+
+        if let Some((name, Ok(audit))) = result.audit_completed {
+            self.cache_audit(&name, audit);
+        }
+
+        if let Some((name, tree)) = result.dependency_tree {
+            let installed = self
+                .merged_packages
+                .packages()
+                .iter()
+                .map(|p| p.name.clone())
+                .collect();
+            self.dependency_modal.set_tree(&name, tree, installed);
+        }
+
+        if let Some((name, info)) = result.service_info {
+            if let Ok(info) = &info {
+                self.service_list.update_service(info.service.clone());
+            }
+            self.service_info_modal.set_info(&name, info);
+        }
+
+        if let Some((path, xml)) = result.plist {
+            self.plist_modal.set_content(&path, xml);
+        }
+
+        if let Some(diff) = result.package_list_diff {
+            self.compare_modal.set_diff(diff);
+        }
+
+        if let Some(report) = result.upgrade_report {
+            self.apply_upgrade_report(report);
+        }
+
+        for op in result.ops_completed {
+            self.handle_op_completed(op);
+        }
+
+        if let Some(services) = result.services {
+            let service_check_pending = self.service_check_pending.take();
+            match services {
+                Ok(services) => {
+                    tracing::debug!("Got {} services from poll", services.len());
+                    if let Some(name) = service_check_pending
+                        && services
+                            .iter()
+                            .any(|s| s.name == name && s.status != ServiceStatus::Started)
+                    {
+                        self.service_offer = Some(name);
+                    }
+                    self.service_list.update_services(services);
+                    self.status_message = "Services loaded".to_string();
+                }
+                // A failed load keeps the list that is shown; a failed
+                // background refresh only goes to the log
+                Err(_) if self.refreshing_services => {}
+                Err(e) => {
+                    self.status_message = format!("Couldn't load services: {}", e);
+                }
+            }
+            self.loading_services = false;
+            self.refreshing_services = false;
+            self.services_loaded_at = Some(Instant::now());
+            self.tab_manager.mark_loaded(Tab::Services);
+        }
+
+        if let Some((service_name, success, message)) = result.start_service_completed {
+            self.finish_service_op(&service_name, success, message, ServiceStatus::Started);
+        }
+
+        if let Some((service_name, success, message)) = result.stop_service_completed {
+            self.finish_service_op(&service_name, success, message, ServiceStatus::Stopped);
+        }
+
+        if let Some((service_name, success, message)) = result.restart_service_completed {
+            self.finish_service_op(&service_name, success, message, ServiceStatus::Started);
+        }
+
+        if let Some((service_name, service)) = result.service_checked {
+            self.finish_service_check(&service_name, service);
+        }
+
+        if let Some((success, message)) = result.services_cleanup_completed {
+            self.cleaning_services = false;
+            self.record_result(success, &message);
+            if success {
+                self.status_message = message;
+            } else {
+                self.show_error_toast(message);
+            }
+            self.load_services();
+        }
+
+        if let Some((success, _message)) = result.stop_all_services_completed {
+            self.finish_stop_all_services(success);
+        }
+
+        if let Some((success, message)) = result.export_packages_completed {
+            self.record_result(success, &message);
+            self.loading_export = false;
+            self.loading = false;
+            self.status_message = message;
+        }
+
+        if let Some((true, message)) = result.auto_export_completed {
+            self.status_message = message;
+        }
+
+        if let Some((success, message)) = result.import_packages_completed {
+            self.record_result(success, &message);
+            self.loading_import = false;
+            self.loading = false;
+            self.status_message = message;
+            self.refresh_snapshots();
+            // Even a failed import may have installed some of the packages
+            self.use_cases.get_package_info.clear();
+            if success {
+                // Reload installed packages after successful import
+                self.load_installed_packages(true);
+            }
+        }
+
+        if let Some((fetched, failed)) = result.fetch_completed {
+            let mut summary = format!("Fetched {} packages", fetched.len());
+            if !failed.is_empty() {
+                summary.push_str(&format!("; failed to fetch: {}", failed.join(", ")));
+            }
+            self.log_manager.push(summary.clone());
+            tracing::info!("{}", summary);
+            self.record_result(failed.is_empty(), &summary);
+            self.status_message = summary;
+            self.fetched_packages.extend(fetched);
+        }
+
+        if let Some(diagnostics) = result.diagnostics {
+            self.loading_issue_report = false;
+            let logs: Vec<String> = self
+                .log_manager
+                .all_logs()
+                .map(|entry| format!("[{}] {}", entry.format_timestamp(), entry.message))
+                .collect();
+            match self.diagnostics_destination.clone() {
+                DiagnosticsDestination::NewIssue => {
+                    let report =
+                        issue_report::build_issue_report(&diagnostics, &self.config, &logs);
+                    self.pending_diagnostics = Some((DiagnosticsDestination::NewIssue, report));
+                }
+                DiagnosticsDestination::Clipboard => {
+                    let bundle =
+                        issue_report::build_diagnostics_bundle(&diagnostics, &self.config, &logs);
+                    self.pending_diagnostics = Some((DiagnosticsDestination::Clipboard, bundle));
+                }
+                DiagnosticsDestination::File(path) => {
+                    let bundle =
+                        issue_report::build_diagnostics_bundle(&diagnostics, &self.config, &logs);
+                    let msg = match std::fs::write(&path, bundle) {
+                        Ok(()) => format!("Diagnostics saved to {}", path.display()),
+                        Err(e) => format!("Couldn't save diagnostics to {}: {}", path.display(), e),
+                    };
+                    tracing::info!("{}", msg);
+                    self.log_manager.push(msg.clone());
+                    self.status_message = msg;
                 }
             }
         }
 
-        if let Some((_name, package)) = result.package_info {
-            self.search_results.update_package(package.clone());
-            self.merged_packages.update_package(package);
-        }
+        self.log_manager.extend(result.logs);
+    }
 
-        if let Some((success, message)) = result.install_completed {
-            self.loading_install = false;
-            self.loading = false;
-            let installed_pkg_name = self.current_install_package.clone();
-            if let Some(pkg) = &installed_pkg_name {
-                self.packages_in_operation.remove(pkg);
+    fn handle_op_completed(&mut self, op: OpResult) {
+        let OpResult {
+            kind,
+            package_name,
+            success,
+            message,
+        } = op;
+        self.record_result(success, &message);
+
+        // Info loaded before the change no longer holds
+        if success {
+            let changed = match kind {
+                OpKind::Install => self.current_install_package.as_deref(),
+                OpKind::Uninstall => self.current_uninstall_package.as_deref(),
+                OpKind::Update => self.current_update_package.as_deref(),
+                OpKind::Pin | OpKind::Unpin => package_name.as_deref(),
+                OpKind::UpdateAll | OpKind::Autoremove => {
+                    self.use_cases.get_package_info.clear();
+                    None
+                }
+                _ => None,
+            };
+            if let Some(name) = changed {
+                self.use_cases.get_package_info.invalidate(name);
             }
-            self.status_message = message.clone();
+        }
 
-            if success {
-                if let Some(pkg_name) = installed_pkg_name {
-                    if let Some(mut pkg) = self.search_results.get_package(&pkg_name) {
-                        pkg.installed = true;
-                        self.search_results.update_package(pkg);
+        match kind {
+            OpKind::InstallHomebrew => {
+                self.loading_homebrew_install = false;
+                self.status_message = message;
+
+                // Even a failed run may have got far enough to leave a brew behind
+                self.brew_missing = !BrewCommand::redetect();
+                if self.brew_missing {
+                    if success {
+                        tracing::warn!(
+                            "The install script finished, but brew still wasn't found; \
+                             it may need to be added to PATH"
+                        );
                     }
+                } else {
+                    tracing::info!("Found brew at {}", BrewCommand::brew_path().display());
+                    self.resolve_brew_paths();
+                    self.load_installed_packages(true);
+                }
+            }
 
-                    self.merged_packages.mark_package_updated(&pkg_name);
-                    self.merged_packages
-                        .remove_from_outdated_selection_by_name(&pkg_name);
+            OpKind::Install => {
+                self.loading_install = false;
+                self.loading = false;
+                let installed_pkg_name = self.current_install_package.clone();
+                if let Some(pkg) = &installed_pkg_name {
+                    self.packages_in_operation.remove(pkg);
                 }
-                self.current_install_package = None;
-            } else {
-                if self.is_password_error(&message) {
-                    if let Some(pkg_name) = &installed_pkg_name {
-                        if let Some(pkg) = self.search_results.get_package(pkg_name) {
-                            self.pending_operation = Some(PendingOperation::Install(pkg));
-                            self.password_modal.show(format!("Install {}", pkg_name));
+                self.status_message = message.clone();
+
+                if success {
+                    if let Some(pkg_name) = installed_pkg_name {
+                        if let Some(mut pkg) = self.search_results.get_package(&pkg_name) {
+                            pkg.installed = true;
+                            self.search_results.update_package(pkg);
+                        }
+                        if let Some(mut pkg) = self.favorite_package(&pkg_name) {
+                            pkg.installed = true;
+                            self.merged_packages.add_installed_package(pkg);
+                        }
+
+                        self.merged_packages.mark_package_updated(&pkg_name);
+                        self.merged_packages
+                            .remove_from_outdated_selection_by_name(&pkg_name);
+
+                        // Only formulae provide services
+                        let is_cask = self
+                            .search_results
+                            .get_package(&pkg_name)
+                            .or_else(|| self.favorite_package(&pkg_name))
+                            .is_some_and(|pkg| pkg.package_type == PackageType::Cask);
+                        if !is_cask {
+                            self.service_check_pending = Some(pkg_name);
+                            self.load_services();
                         }
                     }
-                } else {
                     self.current_install_package = None;
+                    self.load_orphan_count(false);
+                } else {
+                    let pkg = installed_pkg_name.as_deref().and_then(|pkg_name| {
+                        self.search_results
+                            .get_package(pkg_name)
+                            .or_else(|| self.favorite_package(pkg_name))
+                    });
+                    match failure::classify(&message) {
+                        FailureKind::Password => {
+                            if let Some(pkg) = pkg {
+                                self.password_modal.show(format!("Install {}", pkg.name));
+                                self.pending_operation = Some(PendingOperation::Install(pkg));
+                            }
+                        }
+                        FailureKind::Network => {
+                            self.current_install_package = None;
+                            if let Some(pkg) = pkg {
+                                self.offer_network_retry(RetryOperation::Install(pkg));
+                            }
+                        }
+                        FailureKind::Other => {
+                            self.current_install_package = None;
+                        }
+                    }
                 }
             }
-        }
-
-        if let Some((success, message)) = result.uninstall_completed {
-            self.loading_uninstall = false;
-            self.loading = false;
-            let uninstall_pkg_name = self.current_uninstall_package.clone();
-            if let Some(pkg) = &uninstall_pkg_name {
-                self.packages_in_operation.remove(pkg);
-            }
-            self.status_message = message.clone();
 
-            if success {
-                if let Some(pkg) = self.current_uninstall_package.as_ref() {
-                    self.merged_packages.remove_installed_package(pkg);
+            OpKind::Uninstall => {
+                self.loading_uninstall = false;
+                self.loading = false;
+                let uninstall_pkg_name = self.current_uninstall_package.clone();
+                if let Some(pkg) = &uninstall_pkg_name {
+                    self.packages_in_operation.remove(pkg);
                 }
-                self.current_uninstall_package = None;
-            } else {
-                if self.is_password_error(&message) {
-                    if let Some(pkg_name) = &uninstall_pkg_name {
-                        if let Some(pkg) = self.merged_packages.get_package(pkg_name) {
-                            self.pending_operation = Some(PendingOperation::Uninstall(pkg));
-                            self.password_modal.show(format!("Uninstall {}", pkg_name));
-                        }
+                self.status_message = message.clone();
+
+                if success {
+                    if let Some(pkg) = self.current_uninstall_package.as_ref() {
+                        self.merged_packages.remove_installed_package(pkg);
                     }
-                } else {
                     self.current_uninstall_package = None;
+                    self.load_orphan_count(false);
+                } else {
+                    if failure::classify(&message) == FailureKind::Password {
+                        if let Some(pkg_name) = &uninstall_pkg_name
+                            && let Some(pkg) = self.merged_packages.get_package(pkg_name)
+                        {
+                            let verb = if self.zapping { "Zap" } else { "Uninstall" };
+                            self.password_modal.show(format!("{} {}", verb, pkg_name));
+                            self.pending_operation = Some(if self.zapping {
+                                PendingOperation::Zap(pkg)
+                            } else {
+                                PendingOperation::Uninstall(pkg)
+                            });
+                        }
+                    } else {
+                        self.current_uninstall_package = None;
+                    }
                 }
             }
-        }
 
-        if let Some((success, message)) = result.update_completed {
-            self.loading_update = false;
-            self.loading = false;
-            let pkg = self.current_update_package.take();
-            if let Some(ref pkg_name) = pkg {
-                self.packages_in_operation.remove(pkg_name);
-            }
-            self.status_message = message;
+            OpKind::Update => {
+                self.loading_update = false;
+                self.loading = false;
+                let pkg = self.current_update_package.take();
+                if let Some(ref pkg_name) = pkg {
+                    self.packages_in_operation.remove(pkg_name);
+                }
+                self.status_message = message.clone();
 
-            if success {
-                if let Some(pkg_name) = pkg {
+                if !success
+                    && failure::classify(&message) == FailureKind::Network
+                    && let Some(package) = pkg
+                        .as_deref()
+                        .and_then(|n| self.merged_packages.get_package(n))
+                {
+                    self.offer_network_retry(RetryOperation::Update(package));
+                }
+
+                if success && let Some(pkg_name) = pkg {
                     self.merged_packages.mark_package_updated(&pkg_name);
                     self.merged_packages
                         .remove_from_outdated_selection_by_name(&pkg_name);
                 }
+
+                if self.loading_update_all && !self.pending_updates.is_empty() {
+                    self.process_next_pending_update();
+                    self.loading_update = true;
+                } else if self.loading_update_all && self.pending_updates.is_empty() {
+                    self.loading_update_all = false;
+                    self.status_message = "Finished updating all packages".to_string();
+                    self.log_manager
+                        .push("Finished updating all packages".to_string());
+                    tracing::info!("Finished updating all packages");
+                    self.merged_packages.clear_outdated_selection();
+                }
             }
 
-            if self.loading_update_all && !self.pending_updates.is_empty() {
-                self.process_next_pending_update();
-                self.loading_update = true;
-            } else if self.loading_update_all && self.pending_updates.is_empty() {
+            OpKind::UpdateAll => {
                 self.loading_update_all = false;
-                self.status_message = "Finished updating all packages".to_string();
-                self.log_manager
-                    .push("Finished updating all packages".to_string());
-                tracing::info!("Finished updating all packages");
+                self.loading = false;
+                self.refresh_snapshots();
+                let only = self.update_all_only.take();
+                if !success && failure::classify(&message) == FailureKind::Network {
+                    self.offer_network_retry(RetryOperation::UpdateAll(only));
+                } else {
+                    self.status_message = message;
+                }
+
+                if success {
+                    self.packages_in_operation.clear();
+                }
+
                 self.merged_packages.clear_outdated_selection();
             }
-        }
 
-        if let Some((success, message)) = result.update_all_completed {
-            self.loading_update_all = false;
-            self.loading = false;
-            self.status_message = message;
+            OpKind::CleanCache => {
+                self.loading_clean_cache = false;
+                self.loading = false;
+                self.status_message = message;
+                self.cleanup_modal.close();
+                self.load_cache_info();
+            }
 
-            if success {
-                for pkg_name in self.packages_in_operation.iter() {
-                    self.merged_packages.mark_package_updated(pkg_name);
-                    self.merged_packages
-                        .remove_from_outdated_selection_by_name(pkg_name);
+            OpKind::CleanupOldVersions => {
+                self.loading_cleanup_old_versions = false;
+                self.loading = false;
+                self.status_message = message;
+                self.cleanup_modal.close();
+            }
+
+            OpKind::Autoremove => {
+                self.loading_autoremove = false;
+                self.loading = false;
+                self.status_message = message;
+                self.cleanup_modal.close();
+                self.load_orphan_count(true);
+                if success {
+                    self.load_installed_packages(true);
                 }
-                self.packages_in_operation.clear();
             }
 
-            self.merged_packages.clear_outdated_selection();
+            OpKind::Pin | OpKind::Unpin => {
+                if let Some(package_name) = &package_name {
+                    self.packages_in_operation.remove(package_name);
+                }
+                self.status_message = message;
+                self.load_installed_packages(true);
+            }
         }
 
-        if let Some((_success, message)) = result.clean_cache_completed {
-            self.loading_clean_cache = false;
-            self.loading = false;
-            self.status_message = message;
-            self.cleanup_modal.close();
-        }
+        self.start_queued_operations();
+    }
 
-        if let Some((_success, message)) = result.cleanup_old_versions_completed {
-            self.loading_cleanup_old_versions = false;
-            self.loading = false;
-            self.status_message = message;
-            self.cleanup_modal.close();
+    /// Switches to `tab`, loading what it shows the first time.
+    fn open_tab(&mut self, tab: Tab) {
+        if self.tab_manager.is_current(Tab::SearchInstall) && tab != Tab::SearchInstall {
+            self.info_pool.cancel(InfoOrigin::SearchResults);
         }
+        self.tab_manager.switch_to(tab);
+        match tab {
+            Tab::Installed | Tab::Favorites => {
+                if !self.tab_manager.is_loaded(Tab::Installed) {
+                    self.load_installed_packages(true);
+                }
+            }
+            Tab::Services => {
+                if !self.tab_manager.is_loaded(Tab::Services) {
+                    self.load_services();
+                }
+            }
+            Tab::Settings => {
+                if !self.tab_manager.is_loaded(Tab::Settings) {
+                    self.load_cache_info();
+                    self.load_orphan_count(false);
+                    self.refresh_snapshots();
+                }
+            }
+            // Picks up the auto-loads cancelled when the tab was left
+            Tab::SearchInstall => self.auto_load_displayed_info(),
+            Tab::Log => {}
+        }
+    }
 
-        if let Some((package_name, _success, message)) = result.pin_completed {
-            self.packages_in_operation.remove(&package_name);
-            self.status_message = message;
-            self.load_installed_packages(true);
+    /// A tab in the top bar, with a spinner while its data is loading so a
+    /// tab left mid-load doesn't look empty. Returns whether it was clicked.
+    fn tab_button(&self, ui: &mut egui::Ui, tab: Tab, label: &str) -> bool {
+        let clicked = ui
+            .selectable_label(self.tab_manager.is_current(tab), label)
+            .clicked();
+        if self.tab_busy(tab) {
+            ui.add(egui::Spinner::new().size(12.0))
+                .on_hover_text("Loading…");
         }
+        clicked
+    }
 
-        if let Some((package_name, _success, message)) = result.unpin_completed {
-            self.packages_in_operation.remove(&package_name);
-            self.status_message = message;
-            self.load_installed_packages(true);
+    /// Whether the data `tab` shows is loading.
+    fn tab_busy(&self, tab: Tab) -> bool {
+        match tab {
+            Tab::Installed => self.loading_installed || self.loading_outdated,
+            Tab::Favorites => self.loading_installed,
+            Tab::SearchInstall => self.loading_search,
+            Tab::Services => self.loading_services,
+            Tab::Settings => {
+                self.loading_cache_info
+                    || self.task_manager.has_task_kind(TaskKind::LoadOrphanCount)
+            }
+            Tab::Log => false,
         }
+    }
 
-        if let Some(services) = result.services {
-            tracing::info!("Got {} services from poll", services.len());
-            self.service_list.update_services(services);
-            self.loading_services = false;
-            self.tab_manager.mark_loaded(Tab::Services);
-            self.status_message = "Services loaded".to_string();
+    fn poll_logs(&mut self) {
+        self.logs_received = false;
+        while let Ok(log) = self.log_rx.try_recv() {
+            self.log_manager
+                .push_captured(log.level, log.target, log.message);
+            self.logs_received = true;
         }
+    }
 
-        if let Some((service_name, success, message)) = result.start_service_completed {
-            self.services_in_operation.remove(&service_name);
-            self.status_message = message;
-            if success {
-                self.load_services();
-            }
+    /// Whether to draw another frame without waiting for input. When this is
+    /// false egui sleeps until an event or a finished task wakes it.
+    fn needs_repaint(&self) -> bool {
+        self.loading_installed
+            || self.loading_outdated
+            || self.loading_search
+            || self.loading_services
+            || self.loading_install
+            || self.loading_uninstall
+            || self.loading_update
+            || self.loading_update_all
+            || self.loading_clean_cache
+            || self.loading_cache_info
+            || self.loading_cleanup_old_versions
+            || self.loading_autoremove
+            || self.loading_export
+            || self.loading_import
+            || self.loading_issue_report
+            || self.loading_homebrew_install
+            || self.task_manager.has_work_in_flight()
+            || self.logs_received
+    }
+}
+
+/// Saves the installed packages to a new snapshot in `dir` before a bulk
+/// operation. A failure is logged but doesn't stop the operation.
+async fn take_snapshot(export: &ExportPackages, dir: &Path, reason: &str, logs: &mut Vec<String>) {
+    let path = snapshots::new_snapshot_path(dir, reason);
+    let result = match tokio::fs::create_dir_all(dir).await {
+        Ok(()) => export.execute(&path).await,
+        Err(e) => Err(e.into()),
+    };
+
+    let msg = match result {
+        Ok(package_list) => {
+            snapshots::prune(dir, snapshots::MAX_SNAPSHOTS);
+            let msg = format!(
+                "Saved a snapshot of {} packages to {}",
+                package_list.total_count(),
+                path.display()
+            );
+            tracing::info!("{}", msg);
+            msg
+        }
+        Err(e) => {
+            let msg = format!("Could not save a snapshot before {}: {:#}", reason, e);
+            tracing::warn!("{}", msg);
+            msg
         }
+    };
+    logs.push(msg);
+}
 
-        if let Some((service_name, success, message)) = result.stop_service_completed {
-            self.services_in_operation.remove(&service_name);
-            self.status_message = message;
-            if success {
-                self.load_services();
-            }
+/// Reports an install brew said succeeded, unless `brew list` disagrees; the
+/// package then isn't marked installed and the user gets a warning instead.
+async fn finish_verified_install(
+    use_case: &InstallPackage,
+    package: &Package,
+    sender: TaskSender,
+    mut logs: Vec<String>,
+) {
+    let warning = match use_case.verify(package).await {
+        Ok(true) => {
+            let message = format!("{} installed successfully", package.name);
+            sender.finish(true, message, logs);
+            return;
         }
+        Ok(false) => format!(
+            "brew reported {} as installed, but it isn't listed; check the log",
+            package.name
+        ),
+        Err(e) => format!(
+            "Could not verify that {} was installed: {}",
+            package.name, e
+        ),
+    };
+    tracing::warn!("{}", warning);
+    logs.push(warning.clone());
+    sender.finish(false, warning, logs);
+}
 
-        if let Some((service_name, success, message)) = result.restart_service_completed {
-            self.services_in_operation.remove(&service_name);
-            self.status_message = message;
-            if success {
-                self.load_services();
-            }
+impl eframe::App for BrewstyApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.poll_logs();
+        // Tasks wake the UI when they finish; keep repainting while busy so
+        // the frame after a result still gets drawn
+        let was_busy = self.needs_repaint();
+        self.poll_async_tasks();
+        if was_busy || self.needs_repaint() {
+            ctx.request_repaint();
         }
 
-        if let Some((_success, message)) = result.export_packages_completed {
-            self.loading_export = false;
-            self.loading = false;
-            self.status_message = message;
+        if self.window_state.update(ctx, &mut self.config) {
+            self.save_config();
         }
 
-        if let Some((success, message)) = result.import_packages_completed {
-            self.loading_import = false;
-            self.loading = false;
-            self.status_message = message;
-            if success {
-                // Reload installed packages after successful import
-                self.load_installed_packages(true);
+        if let Some((destination, text)) = self.pending_diagnostics.take() {
+            ctx.copy_text(text);
+            self.status_message = "Diagnostics copied to clipboard".to_string();
+            if destination == DiagnosticsDestination::NewIssue {
+                ctx.open_url(egui::OpenUrl::new_tab(issue_report::NEW_ISSUE_URL));
+                self.log_manager.push(
+                    "Diagnostics copied to clipboard; paste them into the new GitHub issue"
+                        .to_string(),
+                );
+            } else {
+                self.log_manager
+                    .push("Diagnostics copied to clipboard".to_string());
             }
         }
 
-        self.log_manager.extend(result.logs);
+        if self.config.theme == ThemeMode::System {
+            if self.system_theme.poll() {
+                self.apply_theme(ctx);
+            }
+            ctx.request_repaint_after(self.system_theme.next_poll_in());
+        }
 
-        if self.task_manager.can_load_more_package_info()
-            && self.task_manager.pending_loads_count() > 0
-        {
-            let to_load = 15 - self.task_manager.pending_loads_count();
-            let batch = self.task_manager.drain_pending_loads(to_load);
-
-            if !batch.is_empty() {
-                tracing::info!(
-                    "Starting batch load of {} packages ({} remaining in queue)",
-                    batch.len(),
-                    self.task_manager.pending_loads_count()
+        if let Some(resized_at) = self.output_panel_resized_at {
+            if resized_at.elapsed() >= OUTPUT_PANEL_SAVE_DELAY
+                && !ctx.input(|i| i.pointer.any_down())
+            {
+                self.output_panel_resized_at = None;
+                self.save_config();
+            } else {
+                ctx.request_repaint_after(
+                    OUTPUT_PANEL_SAVE_DELAY.saturating_sub(resized_at.elapsed()),
                 );
-
-                for (name, pkg_type) in batch {
-                    self.load_package_info_immediate(name, pkg_type);
-                }
             }
         }
-    }
 
-    fn poll_logs(&mut self) {
-        while let Ok(log_entry) = self.log_rx.try_recv() {
-            self.log_manager.push(log_entry);
+        self.reload_services_periodically(ctx);
+        self.recheck_started_services(ctx);
+        self.export_periodically(ctx);
+
+        // Resizing changes the widths every frame; save once the drag ends
+        if !ctx.input(|i| i.pointer.any_down()) && self.column_widths.take_changed() {
+            self.config.column_widths = self.column_widths.widths().clone();
+            self.save_config();
         }
-    }
-}
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
+        if self.merged_packages.take_outdated_since_changed() {
+            self.config.outdated_since = self.merged_packages.outdated_since().clone();
+            self.save_config();
+        }
 
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} B", bytes)
-    }
-}
+        if self.merged_packages.take_ignored_updates_changed() {
+            self.config.ignored_updates = self.merged_packages.ignored_updates().clone();
+            self.save_config();
+        }
 
-impl eframe::App for BrewstyApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        self.poll_logs();
-        self.poll_async_tasks();
-        ctx.request_repaint();
+        if self.merged_packages.take_brew_updated_casks_changed() {
+            let mut names: Vec<String> = self
+                .merged_packages
+                .brew_updated_casks()
+                .iter()
+                .cloned()
+                .collect();
+            names.sort();
+            self.config.brew_updated_casks = names;
+            self.save_config();
+        }
+
+        if self.merged_packages.take_collapsed_changed() {
+            (
+                self.config.outdated_collapsed,
+                self.config.installed_collapsed,
+            ) = self.merged_packages.collapsed_sections();
+            self.save_config();
+        }
 
         if !self.initialized {
             self.initialized = true;
+            self.apply_brew_env();
+            self.brew_missing = !BrewCommand::is_found();
+            if self.brew_missing {
+                tracing::warn!(
+                    "brew wasn't found at {}",
+                    BrewCommand::brew_path().display()
+                );
+            }
+            self.resolve_brew_paths();
+
+            // The banner is shown once per crash report, even if left undismissed
+            if let Some(name) = self
+                .crash_report
+                .as_ref()
+                .and_then(|path| path.file_name())
+                .and_then(|name| name.to_str())
+            {
+                self.config.seen_crash_report = Some(name.to_string());
+                self.save_config();
+            }
+
             // Only load installed packages if auto-update is enabled
             self.load_installed_packages(self.config.auto_update_check);
 
+            if self.config.check_for_app_updates {
+                self.check_for_app_update();
+            }
+
             // Apply initial theme
             self.apply_theme(ctx);
         }
 
+        let current = self.tab_manager.current();
+        if current.can_hide() && self.config.is_tab_hidden(current.key()) {
+            self.open_tab(Tab::Installed);
+        }
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             ui.add_space(8.0);
             ui.horizontal(|ui| {
@@ -1899,98 +4427,28 @@ impl eframe::App for BrewstyApp {
                 ui.label(format!("v{}", env!("CARGO_PKG_VERSION")));
                 ui.separator();
 
-                if ui
-                    .selectable_label(
-                        self.tab_manager.is_current(Tab::Installed),
-                        "Installed & Outdated",
-                    )
-                    .clicked()
-                {
-                    self.tab_manager.switch_to(Tab::Installed);
-                    if !self.tab_manager.is_loaded(Tab::Installed) {
-                        self.load_installed_packages(true);
+                for tab in Tab::ALL {
+                    if self.config.is_tab_hidden(tab.key()) && tab.can_hide() {
+                        continue;
                     }
-                }
-                if ui
-                    .selectable_label(
-                        self.tab_manager.is_current(Tab::SearchInstall),
-                        "Search & Install",
-                    )
-                    .clicked()
-                {
-                    self.tab_manager.switch_to(Tab::SearchInstall);
-                }
-                if ui
-                    .selectable_label(self.tab_manager.is_current(Tab::Services), "Services")
-                    .clicked()
-                {
-                    self.tab_manager.switch_to(Tab::Services);
-                    if !self.tab_manager.is_loaded(Tab::Services) {
-                        self.load_services();
+                    if self.tab_button(ui, tab, tab.label()) {
+                        self.open_tab(tab);
                     }
                 }
-                if ui
-                    .selectable_label(self.tab_manager.is_current(Tab::Settings), "Settings")
-                    .clicked()
-                {
-                    self.tab_manager.switch_to(Tab::Settings);
-                }
-                if ui
-                    .selectable_label(self.tab_manager.is_current(Tab::Log), "Log")
-                    .clicked()
-                {
-                    self.tab_manager.switch_to(Tab::Log);
-                }
             });
             ui.add_space(8.0);
         });
 
-        egui::TopBottomPanel::bottom("bottom_panel")
-            .resizable(true)
-            .default_height(self.output_panel_height)
-            .show(ctx, |ui| {
-                ui.add_space(8.0);
-                ui.horizontal(|ui| {
-                    if ui.button("Clear Output").clicked() {
-                        self.log_manager = LogManager::new();
-                    }
-                    ui.separator();
-                    if ui.button("📋 Copy Output").clicked() {
-                        let output = self
-                            .log_manager
-                            .all_logs()
-                            .map(|entry| {
-                                format!("[{}] {}", entry.format_timestamp(), entry.message)
-                            })
-                            .collect::<Vec<_>>()
-                            .join("\n");
-                        ctx.copy_text(output);
-                    }
-                });
-
-                ui.separator();
-
-                egui::ScrollArea::vertical()
-                    .auto_shrink([false; 2])
-                    .stick_to_bottom(true)
-                    .show(ui, |ui| {
-                        ui.set_width(ui.available_width());
-
-                        for entry in self.log_manager.filtered_logs() {
-                            ui.horizontal(|ui| {
-                                ui.label(
-                                    egui::RichText::new(format!("[{}]", entry.format_timestamp()))
-                                        .color(egui::Color32::GRAY)
-                                        .monospace(),
-                                );
-                                ui.monospace(&entry.message);
-                            });
-                        }
-                    });
+        self.show_crash_banner(ctx);
+        self.show_brew_missing_banner(ctx);
+        self.show_network_banner(ctx);
+        self.show_service_offer_banner(ctx);
+        self.show_app_update_banner(ctx);
 
-                self.output_panel_height = ui.min_rect().height();
-            });
+        self.show_output_panel(ctx);
+        self.show_toast(ctx);
 
+        let operation_progress = self.operation_progress();
         egui::CentralPanel::default().show(ctx, |ui| {
             match self.tab_manager.current() {
                 Tab::Installed => {
@@ -1999,25 +4457,63 @@ impl eframe::App for BrewstyApp {
                         &mut self.merged_packages,
                         &mut self.filter_state,
                         &self.packages_in_operation,
+                        &operation_progress,
+                        &self.fetched_packages,
+                        self.task_manager.has_task_kind(TaskKind::FetchPackages),
                         self.loading_installed,
                         self.loading_outdated,
                         &mut self.info_modal,
+                        &self.config.favorites,
+                        self.config.compact_mode,
+                        self.config.show_install_date,
+                        &mut self.column_widths,
+                        self.metadata_updated_at,
+                        Duration::from_secs(self.config.metadata_stale_after_hours * 60 * 60),
                     );
 
                     for action in actions {
                         match action {
                             InstalledAction::Refresh => self.load_installed_packages(true),
-                            InstalledAction::Install(pkg) => self.handle_install(pkg),
+                            InstalledAction::Install(pkg) => self.request_install(pkg),
                             InstalledAction::Uninstall(pkg) => self.handle_uninstall(pkg),
+                            InstalledAction::Zap(pkg) => self.confirm_zap = Some(pkg),
                             InstalledAction::Update(pkg) => self.handle_update(pkg),
                             InstalledAction::UpdateSelected(pkgs) => {
                                 self.handle_update_selected(pkgs)
                             }
+                            InstalledAction::FetchSelected(pkgs) => {
+                                self.handle_fetch_selected(pkgs)
+                            }
                             InstalledAction::Pin(pkg) => self.handle_pin(pkg),
                             InstalledAction::Unpin(pkg) => self.handle_unpin(pkg),
+                            InstalledAction::ToggleFavorite(pkg) => self.toggle_favorite(pkg),
                             InstalledAction::LoadInfo(name, pkg_type) => {
-                                self.load_package_info(name, pkg_type)
+                                self.request_package_info(name, pkg_type)
                             }
+                            InstalledAction::OpenInfo(pkg) => self.open_info(pkg),
+                            InstalledAction::Reveal(pkg) => self.reveal_package(&pkg),
+                        }
+                    }
+                }
+
+                Tab::Favorites => {
+                    let actions = FavoritesTab::show(
+                        ui,
+                        &self.config.favorites,
+                        &self.merged_packages,
+                        &self.packages_in_operation,
+                        self.loading_installed || self.loading_outdated,
+                        self.config.compact_mode,
+                        &mut self.column_widths,
+                    );
+
+                    for action in actions {
+                        match action {
+                            FavoritesAction::Refresh => self.load_installed_packages(true),
+                            FavoritesAction::Install(pkg) => self.request_install(pkg),
+                            FavoritesAction::Uninstall(pkg) => self.handle_uninstall(pkg),
+                            FavoritesAction::Update(pkg) => self.handle_update(pkg),
+                            FavoritesAction::ToggleFavorite(pkg) => self.toggle_favorite(pkg),
                         }
                     }
                 }
@@ -2028,22 +4524,38 @@ impl eframe::App for BrewstyApp {
                         &mut self.search_results,
                         &mut self.filter_state,
                         &self.packages_in_operation,
+                        &operation_progress,
                         self.loading_search,
                         &mut self.auto_load_version_info,
+                        &mut self.unified_search,
                         &mut self.info_modal,
+                        &self.config.favorites,
+                        self.config.compact_mode,
+                        &mut self.column_widths,
+                        self.config.search_result_limit,
                     );
 
                     for action in actions {
                         match action {
                             SearchAction::Search => self.handle_search(),
-                            SearchAction::Install(pkg) => self.handle_install(pkg),
+                            SearchAction::QueryChanged => {
+                                if self.unified_search && !self.loading_search {
+                                    self.show_installed_matches();
+                                }
+                            }
+                            SearchAction::Install(pkg) => self.request_install(pkg),
                             SearchAction::Uninstall(pkg) => self.handle_uninstall(pkg),
                             SearchAction::Update(pkg) => self.handle_update(pkg),
                             SearchAction::LoadInfo(name, pkg_type) => {
-                                self.load_package_info(name, pkg_type)
+                                self.request_package_info(name, pkg_type)
                             }
+                            SearchAction::OpenInfo(pkg) => self.open_info(pkg),
                             SearchAction::Pin(pkg) => self.handle_pin(pkg),
                             SearchAction::Unpin(pkg) => self.handle_unpin(pkg),
+                            SearchAction::ToggleFavorite(pkg) => self.toggle_favorite(pkg),
+                            SearchAction::TypeFilterChanged | SearchAction::ShowAllResults => {
+                                self.auto_load_displayed_info()
+                            }
                         }
                     }
                 }
@@ -2052,16 +4564,48 @@ impl eframe::App for BrewstyApp {
                     let actions = ServicesTab::show(
                         ui,
                         &mut self.service_list,
+                        &mut self.filter_state,
                         &self.services_in_operation,
                         self.loading_services,
+                        self.cleaning_services,
+                        &mut self.column_widths,
                     );
 
                     for action in actions {
                         match action {
                             ServiceAction::Refresh => self.load_services(),
+                            ServiceAction::ShowInfo(name) => self.handle_service_info(name),
+                            ServiceAction::ViewPlist(path) => {
+                                self.handle_view_plist(PathBuf::from(path))
+                            }
                             ServiceAction::Start(name) => self.handle_start_service(name),
                             ServiceAction::Stop(name) => self.handle_stop_service(name),
                             ServiceAction::Restart(name) => self.handle_restart_service(name),
+                            ServiceAction::StartAllStopped => {
+                                self.confirm_all_services = Some((
+                                    BulkServiceOp::Start,
+                                    self.service_list.names_with_status(&ServiceStatus::Stopped),
+                                ));
+                            }
+                            ServiceAction::StopAllRunning => {
+                                self.confirm_all_services = Some((
+                                    BulkServiceOp::Stop,
+                                    self.service_list.names_with_status(&ServiceStatus::Started),
+                                ));
+                            }
+                            ServiceAction::StartSelected(names) => {
+                                self.handle_selected_services(BulkServiceOp::Start, names)
+                            }
+                            ServiceAction::StopSelected(names) => {
+                                self.handle_selected_services(BulkServiceOp::Stop, names)
+                            }
+                            ServiceAction::CleanupStale => self.confirm_services_cleanup = true,
+                            ServiceAction::RestartAllRunning => {
+                                self.confirm_all_services = Some((
+                                    BulkServiceOp::Restart,
+                                    self.service_list.names_with_status(&ServiceStatus::Started),
+                                ));
+                            }
                         }
                     }
                 }
@@ -2074,24 +4618,72 @@ impl eframe::App for BrewstyApp {
                         &mut self.log_manager,
                         self.loading_export,
                         self.loading_import,
+                        self.task_manager
+                            .has_task_kind(TaskKind::ComparePackageList),
+                        self.loading_issue_report,
+                        self.cache_info.as_ref(),
+                        self.loading_cache_info,
+                        self.orphan_count,
+                        self.task_manager.has_task_kind(TaskKind::LoadOrphanCount),
+                        self.brew_paths.as_ref(),
+                        &self.snapshots,
                     );
 
                     for action in actions {
                         match action {
                             SettingsAction::SaveConfig => self.save_config(),
                             SettingsAction::ApplyTheme => self.apply_theme(ctx),
+                            SettingsAction::ApplyBrewEnv => self.apply_brew_env(),
+                            SettingsAction::ChooseAutoExportDir => self.choose_auto_export_dir(),
+                            // Export right away to the new setup
+                            SettingsAction::RestartAutoExport => self.auto_exported_at = None,
+                            SettingsAction::ApplyLogVerbosity => {
+                                log_capture::set_verbosity(self.config.log_verbosity)
+                            }
+                            SettingsAction::ApplySearchResultLimit => {
+                                self.auto_load_displayed_info()
+                            }
+                            SettingsAction::ApplyFileLogging => log_file::set_enabled(
+                                &self.config_repo.log_dir(),
+                                self.config.file_logging_enabled,
+                            ),
+                            SettingsAction::OpenLogFolder => self.open_log_folder(),
+                            SettingsAction::ApplyLogTargets => {
+                                log_capture::set_capture_targets(self.log_manager.target_levels())
+                            }
                             SettingsAction::ShowCleanupPreview(cleanup_type) => {
                                 self.show_cleanup_preview(cleanup_type)
                             }
-                            SettingsAction::UpdateAll => self.handle_update_all(),
-                            SettingsAction::ExportPackages => self.handle_export_packages(),
+                            SettingsAction::RefreshCacheInfo => self.load_cache_info(),
+                            SettingsAction::RefreshOrphanCount => self.load_orphan_count(true),
+                            SettingsAction::CheckForAppUpdate => self.check_for_app_update(),
+                            SettingsAction::OpenFolder(path) => self.open_folder(&path),
+                            SettingsAction::UpdateAll => self.preview_update_all(),
+                            SettingsAction::ExportPackages => {
+                                self.handle_export_packages(ExportFormat::Json)
+                            }
+                            SettingsAction::ExportMarkdown => {
+                                self.handle_export_packages(ExportFormat::Markdown)
+                            }
+                            SettingsAction::ExportCsv => self.handle_export_csv(),
                             SettingsAction::ImportPackages => self.handle_import_packages(),
+                            SettingsAction::CompareWithExport => self.handle_compare_with_export(),
+                            SettingsAction::RestoreSnapshot(path) => {
+                                self.import_packages_from(path)
+                            }
+                            SettingsAction::ReportIssue => {
+                                self.handle_collect_diagnostics(DiagnosticsDestination::NewIssue)
+                            }
+                            SettingsAction::CopyDiagnostics => {
+                                self.handle_collect_diagnostics(DiagnosticsDestination::Clipboard)
+                            }
+                            SettingsAction::SaveDiagnostics => self.handle_save_diagnostics(),
                         }
                     }
                 }
 
                 Tab::Log => {
-                    let actions = LogTab::show(ui, &self.log_manager);
+                    let actions = LogTab::show(ui, &self.log_manager, &mut self.log_tab_scroll);
                     for action in actions {
                         match action {
                             LogAction::CopyAll => {
@@ -2105,7 +4697,9 @@ impl eframe::App for BrewstyApp {
                                     .join("\n");
                                 ctx.copy_text(output);
                             }
-                            LogAction::Clear => self.log_manager = LogManager::new(),
+                            LogAction::Clear => self.log_manager.clear(),
+                            LogAction::CopyCommand(command) => ctx.copy_text(command),
+                            LogAction::ClearCommands => command_log::clear(),
                         }
                     }
                 }
@@ -2116,6 +4710,7 @@ impl eframe::App for BrewstyApp {
                     CleanupAction::Confirm(cleanup_type) => match cleanup_type {
                         CleanupType::Cache => self.handle_clean_cache(),
                         CleanupType::OldVersions => self.handle_cleanup_old_versions(),
+                        CleanupType::Autoremove => self.handle_autoremove(),
                     },
                     CleanupAction::Cancel => {
                         self.cleanup_modal.close();
@@ -2123,19 +4718,76 @@ impl eframe::App for BrewstyApp {
                 }
             }
 
-            self.info_modal.render(ctx);
+            if let Some(action) = self.upgrade_modal.render(ctx) {
+                if let UpgradeAction::Confirm { upgrade, skipped } = action {
+                    if skipped.is_empty() {
+                        self.handle_update_all(None);
+                    } else {
+                        self.log_manager
+                            .push(format!("Update All: skipping {}", skipped.join(", ")));
+                        let packages = upgrade
+                            .iter()
+                            .map(|name| {
+                                self.merged_packages.get_package(name).unwrap_or_else(|| {
+                                    Package::new(name.clone(), PackageType::Formula)
+                                })
+                            })
+                            .collect();
+                        self.handle_update_all(Some(packages));
+                    }
+                }
+                self.upgrade_modal.close();
+            }
+
+            let auditing = self.task_manager.auditing().map(str::to_string);
+            let metadata = self.merged_packages.metadata();
+            match self.info_modal.render(ctx, auditing.as_deref(), metadata) {
+                Some(InfoAction::Audit(package)) => self.handle_audit(package),
+                Some(InfoAction::ShowDependencies(package)) => self.handle_dependency_tree(package),
+                Some(InfoAction::SaveMetadata(name, metadata)) => {
+                    self.save_package_metadata(name, metadata)
+                }
+                None => {}
+            }
+            if let Some(DependencyAction::ShowInfo(name, package_type)) =
+                self.dependency_modal.render(ctx)
+            {
+                self.show_info_for(name, package_type);
+            }
+            match self.service_info_modal.render(ctx) {
+                Some(ServiceInfoAction::Reveal(path)) => self.reveal(&path),
+                Some(ServiceInfoAction::ViewPlist(path)) => self.handle_view_plist(path),
+                None => {}
+            }
+            if let Some(PlistAction::Reveal(path)) = self.plist_modal.render(ctx) {
+                self.reveal(&path);
+            }
+            self.compare_modal.render(ctx);
 
+            self.show_homebrew_install_confirm(ctx);
+            self.show_arch_mismatch_confirm(ctx);
+            self.show_zap_confirm(ctx);
+            self.show_all_services_confirm(ctx);
+            self.show_services_cleanup_confirm(ctx);
             self.password_modal.render(ctx);
             if let Some((confirmed, password)) = self.password_modal.take_result() {
-                if confirmed && !password.is_empty() {
-                    self.retry_with_password(&password);
+                if confirmed && !password.expose_secret().is_empty() {
+                    self.retry_with_password(password);
                 } else {
                     self.pending_operation = None;
                     self.log_manager
                         .push("Password entry cancelled.".to_string());
                     tracing::info!("Password entry cancelled");
+                    self.start_queued_operations();
                 }
             }
         });
     }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        let stopped = BrewCommand::terminate_running();
+        if stopped > 0 {
+            tracing::info!("Stopped {} running brew process(es) on exit", stopped);
+        }
+    }
 }
@@ -0,0 +1,140 @@
+use crate::domain::entities::{FavoritePackage, Package};
+use crate::presentation::components::column_widths::{ColumnWidths, TableLayout};
+use crate::presentation::components::grid_density::{self, status_cell};
+use crate::presentation::components::{MergedPackageList, favorite_button};
+use eframe::egui;
+use egui::Color32;
+use std::collections::HashSet;
+
+const FAVORITES_TABLE: TableLayout = TableLayout {
+    id: "favorites_table",
+    columns: &[
+        ("Name", 240.0),
+        ("Version", 140.0),
+        ("Type", 80.0),
+        ("Status", 120.0),
+        ("Actions", 0.0),
+    ],
+};
+
+pub enum FavoritesAction {
+    Refresh,
+    Install(Package),
+    Uninstall(Package),
+    Update(Package),
+    ToggleFavorite(Package),
+}
+
+pub struct FavoritesTab;
+
+impl FavoritesTab {
+    pub fn show(
+        ui: &mut egui::Ui,
+        favorites: &[FavoritePackage],
+        merged_packages: &MergedPackageList,
+        packages_in_operation: &HashSet<String>,
+        loading_installed: bool,
+        compact: bool,
+        column_widths: &mut ColumnWidths,
+    ) -> Vec<FavoritesAction> {
+        let mut actions = Vec::new();
+
+        ui.horizontal(|ui| {
+            ui.label(format!("{} favorite(s)", favorites.len()));
+            ui.separator();
+            if ui.button("Refresh").clicked() {
+                actions.push(FavoritesAction::Refresh);
+            }
+        });
+
+        ui.separator();
+
+        if favorites.is_empty() {
+            ui.label("No favorites yet. Click ☆ next to a package to star it.");
+            return actions;
+        }
+
+        if loading_installed {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Loading packages...");
+            });
+            return actions;
+        }
+
+        grid_density::apply_table_style(ui, compact);
+        let row_height = grid_density::row_height(ui);
+        let header_height = grid_density::header_height(ui, compact);
+
+        column_widths
+            .table(ui, &FAVORITES_TABLE)
+            .auto_shrink([false, false])
+            .header(header_height, |row| {
+                column_widths.header(row, &FAVORITES_TABLE, compact)
+            })
+            .body(|mut body| {
+                for favorite in favorites {
+                    let installed = merged_packages.get_package(&favorite.name);
+                    let package = installed.clone().unwrap_or_else(|| {
+                        Package::new(favorite.name.clone(), favorite.package_type.clone())
+                    });
+                    let outdated = merged_packages.is_outdated(&favorite.name);
+                    let busy = packages_in_operation.contains(&favorite.name);
+
+                    body.row(row_height, |mut row| {
+                        row.col(|ui| {
+                            if favorite_button(ui, true) {
+                                actions.push(FavoritesAction::ToggleFavorite(package.clone()));
+                            }
+                            ui.label(&favorite.name);
+                        });
+
+                        row.col(|ui| {
+                            ui.label(package.version.as_deref().unwrap_or("-"));
+                        });
+
+                        row.col(|ui| {
+                            ui.label(favorite.package_type.to_string());
+                        });
+
+                        row.col(|ui| {
+                            if busy {
+                                ui.spinner();
+                            } else if installed.is_none() {
+                                status_cell(ui, "Not installed", Color32::GRAY, compact);
+                            } else if outdated {
+                                status_cell(
+                                    ui,
+                                    "Outdated",
+                                    Color32::from_rgb(255, 165, 0),
+                                    compact,
+                                );
+                            } else {
+                                status_cell(ui, "Installed", Color32::from_rgb(0, 255, 0), compact);
+                            }
+                        });
+
+                        row.col(|ui| {
+                            ui.add_enabled_ui(!busy, |ui| {
+                                if installed.is_none() {
+                                    if ui.button("Install").clicked() {
+                                        actions.push(FavoritesAction::Install(package.clone()));
+                                    }
+                                } else {
+                                    if outdated && !package.pinned && ui.button("Update").clicked()
+                                    {
+                                        actions.push(FavoritesAction::Update(package.clone()));
+                                    }
+                                    if ui.button("Uninstall").clicked() {
+                                        actions.push(FavoritesAction::Uninstall(package.clone()));
+                                    }
+                                }
+                            });
+                        });
+                    });
+                }
+            });
+
+        actions
+    }
+}
@@ -1,17 +1,26 @@
-use crate::domain::entities::{Package, PackageType};
-use crate::presentation::components::{FilterState, InfoModal, MergedPackageList};
+use crate::domain::entities::{FavoritePackage, OperationProgress, Package, PackageType};
+use crate::presentation::components::merged_package_list::time_ago;
+use crate::presentation::components::{ColumnWidths, FilterState, InfoModal, MergedPackageList};
 use eframe::egui;
 use std::collections::HashSet;
+use std::time::{Duration, SystemTime};
 
 pub enum InstalledAction {
     Refresh,
     Install(Package),
     Uninstall(Package),
+    /// Uninstall a cask with `--zap`, once confirmed.
+    Zap(Package),
     Update(Package),
     UpdateSelected(Vec<String>),
+    FetchSelected(Vec<String>),
     Pin(Package),
     Unpin(Package),
+    ToggleFavorite(Package),
     LoadInfo(String, PackageType),
+    /// Show the Info window, loading the info first if it isn't yet.
+    OpenInfo(Package),
+    Reveal(Package),
 }
 
 pub struct InstalledTab;
@@ -23,9 +32,18 @@ impl InstalledTab {
         merged_packages: &mut MergedPackageList,
         filter_state: &mut FilterState,
         packages_in_operation: &HashSet<String>,
+        operation_progress: &[(String, OperationProgress)],
+        fetched_packages: &HashSet<String>,
+        fetching: bool,
         loading_installed: bool,
         loading_outdated: bool,
         info_modal: &mut InfoModal,
+        favorites: &[FavoritePackage],
+        compact: bool,
+        show_install_date: bool,
+        column_widths: &mut ColumnWidths,
+        metadata_updated_at: Option<SystemTime>,
+        metadata_stale_after: Duration,
     ) -> Vec<InstalledAction> {
         let mut actions = Vec::new();
 
@@ -39,10 +57,30 @@ impl InstalledTab {
             ui.checkbox(&mut show_casks, "Show Casks");
             filter_state.set_show_formulae(show_formulae);
             filter_state.set_show_casks(show_casks);
+            Self::tag_filter(ui, filter_state, merged_packages.tags());
             ui.separator();
+            let mut only_outdated = merged_packages.only_outdated();
+            if ui
+                .toggle_value(&mut only_outdated, "Only Outdated")
+                .on_hover_text("Hide the installed packages to focus on updates")
+                .changed()
+            {
+                merged_packages.set_only_outdated(only_outdated);
+            }
             if ui.button("Refresh").clicked() {
                 actions.push(InstalledAction::Refresh);
             }
+            if ui
+                .button("📋 Copy as Markdown")
+                .on_hover_text("Copy the outdated packages as a Markdown table")
+                .clicked()
+            {
+                let date = chrono::Local::now().format("%Y-%m-%d").to_string();
+                ui.ctx().copy_text(merged_packages.outdated_markdown(&date));
+            }
+            if let Some(at) = metadata_updated_at {
+                Self::metadata_age(ui, at, metadata_stale_after);
+            }
         });
 
         ui.separator();
@@ -57,8 +95,10 @@ impl InstalledTab {
             let mut uninstall_action = None;
             let mut update_action = None;
             let mut update_selected_action = None;
+            let mut fetch_selected_action = None;
             let mut pin_action = None;
             let mut unpin_action = None;
+            let mut toggle_favorite_action = None;
             let mut load_info_action = None;
 
             merged_packages.show_merged_with_search_and_pin(
@@ -67,13 +107,23 @@ impl InstalledTab {
                 &mut uninstall_action,
                 &mut update_action,
                 &mut update_selected_action,
+                &mut fetch_selected_action,
+                fetched_packages,
+                fetching,
                 filter_state.show_formulae(),
                 filter_state.show_casks(),
                 filter_state.installed_search_query(),
+                filter_state.tag_filter(),
                 &mut load_info_action,
                 packages_in_operation,
+                operation_progress,
                 &mut pin_action,
                 &mut unpin_action,
+                favorites,
+                &mut toggle_favorite_action,
+                compact,
+                show_install_date,
+                column_widths,
             );
 
             if let Some(package) = install_action {
@@ -88,6 +138,9 @@ impl InstalledTab {
             if let Some(package_names) = update_selected_action {
                 actions.push(InstalledAction::UpdateSelected(package_names));
             }
+            if let Some(package_names) = fetch_selected_action {
+                actions.push(InstalledAction::FetchSelected(package_names));
+            }
             if let Some(package) = pin_action {
                 actions.push(InstalledAction::Pin(package));
             }
@@ -100,11 +153,67 @@ impl InstalledTab {
                     package.package_type,
                 ));
             }
+            if let Some(package) = toggle_favorite_action {
+                actions.push(InstalledAction::ToggleFavorite(package));
+            }
             if let Some(package) = merged_packages.get_show_info_action() {
                 info_modal.show(package);
             }
+            if let Some(package) = merged_packages.get_open_info_action() {
+                actions.push(InstalledAction::OpenInfo(package));
+            }
+            if let Some(package) = merged_packages.get_reveal_action() {
+                actions.push(InstalledAction::Reveal(package));
+            }
+            if let Some(package) = merged_packages.get_zap_action() {
+                actions.push(InstalledAction::Zap(package));
+            }
         }
 
         actions
     }
+
+    /// Narrows the list to one of the user's tags. Hidden until a tag exists,
+    /// and cleared if the chosen tag is no longer on any package.
+    fn tag_filter(ui: &mut egui::Ui, filter_state: &mut FilterState, tags: Vec<String>) {
+        let selected = filter_state.tag_filter_mut();
+        if selected.as_ref().is_some_and(|tag| !tags.contains(tag)) {
+            *selected = None;
+        }
+        if tags.is_empty() {
+            return;
+        }
+
+        ui.separator();
+        egui::ComboBox::from_id_salt("installed_tag_filter")
+            .selected_text(selected.as_deref().unwrap_or("All tags"))
+            .show_ui(ui, |ui| {
+                ui.selectable_value(selected, None, "All tags");
+                for tag in tags {
+                    let label = tag.clone();
+                    ui.selectable_value(selected, Some(tag), label);
+                }
+            });
+    }
+
+    /// How old brew's metadata is, in orange once it is older than
+    /// `stale_after`, since the outdated list may then be missing versions.
+    fn metadata_age(ui: &mut egui::Ui, updated_at: SystemTime, stale_after: Duration) {
+        let now = SystemTime::now();
+        let text = format!("brew last updated: {}", time_ago(updated_at, now));
+        let stale = now
+            .duration_since(updated_at)
+            .is_ok_and(|age| age > stale_after);
+
+        ui.separator();
+        if stale {
+            ui.colored_label(egui::Color32::from_rgb(255, 165, 0), text)
+                .on_hover_text(
+                    "The outdated list may be missing newer versions. Run Update All with \
+                     \"Run brew update first\" on, or `brew update` in a terminal, to refresh it.",
+                );
+        } else {
+            ui.label(text);
+        }
+    }
 }
@@ -1,15 +1,22 @@
-use crate::presentation::components::LogManager;
+use crate::infrastructure::brew::command_log::{self, CommandRecord};
+use crate::presentation::components::{FollowScroll, LogManager, accessible};
 use eframe::egui;
 
 pub enum LogAction {
     CopyAll,
     Clear,
+    CopyCommand(String),
+    ClearCommands,
 }
 
 pub struct LogTab;
 
 impl LogTab {
-    pub fn show(ui: &mut egui::Ui, log_manager: &LogManager) -> Vec<LogAction> {
+    pub fn show(
+        ui: &mut egui::Ui,
+        log_manager: &LogManager,
+        follow: &mut FollowScroll,
+    ) -> Vec<LogAction> {
         let mut actions = Vec::new();
 
         ui.heading("Command Log");
@@ -26,34 +33,123 @@ impl LogTab {
 
         ui.separator();
 
-        egui::ScrollArea::vertical()
-            .auto_shrink([false; 2])
+        egui::CollapsingHeader::new("Commands")
+            .id_salt("command_records")
+            .show(ui, |ui| Self::show_commands(ui, &mut actions));
+
+        ui.separator();
+
+        let font = egui::FontId::monospace(12.0);
+        let row_height = ui.fonts(|f| f.row_height(&font));
+        let timestamp_width = ui.fonts(|f| {
+            f.layout_no_wrap("[00:00:00]".to_string(), font.clone(), egui::Color32::GRAY)
+                .size()
+                .x
+        });
+
+        egui::Frame::default()
+            .fill(egui::Color32::BLACK)
+            .inner_margin(8.0)
             .show(ui, |ui| {
-                ui.visuals_mut().override_text_color = Some(egui::Color32::from_rgb(0, 255, 0));
-                let bg_frame = egui::Frame::default()
-                    .fill(egui::Color32::BLACK)
-                    .inner_margin(8.0);
-                bg_frame.show(ui, |ui| {
-                    ui.set_width(ui.available_width());
-                    ui.set_style({
-                        let mut style = (*ui.ctx().style()).clone();
-                        style.override_font_id = Some(egui::FontId::monospace(12.0));
-                        style
-                    });
-
-                    for entry in log_manager.filtered_logs_reversed() {
+                let line_count = log_manager.visible_line_count();
+                let spaced_row_height = row_height + ui.spacing().item_spacing.y;
+                let area = follow.scroll_area(
+                    egui::ScrollArea::both().auto_shrink([false; 2]),
+                    line_count,
+                    spaced_row_height,
+                );
+                let output = area.show_rows(ui, row_height, line_count, |ui, rows| {
+                    for row in rows {
+                        let Some(line) = log_manager.visible_line_newest_first(row) else {
+                            continue;
+                        };
                         ui.horizontal(|ui| {
-                            ui.label(
-                                egui::RichText::new(format!("[{}]", entry.format_timestamp()))
-                                    .color(egui::Color32::GRAY)
-                                    .monospace(),
+                            if line.line == 0 {
+                                ui.label(
+                                    egui::RichText::new(format!(
+                                        "[{}]",
+                                        line.entry.format_timestamp()
+                                    ))
+                                    .font(font.clone())
+                                    .color(egui::Color32::GRAY),
+                                );
+                            } else {
+                                ui.add_space(timestamp_width);
+                            }
+                            ui.add(
+                                egui::Label::new(
+                                    egui::RichText::new(line.text)
+                                        .font(font.clone())
+                                        .color(egui::Color32::from_rgb(0, 255, 0)),
+                                )
+                                .extend(),
                             );
-                            ui.monospace(&entry.message);
                         });
                     }
                 });
+                follow.update(&output, line_count);
+                follow.show_jump_button(ui, &output, line_count);
             });
 
         actions
     }
+
+    fn show_commands(ui: &mut egui::Ui, actions: &mut Vec<LogAction>) {
+        command_log::with_records(|records| {
+            ui.horizontal(|ui| {
+                ui.label(format!("{} brew command(s) run", records.len()));
+                if ui
+                    .add_enabled(!records.is_empty(), egui::Button::new("Clear"))
+                    .clicked()
+                {
+                    actions.push(LogAction::ClearCommands);
+                }
+            });
+
+            egui::ScrollArea::vertical()
+                .id_salt("command_records_scroll")
+                .max_height(200.0)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    egui::Grid::new("command_records_grid")
+                        .striped(true)
+                        .num_columns(5)
+                        .show(ui, |ui| {
+                            for record in records.iter().rev() {
+                                Self::command_row(ui, record, actions);
+                                ui.end_row();
+                            }
+                        });
+                });
+        });
+    }
+
+    fn command_row(ui: &mut egui::Ui, record: &CommandRecord, actions: &mut Vec<LogAction>) {
+        ui.monospace(record.started_at.format("%H:%M:%S").to_string());
+        ui.monospace(format!("{:.2}s", record.duration.as_secs_f64()));
+
+        let status = match (&record.error, record.exit_code) {
+            (Some(_), _) => egui::RichText::new("failed").color(egui::Color32::RED),
+            (None, Some(0)) => {
+                egui::RichText::new("exit 0").color(egui::Color32::from_rgb(0, 200, 0))
+            }
+            (None, Some(code)) => {
+                egui::RichText::new(format!("exit {}", code)).color(egui::Color32::RED)
+            }
+            (None, None) => egui::RichText::new("killed").color(egui::Color32::RED),
+        };
+        let status = ui.label(status.monospace());
+        if let Some(error) = &record.error {
+            status.on_hover_text(error);
+        }
+
+        let shell_command = record.shell_command();
+        ui.monospace(&shell_command).on_hover_text(format!(
+            "{} bytes stdout, {} bytes stderr",
+            record.stdout_bytes, record.stderr_bytes
+        ));
+        if accessible::icon_button(ui, "📋", "Copy as shell command").clicked() {
+            actions.push(LogAction::CopyCommand(shell_command));
+        }
+    }
 }
@@ -1,3 +1,4 @@
+pub mod favorites;
 pub mod installed;
 pub mod log;
 pub mod search;
@@ -1,16 +1,25 @@
-use crate::domain::entities::{Package, PackageType};
-use crate::presentation::components::{FilterState, InfoModal, PackageList};
+use crate::domain::entities::{FavoritePackage, OperationProgress, Package, PackageType};
+use crate::presentation::components::{ColumnWidths, FilterState, InfoModal, PackageList};
 use eframe::egui;
 use std::collections::HashSet;
 
 pub enum SearchAction {
     Search,
+    /// The query was edited; installed matches can be shown before searching.
+    QueryChanged,
     Install(Package),
     Uninstall(Package),
     Update(Package),
     LoadInfo(String, PackageType),
+    /// Show the Info window, loading the info first if it isn't yet.
+    OpenInfo(Package),
     Pin(Package),
     Unpin(Package),
+    ToggleFavorite(Package),
+    /// Every result was listed, not just the first ones.
+    ShowAllResults,
+    /// Formulae or casks were shown or hidden.
+    TypeFilterChanged,
 }
 
 pub struct SearchTab;
@@ -22,9 +31,15 @@ impl SearchTab {
         search_results: &mut PackageList,
         filter_state: &mut FilterState,
         packages_in_operation: &HashSet<String>,
+        operation_progress: &[(String, OperationProgress)],
         loading_search: bool,
         auto_load_version_info: &mut bool,
+        unified_search: &mut bool,
         info_modal: &mut InfoModal,
+        favorites: &[FavoritePackage],
+        compact: bool,
+        column_widths: &mut ColumnWidths,
+        result_limit: usize,
     ) -> Vec<SearchAction> {
         let mut actions = Vec::new();
 
@@ -32,6 +47,9 @@ impl SearchTab {
             ui.label("Search:");
             let response =
                 ui.text_edit_singleline(filter_state.search_query_mut());
+            if response.changed() {
+                actions.push(SearchAction::QueryChanged);
+            }
             if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
                 actions.push(SearchAction::Search);
             }
@@ -43,12 +61,17 @@ impl SearchTab {
         ui.horizontal(|ui| {
             let mut show_formulae = filter_state.show_formulae();
             let mut show_casks = filter_state.show_casks();
-            ui.checkbox(&mut show_formulae, "Show Formulae");
-            ui.checkbox(&mut show_casks, "Show Casks");
+            let formulae_toggled = ui.checkbox(&mut show_formulae, "Show Formulae").changed();
+            let casks_toggled = ui.checkbox(&mut show_casks, "Show Casks").changed();
             filter_state.set_show_formulae(show_formulae);
             filter_state.set_show_casks(show_casks);
+            if formulae_toggled || casks_toggled {
+                actions.push(SearchAction::TypeFilterChanged);
+            }
             ui.separator();
             ui.checkbox(auto_load_version_info, "Auto-load version info");
+            ui.checkbox(unified_search, "Include installed")
+                .on_hover_text("List matching installed packages first, then available ones");
         });
 
         ui.separator();
@@ -58,13 +81,17 @@ impl SearchTab {
                 ui.spinner();
                 ui.label("Searching...");
             });
-        } else {
+        }
+
+        // Installed matches are already known, so they stay up while brew searches
+        if !loading_search || *unified_search {
             let mut install_action = None;
             let mut uninstall_action = None;
             let mut update_action = None;
             let mut load_info_action = None;
             let mut pin_action = None;
             let mut unpin_action = None;
+            let mut toggle_favorite_action = None;
 
             search_results.show_filtered_with_search_and_pin(
                 ui,
@@ -76,8 +103,14 @@ impl SearchTab {
                 "", // Filter string is empty here as we filter by query logic
                 &mut load_info_action,
                 packages_in_operation,
+                operation_progress,
                 &mut pin_action,
                 &mut unpin_action,
+                favorites,
+                &mut toggle_favorite_action,
+                compact,
+                column_widths,
+                result_limit,
             );
 
             if let Some(package) = install_action {
@@ -98,9 +131,18 @@ impl SearchTab {
             if let Some(package) = unpin_action {
                 actions.push(SearchAction::Unpin(package));
             }
+            if let Some(package) = toggle_favorite_action {
+                actions.push(SearchAction::ToggleFavorite(package));
+            }
             if let Some(package) = search_results.get_show_info_action() {
                 info_modal.show(package);
             }
+            if let Some(package) = search_results.get_open_info_action() {
+                actions.push(SearchAction::OpenInfo(package));
+            }
+            if search_results.take_show_all_action() {
+                actions.push(SearchAction::ShowAllResults);
+            }
         }
 
         actions
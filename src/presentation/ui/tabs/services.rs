@@ -1,12 +1,28 @@
-use crate::presentation::components::ServiceList;
+use crate::domain::entities::ServiceStatus;
+use crate::presentation::components::{ColumnWidths, FilterState, ServiceList};
 use eframe::egui;
 use std::collections::HashSet;
 
+#[derive(Debug)]
 pub enum ServiceAction {
     Refresh,
+    /// Open the detail view for the service.
+    ShowInfo(String),
+    /// Open the plist at this path in the plist viewer.
+    ViewPlist(String),
     Start(String),
     Stop(String),
     Restart(String),
+    StartAllStopped,
+    /// Asks for confirmation first.
+    StopAllRunning,
+    /// Asks for confirmation first.
+    RestartAllRunning,
+    /// The ticked services, in list order.
+    StartSelected(Vec<String>),
+    StopSelected(Vec<String>),
+    /// Asks for confirmation first.
+    CleanupStale,
 }
 
 pub struct ServicesTab;
@@ -15,17 +31,93 @@ impl ServicesTab {
     pub fn show(
         ui: &mut egui::Ui,
         service_list: &mut ServiceList,
+        filter_state: &mut FilterState,
         services_in_operation: &HashSet<String>,
         loading_services: bool,
+        cleaning_services: bool,
+        column_widths: &mut ColumnWidths,
     ) -> Vec<ServiceAction> {
         let mut actions = Vec::new();
 
         ui.horizontal(|ui| {
             ui.label("Brew Services");
             ui.separator();
+            ui.label("Search:");
+            ui.text_edit_singleline(filter_state.services_search_query_mut());
+            ui.separator();
             if ui.button("Refresh").clicked() {
                 actions.push(ServiceAction::Refresh);
             }
+            ui.separator();
+            // One bulk operation at a time; each queues one service after another
+            let idle = !loading_services && services_in_operation.is_empty();
+            let stopped = service_list.names_with_status(&ServiceStatus::Stopped);
+            let running = service_list.names_with_status(&ServiceStatus::Started);
+            if ui
+                .add_enabled(
+                    idle && !stopped.is_empty(),
+                    egui::Button::new("Start All Stopped..."),
+                )
+                .clicked()
+            {
+                actions.push(ServiceAction::StartAllStopped);
+            }
+            if ui
+                .add_enabled(
+                    idle && !running.is_empty(),
+                    egui::Button::new("Stop All Running..."),
+                )
+                .clicked()
+            {
+                actions.push(ServiceAction::StopAllRunning);
+            }
+            if ui
+                .add_enabled(
+                    idle && !running.is_empty(),
+                    egui::Button::new("Restart All Running..."),
+                )
+                .clicked()
+            {
+                actions.push(ServiceAction::RestartAllRunning);
+            }
+            ui.separator();
+            if ui
+                .add_enabled(
+                    idle && !cleaning_services,
+                    egui::Button::new("Cleanup Stale Services..."),
+                )
+                .on_hover_text(
+                    "Runs brew services cleanup, removing the services of formulae \
+                     that are no longer installed",
+                )
+                .clicked()
+            {
+                actions.push(ServiceAction::CleanupStale);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            if ui.button("Select All").clicked() {
+                service_list.select_all();
+            }
+            if ui.button("Select None").clicked() {
+                service_list.clear_selection();
+            }
+            ui.separator();
+            let idle = !loading_services && services_in_operation.is_empty();
+            let can_run = idle && service_list.has_selection();
+            if ui
+                .add_enabled(can_run, egui::Button::new("Start Selected"))
+                .clicked()
+            {
+                actions.push(ServiceAction::StartSelected(service_list.selected()));
+            }
+            if ui
+                .add_enabled(can_run, egui::Button::new("Stop Selected"))
+                .clicked()
+            {
+                actions.push(ServiceAction::StopSelected(service_list.selected()));
+            }
         });
 
         ui.separator();
@@ -39,13 +131,19 @@ impl ServicesTab {
             let mut start_action = None;
             let mut stop_action = None;
             let mut restart_action = None;
+            let mut info_action = None;
+            let mut plist_action = None;
 
             service_list.show(
                 ui,
                 &mut start_action,
                 &mut stop_action,
                 &mut restart_action,
+                &mut info_action,
+                &mut plist_action,
+                filter_state.services_search_query(),
                 services_in_operation,
+                column_widths,
             );
 
             if let Some(service_name) = start_action {
@@ -57,6 +155,12 @@ impl ServicesTab {
             if let Some(service_name) = restart_action {
                 actions.push(ServiceAction::Restart(service_name));
             }
+            if let Some(service_name) = info_action {
+                actions.push(ServiceAction::ShowInfo(service_name));
+            }
+            if let Some(path) = plist_action {
+                actions.push(ServiceAction::ViewPlist(path));
+            }
         }
 
         actions
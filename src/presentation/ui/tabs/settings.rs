@@ -1,25 +1,72 @@
-use crate::domain::entities::{AppConfig, ThemeMode};
-use crate::presentation::components::{CleanupType, LogLevel, LogManager};
+use crate::application::dto::CacheInfoDto;
+use crate::domain::entities::{
+    AUTO_EXPORT_KEEP_RANGE, AppConfig, AutoExport, COMMAND_TIMEOUT_SECS_RANGE,
+    DEFAULT_ACCENT_COLOR, ExportInterval, LogVerbosity, METADATA_STALE_AFTER_HOURS_RANGE,
+    READ_TIMEOUT_SECS_RANGE, SEARCH_RESULT_LIMIT_RANGE, SERVICES_REFRESH_SECS_RANGE, ThemeMode,
+    UI_SCALE_RANGE,
+};
+use crate::infrastructure::brew::command::{BrewCommand, KNOWN_ENV_VARS};
+use crate::infrastructure::brew::paths::BrewPaths;
+use crate::infrastructure::snapshots::Snapshot;
+use crate::infrastructure::system;
+use crate::presentation::components::cleanup_modal::format_size;
+use crate::presentation::components::{CleanupType, LogLevel, LogManager, Tab, accessible};
+use crate::presentation::services::log_capture;
 use eframe::egui;
+use std::path::PathBuf;
+
+/// Minimum widths at which the sections are laid out side by side.
+const THREE_COLUMN_MIN_WIDTH: f32 = 1100.0;
+const TWO_COLUMN_MIN_WIDTH: f32 = 700.0;
 
 pub enum SettingsAction {
     SaveConfig,
     ApplyTheme,
+    ApplyBrewEnv,
+    ApplyLogTargets,
+    ApplyLogVerbosity,
+    ApplyFileLogging,
+    /// List as many search results as the new limit allows.
+    ApplySearchResultLimit,
+    OpenLogFolder,
     ShowCleanupPreview(CleanupType),
+    RefreshCacheInfo,
+    RefreshOrphanCount,
+    CheckForAppUpdate,
+    OpenFolder(PathBuf),
     UpdateAll,
     ExportPackages,
+    ExportMarkdown,
+    ExportCsv,
     ImportPackages,
+    CompareWithExport,
+    ChooseAutoExportDir,
+    /// Export now, then on the schedule from now on.
+    RestartAutoExport,
+    RestoreSnapshot(PathBuf),
+    ReportIssue,
+    CopyDiagnostics,
+    SaveDiagnostics,
 }
 
 pub struct SettingsTab;
 
 impl SettingsTab {
+    #[allow(clippy::too_many_arguments)]
     pub fn show(
         ui: &mut egui::Ui,
         config: &mut AppConfig,
         log_manager: &mut LogManager,
         loading_export: bool,
         loading_import: bool,
+        comparing: bool,
+        loading_issue_report: bool,
+        cache_info: Option<&CacheInfoDto>,
+        loading_cache_info: bool,
+        orphan_count: Option<usize>,
+        loading_orphan_count: bool,
+        brew_paths: Option<&BrewPaths>,
+        snapshots: &[Snapshot],
     ) -> Vec<SettingsAction> {
         let mut actions = Vec::new();
 
@@ -27,117 +74,866 @@ impl SettingsTab {
             ui.heading("Settings & Maintenance");
             ui.separator();
 
-            ui.columns(3, |columns| {
-                // Column 1: General & Logs
-                columns[0].vertical(|ui| {
-                    ui.group(|ui| {
-                        ui.heading("General");
-                        
-                        ui.horizontal(|ui| {
-                            ui.label("Theme:");
-                            egui::ComboBox::new("theme_combo", "")
-                                .selected_text(format!("{:?}", config.theme))
-                                .show_ui(ui, |ui| {
-                                    if ui.selectable_value(&mut config.theme, ThemeMode::System, "System").clicked() {
-                                        actions.push(SettingsAction::SaveConfig);
-                                        actions.push(SettingsAction::ApplyTheme);
-                                    }
-                                    if ui.selectable_value(&mut config.theme, ThemeMode::Light, "Light").clicked() {
-                                        actions.push(SettingsAction::SaveConfig);
-                                        actions.push(SettingsAction::ApplyTheme);
-                                    }
-                                    if ui.selectable_value(&mut config.theme, ThemeMode::Dark, "Dark").clicked() {
-                                        actions.push(SettingsAction::SaveConfig);
-                                        actions.push(SettingsAction::ApplyTheme);
-                                    }
-                                });
-                        });
-
-                        if ui.checkbox(&mut config.auto_update_check, "Check updates on startup").changed() {
-                            actions.push(SettingsAction::SaveConfig);
-                        }
+            let width = ui.available_width();
+            if width >= THREE_COLUMN_MIN_WIDTH {
+                ui.columns(3, |columns| {
+                    columns[0].vertical(|ui| {
+                        Self::general_section(ui, config, log_manager, &mut actions)
+                    });
+                    columns[1].vertical(|ui| {
+                        Self::maintenance_section(
+                            ui,
+                            config,
+                            cache_info,
+                            loading_cache_info,
+                            orphan_count,
+                            loading_orphan_count,
+                            brew_paths,
+                            &mut actions,
+                        )
+                    });
+                    columns[2].vertical(|ui| {
+                        Self::management_section(
+                            ui,
+                            loading_export,
+                            loading_import,
+                            comparing,
+                            loading_issue_report,
+                            snapshots,
+                            &mut config.auto_export,
+                            &mut actions,
+                        )
+                    });
+                });
+            } else if width >= TWO_COLUMN_MIN_WIDTH {
+                ui.columns(2, |columns| {
+                    columns[0].vertical(|ui| {
+                        Self::general_section(ui, config, log_manager, &mut actions)
+                    });
+                    columns[1].vertical(|ui| {
+                        Self::maintenance_section(
+                            ui,
+                            config,
+                            cache_info,
+                            loading_cache_info,
+                            orphan_count,
+                            loading_orphan_count,
+                            brew_paths,
+                            &mut actions,
+                        );
+                        ui.add_space(16.0);
+                        Self::management_section(
+                            ui,
+                            loading_export,
+                            loading_import,
+                            comparing,
+                            loading_issue_report,
+                            snapshots,
+                            &mut config.auto_export,
+                            &mut actions,
+                        );
+                    });
+                });
+            } else {
+                Self::general_section(ui, config, log_manager, &mut actions);
+                ui.add_space(16.0);
+                Self::maintenance_section(
+                    ui,
+                    config,
+                    cache_info,
+                    loading_cache_info,
+                    orphan_count,
+                    loading_orphan_count,
+                    brew_paths,
+                    &mut actions,
+                );
+                ui.add_space(16.0);
+                Self::management_section(
+                    ui,
+                    loading_export,
+                    loading_import,
+                    comparing,
+                    loading_issue_report,
+                    snapshots,
+                    &mut config.auto_export,
+                    &mut actions,
+                );
+            }
+        });
+
+        actions
+    }
+
+    fn general_section(
+        ui: &mut egui::Ui,
+        config: &mut AppConfig,
+        log_manager: &mut LogManager,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.heading("General");
 
-                        if ui.checkbox(&mut config.confirm_before_actions, "Confirm danger actions").changed() {
-                            actions.push(SettingsAction::SaveConfig);
+            ui.horizontal(|ui| {
+                ui.label("Theme:");
+                egui::ComboBox::new("theme_combo", "")
+                    .selected_text(format!("{:?}", config.theme))
+                    .show_ui(ui, |ui| {
+                        for (mode, label) in [
+                            (ThemeMode::System, "System"),
+                            (ThemeMode::Light, "Light"),
+                            (ThemeMode::Dark, "Dark"),
+                        ] {
+                            if ui
+                                .selectable_value(&mut config.theme, mode, label)
+                                .clicked()
+                            {
+                                actions.push(SettingsAction::SaveConfig);
+                                actions.push(SettingsAction::ApplyTheme);
+                            }
                         }
                     });
+            });
 
-                    ui.add_space(10.0);
-
-                    ui.group(|ui| {
-                        ui.heading("Log Levels");
-                        ui.vertical(|ui| {
-                            let mut debug = log_manager.is_level_visible(LogLevel::Debug);
-                            let mut info = log_manager.is_level_visible(LogLevel::Info);
-                            let mut warn = log_manager.is_level_visible(LogLevel::Warn);
-                            let mut error = log_manager.is_level_visible(LogLevel::Error);
-
-                            ui.checkbox(&mut debug, "Debug");
-                             ui.checkbox(&mut info, "Info");
-                            ui.checkbox(&mut warn, "Warn");
-                            ui.checkbox(&mut error, "Error");
-
-                            if debug != log_manager.is_level_visible(LogLevel::Debug) { log_manager.set_level_visible(LogLevel::Debug, debug); }
-                            if info != log_manager.is_level_visible(LogLevel::Info) { log_manager.set_level_visible(LogLevel::Info, info); }
-                            if warn != log_manager.is_level_visible(LogLevel::Warn) { log_manager.set_level_visible(LogLevel::Warn, warn); }
-                            if error != log_manager.is_level_visible(LogLevel::Error) { log_manager.set_level_visible(LogLevel::Error, error); }
-                        });
-                    });
+            ui.horizontal(|ui| {
+                ui.label("Accent color:");
+                if egui::color_picker::color_edit_button_srgb(ui, &mut config.accent_color)
+                    .changed()
+                {
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplyTheme);
+                }
+                if ui
+                    .add_enabled(
+                        config.accent_color != DEFAULT_ACCENT_COLOR,
+                        egui::Button::new("Reset"),
+                    )
+                    .clicked()
+                {
+                    config.accent_color = DEFAULT_ACCENT_COLOR;
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplyTheme);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("UI scale:");
+                let slider = ui.add(
+                    egui::Slider::new(&mut config.ui_scale, UI_SCALE_RANGE)
+                        .step_by(0.05)
+                        .fixed_decimals(2)
+                        .suffix("×"),
+                );
+                if slider.changed() {
+                    actions.push(SettingsAction::ApplyTheme);
+                }
+                // Rescale live while dragging but only save once it's let go
+                if slider.drag_stopped() || (slider.changed() && !slider.dragged()) {
+                    actions.push(SettingsAction::SaveConfig);
+                }
+                if ui
+                    .add_enabled(config.ui_scale != 1.0, egui::Button::new("Reset"))
+                    .clicked()
+                {
+                    config.ui_scale = 1.0;
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplyTheme);
+                }
+            });
+
+            if ui
+                .checkbox(&mut config.compact_mode, "Compact package lists")
+                .on_hover_text("Tighter rows, with status shown as a colored dot")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+            }
+
+            if ui
+                .checkbox(&mut config.show_install_date, "Show install dates")
+                .on_hover_text("Adds a sortable Installed column to the installed packages")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+            }
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Tabs:")
+                    .on_hover_text("Installed and Settings are always shown");
+                for tab in Tab::ALL.into_iter().filter(|tab| tab.can_hide()) {
+                    let mut shown = !config.is_tab_hidden(tab.key());
+                    if ui.checkbox(&mut shown, tab.label()).changed() {
+                        config.set_tab_hidden(tab.key(), !shown);
+                        actions.push(SettingsAction::SaveConfig);
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Refresh services every (s):")
+                    .on_hover_text("While the Services tab is open; 0 turns it off");
+                let response = ui.add(
+                    egui::DragValue::new(&mut config.services_refresh_secs)
+                        .range(SERVICES_REFRESH_SECS_RANGE),
+                );
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    actions.push(SettingsAction::SaveConfig);
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Search results shown:")
+                    .on_hover_text("More are listed on request, without searching again");
+                let response = ui.add(
+                    egui::DragValue::new(&mut config.search_result_limit)
+                        .range(SEARCH_RESULT_LIMIT_RANGE),
+                );
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplySearchResultLimit);
+                }
+            });
+
+            if ui
+                .checkbox(&mut config.auto_update_check, "Check updates on startup")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+            }
+
+            if ui
+                .checkbox(
+                    &mut config.check_for_app_updates,
+                    "Check for Brewsty updates",
+                )
+                .on_hover_text("Ask GitHub for the latest Brewsty release at startup")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+                if config.check_for_app_updates {
+                    actions.push(SettingsAction::CheckForAppUpdate);
+                }
+            }
+
+            if ui
+                .checkbox(&mut config.confirm_before_actions, "Confirm danger actions")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.heading("Homebrew Environment");
+
+            let no_auto_update_in_env = BrewCommand::env_flag_set("HOMEBREW_NO_AUTO_UPDATE");
+            Self::env_indicator(ui, "HOMEBREW_NO_AUTO_UPDATE", no_auto_update_in_env);
+            Self::env_indicator(
+                ui,
+                "HOMEBREW_NO_ANALYTICS",
+                BrewCommand::env_flag_set("HOMEBREW_NO_ANALYTICS"),
+            );
+
+            ui.add_enabled_ui(!no_auto_update_in_env, |ui| {
+                if ui
+                    .checkbox(&mut config.homebrew_no_auto_update, "Skip brew auto-update")
+                    .on_hover_text("Sets HOMEBREW_NO_AUTO_UPDATE for brew commands run by Brewsty")
+                    .on_disabled_hover_text("Already set in the environment")
+                    .changed()
+                {
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplyBrewEnv);
+                }
+            });
+
+            ui.add_space(4.0);
+            egui::Grid::new("timeout_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    ui.label("Query timeout (s):")
+                        .on_hover_text("list, search, info and outdated checks");
+                    let query = ui.add(
+                        egui::DragValue::new(&mut config.read_timeout_secs)
+                            .range(READ_TIMEOUT_SECS_RANGE),
+                    );
+                    ui.end_row();
+
+                    ui.label("Operation timeout (s):")
+                        .on_hover_text("install, upgrade, cleanup and services");
+                    let operation = ui.add(
+                        egui::DragValue::new(&mut config.command_timeout_secs)
+                            .range(COMMAND_TIMEOUT_SECS_RANGE),
+                    );
+                    ui.end_row();
+
+                    // Only save once a drag ends, not on every intermediate value
+                    let committed =
+                        |r: &egui::Response| r.drag_stopped() || (r.changed() && !r.dragged());
+                    if committed(&query) || committed(&operation) {
+                        actions.push(SettingsAction::SaveConfig);
+                        actions.push(SettingsAction::ApplyBrewEnv);
+                    }
                 });
 
-                // Column 2: Maintenance
-                columns[1].vertical(|ui| {
-                    ui.heading("Maintenance");
-                    ui.separator();
-                    ui.vertical_centered(|ui| {
-                        if ui.button("Clean Cache").clicked() {
-                            actions.push(SettingsAction::ShowCleanupPreview(CleanupType::Cache));
-                        }
-                        ui.label("Remove old downloads");
+            ui.add_space(4.0);
+            if Self::env_vars(ui, &mut config.brew_env) {
+                actions.push(SettingsAction::SaveConfig);
+                actions.push(SettingsAction::ApplyBrewEnv);
+            }
+        });
+
+        ui.add_space(10.0);
 
-                        ui.add_space(10.0);
+        ui.group(|ui| {
+            ui.set_width(ui.available_width());
+            ui.heading("Log Levels");
 
-                        if ui.button("Cleanup Old Versions").clicked() {
-                            actions.push(SettingsAction::ShowCleanupPreview(CleanupType::OldVersions));
+            ui.horizontal(|ui| {
+                ui.label("Verbosity:");
+                let default_label = format!("Default ({})", log_capture::default_verbosity_label());
+                let selected_label = match config.log_verbosity {
+                    None => default_label.clone(),
+                    Some(verbosity) => format!("{:?}", verbosity),
+                };
+                egui::ComboBox::new("verbosity_combo", "")
+                    .selected_text(selected_label)
+                    .show_ui(ui, |ui| {
+                        let options = [
+                            (None, default_label.as_str()),
+                            (Some(LogVerbosity::Error), "Error"),
+                            (Some(LogVerbosity::Warn), "Warn"),
+                            (Some(LogVerbosity::Info), "Info"),
+                            (Some(LogVerbosity::Debug), "Debug"),
+                            (Some(LogVerbosity::Trace), "Trace"),
+                        ];
+                        for (verbosity, label) in options {
+                            if ui
+                                .selectable_value(&mut config.log_verbosity, verbosity, label)
+                                .clicked()
+                            {
+                                actions.push(SettingsAction::SaveConfig);
+                                actions.push(SettingsAction::ApplyLogVerbosity);
+                            }
                         }
-                        ui.label("Remove old versions");
+                    })
+                    .response
+                    .on_hover_text(
+                        "Which events are recorded at all; the toggles below filter the view",
+                    );
+            });
+
+            ui.horizontal(|ui| {
+                if ui
+                    .checkbox(&mut config.file_logging_enabled, "Write logs to file")
+                    .on_hover_text("Keeps rotating log files with timestamps and module names")
+                    .changed()
+                {
+                    actions.push(SettingsAction::SaveConfig);
+                    actions.push(SettingsAction::ApplyFileLogging);
+                }
+                if ui.button("Open log folder").clicked() {
+                    actions.push(SettingsAction::OpenLogFolder);
+                }
+            });
 
-                        ui.add_space(10.0);
+            ui.vertical(|ui| {
+                for (level, label) in [
+                    (LogLevel::Trace, "Trace"),
+                    (LogLevel::Debug, "Debug"),
+                    (LogLevel::Info, "Info"),
+                    (LogLevel::Warn, "Warn"),
+                    (LogLevel::Error, "Error"),
+                ] {
+                    let mut visible = log_manager.is_level_visible(level);
+                    if ui.checkbox(&mut visible, label).changed() {
+                        log_manager.set_level_visible(level, visible);
+                    }
+                }
+            });
 
-                        if ui.button("Update All Packages").clicked() {
-                            actions.push(SettingsAction::UpdateAll);
+            ui.add_space(4.0);
+            ui.label("Per module:");
+            egui::Grid::new("log_target_grid")
+                .num_columns(2)
+                .show(ui, |ui| {
+                    let targets = log_manager.target_levels().to_vec();
+                    for target in targets {
+                        ui.monospace(
+                            target
+                                .target
+                                .strip_prefix("brewsty::")
+                                .unwrap_or(&target.target),
+                        );
+                        let mut level = target.level;
+                        egui::ComboBox::from_id_salt(&target.target)
+                            .selected_text(Self::target_level_label(level))
+                            .show_ui(ui, |ui| {
+                                for option in [
+                                    None,
+                                    Some(LogLevel::Error),
+                                    Some(LogLevel::Warn),
+                                    Some(LogLevel::Info),
+                                    Some(LogLevel::Debug),
+                                    Some(LogLevel::Trace),
+                                ] {
+                                    ui.selectable_value(
+                                        &mut level,
+                                        option,
+                                        Self::target_level_label(option),
+                                    );
+                                }
+                            });
+                        if level != target.level {
+                            log_manager.set_target_level(&target.target, level);
+                            actions.push(SettingsAction::ApplyLogTargets);
                         }
-                        ui.label("Update all installed");
-                    });
+                        ui.end_row();
+                    }
                 });
+        });
+    }
+
+    fn target_level_label(level: Option<LogLevel>) -> &'static str {
+        match level {
+            None => "Off",
+            Some(LogLevel::Error) => "Error",
+            Some(LogLevel::Warn) => "Warn",
+            Some(LogLevel::Info) => "Info",
+            Some(LogLevel::Debug) => "Debug",
+            Some(LogLevel::Trace) => "Trace",
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn maintenance_section(
+        ui: &mut egui::Ui,
+        config: &mut AppConfig,
+        cache_info: Option<&CacheInfoDto>,
+        loading_cache_info: bool,
+        orphan_count: Option<usize>,
+        loading_orphan_count: bool,
+        brew_paths: Option<&BrewPaths>,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        ui.heading("Maintenance");
+        ui.separator();
+        ui.vertical_centered(|ui| {
+            if Self::full_width_button(ui, "Clean Cache", true)
+                .on_hover_text(
+                    "Runs brew cleanup -s, removing every cached download, including those \
+                     of the latest versions, so reinstalling anything downloads it again. \
+                     Shows what would be removed first.",
+                )
+                .clicked()
+            {
+                actions.push(SettingsAction::ShowCleanupPreview(CleanupType::Cache));
+            }
+            ui.label("Remove old downloads");
+            Self::cache_size(ui, cache_info, loading_cache_info, actions);
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Cleanup Old Versions", true)
+                .on_hover_text(
+                    "Runs brew cleanup --prune=all, deleting every installed version but the \
+                     newest, so you can no longer switch back to an older one. Shows what \
+                     would be removed first.",
+                )
+                .clicked()
+            {
+                actions.push(SettingsAction::ShowCleanupPreview(CleanupType::OldVersions));
+            }
+            ui.label("Remove old versions");
+
+            ui.add_space(10.0);
 
-                // Column 3: Package Mgmt
-                columns[2].vertical(|ui| {
-                    ui.heading("Management");
-                    ui.separator();
-                    ui.vertical_centered(|ui| {
-                        if ui
-                            .add_enabled(!loading_export, egui::Button::new("Export Packages"))
-                            .clicked()
-                        {
-                            actions.push(SettingsAction::ExportPackages);
+            if Self::full_width_button(ui, "Remove Unneeded Dependencies", true)
+                .on_hover_text(
+                    "Runs brew autoremove, uninstalling formulae that were only installed as \
+                     dependencies of something no longer installed. Anything you use directly \
+                     but never installed yourself goes too. Shows what would be removed first.",
+                )
+                .clicked()
+            {
+                actions.push(SettingsAction::ShowCleanupPreview(CleanupType::Autoremove));
+            }
+            ui.label("Uninstall orphaned formulae");
+            Self::orphans(ui, orphan_count, loading_orphan_count, actions);
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Update All Packages", true)
+                .on_hover_text(
+                    "Runs brew upgrade for every outdated package you leave checked, after \
+                     previewing it with brew upgrade --dry-run. A snapshot of the installed \
+                     packages is saved first so they can be restored.",
+                )
+                .clicked()
+            {
+                actions.push(SettingsAction::UpdateAll);
+            }
+            ui.label("Update all installed");
+            if ui
+                .checkbox(&mut config.update_before_upgrade, "Run brew update first")
+                .on_hover_text("Refresh the formula and cask metadata so newer versions are found")
+                .changed()
+            {
+                actions.push(SettingsAction::SaveConfig);
+            }
+            ui.horizontal(|ui| {
+                ui.label("Metadata stale after (h):")
+                    .on_hover_text("Older metadata is shown in orange in the Installed tab");
+                let response = ui.add(
+                    egui::DragValue::new(&mut config.metadata_stale_after_hours)
+                        .range(METADATA_STALE_AFTER_HOURS_RANGE),
+                );
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    actions.push(SettingsAction::SaveConfig);
+                }
+            });
+
+            if let Some(paths) = brew_paths {
+                ui.add_space(10.0);
+                Self::folder_links(ui, paths, actions);
+            }
+        });
+    }
+
+    fn folder_links(ui: &mut egui::Ui, paths: &BrewPaths, actions: &mut Vec<SettingsAction>) {
+        ui.horizontal_wrapped(|ui| {
+            ui.weak(format!("Open in {}:", system::FILE_MANAGER));
+            for (label, path) in [
+                ("Cache", paths.cache.clone()),
+                ("Cellar", paths.cellar()),
+                ("Caskroom", paths.caskroom()),
+            ] {
+                if ui
+                    .link(label)
+                    .on_hover_text(path.display().to_string())
+                    .clicked()
+                {
+                    actions.push(SettingsAction::OpenFolder(path));
+                }
+            }
+        });
+    }
+
+    fn cache_size(
+        ui: &mut egui::Ui,
+        cache_info: Option<&CacheInfoDto>,
+        loading_cache_info: bool,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        ui.horizontal(|ui| {
+            if loading_cache_info {
+                ui.spinner();
+                ui.weak("Measuring cache...");
+                return;
+            }
+
+            match cache_info {
+                Some(info) => ui.weak(format!(
+                    "Cache: {} in {} downloads",
+                    format_size(info.total_size),
+                    info.package_count
+                )),
+                None => ui.weak("Cache size unknown"),
+            };
+            if accessible::icon_button(ui, "🔄", "Measure the cache again").clicked() {
+                actions.push(SettingsAction::RefreshCacheInfo);
+            }
+        });
+    }
+
+    /// How many formulae `brew autoremove` would remove; clicking the count
+    /// opens its preview.
+    fn orphans(
+        ui: &mut egui::Ui,
+        orphan_count: Option<usize>,
+        loading_orphan_count: bool,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        ui.horizontal_wrapped(|ui| {
+            if loading_orphan_count {
+                ui.spinner();
+                ui.weak("Looking for unneeded dependencies...");
+                return;
+            }
+
+            match orphan_count {
+                Some(0) => {
+                    ui.weak("No unneeded dependencies");
+                }
+                Some(count) => {
+                    let text = if count == 1 {
+                        "1 formula was installed only as a dependency and is no longer needed"
+                            .to_string()
+                    } else {
+                        format!(
+                            "{} formulae were installed only as dependencies and are no longer needed",
+                            count
+                        )
+                    };
+                    if ui.link(text).on_hover_text("Show what would be removed").clicked() {
+                        actions.push(SettingsAction::ShowCleanupPreview(CleanupType::Autoremove));
+                    }
+                }
+                None => {
+                    ui.weak("Unneeded dependencies unknown");
+                }
+            }
+            if accessible::icon_button(ui, "🔄", "Check for unneeded dependencies again").clicked()
+            {
+                actions.push(SettingsAction::RefreshOrphanCount);
+            }
+        });
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn management_section(
+        ui: &mut egui::Ui,
+        loading_export: bool,
+        loading_import: bool,
+        comparing: bool,
+        loading_issue_report: bool,
+        snapshots: &[Snapshot],
+        auto_export: &mut AutoExport,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        ui.heading("Management");
+        ui.separator();
+        ui.vertical_centered(|ui| {
+            if Self::full_width_button(ui, "Export Packages", !loading_export).clicked() {
+                actions.push(SettingsAction::ExportPackages);
+            }
+            ui.label("Export to JSON");
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Export as Markdown", !loading_export).clicked() {
+                actions.push(SettingsAction::ExportMarkdown);
+            }
+            ui.label("Export a readable list for a README or gist");
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Export CSV", !loading_export).clicked() {
+                actions.push(SettingsAction::ExportCsv);
+            }
+            ui.label("Export to a spreadsheet");
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Import Packages", !loading_import).clicked() {
+                actions.push(SettingsAction::ImportPackages);
+            }
+            ui.label("Import from JSON");
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Compare with Export…", !comparing).clicked() {
+                actions.push(SettingsAction::CompareWithExport);
+            }
+            ui.label("See what changed since an exported list");
+
+            ui.add_space(10.0);
+
+            ui.add_enabled_ui(!loading_import && !snapshots.is_empty(), |ui| {
+                ui.menu_button("Restore Snapshot ⏷", |ui| {
+                    for snapshot in snapshots {
+                        if ui.button(snapshot.label()).clicked() {
+                            actions.push(SettingsAction::RestoreSnapshot(snapshot.path.clone()));
+                            ui.close_menu();
                         }
-                        ui.label("Export to JSON");
+                    }
+                })
+                .response
+                .on_hover_text("Installs the packages from the snapshot that are missing now")
+                .on_disabled_hover_text("Snapshots are taken before Update All and large imports");
+            });
+            ui.label("Saved before bulk operations");
 
-                        ui.add_space(10.0);
+            ui.add_space(10.0);
+
+            Self::auto_export(ui, auto_export, actions);
+
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Report an Issue", !loading_issue_report).clicked() {
+                actions.push(SettingsAction::ReportIssue);
+            }
+            if loading_issue_report {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Collecting diagnostics...");
+                });
+            } else {
+                ui.label("Copy diagnostics and open GitHub");
+            }
 
-                        if ui
-                            .add_enabled(!loading_import, egui::Button::new("Import Packages"))
-                            .clicked()
-                        {
-                            actions.push(SettingsAction::ImportPackages);
+            ui.add_space(10.0);
+
+            if Self::full_width_button(ui, "Copy Diagnostics", !loading_issue_report).clicked() {
+                actions.push(SettingsAction::CopyDiagnostics);
+            }
+            if Self::full_width_button(ui, "Save Diagnostics…", !loading_issue_report).clicked() {
+                actions.push(SettingsAction::SaveDiagnostics);
+            }
+            ui.label("Versions, brew config, settings and recent log, secrets redacted");
+        });
+    }
+
+    fn auto_export(
+        ui: &mut egui::Ui,
+        auto_export: &mut AutoExport,
+        actions: &mut Vec<SettingsAction>,
+    ) {
+        if ui
+            .checkbox(&mut auto_export.enabled, "Export automatically")
+            .on_hover_text("On startup and then on a schedule, to a dated JSON file")
+            .changed()
+        {
+            actions.push(SettingsAction::SaveConfig);
+            actions.push(SettingsAction::RestartAutoExport);
+        }
+
+        ui.add_enabled_ui(auto_export.enabled, |ui| {
+            ui.horizontal(|ui| {
+                if ui.button("Choose Folder…").clicked() {
+                    actions.push(SettingsAction::ChooseAutoExportDir);
+                }
+                match &auto_export.dir {
+                    Some(dir) => ui.weak(dir.display().to_string()),
+                    None => ui.weak("No folder chosen"),
+                };
+            });
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt("auto_export_interval")
+                    .selected_text(auto_export.interval.to_string())
+                    .show_ui(ui, |ui| {
+                        for interval in [ExportInterval::Daily, ExportInterval::Weekly] {
+                            if ui
+                                .selectable_value(
+                                    &mut auto_export.interval,
+                                    interval,
+                                    interval.to_string(),
+                                )
+                                .clicked()
+                            {
+                                actions.push(SettingsAction::SaveConfig);
+                            }
                         }
-                        ui.label("Import from JSON");
                     });
-                });
+                ui.label("Keep:");
+                let response = ui
+                    .add(egui::DragValue::new(&mut auto_export.keep).range(AUTO_EXPORT_KEEP_RANGE));
+                if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                    actions.push(SettingsAction::SaveConfig);
+                }
             });
         });
+    }
 
-        actions
+    fn full_width_button(ui: &mut egui::Ui, text: &str, enabled: bool) -> egui::Response {
+        let button = egui::Button::new(text).min_size(egui::vec2(ui.available_width(), 0.0));
+        ui.add_enabled(enabled, button)
+    }
+
+    /// Editable `NAME = value` rows. Returns whether a change should be saved,
+    /// which for text is once editing a field ends.
+    fn env_vars(ui: &mut egui::Ui, vars: &mut Vec<(String, String)>) -> bool {
+        let mut changed = false;
+        let mut remove = None;
+
+        ui.label("Extra variables:")
+            .on_hover_text("Passed verbatim to every brew command Brewsty runs");
+        egui::Grid::new("brew_env_grid")
+            .num_columns(3)
+            .show(ui, |ui| {
+                for (i, (key, value)) in vars.iter_mut().enumerate() {
+                    let mut key_edit = ui.add(
+                        egui::TextEdit::singleline(key)
+                            .hint_text("NAME")
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(140.0),
+                    );
+                    if let Some((_, _, description)) =
+                        KNOWN_ENV_VARS.iter().find(|(name, _, _)| name == key)
+                    {
+                        key_edit = key_edit.on_hover_text(*description);
+                    }
+                    let value_edit = ui.add(
+                        egui::TextEdit::singleline(value)
+                            .hint_text("value")
+                            .desired_width(140.0),
+                    );
+                    changed |= key_edit.lost_focus() || value_edit.lost_focus();
+                    let remove_label = if key.is_empty() {
+                        "Remove variable".to_string()
+                    } else {
+                        format!("Remove {}", key)
+                    };
+                    if accessible::icon_button(ui, "✖", &remove_label).clicked() {
+                        remove = Some(i);
+                    }
+                    ui.end_row();
+
+                    // A new, still empty row isn't an error yet
+                    if !key.is_empty()
+                        && let Err(e) = BrewCommand::check_env_var(key, value)
+                    {
+                        ui.colored_label(ui.visuals().error_fg_color, e.to_string());
+                        ui.end_row();
+                    }
+                }
+            });
+
+        if let Some(i) = remove {
+            vars.remove(i);
+            changed = true;
+        }
+        ui.horizontal(|ui| {
+            if ui.small_button("➕ Add variable").clicked() {
+                vars.push((String::new(), String::new()));
+            }
+            ui.menu_button("Homebrew variables ⏷", |ui| {
+                for (name, suggested, description) in KNOWN_ENV_VARS {
+                    let added = vars.iter().any(|(key, _)| key == name);
+                    let in_env = BrewCommand::env_flag_set(name);
+                    let response = ui
+                        .add_enabled(
+                            !added,
+                            egui::Button::new(egui::RichText::new(*name).monospace()),
+                        )
+                        .on_hover_text(if in_env {
+                            format!("{}\nAlready set where Brewsty was launched", description)
+                        } else {
+                            description.to_string()
+                        })
+                        .on_disabled_hover_text("Already in the list");
+                    if response.clicked() {
+                        vars.push((name.to_string(), suggested.to_string()));
+                        changed = true;
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+
+        changed
+    }
+
+    fn env_indicator(ui: &mut egui::Ui, name: &str, set: bool) {
+        ui.horizontal(|ui| {
+            ui.monospace(name);
+            if set {
+                ui.colored_label(egui::Color32::from_rgb(0, 200, 0), "set");
+            } else {
+                ui.weak("not set");
+            }
+        });
     }
 }